@@ -463,6 +463,26 @@ impl sc_cli::CliConfiguration for NormalizedRunCmd {
 		self.base.runtime_cache_size()
 	}
 
+	fn runtime_cache_path(&self) -> sc_cli::Result<Option<PathBuf>> {
+		self.base.runtime_cache_path()
+	}
+
+	fn runtime_cache_max_size(&self) -> sc_cli::Result<u64> {
+		self.base.runtime_cache_max_size()
+	}
+
+	fn host_function_shims(&self) -> sc_cli::Result<sc_service::HostFunctionShims> {
+		self.base.host_function_shims()
+	}
+
+	fn precompile_runtime_upgrades(&self) -> sc_cli::Result<bool> {
+		self.base.precompile_runtime_upgrades()
+	}
+
+	fn runtime_api_cache_size(&self) -> sc_cli::Result<usize> {
+		self.base.runtime_api_cache_size()
+	}
+
 	fn base_path(&self) -> sc_cli::Result<Option<BasePath>> {
 		self.base.base_path()
 	}
@@ -196,6 +196,7 @@ impl RelayChainInterface for RelayChainInProcessInterface {
 			at: hash,
 			function: method_name,
 			arguments: payload.to_vec(),
+			pure: false,
 			overlayed_changes: &Default::default(),
 			call_context: CallContext::Offchain,
 			recorder: &None,
@@ -378,6 +379,10 @@ fn build_polkadot_full_node(
 			hwbench,
 			execute_workers_max_num: None,
 			prepare_workers_hard_max_num: None,
+			pvf_artifacts_cache_limit: None,
+			pvf_artifacts_max_count: None,
+			pvf_artifacts_min_stale_time: None,
+			pvf_prefetch_scheduled: false,
 			prepare_workers_soft_max_num: None,
 			enable_approval_voting_parallel: false,
 		},
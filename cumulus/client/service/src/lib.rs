@@ -348,6 +348,20 @@ pub fn prepare_node_config(mut parachain_config: Configuration) -> Configuration
 	parachain_config
 }
 
+/// The storage key of the validation code that is scheduled to replace the current one, i.e.
+/// `PendingValidationCode` of `cumulus-pallet-parachain-system`.
+///
+/// `parachain_system_name` is the name of the pallet in the runtime. Pushing the key to
+/// `pending_code_storage_keys` of the executor configuration makes the node compile validation
+/// code upgrades ahead of time.
+pub fn pending_validation_code_storage_key(parachain_system_name: &str) -> Vec<u8> {
+	[
+		sp_crypto_hashing::twox_128(parachain_system_name.as_bytes()),
+		sp_crypto_hashing::twox_128(b"PendingValidationCode"),
+	]
+	.concat()
+}
+
 /// Build a relay chain interface.
 /// Will return a minimal relay chain node with RPC
 /// client or an inprocess node, based on the [`CollatorOptions`] passed in.
@@ -155,6 +155,13 @@ pub struct Cli<Config: CliConfig> {
 	#[arg(long)]
 	pub export_pov_to_path: Option<PathBuf>,
 
+	/// The name of the parachain system pallet in the runtime.
+	///
+	/// Used to find validation code upgrades scheduled by the runtime, which are compiled ahead of
+	/// time.
+	#[arg(long, default_value = "ParachainSystem")]
+	pub parachain_system_name: String,
+
 	/// Relay chain arguments
 	#[arg(raw = true)]
 	pub relay_chain_args: Vec<String>,
@@ -168,6 +175,7 @@ impl<Config: CliConfig> Cli<Config> {
 		NodeExtraArgs {
 			use_slot_based_consensus: self.experimental_use_slot_based,
 			export_pov: self.export_pov_to_path.clone(),
+			parachain_system_name: self.parachain_system_name.clone(),
 		}
 	}
 }
@@ -105,4 +105,7 @@ pub struct NodeExtraArgs {
 
 	/// If set, each `PoV` build by the node will be exported to this folder.
 	pub export_pov: Option<PathBuf>,
+
+	/// The name of the parachain system pallet in the runtime.
+	pub parachain_system_name: String,
 }
@@ -25,8 +25,9 @@ use crate::common::{
 };
 use cumulus_client_cli::CollatorOptions;
 use cumulus_client_service::{
-	build_network, build_relay_chain_interface, prepare_node_config, start_relay_chain_tasks,
-	BuildNetworkParams, CollatorSybilResistance, DARecoveryProfile, StartRelayChainTasksParams,
+	build_network, build_relay_chain_interface, pending_validation_code_storage_key,
+	prepare_node_config, start_relay_chain_tasks, BuildNetworkParams, CollatorSybilResistance,
+	DARecoveryProfile, StartRelayChainTasksParams,
 };
 use cumulus_primitives_core::{BlockT, ParaId};
 use cumulus_relay_chain_interface::{OverseerHandle, RelayChainInterface};
@@ -129,6 +130,9 @@ pub(crate) trait BaseNodeSpec {
 			.with_execution_method(config.executor.wasm_method)
 			.with_max_runtime_instances(config.executor.max_runtime_instances)
 			.with_runtime_cache_size(config.executor.runtime_cache_size)
+			.with_runtime_cache_path(config.executor.runtime_cache_path.clone())
+			.with_runtime_cache_max_size(config.executor.runtime_cache_max_size)
+			.with_host_function_shims(config.executor.host_function_shims.clone())
 			.with_onchain_heap_alloc_strategy(heap_pages)
 			.with_offchain_heap_alloc_strategy(heap_pages)
 			.build();
@@ -210,7 +214,10 @@ pub(crate) trait NodeSpec: BaseNodeSpec {
 	{
 		Box::pin(
 			async move {
-				let parachain_config = prepare_node_config(parachain_config);
+				let mut parachain_config = prepare_node_config(parachain_config);
+				parachain_config.executor.pending_code_storage_keys.push(
+					pending_validation_code_storage_key(&node_extra_args.parachain_system_name),
+				);
 
 				let params = Self::new_partial(&parachain_config)?;
 				let (block_import, mut telemetry, telemetry_worker_handle) = params.other;
@@ -100,6 +100,18 @@ pub fn get_proof_size() -> Option<u64> {
 	(proof_size != PROOF_RECORDING_DISABLED).then_some(proof_size)
 }
 
+/// Provides the storage proof size from the host side.
+///
+/// Meant to be used as `frame_system::Config::StorageProofSize` in parachain runtimes, so that
+/// calls annotated with `#[pallet::weight_refund(auto(..))]` reclaim their unused proof size.
+pub struct HostStorageProofSize;
+
+impl frame_support::weight_refund::StorageProofSize for HostStorageProofSize {
+	fn storage_proof_size() -> Option<u64> {
+		get_proof_size()
+	}
+}
+
 /// Storage weight reclaim mechanism.
 ///
 /// This extension checks the size of the node-side storage proof
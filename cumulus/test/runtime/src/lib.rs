@@ -225,6 +225,7 @@ impl frame_system::Config for Runtime {
 	type SS58Prefix = SS58Prefix;
 	type OnSetCode = cumulus_pallet_parachain_system::ParachainSetCode<Self>;
 	type MaxConsumers = frame_support::traits::ConstU32<16>;
+	type StorageProofSize = cumulus_primitives_storage_weight_reclaim::HostStorageProofSize;
 }
 
 parameter_types! {
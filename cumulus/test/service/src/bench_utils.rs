@@ -188,6 +188,7 @@ pub fn get_wasm_module() -> Box<dyn sc_executor_common::wasm_runtime::WasmModule
 	let config = sc_executor_wasmtime::Config {
 		allow_missing_func_imports: true,
 		cache_path: None,
+		host_function_shims: Default::default(),
 		semantics: sc_executor_wasmtime::Semantics {
 			heap_alloc_strategy: DEFAULT_HEAP_ALLOC_STRATEGY,
 			instantiation_strategy: sc_executor::WasmtimeInstantiationStrategy::PoolingCopyOnWrite,
@@ -198,6 +199,7 @@ pub fn get_wasm_module() -> Box<dyn sc_executor_common::wasm_runtime::WasmModule
 			wasm_bulk_memory: false,
 			wasm_reference_types: false,
 			wasm_simd: false,
+			fuel: None,
 		},
 	};
 	Box::new(
@@ -53,8 +53,9 @@ use cumulus_client_pov_recovery::{RecoveryDelayRange, RecoveryHandle};
 #[allow(deprecated)]
 use cumulus_client_service::old_consensus;
 use cumulus_client_service::{
-	build_network, prepare_node_config, start_relay_chain_tasks, BuildNetworkParams,
-	CollatorSybilResistance, DARecoveryProfile, StartRelayChainTasksParams,
+	build_network, pending_validation_code_storage_key, prepare_node_config,
+	start_relay_chain_tasks, BuildNetworkParams, CollatorSybilResistance, DARecoveryProfile,
+	StartRelayChainTasksParams,
 };
 use cumulus_primitives_core::{relay_chain::ValidationCode, ParaId};
 use cumulus_relay_chain_inprocess_interface::RelayChainInProcessInterface;
@@ -340,6 +341,10 @@ where
 	RB: Fn(Arc<Client>) -> Result<jsonrpsee::RpcModule<()>, sc_service::Error> + Send + 'static,
 {
 	let mut parachain_config = prepare_node_config(parachain_config);
+	parachain_config
+		.executor
+		.pending_code_storage_keys
+		.push(pending_validation_code_storage_key("ParachainSystem"));
 
 	let params = new_partial(&mut parachain_config, proof_recording_during_import)?;
 
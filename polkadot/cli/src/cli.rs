@@ -45,6 +45,9 @@ pub enum Subcommand {
 	/// Revert the chain to a previous state.
 	Revert(sc_cli::RevertCmd),
 
+	/// Replay blocks on top of a fork with a new runtime.
+	ReplayBlocks(sc_cli::ReplayBlocksCmd),
+
 	/// Sub-commands concerned with benchmarking.
 	/// The pallet benchmarking moved to the `pallet` sub-command.
 	#[command(subcommand)]
@@ -56,6 +59,9 @@ pub enum Subcommand {
 
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
+
+	/// Report the host functions imported by the runtimes of the chain.
+	HostFunctions(sc_cli::HostFunctionsCmd),
 }
 
 #[allow(missing_docs)]
@@ -142,6 +148,27 @@ pub struct RunCmd {
 	///  **Dangerous!** Do not touch unless explicitly advised to.
 	#[arg(long)]
 	pub prepare_workers_hard_max_num: Option<usize>,
+	/// Override the total size, in MiB, of the prepared pvf artifacts above which the least
+	/// recently used ones are pruned.
+	///
+	/// Artifacts used within `--pvf-artifacts-min-stale-time` are never pruned, so the cache may
+	/// stay above this size.
+	#[arg(long, value_name = "MiB")]
+	pub pvf_artifacts_cache_size: Option<u64>,
+	/// Prune the least recently used prepared pvf artifacts once there are more than this many.
+	///
+	/// Artifacts used within `--pvf-artifacts-min-stale-time` are never pruned, so the cache may
+	/// hold more artifacts than this.
+	#[arg(long, value_name = "COUNT")]
+	pub pvf_artifacts_max_count: Option<usize>,
+	/// Override the time, in hours, within which a used pvf artifact is never pruned. Defaults to
+	/// 24 hours.
+	#[arg(long, value_name = "HOURS")]
+	pub pvf_artifacts_min_stale_time: Option<u64>,
+	/// Prepare the pvfs of all parachains in the claim queue ahead of the next session in which
+	/// the node is a validator, not only the ones of backed candidates.
+	#[arg(long)]
+	pub pvf_prefetch_scheduled: bool,
 	/// TESTING ONLY: disable the version check between nodes and workers.
 	#[arg(long, hide = true)]
 	pub disable_worker_version_check: bool,
@@ -33,7 +33,7 @@ use sp_keyring::Sr25519Keyring;
 
 pub use crate::error::Error;
 #[cfg(feature = "pyroscope")]
-use std::net::ToSocketAddrs;
+use std::{net::ToSocketAddrs, time::Duration};
 
 type Result<T> = std::result::Result<T, Error>;
 
@@ -234,6 +234,16 @@ where
 				execute_workers_max_num: cli.run.execute_workers_max_num,
 				prepare_workers_hard_max_num: cli.run.prepare_workers_hard_max_num,
 				prepare_workers_soft_max_num: cli.run.prepare_workers_soft_max_num,
+				pvf_artifacts_cache_limit: cli
+					.run
+					.pvf_artifacts_cache_size
+					.map(|size| size.saturating_mul(1024 * 1024)),
+				pvf_artifacts_max_count: cli.run.pvf_artifacts_max_count,
+				pvf_artifacts_min_stale_time: cli
+					.run
+					.pvf_artifacts_min_stale_time
+					.map(|hours| Duration::from_secs(hours.saturating_mul(60 * 60))),
+				pvf_prefetch_scheduled: cli.run.pvf_prefetch_scheduled,
 				enable_approval_voting_parallel: cli.run.enable_approval_voting_parallel,
 			},
 		)
@@ -367,6 +377,24 @@ pub fn run() -> Result<()> {
 				))
 			})?)
 		},
+		Some(Subcommand::ReplayBlocks(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			let chain_spec = &runner.config().chain_spec;
+
+			set_default_ss58_version(chain_spec);
+
+			Ok(runner.async_run(|mut config| {
+				let (client, backend, _, task_manager) =
+					polkadot_service::new_chain_ops(&mut config)?;
+				Ok((
+					cmd.run::<polkadot_service::Block, _, _, sp_io::SubstrateHostFunctions>(
+						client, backend,
+					)
+					.map_err(Error::SubstrateCli),
+					task_manager,
+				))
+			})?)
+		},
 		Some(Subcommand::Benchmark(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			let chain_spec = &runner.config().chain_spec;
@@ -461,6 +489,17 @@ pub fn run() -> Result<()> {
 			let runner = cli.create_runner(cmd)?;
 			Ok(runner.sync_run(|config| cmd.run::<polkadot_service::Block>(&config))?)
 		},
+		Some(Subcommand::HostFunctions(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			Ok(runner.async_run(|mut config| {
+				let (client, _, _, task_manager) = polkadot_service::new_chain_ops(&mut config)?;
+				Ok((
+					cmd.run::<_, _, _, polkadot_service::HostFunctions>(client)
+						.map_err(Error::SubstrateCli),
+					task_manager,
+				))
+			})?)
+		},
 	}?;
 
 	#[cfg(feature = "pyroscope")]
@@ -24,8 +24,9 @@
 #![warn(missing_docs)]
 
 use polkadot_node_core_pvf::{
-	InternalValidationError, InvalidCandidate as WasmInvalidCandidate, PossiblyInvalidError,
-	PrepareError, PrepareJobKind, PvfPrepData, ValidationError, ValidationHost,
+	ArtifactsCleanupConfig, InternalValidationError, InvalidCandidate as WasmInvalidCandidate,
+	PossiblyInvalidError, PrepareError, PrepareJobKind, PvfPrepData, ValidationError,
+	ValidationHost,
 };
 use polkadot_node_primitives::{InvalidCandidate, PoV, ValidationResult};
 use polkadot_node_subsystem::{
@@ -53,9 +54,9 @@ use polkadot_primitives::{
 		CandidateReceiptV2 as CandidateReceipt,
 		CommittedCandidateReceiptV2 as CommittedCandidateReceipt,
 	},
-	AuthorityDiscoveryId, CandidateCommitments, ExecutorParams, Hash, PersistedValidationData,
-	PvfExecKind as RuntimePvfExecKind, PvfPrepKind, SessionIndex, ValidationCode,
-	ValidationCodeHash, ValidatorId,
+	AuthorityDiscoveryId, CandidateCommitments, ExecutorParams, Hash, Id as ParaId,
+	OccupiedCoreAssumption, PersistedValidationData, PvfExecKind as RuntimePvfExecKind,
+	PvfPrepKind, SessionIndex, ValidationCode, ValidationCodeHash, ValidatorId,
 };
 use sp_application_crypto::{AppCrypto, ByteArray};
 use sp_keystore::KeystorePtr;
@@ -65,7 +66,7 @@ use codec::Encode;
 use futures::{channel::oneshot, prelude::*, stream::FuturesUnordered};
 
 use std::{
-	collections::HashSet,
+	collections::{BTreeSet, HashSet},
 	path::PathBuf,
 	pin::Pin,
 	sync::Arc,
@@ -114,6 +115,11 @@ pub struct Config {
 	pub pvf_prepare_workers_soft_max_num: usize,
 	/// The absolute number of pvf workers that can be spawned in the pvf prepare pool.
 	pub pvf_prepare_workers_hard_max_num: usize,
+	/// The limits above which the least recently used prepared artifacts are pruned.
+	pub pvf_artifacts_cleanup: ArtifactsCleanupConfig,
+	/// Whether to prepare PVFs of the parachains in the claim queue ahead of the next session,
+	/// in addition to the ones of the backed candidates.
+	pub pvf_prefetch_scheduled: bool,
 }
 
 /// The candidate validation subsystem.
@@ -255,26 +261,27 @@ async fn run<Context>(
 		pvf_execute_workers_max_num,
 		pvf_prepare_workers_soft_max_num,
 		pvf_prepare_workers_hard_max_num,
+		pvf_artifacts_cleanup,
+		pvf_prefetch_scheduled,
 	}: Config,
 ) -> SubsystemResult<()> {
-	let (validation_host, task) = polkadot_node_core_pvf::start(
-		polkadot_node_core_pvf::Config::new(
-			artifacts_cache_path,
-			node_version,
-			secure_validator_mode,
-			prep_worker_path,
-			exec_worker_path,
-			pvf_execute_workers_max_num,
-			pvf_prepare_workers_soft_max_num,
-			pvf_prepare_workers_hard_max_num,
-		),
-		pvf_metrics,
-	)
-	.await?;
+	let mut pvf_config = polkadot_node_core_pvf::Config::new(
+		artifacts_cache_path,
+		node_version,
+		secure_validator_mode,
+		prep_worker_path,
+		exec_worker_path,
+		pvf_execute_workers_max_num,
+		pvf_prepare_workers_soft_max_num,
+		pvf_prepare_workers_hard_max_num,
+	);
+	pvf_config.artifacts_cleanup = pvf_artifacts_cleanup;
+	let (validation_host, task) = polkadot_node_core_pvf::start(pvf_config, pvf_metrics).await?;
 	ctx.spawn_blocking("pvf-validation-host", task.boxed())?;
 
 	let mut tasks = FuturesUnordered::new();
-	let mut prepare_state = PrepareValidationState::default();
+	let mut prepare_state =
+		PrepareValidationState { prefetch_scheduled: pvf_prefetch_scheduled, ..Default::default() };
 
 	loop {
 		loop {
@@ -328,8 +335,14 @@ struct PrepareValidationState {
 	is_next_session_authority: bool,
 	// PVF host won't prepare the same code hash twice, so here we just avoid extra communication
 	already_prepared_code_hashes: HashSet<ValidationCodeHash>,
+	// Scheduled parachains with a prepared PVF, their code hashes are not requested again until
+	// the next session. Code upgrades within the session are picked up through the backed
+	// candidates.
+	prepared_paras: HashSet<ParaId>,
 	// How many PVFs per block we take to prepare themselves for the next session validation
 	per_block_limit: usize,
+	// Whether PVFs of the parachains in the claim queue are prepared before the backed ones
+	prefetch_scheduled: bool,
 }
 
 impl Default for PrepareValidationState {
@@ -338,7 +351,9 @@ impl Default for PrepareValidationState {
 			session_index: None,
 			is_next_session_authority: false,
 			already_prepared_code_hashes: HashSet::new(),
+			prepared_paras: HashSet::new(),
 			per_block_limit: 1,
+			prefetch_scheduled: false,
 		}
 	}
 }
@@ -357,6 +372,7 @@ async fn maybe_prepare_validation<Sender>(
 	if new_session_index.is_some() {
 		state.session_index = new_session_index;
 		state.already_prepared_code_hashes.clear();
+		state.prepared_paras.clear();
 		state.is_next_session_authority = check_next_session_authority(
 			sender,
 			keystore,
@@ -366,17 +382,37 @@ async fn maybe_prepare_validation<Sender>(
 		.await;
 	}
 
-	// On every active leaf check candidates and prepare PVFs our node doesn't have yet.
+	// On every active leaf check scheduled parachains and candidates and prepare PVFs our node
+	// doesn't have yet.
 	if state.is_next_session_authority {
+		let scheduled = if state.prefetch_scheduled {
+			scheduled_code_hashes(sender, leaf.hash, &state.prepared_paras).await
+		} else {
+			Vec::new()
+		};
+		let mut scheduled_code_hashes = Vec::new();
+		for (para_id, code_hash) in &scheduled {
+			if state.already_prepared_code_hashes.contains(code_hash) {
+				state.prepared_paras.insert(*para_id);
+			} else if !scheduled_code_hashes.contains(code_hash) {
+				scheduled_code_hashes.push(*code_hash);
+			}
+		}
 		let code_hashes = prepare_pvfs_for_backed_candidates(
 			sender,
 			validation_backend,
 			leaf.hash,
+			scheduled_code_hashes,
 			&state.already_prepared_code_hashes,
 			state.per_block_limit,
 		)
 		.await;
 		state.already_prepared_code_hashes.extend(code_hashes.unwrap_or_default());
+		for (para_id, code_hash) in scheduled {
+			if state.already_prepared_code_hashes.contains(&code_hash) {
+				state.prepared_paras.insert(para_id);
+			}
+		}
 	}
 }
 
@@ -457,11 +493,58 @@ where
 	is_past_present_or_future_authority && !is_present_validator
 }
 
-// Sends PVF with unknown code hashes to the validation host returning the list of code hashes sent.
+// Returns the code hashes of the parachains in the claim queue, skipping the parachains whose PVF
+// is already prepared in this session.
+async fn scheduled_code_hashes<Sender>(
+	sender: &mut Sender,
+	relay_parent: Hash,
+	prepared_paras: &HashSet<ParaId>,
+) -> Vec<(ParaId, ValidationCodeHash)>
+where
+	Sender: SubsystemSender<RuntimeApiMessage>,
+{
+	let Some(claim_queue) = claim_queue(relay_parent, sender).await else { return Vec::new() };
+	let para_ids = claim_queue
+		.iter_all_claims()
+		.flat_map(|(_, paras)| paras.iter().copied())
+		.filter(|para_id| !prepared_paras.contains(para_id))
+		.collect::<BTreeSet<_>>();
+
+	let mut code_hashes = Vec::new();
+	for para_id in para_ids {
+		let Ok(Ok(Some(code_hash))) = util::request_validation_code_hash(
+			relay_parent,
+			para_id,
+			OccupiedCoreAssumption::Included,
+			sender,
+		)
+		.await
+		.await
+		else {
+			gum::warn!(
+				target: LOG_TARGET,
+				?relay_parent,
+				?para_id,
+				"cannot fetch validation code hash of a scheduled para from runtime API",
+			);
+			continue;
+		};
+
+		code_hashes.push((para_id, code_hash));
+	}
+
+	code_hashes
+}
+
+// Sends PVF with unknown code hashes to the validation host returning the list of code hashes that
+// are prepared or being prepared now. Code hashes of the scheduled parachains go first, followed by
+// the ones of backed candidates. Code hashes the validation host already has an artifact for, e.g.
+// from the on-disk cache, are returned without being sent again.
 async fn prepare_pvfs_for_backed_candidates<Sender>(
 	sender: &mut Sender,
 	mut validation_backend: impl ValidationBackend,
 	relay_parent: Hash,
+	scheduled_code_hashes: Vec<ValidationCodeHash>,
 	already_prepared: &HashSet<ValidationCodeHash>,
 	per_block_limit: usize,
 ) -> Option<Vec<ValidationCodeHash>>
@@ -476,21 +559,26 @@ where
 		);
 		return None
 	};
-	let code_hashes = events
-		.into_iter()
-		.filter_map(|e| match e {
-			CandidateEvent::CandidateBacked(receipt, ..) => {
-				let h = receipt.descriptor.validation_code_hash();
-				if already_prepared.contains(&h) {
-					None
-				} else {
-					Some(h)
-				}
-			},
-			_ => None,
-		})
-		.take(per_block_limit)
-		.collect::<Vec<_>>();
+	let backed_code_hashes = events.into_iter().filter_map(|e| match e {
+		CandidateEvent::CandidateBacked(receipt, ..) => {
+			let h = receipt.descriptor.validation_code_hash();
+			if already_prepared.contains(&h) || scheduled_code_hashes.contains(&h) {
+				None
+			} else {
+				Some(h)
+			}
+		},
+		_ => None,
+	});
+	let mut code_hashes = Vec::new();
+	for code_hash in scheduled_code_hashes.iter().copied().chain(backed_code_hashes) {
+		if !code_hashes.contains(&code_hash) {
+			code_hashes.push(code_hash);
+		}
+	}
+	if code_hashes.is_empty() {
+		return None
+	}
 
 	let Ok(executor_params) = util::executor_params_at_relay_parent(relay_parent, sender).await
 	else {
@@ -503,9 +591,16 @@ where
 	};
 	let timeout = pvf_prep_timeout(&executor_params, PvfPrepKind::Prepare);
 
+	let unprepared_code_hashes = validation_backend
+		.unprepared_code_hashes(code_hashes.clone(), &executor_params)
+		.await;
+	let mut processed_code_hashes = code_hashes
+		.into_iter()
+		.filter(|code_hash| !unprepared_code_hashes.contains(code_hash))
+		.collect::<Vec<_>>();
+
 	let mut active_pvfs = vec![];
-	let mut processed_code_hashes = vec![];
-	for code_hash in code_hashes {
+	for code_hash in unprepared_code_hashes.into_iter().take(per_block_limit) {
 		let Ok(Ok(Some(validation_code))) =
 			util::request_validation_code_by_hash(relay_parent, code_hash, sender)
 				.await
@@ -532,7 +627,7 @@ where
 	}
 
 	if active_pvfs.is_empty() {
-		return None
+		return Some(processed_code_hashes)
 	}
 
 	if let Err(err) = validation_backend.heads_up(active_pvfs).await {
@@ -813,6 +908,7 @@ async fn validate_candidate_exhaustive(
 					pov,
 					exec_kind.into(),
 					exec_kind,
+					para_id,
 				)
 				.await
 		},
@@ -827,6 +923,7 @@ async fn validate_candidate_exhaustive(
 					PVF_APPROVAL_EXECUTION_RETRY_DELAY,
 					exec_kind.into(),
 					exec_kind,
+					para_id,
 				)
 				.await,
 	};
@@ -972,6 +1069,8 @@ trait ValidationBackend {
 		prepare_priority: polkadot_node_core_pvf::Priority,
 		// The kind for the execution job.
 		exec_kind: PvfExecKind,
+		// The parachain the candidate belongs to.
+		para_id: ParaId,
 	) -> Result<WasmValidationResult, ValidationError>;
 
 	/// Tries executing a PVF. Will retry once if an error is encountered that may have
@@ -994,6 +1093,8 @@ trait ValidationBackend {
 		prepare_priority: polkadot_node_core_pvf::Priority,
 		// The kind for the execution job.
 		exec_kind: PvfExecKind,
+		// The parachain the candidate belongs to.
+		para_id: ParaId,
 	) -> Result<WasmValidationResult, ValidationError> {
 		let prep_timeout = pvf_prep_timeout(&executor_params, PvfPrepKind::Prepare);
 		// Construct the PVF a single time, since it is an expensive operation. Cloning it is cheap.
@@ -1016,6 +1117,7 @@ trait ValidationBackend {
 				pov.clone(),
 				prepare_priority,
 				exec_kind,
+				para_id,
 			)
 			.await;
 		if validation_result.is_ok() {
@@ -1102,6 +1204,7 @@ trait ValidationBackend {
 						pov.clone(),
 						prepare_priority,
 						exec_kind,
+						para_id,
 					)
 					.await;
 			}
@@ -1114,6 +1217,13 @@ trait ValidationBackend {
 
 	async fn heads_up(&mut self, active_pvfs: Vec<PvfPrepData>) -> Result<(), String>;
 
+	/// Returns the code hashes the validation host has no artifact for and doesn't prepare yet.
+	async fn unprepared_code_hashes(
+		&mut self,
+		code_hashes: Vec<ValidationCodeHash>,
+		executor_params: &ExecutorParams,
+	) -> Vec<ValidationCodeHash>;
+
 	async fn update_active_leaves(
 		&mut self,
 		update: ActiveLeavesUpdate,
@@ -1134,10 +1244,12 @@ impl ValidationBackend for ValidationHost {
 		prepare_priority: polkadot_node_core_pvf::Priority,
 		// The kind for the execution job.
 		exec_kind: PvfExecKind,
+		// The parachain the candidate belongs to.
+		para_id: ParaId,
 	) -> Result<WasmValidationResult, ValidationError> {
 		let (tx, rx) = oneshot::channel();
 		if let Err(err) = self
-			.execute_pvf(pvf, exec_timeout, pvd, pov, prepare_priority, exec_kind, tx)
+			.execute_pvf(pvf, exec_timeout, pvd, pov, prepare_priority, exec_kind, para_id, tx)
 			.await
 		{
 			return Err(InternalValidationError::HostCommunication(format!(
@@ -1170,6 +1282,24 @@ impl ValidationBackend for ValidationHost {
 		self.heads_up(active_pvfs).await
 	}
 
+	async fn unprepared_code_hashes(
+		&mut self,
+		code_hashes: Vec<ValidationCodeHash>,
+		executor_params: &ExecutorParams,
+	) -> Vec<ValidationCodeHash> {
+		match self.unprepared_code_hashes(code_hashes.clone(), executor_params).await {
+			Ok(unprepared) => unprepared,
+			Err(err) => {
+				gum::warn!(
+					target: LOG_TARGET,
+					?err,
+					"cannot query prepared artifacts from the validation host",
+				);
+				code_hashes
+			},
+		}
+	}
+
 	async fn update_active_leaves(
 		&mut self,
 		update: ActiveLeavesUpdate,
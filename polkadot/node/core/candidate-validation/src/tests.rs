@@ -457,6 +457,7 @@ impl ValidationBackend for MockValidateCandidateBackend {
 		_pov: Arc<PoV>,
 		_prepare_priority: polkadot_node_core_pvf::Priority,
 		_exec_kind: PvfExecKind,
+		_para_id: ParaId,
 	) -> Result<WasmValidationResult, ValidationError> {
 		// This is expected to panic if called more times than expected, indicating an error in the
 		// test.
@@ -474,6 +475,14 @@ impl ValidationBackend for MockValidateCandidateBackend {
 		unreachable!()
 	}
 
+	async fn unprepared_code_hashes(
+		&mut self,
+		_code_hashes: Vec<ValidationCodeHash>,
+		_executor_params: &ExecutorParams,
+	) -> Vec<ValidationCodeHash> {
+		unreachable!()
+	}
+
 	async fn update_active_leaves(
 		&mut self,
 		_update: ActiveLeavesUpdate,
@@ -1433,6 +1442,7 @@ impl ValidationBackend for MockPreCheckBackend {
 		_pov: Arc<PoV>,
 		_prepare_priority: polkadot_node_core_pvf::Priority,
 		_exec_kind: PvfExecKind,
+		_para_id: ParaId,
 	) -> Result<WasmValidationResult, ValidationError> {
 		unreachable!()
 	}
@@ -1445,6 +1455,14 @@ impl ValidationBackend for MockPreCheckBackend {
 		unreachable!()
 	}
 
+	async fn unprepared_code_hashes(
+		&mut self,
+		_code_hashes: Vec<ValidationCodeHash>,
+		_executor_params: &ExecutorParams,
+	) -> Vec<ValidationCodeHash> {
+		unreachable!()
+	}
+
 	async fn update_active_leaves(
 		&mut self,
 		_update: ActiveLeavesUpdate,
@@ -1584,6 +1602,7 @@ fn precheck_properly_classifies_outcomes() {
 #[derive(Default, Clone)]
 struct MockHeadsUp {
 	heads_up_call_count: Arc<AtomicUsize>,
+	prepared_code_hashes: HashSet<ValidationCodeHash>,
 }
 
 #[async_trait]
@@ -1596,6 +1615,7 @@ impl ValidationBackend for MockHeadsUp {
 		_pov: Arc<PoV>,
 		_prepare_priority: polkadot_node_core_pvf::Priority,
 		_exec_kind: PvfExecKind,
+		_para_id: ParaId,
 	) -> Result<WasmValidationResult, ValidationError> {
 		unreachable!()
 	}
@@ -1609,6 +1629,17 @@ impl ValidationBackend for MockHeadsUp {
 		Ok(())
 	}
 
+	async fn unprepared_code_hashes(
+		&mut self,
+		code_hashes: Vec<ValidationCodeHash>,
+		_executor_params: &ExecutorParams,
+	) -> Vec<ValidationCodeHash> {
+		code_hashes
+			.into_iter()
+			.filter(|code_hash| !self.prepared_code_hashes.contains(code_hash))
+			.collect()
+	}
+
 	async fn update_active_leaves(
 		&mut self,
 		_update: ActiveLeavesUpdate,
@@ -2140,6 +2171,224 @@ fn maybe_prepare_validation_prepares_a_limited_number_of_pvfs() {
 	assert_eq!(state.already_prepared_code_hashes.len(), 2);
 }
 
+#[test]
+fn maybe_prepare_validation_prefetches_pvfs_of_scheduled_paras() {
+	let pool = TaskExecutor::new();
+	let (mut ctx, mut ctx_handle) =
+		polkadot_node_subsystem_test_helpers::make_subsystem_context::<AllMessages, _>(pool);
+
+	let keystore = alice_keystore();
+	let backend = MockHeadsUp::default();
+	let activated_hash = Hash::random();
+	let update = dummy_active_leaves_update(activated_hash);
+	let mut state = PrepareValidationState {
+		per_block_limit: 2,
+		prefetch_scheduled: true,
+		..Default::default()
+	};
+
+	let check_fut =
+		maybe_prepare_validation(ctx.sender(), keystore, backend.clone(), update, &mut state);
+
+	let test_fut = async move {
+		assert_matches!(
+			ctx_handle.recv().await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(_, RuntimeApiRequest::SessionIndexForChild(tx))) => {
+				let _ = tx.send(Ok(1));
+			}
+		);
+
+		assert_matches!(
+			ctx_handle.recv().await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(_, RuntimeApiRequest::Authorities(tx))) => {
+				let _ = tx.send(Ok(vec![Sr25519Keyring::Alice.public().into()]));
+			}
+		);
+
+		assert_matches!(
+			ctx_handle.recv().await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(_, RuntimeApiRequest::SessionInfo(index, tx))) => {
+				assert_eq!(index, 1);
+				let _ = tx.send(Ok(Some(dummy_session_info(vec![Sr25519Keyring::Bob.public()]))));
+			}
+		);
+
+		assert_matches!(
+			ctx_handle.recv().await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(_, RuntimeApiRequest::Version(tx))) => {
+				let _ = tx.send(Ok(RuntimeApiRequest::CLAIM_QUEUE_RUNTIME_REQUIREMENT));
+			}
+		);
+
+		assert_matches!(
+			ctx_handle.recv().await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(_, RuntimeApiRequest::ClaimQueue(tx))) => {
+				let claim_queue = BTreeMap::from([
+					(CoreIndex(0), vec![ParaId::from(1_u32), ParaId::from(2_u32)].into()),
+					(CoreIndex(1), vec![ParaId::from(1_u32)].into()),
+				]);
+				let _ = tx.send(Ok(claim_queue));
+			}
+		);
+
+		for (expected_para_id, code) in [(1_u32, 0_u8), (2, 1)] {
+			assert_matches!(
+				ctx_handle.recv().await,
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_,
+					RuntimeApiRequest::ValidationCodeHash(para_id, OccupiedCoreAssumption::Included, tx),
+				)) => {
+					assert_eq!(para_id, ParaId::from(expected_para_id));
+					let _ = tx.send(Ok(Some(ValidationCode(vec![code; 16]).hash())));
+				}
+			);
+		}
+
+		assert_matches!(
+			ctx_handle.recv().await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(_, RuntimeApiRequest::CandidateEvents(tx))) => {
+				let candidates = vec![
+					dummy_candidate_backed(activated_hash, ValidationCode(vec![2; 16]).hash()),
+				];
+				let _ = tx.send(Ok(candidates));
+			}
+		);
+
+		assert_matches!(
+			ctx_handle.recv().await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(_, RuntimeApiRequest::SessionIndexForChild(tx))) => {
+				let _ = tx.send(Ok(1));
+			}
+		);
+
+		assert_matches!(
+			ctx_handle.recv().await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(_, RuntimeApiRequest::SessionExecutorParams(index, tx))) => {
+				assert_eq!(index, 1);
+				let _ = tx.send(Ok(Some(ExecutorParams::default())));
+			}
+		);
+
+		for code in [0_u8, 1] {
+			assert_matches!(
+				ctx_handle.recv().await,
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(_, RuntimeApiRequest::ValidationCodeByHash(hash, tx))) => {
+					assert_eq!(hash, ValidationCode(vec![code; 16]).hash());
+					let _ = tx.send(Ok(Some(ValidationCode(Vec::new()))));
+				}
+			);
+		}
+	};
+
+	let test_fut = future::join(test_fut, check_fut);
+	executor::block_on(test_fut);
+
+	assert_eq!(backend.heads_up_call_count.load(Ordering::SeqCst), 1);
+	assert!(state.is_next_session_authority);
+	assert_eq!(
+		state.already_prepared_code_hashes,
+		HashSet::from([ValidationCode(vec![0; 16]).hash(), ValidationCode(vec![1; 16]).hash()]),
+	);
+	assert_eq!(state.prepared_paras, HashSet::from([ParaId::from(1_u32), ParaId::from(2_u32)]));
+}
+
+#[test]
+fn maybe_prepare_validation_skips_prepared_and_cached_pvfs_of_scheduled_paras() {
+	let pool = TaskExecutor::new();
+	let (mut ctx, mut ctx_handle) =
+		polkadot_node_subsystem_test_helpers::make_subsystem_context::<AllMessages, _>(pool);
+
+	let keystore = alice_keystore();
+	let backend = MockHeadsUp {
+		prepared_code_hashes: HashSet::from([ValidationCode(vec![1; 16]).hash()]),
+		..Default::default()
+	};
+	let activated_hash = Hash::random();
+	let update = dummy_active_leaves_update(activated_hash);
+	let mut state = PrepareValidationState {
+		session_index: Some(1),
+		is_next_session_authority: true,
+		already_prepared_code_hashes: HashSet::from([ValidationCode(vec![0; 16]).hash()]),
+		prepared_paras: HashSet::from([ParaId::from(1_u32)]),
+		per_block_limit: 2,
+		prefetch_scheduled: true,
+	};
+
+	let check_fut =
+		maybe_prepare_validation(ctx.sender(), keystore, backend.clone(), update, &mut state);
+
+	let test_fut = async move {
+		assert_matches!(
+			ctx_handle.recv().await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(_, RuntimeApiRequest::SessionIndexForChild(tx))) => {
+				let _ = tx.send(Ok(1));
+			}
+		);
+
+		assert_matches!(
+			ctx_handle.recv().await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(_, RuntimeApiRequest::Version(tx))) => {
+				let _ = tx.send(Ok(RuntimeApiRequest::CLAIM_QUEUE_RUNTIME_REQUIREMENT));
+			}
+		);
+
+		assert_matches!(
+			ctx_handle.recv().await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(_, RuntimeApiRequest::ClaimQueue(tx))) => {
+				let claim_queue = BTreeMap::from([
+					(CoreIndex(0), vec![ParaId::from(1_u32), ParaId::from(2_u32)].into()),
+				]);
+				let _ = tx.send(Ok(claim_queue));
+			}
+		);
+
+		// Para 1 is already prepared in this session, so only the code hash of para 2 is requested.
+		assert_matches!(
+			ctx_handle.recv().await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_,
+				RuntimeApiRequest::ValidationCodeHash(para_id, OccupiedCoreAssumption::Included, tx),
+			)) => {
+				assert_eq!(para_id, ParaId::from(2_u32));
+				let _ = tx.send(Ok(Some(ValidationCode(vec![1; 16]).hash())));
+			}
+		);
+
+		assert_matches!(
+			ctx_handle.recv().await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(_, RuntimeApiRequest::CandidateEvents(tx))) => {
+				let _ = tx.send(Ok(Vec::new()));
+			}
+		);
+
+		assert_matches!(
+			ctx_handle.recv().await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(_, RuntimeApiRequest::SessionIndexForChild(tx))) => {
+				let _ = tx.send(Ok(1));
+			}
+		);
+
+		// The validation host already has an artifact for para 2, so its code is not fetched.
+		assert_matches!(
+			ctx_handle.recv().await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(_, RuntimeApiRequest::SessionExecutorParams(index, tx))) => {
+				assert_eq!(index, 1);
+				let _ = tx.send(Ok(Some(ExecutorParams::default())));
+			}
+		);
+	};
+
+	let test_fut = future::join(test_fut, check_fut);
+	executor::block_on(test_fut);
+
+	assert_eq!(backend.heads_up_call_count.load(Ordering::SeqCst), 0);
+	assert_eq!(
+		state.already_prepared_code_hashes,
+		HashSet::from([ValidationCode(vec![0; 16]).hash(), ValidationCode(vec![1; 16]).hash()]),
+	);
+	assert_eq!(state.prepared_paras, HashSet::from([ParaId::from(1_u32), ParaId::from(2_u32)]));
+}
+
 #[test]
 fn maybe_prepare_validation_does_not_prepare_already_prepared_pvfs() {
 	let pool = TaskExecutor::new();
@@ -2158,6 +2407,7 @@ fn maybe_prepare_validation_does_not_prepare_already_prepared_pvfs() {
 			ValidationCode(vec![0; 16]).hash(),
 			ValidationCode(vec![1; 16]).hash(),
 		]),
+		..Default::default()
 	};
 
 	let check_fut =
@@ -37,6 +37,10 @@ pub struct WorkerResponse {
 	pub duration: Duration,
 	/// The uncompressed PoV size.
 	pub pov_size: u32,
+	/// `ru_maxrss` (maximum resident set size) of the job process (in kilobytes), if known.
+	///
+	/// Includes the resident memory the job process inherited from the worker it was forked from.
+	pub max_rss: Option<i64>,
 }
 
 /// An error occurred in the worker process.
@@ -73,6 +77,8 @@ pub enum JobResponse {
 	Ok {
 		/// The result of parachain validation.
 		result_descriptor: ValidationResult,
+		/// The amount of fuel consumed by the execution, if it was metered.
+		fuel_consumed: Option<u64>,
 	},
 	/// A possibly transient runtime instantiation error happened during the execution; may be
 	/// retried with re-preparation
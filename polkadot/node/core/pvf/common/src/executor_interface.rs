@@ -24,12 +24,15 @@ use polkadot_primitives::{
 use sc_executor_common::{
 	error::WasmError,
 	runtime_blob::RuntimeBlob,
-	wasm_runtime::{HeapAllocStrategy, WasmModule as _},
+	wasm_runtime::{HeapAllocStrategy, WasmModule},
 };
-use sc_executor_wasmtime::{Config, DeterministicStackLimit, Semantics, WasmtimeRuntime};
+use sc_executor_wasmtime::{Config, DeterministicStackLimit, Semantics};
 use sp_core::storage::{ChildInfo, TrackedStorageKey};
 use sp_externalities::MultiRemovalResults;
-use std::any::{Any, TypeId};
+use std::{
+	any::{Any, TypeId},
+	time::Duration,
+};
 
 // Memory configuration
 //
@@ -53,6 +56,7 @@ const EXTRA_HEAP_PAGES: u32 = 2048;
 pub const DEFAULT_CONFIG: Config = Config {
 	allow_missing_func_imports: true,
 	cache_path: None,
+	host_function_shims: sc_executor_wasmtime::HostFunctionShims::empty(),
 	semantics: Semantics {
 		heap_alloc_strategy: sc_executor_common::wasm_runtime::HeapAllocStrategy::Dynamic {
 			maximum_pages: Some(DEFAULT_HEAP_PAGES_ESTIMATE + EXTRA_HEAP_PAGES),
@@ -93,11 +97,47 @@ pub const DEFAULT_CONFIG: Config = Config {
 		wasm_simd: false,
 		wasm_bulk_memory: false,
 		wasm_multi_value: false,
+
+		// Deterministic metering is disabled by default. It can be enabled by setting the fuel
+		// budget in the executor parameters.
+		fuel: None,
 	},
 };
 
+// PolkaVM configuration
+//
+// PolkaVM PVFs are only accepted if `ExecutorParam::PolkaVm` is set, otherwise they fail
+// prevalidation like any other code that isn't valid WASM.
+//
+// Unlike WASM, PolkaVM programs are compiled in a single pass that is fast enough to be done right
+// before the execution, and the compiled code can't be serialized anyway. Thus the artifact of a
+// PolkaVM PVF is the prevalidated program blob itself, prefixed with `POLKAVM_ARTIFACT_PREFIX` to
+// tell it apart from the artifacts produced by wasmtime.
+//
+// PolkaVM PVFs are always metered: every executed instruction consumes gas, and the amount of gas
+// given to the PVF is `ExecutorParam::PvfExecFuel`, or is derived from the execution timeout if
+// that isn't set. This makes the outcome of the execution independent of the hardware of the
+// validator. WASM PVFs are only metered if `ExecutorParam::PvfExecFuel` is set.
+const POLKAVM_ARTIFACT_PREFIX: &[u8] = b"PVF-PVM\0";
+
+/// The amount of gas given to a PolkaVM PVF per millisecond of its execution timeout.
+///
+/// VALUE SHOULD NEVER BE CHANGED, as it determines which PolkaVM candidates are valid.
+pub const POLKAVM_GAS_PER_MILLISECOND: u64 = 100_000;
+
+/// Returns the amount of gas a PolkaVM PVF is given when executed with `execution_timeout`.
+pub fn polkavm_gas_limit(execution_timeout: Duration) -> u64 {
+	u64::try_from(execution_timeout.as_millis())
+		.unwrap_or(u64::MAX)
+		.saturating_mul(POLKAVM_GAS_PER_MILLISECOND)
+}
+
 /// Executes the given PVF in the form of a compiled artifact and returns the result of
-/// execution upon success.
+/// execution upon success, along with the amount of fuel consumed if the execution was metered.
+///
+/// The PVF fails once it runs out of fuel. If the fuel budget isn't set in the executor
+/// parameters, PolkaVM PVFs are given [`polkavm_gas_limit`] of `execution_timeout` and WASM PVFs
+/// aren't metered. The timeout itself isn't enforced, which is up to the caller.
 ///
 /// # Safety
 ///
@@ -109,8 +149,9 @@ pub const DEFAULT_CONFIG: Config = Config {
 pub unsafe fn execute_artifact(
 	compiled_artifact_blob: &[u8],
 	executor_params: &ExecutorParams,
+	execution_timeout: Duration,
 	params: &[u8],
-) -> Result<Vec<u8>, ExecuteError> {
+) -> Result<(Vec<u8>, Option<u64>), ExecuteError> {
 	let mut extensions = sp_externalities::Extensions::new();
 
 	extensions.register(sp_core::traits::ReadRuntimeVersionExt::new(ReadRuntimeVersion));
@@ -118,8 +159,13 @@ pub unsafe fn execute_artifact(
 	let mut ext = ValidationExternalities(extensions);
 
 	match sc_executor::with_externalities_safe(&mut ext, || {
-		let runtime = create_runtime_from_artifact_bytes(compiled_artifact_blob, executor_params)?;
-		runtime.new_instance()?.call("validate_block", params)
+		let gas_limit = executor_params
+			.pvf_exec_fuel()
+			.unwrap_or_else(|| polkavm_gas_limit(execution_timeout));
+		let runtime = create_runtime(compiled_artifact_blob, executor_params, Some(gas_limit))?;
+		let mut instance = runtime.new_instance()?;
+		let result = instance.call("validate_block", params)?;
+		Ok((result, instance.fuel_consumed()))
 	}) {
 		Ok(Ok(ok)) => Ok(ok),
 		Ok(Err(err)) | Err(err) => Err(err),
@@ -138,14 +184,40 @@ pub unsafe fn execute_artifact(
 pub unsafe fn create_runtime_from_artifact_bytes(
 	compiled_artifact_blob: &[u8],
 	executor_params: &ExecutorParams,
-) -> Result<WasmtimeRuntime, WasmError> {
+) -> Result<Box<dyn WasmModule>, WasmError> {
+	create_runtime(compiled_artifact_blob, executor_params, None)
+}
+
+/// Constructs the runtime for the given PVF, giving `gas_limit` gas to PolkaVM PVFs.
+///
+/// # Safety
+///
+/// See [`create_runtime_from_artifact_bytes`].
+unsafe fn create_runtime(
+	compiled_artifact_blob: &[u8],
+	executor_params: &ExecutorParams,
+	gas_limit: Option<u64>,
+) -> Result<Box<dyn WasmModule>, WasmError> {
+	if let Some(program) = compiled_artifact_blob.strip_prefix(POLKAVM_ARTIFACT_PREFIX) {
+		let blob = RuntimeBlob::new_with_polkavm(program)?;
+		let program = blob.as_polkavm_blob().ok_or_else(|| {
+			WasmError::Other("the artifact doesn't contain a PolkaVM program".into())
+		})?;
+
+		return sc_executor_polkavm::create_runtime::<HostFunctions>(
+			program,
+			params_to_polkavm_config(executor_params, gas_limit),
+		)
+	}
+
 	let mut config = DEFAULT_CONFIG.clone();
 	config.semantics = params_to_wasmtime_semantics(executor_params).0;
 
-	sc_executor_wasmtime::create_runtime_from_artifact_bytes::<HostFunctions>(
+	let runtime = sc_executor_wasmtime::create_runtime_from_artifact_bytes::<HostFunctions>(
 		compiled_artifact_blob,
 		config,
-	)
+	)?;
+	Ok(Box::new(runtime))
 }
 
 /// Takes the default config and overwrites any settings with existing executor parameters.
@@ -167,6 +239,8 @@ pub fn params_to_wasmtime_semantics(par: &ExecutorParams) -> (Semantics, Determi
 			ExecutorParam::StackLogicalMax(slm) => stack_limit.logical_max = *slm,
 			ExecutorParam::StackNativeMax(snm) => stack_limit.native_stack_max = *snm,
 			ExecutorParam::WasmExtBulkMemory => sem.wasm_bulk_memory = true,
+			ExecutorParam::PvfExecFuel(fuel) => sem.fuel = Some(*fuel),
+			ExecutorParam::PolkaVm |
 			ExecutorParam::PrecheckingMaxMemory(_) |
 			ExecutorParam::PvfPrepTimeout(_, _) |
 			ExecutorParam::PvfExecTimeout(_, _) => (), /* Not used here */
@@ -176,10 +250,34 @@ pub fn params_to_wasmtime_semantics(par: &ExecutorParams) -> (Semantics, Determi
 	(sem, stack_limit)
 }
 
+/// Builds the configuration of the PolkaVM executor out of the executor parameters.
+fn params_to_polkavm_config(
+	par: &ExecutorParams,
+	gas_limit: Option<u64>,
+) -> sc_executor_polkavm::Config {
+	sc_executor_polkavm::Config {
+		backend: Default::default(),
+		heap_alloc_strategy: params_to_wasmtime_semantics(par).0.heap_alloc_strategy,
+		allow_missing_func_imports: DEFAULT_CONFIG.allow_missing_func_imports,
+		// Every PVF is executed in a fresh job process, so there's nothing to reuse instances for.
+		max_idle_instances: 0,
+		gas_limit,
+	}
+}
+
 /// Runs the prevalidation on the given code. Returns a [`RuntimeBlob`] if it succeeds.
-pub fn prevalidate(code: &[u8]) -> Result<RuntimeBlob, sc_executor_common::error::WasmError> {
+///
+/// PolkaVM programs are only accepted if [`ExecutorParam::PolkaVm`] is set.
+pub fn prevalidate(
+	code: &[u8],
+	executor_params: &ExecutorParams,
+) -> Result<RuntimeBlob, sc_executor_common::error::WasmError> {
 	// Construct the runtime blob and do some basic checks for consistency.
-	let blob = RuntimeBlob::new(code)?;
+	let blob = if executor_params.polkavm_enabled() {
+		RuntimeBlob::new_with_polkavm(code)?
+	} else {
+		RuntimeBlob::new(code)?
+	};
 	// In the future this function should take care of any further prevalidation logic.
 	Ok(blob)
 }
@@ -190,6 +288,16 @@ pub fn prepare(
 	blob: RuntimeBlob,
 	executor_params: &ExecutorParams,
 ) -> Result<Vec<u8>, sc_executor_common::error::WasmError> {
+	if let Some(program) = blob.as_polkavm_blob() {
+		// Compile the program once to make sure it can be executed.
+		sc_executor_polkavm::create_runtime::<HostFunctions>(
+			program,
+			params_to_polkavm_config(executor_params, None),
+		)?;
+
+		return Ok([POLKAVM_ARTIFACT_PREFIX, program.as_bytes()].concat())
+	}
+
 	let (semantics, _) = params_to_wasmtime_semantics(executor_params);
 	sc_executor_wasmtime::prepare_runtime_artifact(blob, &semantics)
 }
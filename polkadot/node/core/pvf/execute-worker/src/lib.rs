@@ -225,6 +225,7 @@ pub fn worker_entrypoint(
 									job_response: JobResponse::PoVDecompressionFailure,
 									duration: Duration::ZERO,
 									pov_size: 0,
+									max_rss: None,
 								}),
 								worker_info,
 							)?;
@@ -307,13 +308,14 @@ pub fn worker_entrypoint(
 fn validate_using_artifact(
 	compiled_artifact_blob: &[u8],
 	executor_params: &ExecutorParams,
+	execution_timeout: Duration,
 	params: &[u8],
 ) -> JobResponse {
-	let descriptor_bytes = match unsafe {
+	let (descriptor_bytes, fuel_consumed) = match unsafe {
 		// SAFETY: this should be safe since the compiled artifact passed here comes from the
 		//         file created by the prepare workers. These files are obtained by calling
 		//         [`executor_interface::prepare`].
-		execute_artifact(compiled_artifact_blob, executor_params, params)
+		execute_artifact(compiled_artifact_blob, executor_params, execution_timeout, params)
 	} {
 		Err(ExecuteError::RuntimeConstruction(wasmerr)) =>
 			return JobResponse::runtime_construction("execute", &wasmerr.to_string()),
@@ -330,7 +332,7 @@ fn validate_using_artifact(
 		Ok(r) => r,
 	};
 
-	JobResponse::Ok { result_descriptor }
+	JobResponse::Ok { result_descriptor, fuel_consumed }
 }
 
 #[cfg(target_os = "linux")]
@@ -479,7 +481,14 @@ fn handle_child_process(
 
 	let execute_thread = thread::spawn_worker_thread_with_stack_size(
 		"execute thread",
-		move || validate_using_artifact(&compiled_artifact_blob, &executor_params, &params),
+		move || {
+			validate_using_artifact(
+				&compiled_artifact_blob,
+				&executor_params,
+				execution_timeout,
+				&params,
+			)
+		},
 		Arc::clone(&condvar),
 		WaitOutcome::Finished,
 		execute_thread_stack_size,
@@ -583,7 +592,9 @@ fn handle_parent_process(
 		// Should retry at any rate.
 		.map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()))?;
 
-	let status = nix::sys::wait::waitpid(job_pid, None);
+	let wait_result = wait_for_job(job_pid);
+	let max_rss = wait_result.as_ref().ok().and_then(|&(_, max_rss)| max_rss);
+	let status = wait_result.map(|(status, _)| status);
 	gum::trace!(
 		target: LOG_TARGET,
 		?worker_info,
@@ -629,7 +640,7 @@ fn handle_parent_process(
 						))));
 					}
 
-					Ok(Ok(WorkerResponse { job_response, pov_size, duration: cpu_tv }))
+					Ok(Ok(WorkerResponse { job_response, pov_size, duration: cpu_tv, max_rss }))
 				},
 				Err(job_error) => {
 					gum::warn!(
@@ -666,6 +677,28 @@ fn handle_parent_process(
 	}
 }
 
+/// Waits for the job process to terminate.
+///
+/// Unlike `waitpid`, this also returns the `ru_maxrss` of the job process alone (in kilobytes), as
+/// opposed to `getrusage` which only reports the maximum over all terminated children. The stat is
+/// only returned on Linux, where it is known to be in kilobytes.
+///
+/// As the job process is forked from the worker, its `ru_maxrss` includes the resident memory
+/// inherited from the worker, so it overestimates the memory used by the execution itself.
+fn wait_for_job(job_pid: Pid) -> nix::Result<(WaitStatus, Option<i64>)> {
+	let mut status = 0;
+	let mut usage = std::mem::MaybeUninit::<libc::rusage>::zeroed();
+	// SAFETY: `status` and `usage` are valid for writes for the duration of the call.
+	let pid = Errno::result(unsafe {
+		libc::wait4(job_pid.as_raw(), &mut status, 0, usage.as_mut_ptr())
+	})?;
+	// SAFETY: `usage` was zero-initialized and `wait4` succeeded, so it is initialized.
+	let usage = unsafe { usage.assume_init() };
+	let max_rss = cfg!(target_os = "linux").then(|| i64::from(usage.ru_maxrss));
+
+	Ok((WaitStatus::from_raw(Pid::from_raw(pid), status)?, max_rss))
+}
+
 /// Write a job response to the pipe and exit process after.
 ///
 /// # Arguments
@@ -691,3 +724,47 @@ fn internal_error_from_errno(context: &'static str, errno: Errno) -> WorkerError
 fn job_error_from_errno(context: &'static str, errno: Errno) -> JobResult {
 	Err(JobError::Kernel(stringify_errno(context, errno)))
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn wait_for_job_reports_status_and_max_rss() {
+		const JOB_MEMORY: usize = 64 * 1024 * 1024;
+
+		// SAFETY: the child only does raw syscalls and memory writes before exiting.
+		match unsafe { nix::unistd::fork() }.unwrap() {
+			ForkResult::Child => unsafe {
+				let ptr = libc::mmap(
+					std::ptr::null_mut(),
+					JOB_MEMORY,
+					libc::PROT_READ | libc::PROT_WRITE,
+					libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+					-1,
+					0,
+				);
+				if ptr == libc::MAP_FAILED {
+					libc::_exit(1);
+				}
+				std::ptr::write_bytes(ptr as *mut u8, 1, JOB_MEMORY);
+				libc::_exit(7);
+			},
+			ForkResult::Parent { child } => {
+				let (status, max_rss) = wait_for_job(child).unwrap();
+				assert_eq!(status, WaitStatus::Exited(child, 7));
+
+				if cfg!(target_os = "linux") {
+					assert!(max_rss.unwrap() >= (JOB_MEMORY / 1024) as i64);
+				} else {
+					assert_eq!(max_rss, None);
+				}
+			},
+		}
+	}
+
+	#[test]
+	fn wait_for_job_fails_for_unknown_process() {
+		assert_eq!(wait_for_job(Pid::from_raw(i32::MAX)), Err(Errno::ECHILD));
+	}
+}
@@ -28,12 +28,13 @@ fn do_prepare_runtime(pvf: PvfPrepData) {
 	let raw_validation_code =
 		sp_maybe_compressed_blob::decompress(&maybe_compressed_code, usize::MAX).unwrap();
 
-	let blob = match prevalidate(&raw_validation_code) {
+	let executor_params = pvf.executor_params();
+	let blob = match prevalidate(&raw_validation_code, &executor_params) {
 		Err(err) => panic!("{:?}", err),
 		Ok(b) => b,
 	};
 
-	match prepare(blob, &pvf.executor_params()) {
+	match prepare(blob, &executor_params) {
 		Ok(_) => (),
 		Err(err) => panic!("{:?}", err),
 	}
@@ -308,12 +308,13 @@ fn prepare_artifact(pvf: PvfPrepData) -> Result<PrepareOutcome, PrepareError> {
 			.map_err(|e| PrepareError::CouldNotDecompressCodeBlob(e.to_string()))?;
 	let observed_wasm_code_len = raw_validation_code.len() as u32;
 
-	let blob = match prevalidate(&raw_validation_code) {
+	let executor_params = pvf.executor_params();
+	let blob = match prevalidate(&raw_validation_code, &executor_params) {
 		Err(err) => return Err(PrepareError::Prevalidation(format!("{:?}", err))),
 		Ok(b) => b,
 	};
 
-	match prepare(blob, &pvf.executor_params()) {
+	match prepare(blob, &executor_params) {
 		Ok(compiled_artifact) => Ok(PrepareOutcome {
 			compiled_artifact: CompiledArtifact::new(compiled_artifact),
 			observed_wasm_code_len,
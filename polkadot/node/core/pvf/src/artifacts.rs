@@ -37,6 +37,10 @@
 //!    into a temporary file and notifies the host that it's done. The host atomically moves
 //!    (renames) the temporary file to the destination filename of the artifact.
 //!
+//!    PVFs compiled to PolkaVM go through the same process, except that their artifact is the
+//!    validated program blob, which is compiled by the execution worker when it constructs the
+//!    runtime.
+//!
 //! 4. If the worker concluded successfully or returned an error, then the pool notifies the queue.
 //!    In both cases, the queue reports to the host that the result is ready.
 //!
@@ -172,10 +176,12 @@ pub struct Artifacts {
 /// Parameters we use to cleanup artifacts
 /// After we hit the cache limit we remove the least used artifacts
 /// but only if they are stale more than minimum stale time
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ArtifactsCleanupConfig {
 	// Max size in bytes. Reaching it the least used artefacts are deleted
 	cache_limit: u64,
+	// Max number of artifacts. Reaching it the least used artefacts are deleted
+	max_count: Option<usize>,
 	// Inactive time after which artefact is allowed to be deleted
 	min_stale_time: Duration,
 }
@@ -185,14 +191,34 @@ impl Default for ArtifactsCleanupConfig {
 		Self {
 			cache_limit: 10 * 1024 * 1024 * 1024,              // 10 GiB
 			min_stale_time: Duration::from_secs(24 * 60 * 60), // 24 hours
+			max_count: None,
 		}
 	}
 }
 
-#[cfg(test)]
 impl ArtifactsCleanupConfig {
+	/// Create a config that prunes the least recently used artifacts once their total size
+	/// exceeds `cache_limit` bytes, sparing the ones used within `min_stale_time`.
 	pub fn new(cache_limit: u64, min_stale_time: Duration) -> Self {
-		Self { cache_limit, min_stale_time }
+		Self { cache_limit, max_count: None, min_stale_time }
+	}
+
+	/// Also prune the least recently used artifacts once there are more than `max_count` of them.
+	pub fn with_max_count(mut self, max_count: usize) -> Self {
+		self.max_count = Some(max_count);
+		self
+	}
+
+	/// Override the total size of the artifacts, in bytes, above which they are pruned.
+	pub fn with_cache_limit(mut self, cache_limit: u64) -> Self {
+		self.cache_limit = cache_limit;
+		self
+	}
+
+	/// Override the inactive time within which artifacts are never pruned, whatever the limits.
+	pub fn with_min_stale_time(mut self, min_stale_time: Duration) -> Self {
+		self.min_stale_time = min_stale_time;
+		self
 	}
 }
 
@@ -283,8 +309,8 @@ impl Artifacts {
 		})
 	}
 
-	/// Remove artifacts older than the given TTL when the total artifact size reaches the limit
-	/// and return id and path of the removed ones
+	/// Remove the least recently used artifacts older than the given TTL while the total artifact
+	/// size or count exceeds the limit and return id and path of the removed ones
 	pub fn prune(&mut self, cleanup_config: &ArtifactsCleanupConfig) -> Vec<(ArtifactId, PathBuf)> {
 		let mut to_remove = vec![];
 		let now = SystemTime::now();
@@ -301,7 +327,11 @@ impl Artifacts {
 		artifact_sizes
 			.sort_by_key(|&(_, _, _, last_time_needed)| std::cmp::Reverse(last_time_needed));
 
-		while total_size > cleanup_config.cache_limit {
+		let exceeds_limits = |total_size, count| {
+			total_size > cleanup_config.cache_limit ||
+				cleanup_config.max_count.map_or(false, |max_count| count > max_count)
+		};
+		while exceeds_limits(total_size, artifact_sizes.len()) {
 			let Some((artifact_id, path, size, last_time_needed)) = artifact_sizes.pop() else {
 				break
 			};
@@ -446,4 +476,50 @@ mod tests {
 		assert!(!artifacts.artifact_ids().contains(&artifact_id3));
 		assert!(pruned.contains(&(artifact_id3, path3)));
 	}
+
+	#[tokio::test]
+	async fn test_pruned_by_count() {
+		let mock_now = SystemTime::now();
+		let tempdir = tempfile::tempdir().unwrap();
+		let cache_path = tempdir.path();
+
+		let path1 = generate_artifact_path(cache_path);
+		let path2 = generate_artifact_path(cache_path);
+		let path3 = generate_artifact_path(cache_path);
+		let artifact_id1 = artifact_id(1);
+		let artifact_id2 = artifact_id(2);
+		let artifact_id3 = artifact_id(3);
+
+		let mut artifacts = Artifacts::new(cache_path).await;
+		let cleanup_config =
+			ArtifactsCleanupConfig::new(u64::MAX, Duration::from_secs(0)).with_max_count(2);
+
+		artifacts.insert_prepared(
+			artifact_id1.clone(),
+			path1.clone(),
+			mock_now - Duration::from_secs(5),
+			1024,
+		);
+		artifacts.insert_prepared(
+			artifact_id2.clone(),
+			path2.clone(),
+			mock_now - Duration::from_secs(10),
+			1024,
+		);
+		artifacts.insert_prepared(
+			artifact_id3.clone(),
+			path3.clone(),
+			mock_now - Duration::from_secs(15),
+			1024,
+		);
+
+		let pruned = artifacts.prune(&cleanup_config);
+
+		assert!(artifacts.artifact_ids().contains(&artifact_id1));
+		assert!(!pruned.contains(&(artifact_id1, path1)));
+		assert!(artifacts.artifact_ids().contains(&artifact_id2));
+		assert!(!pruned.contains(&(artifact_id2, path2)));
+		assert!(!artifacts.artifact_ids().contains(&artifact_id3));
+		assert!(pruned.contains(&(artifact_id3, path3)));
+	}
 }
@@ -36,7 +36,9 @@ use polkadot_node_core_pvf_common::{
 };
 use polkadot_node_primitives::PoV;
 use polkadot_node_subsystem::{messages::PvfExecKind, ActiveLeavesUpdate};
-use polkadot_primitives::{ExecutorParams, ExecutorParamsHash, Hash, PersistedValidationData};
+use polkadot_primitives::{
+	ExecutorParams, ExecutorParamsHash, Hash, Id as ParaId, PersistedValidationData,
+};
 use slotmap::HopSlotMap;
 use std::{
 	collections::{HashMap, VecDeque},
@@ -78,6 +80,7 @@ pub struct PendingExecutionRequest {
 	pub executor_params: ExecutorParams,
 	pub result_tx: ResultSender,
 	pub exec_kind: PvfExecKind,
+	pub para_id: ParaId,
 }
 
 struct ExecuteJob {
@@ -89,6 +92,7 @@ struct ExecuteJob {
 	executor_params: ExecutorParams,
 	result_tx: ResultSender,
 	waiting_since: Instant,
+	para_id: ParaId,
 }
 
 struct WorkerData {
@@ -149,6 +153,7 @@ enum QueueEvent {
 		Worker,
 		Result<WorkerInterfaceResponse, WorkerInterfaceError>,
 		ArtifactId,
+		ParaId,
 		ResultSender,
 	),
 }
@@ -380,6 +385,7 @@ fn handle_to_queue(queue: &mut Queue, to_queue: ToQueue) {
 				executor_params,
 				result_tx,
 				exec_kind,
+				para_id,
 			} = pending_execution_request;
 			gum::debug!(
 				target: LOG_TARGET,
@@ -397,6 +403,7 @@ fn handle_to_queue(queue: &mut Queue, to_queue: ToQueue) {
 				executor_params,
 				result_tx,
 				waiting_since: Instant::now(),
+				para_id,
 			};
 			queue.unscheduled.add(job, exec_kind.into());
 			queue.try_assign_next_job(None);
@@ -409,8 +416,8 @@ async fn handle_mux(queue: &mut Queue, event: QueueEvent) {
 		QueueEvent::Spawn(idle, handle, job) => {
 			handle_worker_spawned(queue, idle, handle, job);
 		},
-		QueueEvent::FinishWork(worker, outcome, artifact_id, result_tx) => {
-			handle_job_finish(queue, worker, outcome, artifact_id, result_tx).await;
+		QueueEvent::FinishWork(worker, outcome, artifact_id, para_id, result_tx) => {
+			handle_job_finish(queue, worker, outcome, artifact_id, para_id, result_tx).await;
 		},
 	}
 }
@@ -441,20 +448,35 @@ async fn handle_job_finish(
 	worker: Worker,
 	worker_result: Result<WorkerInterfaceResponse, WorkerInterfaceError>,
 	artifact_id: ArtifactId,
+	para_id: ParaId,
 	result_tx: ResultSender,
 ) {
+	if let Ok(WorkerInterfaceResponse { worker_response, .. }) = &worker_result {
+		if !matches!(worker_response.job_response, JobResponse::PoVDecompressionFailure) {
+			queue.metrics.observe_para_execution(
+				para_id,
+				worker_response.duration,
+				worker_response.max_rss,
+				worker_response.pov_size,
+			);
+		}
+	}
+
+	let mut fuel_consumed = None;
 	let (idle_worker, result, duration, sync_channel, pov_size) = match worker_result {
 		Ok(WorkerInterfaceResponse {
 			worker_response:
 				WorkerResponse {
-					job_response: JobResponse::Ok { result_descriptor },
+					job_response: JobResponse::Ok { result_descriptor, fuel_consumed: fuel },
 					duration,
 					pov_size,
+					..
 				},
 			idle_worker,
 		}) => {
 			// TODO: propagate the soft timeout
 
+			fuel_consumed = fuel;
 			(Some(idle_worker), Ok(result_descriptor), Some(duration), None, Some(pov_size))
 		},
 		Ok(WorkerInterfaceResponse {
@@ -540,6 +562,9 @@ async fn handle_job_finish(
 	if let Some(pov_size) = pov_size {
 		queue.metrics.observe_pov_size(pov_size as usize, false)
 	}
+	if let (Some(fuel_consumed), Some(duration)) = (fuel_consumed, duration) {
+		queue.metrics.observe_execution_fuel(fuel_consumed, duration);
+	}
 	if let Err(ref err) = result {
 		gum::warn!(
 			target: LOG_TARGET,
@@ -556,6 +581,7 @@ async fn handle_job_finish(
 			?worker,
 			worker_rip = idle_worker.is_none(),
 			?duration,
+			?fuel_consumed,
 			"execute worker concluded successfully",
 		);
 	}
@@ -691,7 +717,7 @@ fn assign(queue: &mut Queue, worker: Worker, job: ExecuteJob) {
 				job.pov,
 			)
 			.await;
-			QueueEvent::FinishWork(worker, result, job.artifact.id, job.result_tx)
+			QueueEvent::FinishWork(worker, result, job.artifact.id, job.para_id, job.result_tx)
 		}
 		.boxed(),
 	);
@@ -914,6 +940,7 @@ mod tests {
 			executor_params: ExecutorParams::default(),
 			result_tx,
 			waiting_since: Instant::now(),
+			para_id: ParaId::from(1_u32),
 		}
 	}
 
@@ -1078,6 +1105,7 @@ mod tests {
 			executor_params: ExecutorParams::default(),
 			result_tx,
 			waiting_since: Instant::now(),
+			para_id: ParaId::from(1_u32),
 		};
 		queue.unscheduled.add(relevant_job, Priority::Backing);
 		for _ in 0..10 {
@@ -1091,6 +1119,7 @@ mod tests {
 				executor_params: ExecutorParams::default(),
 				result_tx,
 				waiting_since: Instant::now(),
+				para_id: ParaId::from(1_u32),
 			};
 			queue.unscheduled.add(expired_job, Priority::Backing);
 			result_rxs.push(result_rx);
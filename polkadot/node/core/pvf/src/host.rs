@@ -40,8 +40,10 @@ use polkadot_node_primitives::PoV;
 use polkadot_node_subsystem::{
 	messages::PvfExecKind, ActiveLeavesUpdate, SubsystemError, SubsystemResult,
 };
-use polkadot_parachain_primitives::primitives::ValidationResult;
-use polkadot_primitives::{Hash, PersistedValidationData};
+use polkadot_parachain_primitives::primitives::{ValidationCodeHash, ValidationResult};
+use polkadot_primitives::{
+	ExecutorParams, ExecutorParamsPrepHash, Hash, Id as ParaId, PersistedValidationData,
+};
 use std::{
 	collections::HashMap,
 	path::PathBuf,
@@ -117,6 +119,7 @@ impl ValidationHost {
 		pov: Arc<PoV>,
 		priority: Priority,
 		exec_kind: PvfExecKind,
+		para_id: ParaId,
 		result_tx: ResultSender,
 	) -> Result<(), String> {
 		self.to_host_tx
@@ -127,6 +130,7 @@ impl ValidationHost {
 				pov,
 				priority,
 				exec_kind,
+				para_id,
 				result_tx,
 			}))
 			.await
@@ -146,6 +150,30 @@ impl ValidationHost {
 			.map_err(|_| "the inner loop hung up".to_string())
 	}
 
+	/// Returns the code hashes among `code_hashes` for which no artifact has been prepared, or is
+	/// being prepared, with the given `executor_params`.
+	///
+	/// This includes the artifacts found in the cache at startup, so callers can avoid fetching
+	/// the code of PVFs the host already has.
+	///
+	/// Returns an error if the request cannot be sent to the validation host, i.e. if it shut down.
+	pub async fn unprepared_code_hashes(
+		&mut self,
+		code_hashes: Vec<ValidationCodeHash>,
+		executor_params: &ExecutorParams,
+	) -> Result<Vec<ValidationCodeHash>, String> {
+		let (result_tx, result_rx) = oneshot::channel();
+		self.to_host_tx
+			.send(ToHost::UnpreparedCodeHashes {
+				code_hashes,
+				executor_params_prep_hash: executor_params.prep_hash(),
+				result_tx,
+			})
+			.await
+			.map_err(|_| "the inner loop hung up".to_string())?;
+		result_rx.await.map_err(|_| "the inner loop hung up".to_string())
+	}
+
 	/// Sends a signal to the validation host requesting to update best block.
 	///
 	/// Returns an error if the request cannot be sent to the validation host, i.e. if it shut down.
@@ -162,10 +190,23 @@ impl ValidationHost {
 }
 
 enum ToHost {
-	PrecheckPvf { pvf: PvfPrepData, result_tx: PrecheckResultSender },
+	PrecheckPvf {
+		pvf: PvfPrepData,
+		result_tx: PrecheckResultSender,
+	},
 	ExecutePvf(ExecutePvfInputs),
-	HeadsUp { active_pvfs: Vec<PvfPrepData> },
-	UpdateActiveLeaves { update: ActiveLeavesUpdate, ancestors: Vec<Hash> },
+	HeadsUp {
+		active_pvfs: Vec<PvfPrepData>,
+	},
+	UnpreparedCodeHashes {
+		code_hashes: Vec<ValidationCodeHash>,
+		executor_params_prep_hash: ExecutorParamsPrepHash,
+		result_tx: oneshot::Sender<Vec<ValidationCodeHash>>,
+	},
+	UpdateActiveLeaves {
+		update: ActiveLeavesUpdate,
+		ancestors: Vec<Hash>,
+	},
 }
 
 struct ExecutePvfInputs {
@@ -175,6 +216,7 @@ struct ExecutePvfInputs {
 	pov: Arc<PoV>,
 	priority: Priority,
 	exec_kind: PvfExecKind,
+	para_id: ParaId,
 	result_tx: ResultSender,
 }
 
@@ -204,6 +246,9 @@ pub struct Config {
 	pub execute_worker_spawn_timeout: Duration,
 	/// The maximum number of execute workers that can run at the same time.
 	pub execute_workers_max_num: usize,
+
+	/// The limits above which the least recently used artifacts are pruned from the cache.
+	pub artifacts_cleanup: ArtifactsCleanupConfig,
 }
 
 impl Config {
@@ -231,6 +276,8 @@ impl Config {
 			execute_worker_program_path,
 			execute_worker_spawn_timeout: Duration::from_secs(3),
 			execute_workers_max_num,
+
+			artifacts_cleanup: ArtifactsCleanupConfig::default(),
 		}
 	}
 }
@@ -319,7 +366,7 @@ pub async fn start(
 	let run_host = async move {
 		run(Inner {
 			cleanup_pulse_interval: Duration::from_secs(3600),
-			cleanup_config: ArtifactsCleanupConfig::default(),
+			cleanup_config: config.artifacts_cleanup.clone(),
 			artifacts,
 			to_host_rx,
 			to_prepare_queue_tx,
@@ -505,6 +552,13 @@ async fn handle_to_host(
 		},
 		ToHost::HeadsUp { active_pvfs } =>
 			handle_heads_up(artifacts, prepare_queue, active_pvfs).await?,
+		ToHost::UnpreparedCodeHashes { code_hashes, executor_params_prep_hash, result_tx } =>
+			handle_unprepared_code_hashes(
+				artifacts,
+				code_hashes,
+				executor_params_prep_hash,
+				result_tx,
+			),
 		ToHost::UpdateActiveLeaves { update, ancestors } =>
 			handle_update_active_leaves(execute_queue, update, ancestors).await?,
 	}
@@ -519,6 +573,10 @@ async fn handle_to_host(
 ///
 /// We don't retry artifacts that previously failed preparation. We don't expect multiple
 /// pre-checking requests.
+///
+/// A successfully pre-checked artifact is kept like any other prepared one, so it is reused for
+/// execution and skipped when preparing ahead of the next session, as long as the executor
+/// parameters don't change.
 async fn handle_precheck_pvf(
 	artifacts: &mut Artifacts,
 	prepare_queue: &mut mpsc::Sender<prepare::ToQueue>,
@@ -567,7 +625,8 @@ async fn handle_execute_pvf(
 	awaiting_prepare: &mut AwaitingPrepare,
 	inputs: ExecutePvfInputs,
 ) -> Result<(), Fatal> {
-	let ExecutePvfInputs { pvf, exec_timeout, pvd, pov, priority, exec_kind, result_tx } = inputs;
+	let ExecutePvfInputs { pvf, exec_timeout, pvd, pov, priority, exec_kind, para_id, result_tx } =
+		inputs;
 	let artifact_id = ArtifactId::from_pvf_prep_data(&pvf);
 	let executor_params = (*pvf.executor_params()).clone();
 
@@ -591,6 +650,7 @@ async fn handle_execute_pvf(
 								executor_params,
 								exec_kind,
 								result_tx,
+								para_id,
 							},
 						},
 					)
@@ -622,6 +682,7 @@ async fn handle_execute_pvf(
 							executor_params,
 							exec_kind,
 							result_tx,
+							para_id,
 						},
 					)
 					.await?;
@@ -637,6 +698,7 @@ async fn handle_execute_pvf(
 						executor_params,
 						result_tx,
 						exec_kind,
+						para_id,
 					},
 				);
 			},
@@ -671,6 +733,7 @@ async fn handle_execute_pvf(
 							executor_params,
 							exec_kind,
 							result_tx,
+							para_id,
 						},
 					)
 					.await?;
@@ -696,6 +759,7 @@ async fn handle_execute_pvf(
 				executor_params,
 				result_tx,
 				exec_kind,
+				para_id,
 			},
 		)
 		.await?;
@@ -704,6 +768,27 @@ async fn handle_execute_pvf(
 	Ok(())
 }
 
+fn handle_unprepared_code_hashes(
+	artifacts: &mut Artifacts,
+	code_hashes: Vec<ValidationCodeHash>,
+	executor_params_prep_hash: ExecutorParamsPrepHash,
+	result_tx: oneshot::Sender<Vec<ValidationCodeHash>>,
+) {
+	let unprepared = code_hashes
+		.into_iter()
+		.filter(|code_hash| {
+			let artifact_id = ArtifactId::new(*code_hash, executor_params_prep_hash);
+			!matches!(
+				artifacts.artifact_state_mut(&artifact_id),
+				Some(ArtifactState::Prepared { .. } | ArtifactState::Preparing { .. })
+			)
+		})
+		.collect();
+
+	// The caller may have given up waiting, that's fine.
+	let _ = result_tx.send(unprepared);
+}
+
 async fn handle_heads_up(
 	artifacts: &mut Artifacts,
 	prepare_queue: &mut mpsc::Sender<prepare::ToQueue>,
@@ -818,8 +903,15 @@ async fn handle_prepare_done(
 	// It's finally time to dispatch all the execution requests that were waiting for this artifact
 	// to be prepared.
 	let pending_requests = awaiting_prepare.take(&artifact_id);
-	for PendingExecutionRequest { exec_timeout, pvd, pov, executor_params, result_tx, exec_kind } in
-		pending_requests
+	for PendingExecutionRequest {
+		exec_timeout,
+		pvd,
+		pov,
+		executor_params,
+		result_tx,
+		exec_kind,
+		para_id,
+	} in pending_requests
 	{
 		if result_tx.is_canceled() {
 			// Preparation could've taken quite a bit of time and the requester may be not
@@ -846,6 +938,7 @@ async fn handle_prepare_done(
 					executor_params,
 					exec_kind,
 					result_tx,
+					para_id,
 				},
 			},
 		)
@@ -1262,6 +1355,80 @@ pub(crate) mod tests {
 		test.poll_ensure_to_sweeper_is_empty().await;
 	}
 
+	#[tokio::test]
+	async fn unprepared_code_hashes_skips_prepared_artifacts() {
+		let tempdir = tempfile::tempdir().unwrap();
+		let mut builder = Builder::default();
+		builder.artifacts.insert_prepared(
+			artifact_id(1),
+			generate_artifact_path(tempdir.path()),
+			SystemTime::now(),
+			1024,
+		);
+		let mut test = builder.build();
+		let mut host = test.host_handle();
+
+		host.heads_up(vec![PvfPrepData::from_discriminator(2)]).await.unwrap();
+		let code_hashes = (1..=3)
+			.map(|discriminator| PvfPrepData::from_discriminator(discriminator).code_hash())
+			.collect::<Vec<_>>();
+		let unprepared = run_until(
+			&mut test.run,
+			host.unprepared_code_hashes(code_hashes.clone(), &ExecutorParams::default())
+				.boxed(),
+		)
+		.await
+		.unwrap();
+		assert_eq!(unprepared, vec![code_hashes[2]]);
+
+		// Artifacts prepared with other executor parameters don't count.
+		let executor_params =
+			ExecutorParams::from(&[polkadot_primitives::ExecutorParam::StackLogicalMax(1)][..]);
+		let unprepared = run_until(
+			&mut test.run,
+			host.unprepared_code_hashes(code_hashes.clone(), &executor_params).boxed(),
+		)
+		.await
+		.unwrap();
+		assert_eq!(unprepared, code_hashes);
+	}
+
+	#[tokio::test]
+	async fn unprepared_code_hashes_skips_prechecked_artifacts() {
+		let mut test = Builder::default().build();
+		let mut host = test.host_handle();
+
+		let (result_tx, result_rx) = oneshot::channel();
+		host.precheck_pvf(PvfPrepData::from_discriminator_precheck(1), result_tx)
+			.await
+			.unwrap();
+		assert_matches!(
+			test.poll_and_recv_to_prepare_queue().await,
+			prepare::ToQueue::Enqueue { .. }
+		);
+		test.from_prepare_queue_tx
+			.send(prepare::FromQueue {
+				artifact_id: artifact_id(1),
+				result: Ok(PrepareSuccess::default()),
+			})
+			.await
+			.unwrap();
+		test.poll_ensure_to_execute_queue_is_empty().await;
+		assert_matches!(result_rx.now_or_never().unwrap().unwrap(), Ok(_));
+
+		let code_hashes = (1..=2)
+			.map(|discriminator| PvfPrepData::from_discriminator(discriminator).code_hash())
+			.collect::<Vec<_>>();
+		let unprepared = run_until(
+			&mut test.run,
+			host.unprepared_code_hashes(code_hashes.clone(), &ExecutorParams::default())
+				.boxed(),
+		)
+		.await
+		.unwrap();
+		assert_eq!(unprepared, vec![code_hashes[1]]);
+	}
+
 	#[tokio::test]
 	async fn execute_pvf_requests() {
 		let mut test = Builder::default().build();
@@ -1283,6 +1450,7 @@ pub(crate) mod tests {
 			pov1.clone(),
 			Priority::Normal,
 			PvfExecKind::Backing(H256::default()),
+			ParaId::from(1_u32),
 			result_tx,
 		)
 		.await
@@ -1296,6 +1464,7 @@ pub(crate) mod tests {
 			pov1,
 			Priority::Critical,
 			PvfExecKind::Backing(H256::default()),
+			ParaId::from(1_u32),
 			result_tx,
 		)
 		.await
@@ -1309,6 +1478,7 @@ pub(crate) mod tests {
 			pov2,
 			Priority::Normal,
 			PvfExecKind::Backing(H256::default()),
+			ParaId::from(1_u32),
 			result_tx,
 		)
 		.await
@@ -1459,6 +1629,7 @@ pub(crate) mod tests {
 			pov.clone(),
 			Priority::Critical,
 			PvfExecKind::Backing(H256::default()),
+			ParaId::from(1_u32),
 			result_tx,
 		)
 		.await
@@ -1508,6 +1679,7 @@ pub(crate) mod tests {
 			pov,
 			Priority::Critical,
 			PvfExecKind::Backing(H256::default()),
+			ParaId::from(1_u32),
 			result_tx,
 		)
 		.await
@@ -1619,6 +1791,7 @@ pub(crate) mod tests {
 			pov.clone(),
 			Priority::Critical,
 			PvfExecKind::Backing(H256::default()),
+			ParaId::from(1_u32),
 			result_tx,
 		)
 		.await
@@ -1651,6 +1824,7 @@ pub(crate) mod tests {
 			pov.clone(),
 			Priority::Critical,
 			PvfExecKind::Backing(H256::default()),
+			ParaId::from(1_u32),
 			result_tx_2,
 		)
 		.await
@@ -1675,6 +1849,7 @@ pub(crate) mod tests {
 			pov.clone(),
 			Priority::Critical,
 			PvfExecKind::Backing(H256::default()),
+			ParaId::from(1_u32),
 			result_tx_3,
 		)
 		.await
@@ -1734,6 +1909,7 @@ pub(crate) mod tests {
 			pov.clone(),
 			Priority::Critical,
 			PvfExecKind::Backing(H256::default()),
+			ParaId::from(1_u32),
 			result_tx,
 		)
 		.await
@@ -1766,6 +1942,7 @@ pub(crate) mod tests {
 			pov.clone(),
 			Priority::Critical,
 			PvfExecKind::Backing(H256::default()),
+			ParaId::from(1_u32),
 			result_tx_2,
 		)
 		.await
@@ -1790,6 +1967,7 @@ pub(crate) mod tests {
 			pov.clone(),
 			Priority::Critical,
 			PvfExecKind::Backing(H256::default()),
+			ParaId::from(1_u32),
 			result_tx_3,
 		)
 		.await
@@ -1865,6 +2043,7 @@ pub(crate) mod tests {
 			pov,
 			Priority::Normal,
 			PvfExecKind::Backing(H256::default()),
+			ParaId::from(1_u32),
 			result_tx,
 		)
 		.await
@@ -81,8 +81,9 @@
 //! doesn't contain the artifact contents though, only a flag for the state of the given artifact
 //! and some associated data. If the artifact failed to process, this also includes the error.
 //!
-//! A pruning task will run at a fixed interval of time. This task will remove all artifacts that
-//! weren't used or received a heads up signal for a while.
+//! A pruning task will run at a fixed interval of time. Once the artifacts exceed the configured
+//! total size or count, this task will remove the least recently used ones that weren't used or
+//! received a heads up signal for a while. See [`ArtifactsCleanupConfig`].
 //!
 //! ## Execution
 //!
@@ -104,6 +105,7 @@ mod worker_interface;
 #[cfg(feature = "test-utils")]
 pub mod testing;
 
+pub use artifacts::ArtifactsCleanupConfig;
 pub use error::{InvalidCandidate, PossiblyInvalidError, ValidationError};
 pub use host::{
 	start, Config, ValidationHost, EXECUTE_BINARY_NAME, HOST_MESSAGE_QUEUE_SIZE,
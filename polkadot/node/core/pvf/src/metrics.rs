@@ -19,6 +19,8 @@
 use polkadot_node_core_pvf_common::prepare::MemoryStats;
 use polkadot_node_metrics::metrics::{self, prometheus};
 use polkadot_node_subsystem::messages::PvfExecKind;
+use polkadot_primitives::Id as ParaId;
+use std::time::Duration;
 
 /// Validation host metrics.
 #[derive(Default, Clone)]
@@ -107,6 +109,18 @@ impl Metrics {
 		}
 	}
 
+	/// Observe the fuel consumed by a metered execution which took `cpu_time`.
+	pub(crate) fn observe_execution_fuel(&self, fuel_consumed: u64, cpu_time: Duration) {
+		if let Some(metrics) = &self.0 {
+			metrics.execution_fuel.observe(fuel_consumed as f64);
+			if !cpu_time.is_zero() {
+				metrics
+					.execution_fuel_rate
+					.observe(fuel_consumed as f64 / cpu_time.as_secs_f64());
+			}
+		}
+	}
+
 	pub(crate) fn observe_code_size(&self, code_size: usize) {
 		if let Some(metrics) = &self.0 {
 			metrics.code_size.observe(code_size as f64);
@@ -122,6 +136,37 @@ impl Metrics {
 		}
 	}
 
+	/// Observe the resources used by an execution of the PVF of `para_id`: the CPU time of the job,
+	/// its `ru_maxrss` (in kilobytes) if known, and the decompressed PoV size.
+	///
+	/// The `ru_maxrss` of the job includes the memory inherited from the worker it was forked from,
+	/// so it is an upper bound of the memory used by the execution.
+	pub(crate) fn observe_para_execution(
+		&self,
+		para_id: ParaId,
+		cpu_time: Duration,
+		max_rss: Option<i64>,
+		pov_size: u32,
+	) {
+		if let Some(metrics) = &self.0 {
+			let para_id = para_id.to_string();
+			metrics
+				.para_execution_cpu_time
+				.with_label_values(&[&para_id])
+				.observe(cpu_time.as_secs_f64());
+			if let Some(max_rss) = max_rss {
+				metrics
+					.para_execution_max_rss
+					.with_label_values(&[&para_id])
+					.observe(max_rss as f64);
+			}
+			metrics
+				.para_execution_pov_size
+				.with_label_values(&[&para_id])
+				.observe(pov_size as f64);
+		}
+	}
+
 	/// When preparation pipeline concluded working on an item.
 	pub(crate) fn on_execute_kind(&self, kind: PvfExecKind) {
 		if let Some(metrics) = &self.0 {
@@ -142,6 +187,8 @@ struct MetricsInner {
 	preparation_time: prometheus::Histogram,
 	execution_time: prometheus::Histogram,
 	execution_queued_time: prometheus::Histogram,
+	execution_fuel: prometheus::Histogram,
+	execution_fuel_rate: prometheus::Histogram,
 	#[cfg(target_os = "linux")]
 	preparation_max_rss: prometheus::Histogram,
 	// Max. allocated memory, tracked by Jemallocator, polling-based
@@ -155,6 +202,9 @@ struct MetricsInner {
 	pov_size: prometheus::HistogramVec,
 	code_size: prometheus::Histogram,
 	exec_kind_selected: prometheus::CounterVec<prometheus::U64>,
+	para_execution_cpu_time: prometheus::HistogramVec,
+	para_execution_max_rss: prometheus::HistogramVec,
+	para_execution_pov_size: prometheus::HistogramVec,
 }
 
 impl metrics::Metrics for Metrics {
@@ -298,6 +348,30 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			execution_fuel: prometheus::register(
+				prometheus::Histogram::with_opts(
+					prometheus::HistogramOpts::new(
+						"polkadot_pvf_execution_fuel",
+						"Fuel consumed by metered PVF executions",
+					).buckets(
+						prometheus::exponential_buckets(1_000_000.0, 4.0, 12)
+							.expect("arguments are always valid; qed"),
+					),
+				)?,
+				registry,
+			)?,
+			execution_fuel_rate: prometheus::register(
+				prometheus::Histogram::with_opts(
+					prometheus::HistogramOpts::new(
+						"polkadot_pvf_execution_fuel_rate",
+						"Fuel consumed per second of CPU time by metered PVF executions",
+					).buckets(
+						prometheus::exponential_buckets(1_000_000.0, 2.0, 14)
+							.expect("arguments are always valid; qed"),
+					),
+				)?,
+				registry,
+			)?,
 			#[cfg(target_os = "linux")]
 			preparation_max_rss: prometheus::register(
 				prometheus::Histogram::with_opts(
@@ -388,6 +462,48 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			para_execution_cpu_time: prometheus::register(
+				prometheus::HistogramVec::new(
+					prometheus::HistogramOpts::new(
+						"polkadot_pvf_para_execution_cpu_time",
+						"CPU time taken by the execution jobs of a parachain (in seconds)",
+					)
+					.buckets(vec![
+						0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 8.0, 10.0,
+						12.0,
+					]),
+					&["para_id"],
+				)?,
+				registry,
+			)?,
+			para_execution_max_rss: prometheus::register(
+				prometheus::HistogramVec::new(
+					prometheus::HistogramOpts::new(
+						"polkadot_pvf_para_execution_max_rss",
+						"ru_maxrss (maximum resident set size) of the execution jobs of a parachain, including the memory inherited from the worker (in kilobytes)",
+					)
+					.buckets(
+						prometheus::exponential_buckets(8192.0, 2.0, 10)
+							.expect("arguments are always valid; qed"),
+					),
+					&["para_id"],
+				)?,
+				registry,
+			)?,
+			para_execution_pov_size: prometheus::register(
+				prometheus::HistogramVec::new(
+					prometheus::HistogramOpts::new(
+						"polkadot_pvf_para_execution_pov_size",
+						"The decompressed size of the proofs of validity executed for a parachain",
+					)
+					.buckets(
+						prometheus::exponential_buckets(16384.0, 2.0, 10)
+							.expect("arguments are always valid; qed"),
+					),
+					&["para_id"],
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(inner)))
 	}
@@ -434,3 +550,59 @@ impl<'a> WorkerRelatedMetrics<'a> {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use metrics::Metrics as _;
+
+	fn histogram(registry: &prometheus::Registry, name: &str, para_id: &str) -> Option<(u64, f64)> {
+		let family = registry.gather().into_iter().find(|family| family.get_name() == name)?;
+		family
+			.get_metric()
+			.iter()
+			.find(|metric| {
+				metric
+					.get_label()
+					.iter()
+					.any(|label| label.get_name() == "para_id" && label.get_value() == para_id)
+			})
+			.map(|metric| {
+				let histogram = metric.get_histogram();
+				(histogram.get_sample_count(), histogram.get_sample_sum())
+			})
+	}
+
+	#[test]
+	fn para_execution_is_observed_per_para() {
+		let registry = prometheus::Registry::new();
+		let metrics = Metrics::try_register(&registry).unwrap();
+
+		metrics.observe_para_execution(1000.into(), Duration::from_millis(500), Some(2048), 100);
+		metrics.observe_para_execution(1000.into(), Duration::from_millis(250), Some(1024), 50);
+		metrics.observe_para_execution(2000.into(), Duration::from_secs(1), None, 10);
+
+		assert_eq!(
+			histogram(&registry, "polkadot_pvf_para_execution_cpu_time", "1000"),
+			Some((2, 0.75))
+		);
+		assert_eq!(
+			histogram(&registry, "polkadot_pvf_para_execution_max_rss", "1000"),
+			Some((2, 3072.0))
+		);
+		assert_eq!(
+			histogram(&registry, "polkadot_pvf_para_execution_pov_size", "1000"),
+			Some((2, 150.0))
+		);
+
+		assert_eq!(
+			histogram(&registry, "polkadot_pvf_para_execution_cpu_time", "2000"),
+			Some((1, 1.0))
+		);
+		assert_eq!(histogram(&registry, "polkadot_pvf_para_execution_max_rss", "2000"), None);
+		assert_eq!(
+			histogram(&registry, "polkadot_pvf_para_execution_pov_size", "2000"),
+			Some((1, 10.0))
+		);
+	}
+}
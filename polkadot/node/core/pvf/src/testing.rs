@@ -43,14 +43,19 @@ pub fn validate_candidate(
 	let code = sp_maybe_compressed_blob::decompress(code, 10 * 1024 * 1024)
 		.expect("Decompressing code failed");
 
-	let blob = prevalidate(&code)?;
 	let executor_params = ExecutorParams::default();
+	let blob = prevalidate(&code, &executor_params)?;
 	let compiled_artifact_blob = prepare(blob, &executor_params)?;
 
-	let result = unsafe {
+	let (result, _fuel_consumed) = unsafe {
 		// SAFETY: This is trivially safe since the artifact is obtained by calling `prepare`
 		//         and is written into a temporary directory in an unmodified state.
-		execute_artifact(&compiled_artifact_blob, &executor_params, params)?
+		execute_artifact(
+			&compiled_artifact_blob,
+			&executor_params,
+			polkadot_primitives::executor_params::DEFAULT_APPROVAL_EXECUTION_TIMEOUT,
+			params,
+		)?
 	};
 
 	Ok(result)
@@ -17,15 +17,27 @@
 //! PVF host integration tests checking the chain production pipeline.
 
 use super::TestHost;
+use assert_matches::assert_matches;
 use codec::{Decode, Encode};
+use polkadot_node_core_pvf::{PrepareError, ValidationError};
 use polkadot_node_primitives::PoV;
 use polkadot_parachain_primitives::primitives::{
 	BlockData as GenericBlockData, HeadData as GenericHeadData,
 };
-use polkadot_primitives::PersistedValidationData;
+use polkadot_primitives::{ExecutorParam, ExecutorParams, PersistedValidationData};
 use sp_core::H256;
+use std::time::Duration;
 use test_parachain_adder::{hash_state, BlockData, HeadData};
 
+/// Whether the adder was built for PolkaVM, i.e. with `SUBSTRATE_RUNTIME_TARGET=riscv`.
+///
+/// The PolkaVM tests in this module are skipped otherwise.
+fn adder_is_polkavm() -> bool {
+	sp_maybe_compressed_blob::decompress(test_parachain_adder::wasm_binary_unwrap(), usize::MAX)
+		.unwrap()
+		.starts_with(b"PVM\0")
+}
+
 #[tokio::test]
 async fn execute_good_block_on_parent() {
 	let parent_head = HeadData { number: 0, parent_hash: [0; 32], post_state: hash_state(0) };
@@ -203,3 +215,138 @@ async fn execute_can_run_serially() {
 
 	futures::future::join_all((0..5).map(|_| execute(host.clone()))).await;
 }
+
+#[test]
+fn polkavm_execution_is_metered() {
+	use polkadot_node_core_pvf_common::executor_interface::{prepare, prevalidate};
+	use polkadot_node_core_pvf_execute_worker::execute_artifact;
+
+	if !adder_is_polkavm() {
+		return
+	}
+
+	let parent_head = HeadData { number: 0, parent_hash: [0; 32], post_state: hash_state(0) };
+	let block_data = BlockData { state: 0, add: 512 };
+	let params = polkadot_parachain_primitives::primitives::ValidationParams {
+		parent_head: GenericHeadData(parent_head.encode()),
+		block_data: GenericBlockData(block_data.encode()),
+		relay_parent_number: 1,
+		relay_parent_storage_root: H256::default(),
+	}
+	.encode();
+
+	let code = sp_maybe_compressed_blob::decompress(
+		test_parachain_adder::wasm_binary_unwrap(),
+		usize::MAX,
+	)
+	.unwrap();
+	let executor_params = ExecutorParams::from(&[ExecutorParam::PolkaVm][..]);
+	let artifact =
+		prepare(prevalidate(&code, &executor_params).unwrap(), &executor_params).unwrap();
+
+	// SAFETY: the artifact was just prepared and wasn't modified.
+	let execute =
+		|timeout| unsafe { execute_artifact(&artifact, &executor_params, timeout, &params) };
+
+	// The execution is aborted once the gas runs out...
+	let err = execute(Duration::from_millis(0)).unwrap_err();
+	assert!(err.to_string().contains("ran out of gas"), "unexpected error: {err}");
+
+	// ...and succeeds given enough gas.
+	let (result, fuel_consumed) = execute(Duration::from_secs(2)).unwrap();
+	assert!(fuel_consumed.is_some());
+	let result =
+		polkadot_parachain_primitives::primitives::ValidationResult::decode(&mut &result[..])
+			.unwrap();
+	let new_head = HeadData::decode(&mut &result.head_data.0[..]).unwrap();
+	assert_eq!(new_head.post_state, hash_state(512));
+}
+
+// Runs the PolkaVM adder through the prepare and execute workers, so that the PolkaVM compiler and
+// its sandbox run under the security restrictions of the workers.
+#[tokio::test]
+async fn polkavm_execution_in_workers() {
+	if !adder_is_polkavm() {
+		return
+	}
+
+	let parent_head = HeadData { number: 0, parent_hash: [0; 32], post_state: hash_state(0) };
+	let block_data = BlockData { state: 0, add: 512 };
+	let pvd = PersistedValidationData {
+		parent_head: GenericHeadData(parent_head.encode()),
+		relay_parent_number: 1u32,
+		relay_parent_storage_root: H256::default(),
+		max_pov_size: 4096 * 1024,
+	};
+	let pov = PoV { block_data: GenericBlockData(block_data.encode()) };
+
+	let host = TestHost::new().await;
+	let validate = |executor_params: &[ExecutorParam]| {
+		host.validate_candidate(
+			test_parachain_adder::wasm_binary_unwrap(),
+			pvd.clone(),
+			pov.clone(),
+			ExecutorParams::from(executor_params),
+			H256::default(),
+		)
+	};
+
+	// PolkaVM PVFs are rejected unless enabled.
+	assert_matches!(
+		validate(&[]).await,
+		Err(ValidationError::Preparation(PrepareError::Prevalidation(_)))
+	);
+
+	let err = validate(&[ExecutorParam::PolkaVm, ExecutorParam::PvfExecFuel(1)])
+		.await
+		.unwrap_err();
+	assert!(err.to_string().contains("ran out of gas"), "unexpected error: {err}");
+
+	// Without a fuel budget, the gas is derived from the execution timeout.
+	let ret = validate(&[ExecutorParam::PolkaVm]).await.unwrap();
+	let new_head = HeadData::decode(&mut &ret.head_data.0[..]).unwrap();
+	assert_eq!(new_head.number, 1);
+	assert_eq!(new_head.parent_hash, parent_head.hash());
+	assert_eq!(new_head.post_state, hash_state(512));
+}
+
+#[tokio::test]
+async fn execution_is_metered_with_fuel_budget() {
+	let parent_head = HeadData { number: 0, parent_hash: [0; 32], post_state: hash_state(0) };
+	let block_data = BlockData { state: 0, add: 512 };
+	let pvd = PersistedValidationData {
+		parent_head: GenericHeadData(parent_head.encode()),
+		relay_parent_number: 1u32,
+		relay_parent_storage_root: H256::default(),
+		max_pov_size: 4096 * 1024,
+	};
+	let pov = PoV { block_data: GenericBlockData(block_data.encode()) };
+
+	let host = TestHost::new().await;
+
+	let err = host
+		.validate_candidate(
+			test_parachain_adder::wasm_binary_unwrap(),
+			pvd.clone(),
+			pov.clone(),
+			ExecutorParams::from(&[ExecutorParam::PvfExecFuel(1)][..]),
+			H256::default(),
+		)
+		.await
+		.unwrap_err();
+	assert!(err.to_string().contains("fuel") || err.to_string().contains("gas"), "{err}");
+
+	let ret = host
+		.validate_candidate(
+			test_parachain_adder::wasm_binary_unwrap(),
+			pvd,
+			pov,
+			ExecutorParams::from(&[ExecutorParam::PvfExecFuel(10_000_000_000)][..]),
+			H256::default(),
+		)
+		.await
+		.unwrap();
+
+	let new_head = HeadData::decode(&mut &ret.head_data.0[..]).unwrap();
+	assert_eq!(new_head.post_state, hash_state(512));
+}
@@ -28,7 +28,7 @@ use polkadot_node_primitives::{PoV, POV_BOMB_LIMIT, VALIDATION_CODE_BOMB_LIMIT};
 use polkadot_node_subsystem::messages::PvfExecKind;
 use polkadot_parachain_primitives::primitives::{BlockData, ValidationResult};
 use polkadot_primitives::{
-	ExecutorParam, ExecutorParams, Hash, PersistedValidationData,
+	ExecutorParam, ExecutorParams, Hash, Id as ParaId, PersistedValidationData,
 	PvfExecKind as RuntimePvfExecKind, PvfPrepKind,
 };
 use sp_core::H256;
@@ -127,6 +127,7 @@ impl TestHost {
 				Arc::new(pov),
 				polkadot_node_core_pvf::Priority::Normal,
 				PvfExecKind::Backing(relay_parent),
+				ParaId::from(1_u32),
 				result_tx,
 			)
 			.await
@@ -747,3 +748,26 @@ async fn invalid_compressed_pov_fails_validation() {
 		Err(ValidationError::Invalid(InvalidCandidate::PoVDecompressionFailure))
 	);
 }
+
+#[test]
+fn polkavm_pvfs_are_rejected_unless_enabled() {
+	use polkadot_node_core_pvf_common::executor_interface::prevalidate;
+
+	// Not a valid PolkaVM program, but the magic bytes are enough to tell it apart from WASM.
+	let code = b"PVM\0\x01\x02\x03";
+
+	let err = prevalidate(code, &ExecutorParams::default()).unwrap_err();
+	assert!(err.to_string().contains("expected a WASM runtime blob"), "{err}");
+
+	let err = prevalidate(code, &ExecutorParams::from(&[ExecutorParam::PolkaVm][..])).unwrap_err();
+	assert!(!err.to_string().contains("expected a WASM runtime blob"), "{err}");
+
+	// WASM PVFs are accepted either way.
+	let code = sp_maybe_compressed_blob::decompress(
+		test_parachain_adder::wasm_binary_unwrap(),
+		usize::MAX,
+	)
+	.unwrap();
+	prevalidate(&code, &ExecutorParams::default()).unwrap();
+	prevalidate(&code, &ExecutorParams::from(&[ExecutorParam::PolkaVm][..])).unwrap();
+}
@@ -52,6 +52,7 @@ use {
 		self as chain_selection_subsystem, Config as ChainSelectionConfig,
 	},
 	polkadot_node_core_dispute_coordinator::Config as DisputeCoordinatorConfig,
+	polkadot_node_core_pvf::ArtifactsCleanupConfig,
 	polkadot_node_network_protocol::{
 		peer_set::{PeerSet, PeerSetProtocolNames},
 		request_response::ReqProtocolNames,
@@ -117,12 +118,13 @@ pub use fake_runtime_api::{GetLastTimestamp, RuntimeApi};
 #[cfg(feature = "full-node")]
 pub type FullBackend = sc_service::TFullBackend<Block>;
 
+/// The host functions provided by the node.
 #[cfg(feature = "full-node")]
-pub type FullClient = sc_service::TFullClient<
-	Block,
-	RuntimeApi,
-	WasmExecutor<(sp_io::SubstrateHostFunctions, frame_benchmarking::benchmarking::HostFunctions)>,
->;
+pub type HostFunctions =
+	(sp_io::SubstrateHostFunctions, frame_benchmarking::benchmarking::HostFunctions);
+
+#[cfg(feature = "full-node")]
+pub type FullClient = sc_service::TFullClient<Block, RuntimeApi, WasmExecutor<HostFunctions>>;
 
 /// The minimum period of blocks on which justifications will be
 /// imported and generated.
@@ -415,6 +417,9 @@ fn new_partial_basics(
 		.with_offchain_heap_alloc_strategy(heap_pages)
 		.with_max_runtime_instances(config.executor.max_runtime_instances)
 		.with_runtime_cache_size(config.executor.runtime_cache_size)
+		.with_runtime_cache_path(config.executor.runtime_cache_path.clone())
+		.with_runtime_cache_max_size(config.executor.runtime_cache_max_size)
+		.with_host_function_shims(config.executor.host_function_shims.clone())
 		.build();
 
 	let (client, backend, keystore_container, task_manager) =
@@ -628,6 +633,16 @@ pub struct NewFullParams<OverseerGenerator: OverseerGen> {
 	pub prepare_workers_soft_max_num: Option<usize>,
 	/// An optional absolute number of pvf workers that can be spawned in the pvf prepare pool.
 	pub prepare_workers_hard_max_num: Option<usize>,
+	/// An optional total size in bytes of the prepared pvf artifacts above which the least
+	/// recently used ones are pruned.
+	pub pvf_artifacts_cache_limit: Option<u64>,
+	/// An optional number of prepared pvf artifacts above which the least recently used ones are
+	/// pruned.
+	pub pvf_artifacts_max_count: Option<usize>,
+	/// An optional inactive time within which prepared pvf artifacts are never pruned.
+	pub pvf_artifacts_min_stale_time: Option<Duration>,
+	/// Whether to prepare the pvfs of the parachains in the claim queue ahead of the next session.
+	pub pvf_prefetch_scheduled: bool,
 	pub overseer_gen: OverseerGenerator,
 	pub overseer_message_channel_capacity_override: Option<usize>,
 	#[allow(dead_code)]
@@ -727,6 +742,10 @@ pub fn new_full<
 		execute_workers_max_num,
 		prepare_workers_soft_max_num,
 		prepare_workers_hard_max_num,
+		pvf_artifacts_cache_limit,
+		pvf_artifacts_max_count,
+		pvf_artifacts_min_stale_time,
+		pvf_prefetch_scheduled,
 		enable_approval_voting_parallel,
 	}: NewFullParams<OverseerGenerator>,
 ) -> Result<NewFull, Error> {
@@ -954,6 +973,20 @@ pub fn new_full<
 				),
 				pvf_prepare_workers_soft_max_num: prepare_workers_soft_max_num.unwrap_or(1),
 				pvf_prepare_workers_hard_max_num: prepare_workers_hard_max_num.unwrap_or(2),
+				pvf_artifacts_cleanup: {
+					let mut cleanup = ArtifactsCleanupConfig::default();
+					if let Some(cache_limit) = pvf_artifacts_cache_limit {
+						cleanup = cleanup.with_cache_limit(cache_limit);
+					}
+					if let Some(max_count) = pvf_artifacts_max_count {
+						cleanup = cleanup.with_max_count(max_count);
+					}
+					if let Some(min_stale_time) = pvf_artifacts_min_stale_time {
+						cleanup = cleanup.with_min_stale_time(min_stale_time);
+					}
+					cleanup
+				},
+				pvf_prefetch_scheduled,
 			})
 		} else {
 			None
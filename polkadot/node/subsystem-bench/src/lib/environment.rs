@@ -359,6 +359,7 @@ impl TestEnvironment {
 		BenchmarkUsage {
 			network_usage: self.network_usage(),
 			cpu_usage: self.cpu_usage(subsystems_under_test, break_down_cpu_usage_per_task),
+			pvf_execution_usage: self.pvf_execution_usage(),
 		}
 	}
 
@@ -430,4 +431,49 @@ impl TestEnvironment {
 
 		usage
 	}
+
+	// Resources used by PVF executions, broken down per parachain. Only reported if a real
+	// validation host registered its metrics in the test registry.
+	fn pvf_execution_usage(&self) -> Vec<ResourceUsage> {
+		let test_metrics = super::display::parse_metrics(self.registry());
+		let mut usage = vec![];
+		let num_blocks = self.config().num_blocks as f64;
+
+		for metric in test_metrics.all() {
+			if metric.name() != "polkadot_pvf_para_execution_cpu_time_sum" {
+				continue;
+			}
+			let Some(para_id) = metric.label_value("para_id") else { continue };
+			let para_metrics = test_metrics.subset_with_label_value("para_id", para_id);
+
+			let cpu_time = metric.value();
+			usage.push(ResourceUsage {
+				resource_name: format!("para {}/execution CPU, seconds", para_id),
+				total: cpu_time,
+				per_block: cpu_time / num_blocks,
+			});
+
+			let pov_size = para_metrics.sum_by("polkadot_pvf_para_execution_pov_size_sum") / 1024.0;
+			usage.push(ResourceUsage {
+				resource_name: format!("para {}/decompressed PoV, KiB", para_id),
+				total: pov_size,
+				per_block: pov_size / num_blocks,
+			});
+
+			// Peak memory doesn't add up across executions, so report the average of the peaks. The
+			// peaks include the memory the job inherited from the worker it was forked from.
+			let executions = para_metrics.sum_by("polkadot_pvf_para_execution_max_rss_count");
+			if executions > 0.0 {
+				let max_rss = para_metrics.sum_by("polkadot_pvf_para_execution_max_rss_sum") /
+					executions / 1024.0;
+				usage.push(ResourceUsage {
+					resource_name: format!("para {}/peak memory per execution, MiB", para_id),
+					total: max_rss,
+					per_block: max_rss,
+				});
+			}
+		}
+
+		usage
+	}
 }
@@ -25,6 +25,9 @@ use std::collections::HashMap;
 pub struct BenchmarkUsage {
 	pub network_usage: Vec<ResourceUsage>,
 	pub cpu_usage: Vec<ResourceUsage>,
+	/// Resources used by PVF executions, per parachain.
+	#[serde(default)]
+	pub pvf_execution_usage: Vec<ResourceUsage>,
 }
 
 impl std::fmt::Display for BenchmarkUsage {
@@ -46,7 +49,24 @@ impl std::fmt::Display for BenchmarkUsage {
 				.sorted()
 				.collect::<Vec<String>>()
 				.join("\n")
-		)
+		)?;
+
+		if !self.pvf_execution_usage.is_empty() {
+			write!(
+				f,
+				"\n{}\n{}\n",
+				format!("{:<64}{:>12}{:>12}", "PVF execution per parachain", "total", "per block")
+					.blue(),
+				self.pvf_execution_usage
+					.iter()
+					.map(|v| v.to_string())
+					.sorted()
+					.collect::<Vec<String>>()
+					.join("\n")
+			)?;
+		}
+
+		Ok(())
 	}
 }
 
@@ -55,10 +75,13 @@ impl BenchmarkUsage {
 		let all_network_usages: Vec<&ResourceUsage> =
 			usages.iter().flat_map(|v| &v.network_usage).collect();
 		let all_cpu_usage: Vec<&ResourceUsage> = usages.iter().flat_map(|v| &v.cpu_usage).collect();
+		let all_pvf_execution_usage: Vec<&ResourceUsage> =
+			usages.iter().flat_map(|v| &v.pvf_execution_usage).collect();
 
 		Self {
 			network_usage: ResourceUsage::average_by_resource_name(&all_network_usages),
 			cpu_usage: ResourceUsage::average_by_resource_name(&all_cpu_usage),
+			pvf_execution_usage: ResourceUsage::average_by_resource_name(&all_pvf_execution_usage),
 		}
 	}
 
@@ -98,6 +98,10 @@ fn main() -> Result<()> {
 						hwbench: None,
 						execute_workers_max_num: None,
 						prepare_workers_hard_max_num: None,
+						pvf_artifacts_cache_limit: None,
+						pvf_artifacts_max_count: None,
+						pvf_artifacts_min_stale_time: None,
+						pvf_prefetch_scheduled: false,
 						prepare_workers_soft_max_num: None,
 						enable_approval_voting_parallel: false,
 					},
@@ -109,6 +109,18 @@ pub enum ExecutorParam {
 	/// Enables WASM bulk memory proposal
 	#[codec(index = 7)]
 	WasmExtBulkMemory,
+	/// Deterministic PVF execution budget, in units of fuel. A valid value is greater than 0.
+	///
+	/// When present, every executed instruction consumes fuel and the candidate is invalid once
+	/// the PVF runs out of it, regardless of the hardware it's executed on. The execution
+	/// timeouts are still enforced on top of that.
+	#[codec(index = 8)]
+	PvfExecFuel(u64),
+	/// Enables PVFs compiled for PolkaVM.
+	///
+	/// When absent, only WASM PVFs are accepted and PolkaVM PVFs fail prevalidation.
+	#[codec(index = 9)]
+	PolkaVm,
 }
 
 /// Possible inconsistencies of executor params.
@@ -221,6 +233,9 @@ impl ExecutorParams {
 				PvfPrepTimeout(..) => Some(param),
 				PvfExecTimeout(..) => None,
 				WasmExtBulkMemory => Some(param),
+				// Only whether the execution is metered affects the artifact, not the budget.
+				PvfExecFuel(..) => Some(&PvfExecFuel(0)),
+				PolkaVm => Some(param),
 			})
 			.for_each(|p| enc.extend(p.encode()));
 
@@ -251,6 +266,21 @@ impl ExecutorParams {
 		None
 	}
 
+	/// Returns the PVF execution fuel budget, if any
+	pub fn pvf_exec_fuel(&self) -> Option<u64> {
+		for param in &self.0 {
+			if let ExecutorParam::PvfExecFuel(fuel) = param {
+				return Some(*fuel)
+			}
+		}
+		None
+	}
+
+	/// Returns whether PVFs compiled for PolkaVM are accepted
+	pub fn polkavm_enabled(&self) -> bool {
+		self.0.iter().any(|param| matches!(param, ExecutorParam::PolkaVm))
+	}
+
 	/// Returns pre-checking memory limit, if any
 	pub fn prechecking_max_memory(&self) -> Option<u64> {
 		for param in &self.0 {
@@ -304,6 +334,8 @@ impl ExecutorParams {
 					PvfExecKind::Approval => "PvfExecKind::Approval",
 				},
 				WasmExtBulkMemory => "WasmExtBulkMemory",
+				PvfExecFuel(_) => "PvfExecFuel",
+				PolkaVm => "PolkaVm",
 			};
 
 			match *param {
@@ -338,6 +370,14 @@ impl ExecutorParams {
 				WasmExtBulkMemory => {
 					check!(param_ident, 1);
 				},
+
+				PvfExecFuel(val) => {
+					check!(param_ident, val, val == 0);
+				},
+
+				PolkaVm => {
+					check!(param_ident, 1);
+				},
 			}
 		}
 
@@ -408,6 +448,8 @@ fn ensure_prep_hash_changes() {
 			PvfExecTimeout(PvfExecKind::Backing, 0),
 			PvfExecTimeout(PvfExecKind::Approval, 0),
 			WasmExtBulkMemory,
+			PvfExecFuel(0),
+			PolkaVm,
 		][..],
 	);
 
@@ -431,8 +473,21 @@ fn ensure_prep_hash_changes() {
 			PvfExecTimeout(_, _) => continue,
 			WasmExtBulkMemory =>
 				(ExecutorParams::default(), ExecutorParams::from(&[WasmExtBulkMemory][..])),
+			PvfExecFuel(_) =>
+				(ExecutorParams::default(), ExecutorParams::from(&[PvfExecFuel(1)][..])),
+			PolkaVm => (ExecutorParams::default(), ExecutorParams::from(&[PolkaVm][..])),
 		};
 
 		assert_ne!(ep1.prep_hash(), ep2.prep_hash());
 	}
 }
+
+#[test]
+fn fuel_budget_does_not_affect_prep_hash() {
+	use ExecutorParam::*;
+
+	assert_eq!(
+		ExecutorParams::from(&[PvfExecFuel(1)][..]).prep_hash(),
+		ExecutorParams::from(&[PvfExecFuel(2)][..]).prep_hash(),
+	);
+}
@@ -45,6 +45,8 @@ benchmarks! {
 		ExecutorParam::PvfPrepTimeout(PvfPrepKind::Prepare, 360_000),
 		ExecutorParam::PvfExecTimeout(PvfExecKind::Backing, 2_000),
 		ExecutorParam::PvfExecTimeout(PvfExecKind::Approval, 12_000),
+		ExecutorParam::PvfExecFuel(10_000_000_000),
+		ExecutorParam::PolkaVm,
 	][..]))
 
 	set_config_with_perbill {}: set_on_demand_fee_variability(RawOrigin::Root, Perbill::from_percent(100))
@@ -510,7 +510,13 @@ pub(crate) mod tests {
 
 		sc_service_test::connectivity(integration_test_config_with_two_authorities(), |config| {
 			let NewFullBase { task_manager, client, network, sync, transaction_pool, .. } =
-				new_full_base::<sc_network::NetworkWorker<_, _>>(config, None, false, |_, _| ())?;
+				new_full_base::<sc_network::NetworkWorker<_, _>>(
+					config,
+					None,
+					None,
+					false,
+					|_, _| (),
+				)?;
 			Ok(sc_service_test::TestNetComponents::new(
 				task_manager,
 				client,
@@ -43,11 +43,24 @@ pub struct Cli {
 	#[arg(long)]
 	pub no_hardware_benchmarks: bool,
 
+	/// Only receive statements with this topic.
+	///
+	/// The topic is given as 32 bytes encoded in hex. Can be passed multiple times to receive
+	/// statements with any of the topics. By default all statements are received.
+	#[arg(long = "statement-topic", value_name = "TOPIC", value_parser = parse_statement_topic)]
+	pub statement_topics: Vec<[u8; 32]>,
+
 	#[allow(missing_docs)]
 	#[clap(flatten)]
 	pub storage_monitor: sc_storage_monitor::StorageMonitorParams,
 }
 
+/// Parse a statement topic from its hex encoding.
+fn parse_statement_topic(topic: &str) -> Result<[u8; 32], String> {
+	array_bytes::hex2array::<_, 32>(topic.strip_prefix("0x").unwrap_or(topic))
+		.map_err(|e| format!("a topic must be 32 bytes encoded in hex: {e:?}"))
+}
+
 /// Possible subcommands of the main binary.
 #[derive(Debug, clap::Subcommand)]
 pub enum Subcommand {
@@ -98,6 +111,12 @@ pub enum Subcommand {
 	/// Revert the chain to a previous state.
 	Revert(sc_cli::RevertCmd),
 
+	/// Replay blocks on top of a fork with a new runtime.
+	ReplayBlocks(sc_cli::ReplayBlocksCmd),
+
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
+
+	/// Report the host functions imported by the runtimes of the chain.
+	HostFunctions(sc_cli::HostFunctionsCmd),
 }
@@ -398,6 +398,7 @@ pub struct NewFullBase {
 pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 	config: Configuration,
 	mixnet_config: Option<sc_mixnet::Config>,
+	statement_topic_filter: Option<sc_network_statement::TopicFilter>,
 	disable_hardware_benchmarks: bool,
 	with_startup_data: impl FnOnce(
 		&sc_consensus_babe::BabeBlockImport<
@@ -492,6 +493,10 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 			metrics.clone(),
 			Arc::clone(&peer_store_handle),
 		);
+	let statement_handler_proto = match statement_topic_filter {
+		Some(topic_filter) => statement_handler_proto.with_topic_filter(topic_filter),
+		None => statement_handler_proto,
+	};
 	net_config.add_notification_protocol(statement_config);
 
 	let mixnet_protocol_name =
@@ -507,6 +512,8 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 		notification_service
 	});
 
+	let bitswap_store = net_config.bitswap_store();
+
 	let warp_sync = Arc::new(grandpa::warp_proof::NetworkProvider::new(
 		backend.clone(),
 		import_setup.1.shared_authority_set().clone(),
@@ -543,6 +550,25 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 		task_manager.spawn_handle().spawn("mixnet", None, mixnet);
 	}
 
+	// Content pinned through the bitswap RPC is served by the bitswap server of the network.
+	let bitswap = config.network.ipfs_server.then(|| {
+		sc_network::bitswap::BitswapClient::new::<Block>(
+			network.clone(),
+			client.clone(),
+			bitswap_store,
+		)
+	});
+	let rpc_builder = move |subscription_executor: sc_rpc::SubscriptionTaskExecutor| {
+		use sc_rpc::bitswap::{Bitswap, BitswapApiServer};
+
+		let mut io = rpc_builder(subscription_executor)?;
+		if let Some(bitswap) = &bitswap {
+			io.merge(Bitswap::new(bitswap.clone()).into_rpc())
+				.map_err(|e| ServiceError::Application(e.into()))?;
+		}
+		Ok(io)
+	};
+
 	let rpc_handlers = sc_service::spawn_tasks(sc_service::SpawnTasksParams {
 		config,
 		backend: backend.clone(),
@@ -814,6 +840,8 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 /// Builds a new service for a full client.
 pub fn new_full(config: Configuration, cli: Cli) -> Result<TaskManager, ServiceError> {
 	let mixnet_config = cli.mixnet_params.config(config.role.is_authority());
+	let statement_topic_filter = (!cli.statement_topics.is_empty())
+		.then(|| sc_network_statement::TopicFilter::from_topics(&cli.statement_topics));
 	let database_path = config.database.path().map(Path::to_path_buf);
 
 	let task_manager = match config.network.network_backend {
@@ -821,6 +849,7 @@ pub fn new_full(config: Configuration, cli: Cli) -> Result<TaskManager, ServiceE
 			let task_manager = new_full_base::<sc_network::NetworkWorker<_, _>>(
 				config,
 				mixnet_config,
+				statement_topic_filter,
 				cli.no_hardware_benchmarks,
 				|_, _| (),
 			)
@@ -831,6 +860,7 @@ pub fn new_full(config: Configuration, cli: Cli) -> Result<TaskManager, ServiceE
 			let task_manager = new_full_base::<sc_network::Litep2pNetworkBackend>(
 				config,
 				mixnet_config,
+				statement_topic_filter,
 				cli.no_hardware_benchmarks,
 				|_, _| (),
 			)
@@ -918,6 +948,7 @@ mod tests {
 					new_full_base::<sc_network::NetworkWorker<_, _>>(
 						config,
 						None,
+						None,
 						false,
 						|block_import: &sc_consensus_babe::BabeBlockImport<Block, _, _>,
 						 babe_link: &sc_consensus_babe::BabeLink<Block>| {
@@ -1116,6 +1147,7 @@ mod tests {
 					new_full_base::<sc_network::NetworkWorker<_, _>>(
 						config,
 						None,
+						None,
 						false,
 						|_, _| (),
 					)?;
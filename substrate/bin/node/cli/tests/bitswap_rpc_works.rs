@@ -0,0 +1,57 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+#![cfg(unix)]
+use assert_cmd::cargo::cargo_bin;
+use polkadot_sdk::substrate_rpc_client::{rpc_params, ws_client, ClientT};
+use std::{
+	process::{self, Command},
+	time::Duration,
+};
+
+use substrate_cli_test_utils as common;
+
+/// Start a dev node with the extra `args` and list the pins of its bitswap store.
+async fn bitswap_pins(args: &[&str]) -> Result<Vec<String>, String> {
+	let mut node = common::KillChildOnDrop(
+		Command::new(cargo_bin("substrate-node"))
+			.stdout(process::Stdio::piped())
+			.stderr(process::Stdio::piped())
+			.args(&["--dev", "--tmp", "--no-hardware-benchmarks"])
+			.args(args)
+			.spawn()
+			.unwrap(),
+	);
+
+	let stderr = node.stderr.take().unwrap();
+	let ws_url = common::extract_info_from_output(stderr).0.ws_url;
+	let rpc = ws_client(&ws_url).await.unwrap();
+
+	rpc.request("bitswap_pins", rpc_params![]).await.map_err(|e| e.to_string())
+}
+
+#[tokio::test]
+async fn bitswap_rpc_is_exposed_with_ipfs_server() {
+	common::run_with_timeout(Duration::from_secs(60 * 10), async move {
+		assert_eq!(bitswap_pins(&["--ipfs-server"]).await, Ok(Vec::new()));
+
+		let error = bitswap_pins(&[]).await.unwrap_err();
+		assert!(error.contains("Method not found"), "unexpected error: {error}");
+	})
+	.await;
+}
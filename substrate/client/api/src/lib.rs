@@ -27,6 +27,7 @@ pub mod in_mem;
 pub mod leaves;
 pub mod notifications;
 pub mod proof_provider;
+pub mod runtime_api_cache;
 
 pub use backend::*;
 pub use call_executor::*;
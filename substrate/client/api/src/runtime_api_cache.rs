@@ -0,0 +1,142 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Cache for the results of pure runtime api calls.
+//!
+//! Runtime api functions declared as `#[pure]` in `decl_runtime_apis!` only depend on the state
+//! of the block they are called at and on their arguments. Their encoded results can therefore
+//! be reused by later calls with the same block hash, function and arguments.
+
+use parking_lot::Mutex;
+use prometheus_endpoint::{register, CounterVec, Opts, PrometheusError, Registry, U64};
+use schnellru::{ByLength, LruMap};
+use sp_runtime::traits::Block as BlockT;
+
+/// The key of a cached runtime api call.
+type CacheKey<Block> = (<Block as BlockT>::Hash, &'static str, Vec<u8>);
+
+/// Prometheus metrics of the [`RuntimeApiCache`].
+#[derive(Clone)]
+struct Metrics {
+	requests: CounterVec<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			requests: register(
+				CounterVec::new(
+					Opts::new(
+						"substrate_runtime_api_cache_requests_total",
+						"Number of lookups in the runtime api result cache",
+					),
+					&["result"], // hit | miss
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// LRU cache of encoded results of pure runtime api calls.
+///
+/// Entries are keyed by the block hash, the name of the function that was called in the runtime
+/// and the encoded arguments.
+pub struct RuntimeApiCache<Block: BlockT> {
+	entries: Mutex<LruMap<CacheKey<Block>, Vec<u8>>>,
+	metrics: Option<Metrics>,
+}
+
+impl<Block: BlockT> RuntimeApiCache<Block> {
+	/// Create a new cache that holds at most `size` results.
+	pub fn new(size: u32, prometheus_registry: Option<&Registry>) -> Self {
+		let metrics = prometheus_registry.and_then(|r| {
+			Metrics::register(r)
+				.map_err(
+					|e| log::warn!(target: "runtime_api_cache", "Failed to register metrics: {e}"),
+				)
+				.ok()
+		});
+
+		Self { entries: Mutex::new(LruMap::new(ByLength::new(size.max(1)))), metrics }
+	}
+
+	/// Returns the cached result of calling `function` with `arguments` at `at`.
+	pub fn get(
+		&self,
+		at: Block::Hash,
+		function: &'static str,
+		arguments: &[u8],
+	) -> Option<Vec<u8>> {
+		let result = self.entries.lock().get(&(at, function, arguments.to_vec())).cloned();
+
+		if let Some(metrics) = &self.metrics {
+			let label = if result.is_some() { "hit" } else { "miss" };
+			metrics.requests.with_label_values(&[label]).inc();
+		}
+
+		result
+	}
+
+	/// Cache the `result` of calling `function` with `arguments` at `at`.
+	pub fn insert(
+		&self,
+		at: Block::Hash,
+		function: &'static str,
+		arguments: Vec<u8>,
+		result: Vec<u8>,
+	) {
+		self.entries.lock().insert((at, function, arguments), result);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use substrate_test_runtime::{Block, H256};
+
+	#[test]
+	fn returns_cached_results() {
+		let cache = RuntimeApiCache::<Block>::new(2, None);
+		let at = H256::repeat_byte(1);
+
+		assert_eq!(cache.get(at, "Api_call", &[1]), None);
+		cache.insert(at, "Api_call", vec![1], vec![42]);
+		assert_eq!(cache.get(at, "Api_call", &[1]), Some(vec![42]));
+
+		// A different block, function or argument is a different entry.
+		assert_eq!(cache.get(H256::repeat_byte(2), "Api_call", &[1]), None);
+		assert_eq!(cache.get(at, "Api_other_call", &[1]), None);
+		assert_eq!(cache.get(at, "Api_call", &[2]), None);
+	}
+
+	#[test]
+	fn evicts_least_recently_used_result() {
+		let cache = RuntimeApiCache::<Block>::new(2, None);
+		let at = H256::repeat_byte(1);
+
+		cache.insert(at, "Api_call", vec![1], vec![1]);
+		cache.insert(at, "Api_call", vec![2], vec![2]);
+		assert!(cache.get(at, "Api_call", &[1]).is_some());
+		cache.insert(at, "Api_call", vec![3], vec![3]);
+
+		assert_eq!(cache.get(at, "Api_call", &[2]), None);
+		assert_eq!(cache.get(at, "Api_call", &[1]), Some(vec![1]));
+		assert_eq!(cache.get(at, "Api_call", &[3]), Some(vec![3]));
+	}
+}
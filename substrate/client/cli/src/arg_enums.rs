@@ -54,6 +54,8 @@ pub enum WasmExecutionMethod {
 	Interpreted,
 	/// Uses a compiled runtime.
 	Compiled,
+	/// Uses PolkaVM. Only runtimes built for PolkaVM can be executed.
+	Polkavm,
 }
 
 impl std::fmt::Display for WasmExecutionMethod {
@@ -61,6 +63,7 @@ impl std::fmt::Display for WasmExecutionMethod {
 		match self {
 			Self::Interpreted => write!(f, "Interpreted"),
 			Self::Compiled => write!(f, "Compiled"),
+			Self::Polkavm => write!(f, "Polkavm"),
 		}
 	}
 }
@@ -77,6 +80,10 @@ pub fn execution_method_from_cli(
 		);
 	}
 
+	if let WasmExecutionMethod::Polkavm = execution_method {
+		return sc_service::config::WasmExecutionMethod::PolkaVm { backend: Default::default() }
+	}
+
 	sc_service::config::WasmExecutionMethod::Compiled {
 		instantiation_strategy: match instantiation_strategy {
 			WasmtimeInstantiationStrategy::PoolingCopyOnWrite =>
@@ -23,8 +23,15 @@ use crate::{
 };
 use clap::Parser;
 use sc_client_api::{BlockBackend, HeaderBackend};
+use sp_api::{Metadata, ProvideRuntimeApi};
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
-use std::{fmt::Debug, str::FromStr, sync::Arc};
+use std::{
+	fmt::Debug,
+	fs, io,
+	path::{Path, PathBuf},
+	str::FromStr,
+	sync::Arc,
+};
 
 /// The `check-block` command used to validate blocks.
 #[derive(Debug, Clone, Parser)]
@@ -38,6 +45,16 @@ pub struct CheckBlockCmd {
 	#[arg(long, value_name = "COUNT")]
 	pub default_heap_pages: Option<u32>,
 
+	/// Instead of importing the block, re-execute it on top of its parent and write a
+	/// timeline of the execution to the given file.
+	///
+	/// The file is in the Chrome trace event format and can be loaded into `chrome://tracing`
+	/// or Perfetto. Spans carry the time spent in them and the size they added to the storage
+	/// proof. The recorded targets can be changed with `--tracing-targets`. Tracing the runtime
+	/// requires runtimes compiled with the `with-tracing` feature.
+	#[arg(long, value_name = "FILE")]
+	pub runtime_trace_output: Option<PathBuf>,
+
 	#[allow(missing_docs)]
 	#[clap(flatten)]
 	pub shared_params: SharedParams,
@@ -52,17 +69,52 @@ impl CheckBlockCmd {
 	pub async fn run<B, C, IQ>(&self, client: Arc<C>, import_queue: IQ) -> error::Result<()>
 	where
 		B: BlockT + for<'de> serde::Deserialize<'de>,
-		C: BlockBackend<B> + HeaderBackend<B> + Send + Sync + 'static,
+		C: BlockBackend<B> + HeaderBackend<B> + ProvideRuntimeApi<B> + Send + Sync + 'static,
+		C::Api: Metadata<B>,
 		IQ: sc_service::ImportQueue<B> + 'static,
 		<B::Hash as FromStr>::Err: Debug,
 		<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
 	{
 		let start = std::time::Instant::now();
-		sc_service::chain_ops::check_block(client, import_queue, self.input.parse()?).await?;
+		if let Some(path) = &self.runtime_trace_output {
+			self.trace_block(client, path)?;
+		} else {
+			sc_service::chain_ops::check_block(client, import_queue, self.input.parse()?).await?;
+		}
 		println!("Completed in {} ms.", start.elapsed().as_millis());
 
 		Ok(())
 	}
+
+	fn trace_block<B, C>(&self, client: Arc<C>, path: &Path) -> error::Result<()>
+	where
+		B: BlockT,
+		C: BlockBackend<B> + HeaderBackend<B> + ProvideRuntimeApi<B> + Send + Sync + 'static,
+		C::Api: Metadata<B>,
+		<B::Hash as FromStr>::Err: Debug,
+		<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
+	{
+		let hash = client.expect_block_hash_from_id(&self.input.parse()?)?;
+		let trace = sc_tracing::block::BlockExecutor::new(
+			client,
+			hash,
+			self.shared_params.tracing_targets(),
+			None,
+			None,
+		)
+		.trace_block_chrome()
+		.map_err(|e| error::Error::Application(Box::new(e)))?;
+
+		let file = io::BufWriter::new(fs::File::create(path)?);
+		serde_json::to_writer(file, &trace).map_err(|e| error::Error::Application(Box::new(e)))?;
+		println!(
+			"Wrote {} trace events of block {hash} to {}.",
+			trace.trace_events.len(),
+			path.display()
+		);
+
+		Ok(())
+	}
 }
 
 impl CliConfiguration for CheckBlockCmd {
@@ -74,3 +126,50 @@ impl CliConfiguration for CheckBlockCmd {
 		Some(&self.import_params)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_block_builder::BlockBuilderBuilder;
+	use substrate_test_runtime_client::{prelude::*, runtime::ExtrinsicBuilder};
+
+	#[test]
+	fn runtime_trace_output_writes_chrome_trace() {
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let mut builder = BlockBuilderBuilder::new(&*client)
+			.on_parent_block(client.chain_info().best_hash)
+			.with_parent_block_number(client.chain_info().best_number)
+			.build()
+			.unwrap();
+		builder
+			.push(ExtrinsicBuilder::new_storage_change(vec![1], Some(vec![1])).build())
+			.unwrap();
+		let block = builder.build().unwrap().block;
+		futures::executor::block_on(client.import(BlockOrigin::Own, block)).unwrap();
+
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("trace.json");
+		let cmd = CheckBlockCmd::parse_from([
+			"check-block",
+			"--runtime-trace-output",
+			path.to_str().unwrap(),
+			"--tracing-targets",
+			"state",
+			"1",
+		]);
+		cmd.trace_block(client, cmd.runtime_trace_output.as_ref().unwrap()).unwrap();
+
+		let trace: serde_json::Value =
+			serde_json::from_reader(fs::File::open(&path).unwrap()).unwrap();
+		assert_eq!(trace["otherData"]["tracing_targets"], "state");
+
+		let events = trace["traceEvents"].as_array().unwrap();
+		assert!(!events.is_empty());
+		for event in events {
+			assert_eq!(event["cat"], "state", "{event}");
+			assert_eq!(event["ph"], "i", "{event}");
+			assert!(event["name"].is_string() && event["ts"].is_number(), "{event}");
+			assert!(event.get("dur").is_none(), "{event}");
+		}
+	}
+}
@@ -0,0 +1,236 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{error, CliConfiguration, DatabaseParams, PruningParams, SharedParams};
+use clap::Parser;
+use log::info;
+use sc_client_api::{HeaderBackend, StorageProvider, UsageProvider};
+use sc_executor::HostFunctions;
+use sp_core::storage::{well_known_keys, StorageKey};
+use sp_runtime::{
+	generic::DigestItem,
+	traits::{Block as BlockT, Header as HeaderT, NumberFor, SaturatedConversion},
+};
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	path::PathBuf,
+	sync::Arc,
+};
+
+/// The `host-functions` command used to report the host functions imported by runtimes.
+///
+/// Scans all runtime code versions found in the database, or the given wasm blobs, and reports
+/// which host function versions they import. This tells which native host function
+/// implementations are still required to execute the historic runtimes of a chain, and which
+/// ones can be dropped or replaced by wasm shims.
+#[derive(Debug, Clone, Parser)]
+pub struct HostFunctionsCmd {
+	/// Scan the given wasm blobs instead of the runtimes stored in the database.
+	#[arg(long, value_name = "PATH", num_args = 1..)]
+	pub wasm: Vec<PathBuf>,
+
+	/// The block number to start scanning the database at.
+	///
+	/// The state of this block, and of all blocks after it that upgrade the runtime, needs to be
+	/// available, so this should be set to the oldest block of a pruned database.
+	#[arg(long, value_name = "NUMBER", default_value_t = 0)]
+	pub from: u64,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl HostFunctionsCmd {
+	/// Run the `host-functions` command
+	///
+	/// `H` are the host functions natively provided by the node.
+	pub async fn run<B, BA, C, H>(&self, client: Arc<C>) -> error::Result<()>
+	where
+		B: BlockT,
+		C: UsageProvider<B> + StorageProvider<B, BA> + HeaderBackend<B>,
+		BA: sc_client_api::backend::Backend<B>,
+		H: HostFunctions,
+	{
+		let runtimes = if self.wasm.is_empty() {
+			runtimes_in_database(&*client, self.from)?
+		} else {
+			self.wasm
+				.iter()
+				.map(|path| Ok((path.display().to_string(), std::fs::read(path)?)))
+				.collect::<error::Result<Vec<_>>>()?
+		};
+
+		// All imported host functions, along with the runtimes importing them.
+		let mut imports = BTreeMap::<String, Vec<String>>::new();
+		for (runtime, code) in &runtimes {
+			let imported = sc_executor::imported_host_functions(code).map_err(|e| {
+				error::Error::Input(format!("Failed to read the imports of {runtime}: {e}"))
+			})?;
+
+			for name in imported {
+				imports.entry(name).or_default().push(runtime.clone());
+			}
+		}
+
+		let provided =
+			H::host_functions().iter().map(|f| f.name().to_owned()).collect::<BTreeSet<_>>();
+
+		println!("Scanned {} runtimes.", runtimes.len());
+
+		println!("\nImported host functions:");
+		for (name, runtimes) in &imports {
+			println!("  {name}: {} runtimes, first {}", runtimes.len(), runtimes[0]);
+		}
+
+		println!("\nImported host functions not provided natively, which need shims:");
+		for (name, runtimes) in imports.iter().filter(|(name, _)| !provided.contains(*name)) {
+			println!("  {name}: {}", runtimes.join(", "));
+		}
+
+		println!("\nProvided host functions not imported by any runtime, which can be dropped:");
+		for name in provided.iter().filter(|name| !imports.contains_key(*name)) {
+			println!("  {name}");
+		}
+
+		Ok(())
+	}
+}
+
+/// Returns all versions of the runtime code found in the database between block `from` and the
+/// best block, labeled by the first block they were found at.
+///
+/// The runtime code can only change in blocks with a [`DigestItem::RuntimeEnvironmentUpdated`],
+/// so the headers of all blocks are walked and the code is only read at the blocks carrying that
+/// digest. This also finds runtimes that were upgraded to and then reverted from again.
+fn runtimes_in_database<B, BA, C>(client: &C, from: u64) -> error::Result<Vec<(String, Vec<u8>)>>
+where
+	B: BlockT,
+	C: UsageProvider<B> + StorageProvider<B, BA> + HeaderBackend<B>,
+	BA: sc_client_api::backend::Backend<B>,
+{
+	let code_key = StorageKey(well_known_keys::CODE.to_vec());
+	let best = client.usage_info().chain.best_number.saturated_into::<u64>();
+
+	let hash_at = |number: u64| -> error::Result<B::Hash> {
+		client
+			.hash(number.saturated_into::<NumberFor<B>>())?
+			.ok_or_else(|| error::Error::Input(format!("Block #{number} not found")))
+	};
+
+	info!("Scanning blocks #{from}..=#{best} for runtime upgrades...");
+
+	let mut runtimes = Vec::new();
+	let mut code_hashes = BTreeSet::new();
+	for number in from..=best {
+		let hash = hash_at(number)?;
+		if number != from {
+			let header = client.header(hash)?.ok_or_else(|| {
+				error::Error::Input(format!("Header of block #{number} not found"))
+			})?;
+			let upgraded = header
+				.digest()
+				.logs()
+				.iter()
+				.any(|item| matches!(item, DigestItem::RuntimeEnvironmentUpdated));
+			if !upgraded {
+				continue
+			}
+		}
+
+		let no_code = || error::Error::Input(format!("No runtime code at block #{number}"));
+		let code_hash = client.storage_hash(hash, &code_key)?.ok_or_else(no_code)?;
+		if code_hashes.insert(code_hash) {
+			let code = client.storage(hash, &code_key)?.ok_or_else(no_code)?;
+			runtimes.push((format!("#{number}"), code.0));
+		}
+	}
+
+	Ok(runtimes)
+}
+
+impl CliConfiguration for HostFunctionsCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_block_builder::BlockBuilderBuilder;
+	use substrate_test_runtime_client::{prelude::*, runtime};
+
+	#[test]
+	fn runtimes_in_database_finds_reverted_upgrades() {
+		let client = substrate_test_runtime_client::new();
+		let code_key = StorageKey(well_known_keys::CODE.to_vec());
+		let code_a =
+			client.storage(client.chain_info().genesis_hash, &code_key).unwrap().unwrap().0;
+		let wasm = sp_maybe_compressed_blob::decompress(
+			&code_a,
+			sp_maybe_compressed_blob::CODE_BLOB_BOMB_LIMIT,
+		)
+		.unwrap();
+		let mut version = runtime::VERSION;
+		version.spec_version += 1;
+		let code_b = sp_version::embed::embed_runtime_version(&wasm, version).unwrap();
+
+		// Upgrade to `code_b` in block #1, revert to `code_a` in block #2 and leave the code
+		// untouched in block #3.
+		for code in [Some(code_b.clone()), Some(code_a.clone()), None] {
+			let mut builder = BlockBuilderBuilder::new(&client)
+				.on_parent_block(client.chain_info().best_hash)
+				.with_parent_block_number(client.chain_info().best_number)
+				.build()
+				.unwrap();
+			if let Some(code) = code {
+				builder.push_storage_change(well_known_keys::CODE.to_vec(), Some(code)).unwrap();
+				builder
+					.push_deposit_log_digest_item(DigestItem::RuntimeEnvironmentUpdated)
+					.unwrap();
+			}
+			let block = builder.build().unwrap().block;
+			futures::executor::block_on(client.import(BlockOrigin::Own, block)).unwrap();
+		}
+		let best_code = client.storage(client.chain_info().best_hash, &code_key).unwrap();
+		assert_eq!(best_code.unwrap().0, code_a);
+
+		assert_eq!(
+			runtimes_in_database(&client, 0).unwrap(),
+			vec![("#0".to_owned(), code_a.clone()), ("#1".to_owned(), code_b)]
+		);
+		assert_eq!(runtimes_in_database(&client, 2).unwrap(), vec![("#2".to_owned(), code_a)]);
+	}
+}
@@ -25,12 +25,14 @@ mod export_blocks_cmd;
 mod export_state_cmd;
 mod generate;
 mod generate_node_key;
+mod host_functions_cmd;
 mod import_blocks_cmd;
 mod insert_key;
 mod inspect_key;
 mod inspect_node_key;
 mod key;
 mod purge_chain_cmd;
+mod replay_blocks_cmd;
 mod revert_cmd;
 mod run_cmd;
 mod sign;
@@ -42,8 +44,9 @@ mod verify;
 pub use self::{
 	build_spec_cmd::BuildSpecCmd, chain_info_cmd::ChainInfoCmd, check_block_cmd::CheckBlockCmd,
 	export_blocks_cmd::ExportBlocksCmd, export_state_cmd::ExportStateCmd, generate::GenerateCmd,
-	generate_node_key::GenerateKeyCmdCommon, import_blocks_cmd::ImportBlocksCmd,
-	insert_key::InsertKeyCmd, inspect_key::InspectKeyCmd, inspect_node_key::InspectNodeKeyCmd,
-	key::KeySubcommand, purge_chain_cmd::PurgeChainCmd, revert_cmd::RevertCmd, run_cmd::RunCmd,
-	sign::SignCmd, vanity::VanityCmd, verify::VerifyCmd,
+	generate_node_key::GenerateKeyCmdCommon, host_functions_cmd::HostFunctionsCmd,
+	import_blocks_cmd::ImportBlocksCmd, insert_key::InsertKeyCmd, inspect_key::InspectKeyCmd,
+	inspect_node_key::InspectNodeKeyCmd, key::KeySubcommand, purge_chain_cmd::PurgeChainCmd,
+	replay_blocks_cmd::ReplayBlocksCmd, revert_cmd::RevertCmd, run_cmd::RunCmd, sign::SignCmd,
+	vanity::VanityCmd, verify::VerifyCmd,
 };
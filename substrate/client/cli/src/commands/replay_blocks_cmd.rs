@@ -0,0 +1,508 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	error,
+	params::{BlockNumberOrHash, DatabaseParams, PruningParams, SharedParams},
+	CliConfiguration,
+};
+use clap::Parser;
+use codec::{Compact, Decode, Encode};
+use sc_client_api::{backend::Backend, BlockBackend, HeaderBackend};
+use sc_executor::{HeapAllocStrategy, HostFunctions, RuntimeVersionOf, WasmExecutor};
+use sp_core::{
+	storage::well_known_keys::CODE,
+	traits::{CallContext, ReadRuntimeVersionExt, RuntimeCode, WrappedRuntimeCode},
+	twox_128,
+};
+use sp_externalities::Extensions;
+use sp_runtime::{
+	traits::{Block as BlockT, HashingFor, Header as HeaderT, One, Saturating},
+	ApplyExtrinsicResult,
+};
+use sp_state_machine::{
+	backend::BackendRuntimeCode, Backend as StateBackend, BasicExternalities, OverlayedChanges,
+	StateMachine,
+};
+use std::{borrow::Cow, fmt::Debug, fs, path::PathBuf, str::FromStr, sync::Arc};
+
+/// The `replay-blocks` command used to test a runtime upgrade against real blocks.
+///
+/// It forks the local chain at a given block, swaps in a new runtime and replays the extrinsics of
+/// the following blocks on top of the fork. The blocks are also re-executed with the runtime they
+/// were originally imported with, and the outcomes of both executions are compared. Everything
+/// happens offline on the local database; the fork is never written to it.
+#[derive(Debug, Clone, Parser)]
+pub struct ReplayBlocksCmd {
+	/// Block hash or number to fork at. The new runtime is used from the next block on.
+	#[arg(value_name = "HASH or NUMBER")]
+	pub input: BlockNumberOrHash,
+
+	/// Path to the runtime wasm blob to swap in at the fork.
+	#[arg(long, value_name = "PATH")]
+	pub runtime: PathBuf,
+
+	/// The number of blocks after the fork to replay.
+	#[arg(long, value_name = "COUNT", default_value_t = 10)]
+	pub blocks: u32,
+
+	/// Run the `try_state` hooks of all pallets after each replayed block.
+	///
+	/// The runtime passed to `--runtime` must be compiled with the `try-runtime` feature.
+	#[arg(long)]
+	pub try_state: bool,
+
+	/// The name of the `frame_system` pallet in the runtime, used to read its events and weight.
+	#[arg(long, value_name = "NAME", default_value = "System")]
+	pub system_pallet: String,
+
+	/// The default number of 64KB pages to ever allocate for Wasm execution.
+	/// Don't alter this unless you know what you're doing.
+	#[arg(long, value_name = "COUNT")]
+	pub default_heap_pages: Option<u32>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+/// The outcome of executing a single block.
+#[derive(Debug, Default)]
+struct BlockOutcome {
+	/// Why the runtime failed to initialize or finalize the block, if it did.
+	failure: Option<String>,
+	/// The result of applying each extrinsic of the block, or why the runtime failed to apply it.
+	extrinsics: Vec<Result<ApplyExtrinsicResult, String>>,
+	/// The encoded `System::Events` after the block.
+	events: Vec<u8>,
+	/// The `ref_time` and `proof_size` of `System::BlockWeight` after the block.
+	weight: (u64, u64),
+}
+
+impl BlockOutcome {
+	fn event_count(&self) -> u32 {
+		Compact::<u32>::decode(&mut &self.events[..]).map_or(0, |c| c.0)
+	}
+}
+
+impl ReplayBlocksCmd {
+	/// Run the `replay-blocks` command
+	///
+	/// `HF` are the host functions natively provided by the node.
+	pub async fn run<B, BA, C, HF>(&self, client: Arc<C>, backend: Arc<BA>) -> error::Result<()>
+	where
+		B: BlockT,
+		BA: Backend<B>,
+		C: BlockBackend<B> + HeaderBackend<B>,
+		HF: HostFunctions,
+		<B::Hash as FromStr>::Err: Debug,
+		<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
+	{
+		let fork_hash = client.expect_block_hash_from_id(&self.input.parse()?)?;
+		let fork_number = *client.expect_header(fork_hash)?.number();
+
+		let code = fs::read(&self.runtime)?;
+		let wrapped_code = WrappedRuntimeCode(Cow::Borrowed(&code));
+		let new_runtime_code = RuntimeCode {
+			code_fetcher: &wrapped_code,
+			heap_pages: None,
+			hash: sp_core::blake2_256(&code).to_vec(),
+		};
+
+		let heap_alloc_strategy =
+			self.default_heap_pages.map_or(sc_executor::DEFAULT_HEAP_ALLOC_STRATEGY, |p| {
+				HeapAllocStrategy::Static { extra_pages: p }
+			});
+		let executor = WasmExecutor::<HF>::builder()
+			.with_onchain_heap_alloc_strategy(heap_alloc_strategy)
+			.with_offchain_heap_alloc_strategy(heap_alloc_strategy)
+			.with_allow_missing_host_functions(true)
+			.build();
+
+		if self.try_state {
+			let version = executor
+				.runtime_version(&mut BasicExternalities::default(), &new_runtime_code)
+				.map_err(|e| format!("Failed to read the version of {:?}: {e}", self.runtime))?;
+			// The id of a runtime API is the `blake2_64` hash of its name.
+			if !version.has_api_with(&sp_core::blake2_64(b"TryRuntime"), |_| true) {
+				return Err(format!(
+					"{:?} does not implement `TryRuntime_execute_block`, which `--try-state` \
+					requires. Compile it with the `try-runtime` feature.",
+					self.runtime
+				)
+				.into())
+			}
+		}
+
+		// All changes of the fork are kept in this overlay on top of the state of the fork block.
+		let fork_state = backend.state_at(fork_hash)?;
+		let mut fork_overlay = OverlayedChanges::default();
+		fork_overlay.set_storage(CODE.to_vec(), Some(code.clone()));
+
+		println!("Forking at block #{fork_number} ({fork_hash}) with runtime {:?}", self.runtime);
+
+		let mut issues = 0;
+		let mut number = fork_number;
+		for _ in 0..self.blocks {
+			number = number.saturating_add(One::one());
+			let Some(hash) = client.hash(number)? else {
+				println!("Block #{number} is not in the database. Stopping.");
+				break
+			};
+			let block = client
+				.block(hash)?
+				.ok_or_else(|| format!("Body of block #{number} ({hash}) is not in the database"))?
+				.block;
+
+			let parent_state = backend.state_at(*block.header().parent_hash())?;
+			let onchain_code = BackendRuntimeCode::new(&parent_state);
+			let original = apply_block(
+				&executor,
+				&parent_state,
+				&mut OverlayedChanges::default(),
+				&onchain_code.runtime_code()?,
+				&block,
+				&self.system_pallet,
+			)?;
+
+			let try_state = self.try_state.then(|| {
+				// `frame_try_runtime::TryRuntime::execute_block(block, state_root_check: false,
+				// signature_check: false, TryStateSelect::All)`
+				let data = (&block, false, false, 1u8).encode();
+				call(
+					&executor,
+					&fork_state,
+					&mut fork_overlay.clone(),
+					&new_runtime_code,
+					"TryRuntime_execute_block",
+					&data,
+				)
+			});
+			let forked = apply_block(
+				&executor,
+				&fork_state,
+				&mut fork_overlay,
+				&new_runtime_code,
+				&block,
+				&self.system_pallet,
+			)?;
+
+			println!("Block #{number} ({hash}):");
+			issues += report(&original, &forked);
+			if let Some(Err(e)) = try_state {
+				println!("  try-state failed: {e}");
+				issues += 1;
+			}
+		}
+
+		if issues > 0 {
+			return Err(format!("Found {issues} issues while replaying blocks").into())
+		}
+		println!("Replayed blocks without issues.");
+		Ok(())
+	}
+}
+
+/// Print how `forked` differs from `original` and return the number of issues.
+fn report(original: &BlockOutcome, forked: &BlockOutcome) -> usize {
+	if original.failure.is_some() || forked.failure.is_some() {
+		let describe = |outcome: &BlockOutcome| {
+			outcome.failure.clone().unwrap_or_else(|| "executed".to_string())
+		};
+		if original.failure == forked.failure {
+			println!("  block: {} (also failed originally)", describe(forked));
+			return 0
+		}
+		println!("  block: {} -> {}", describe(original), describe(forked));
+		return 1
+	}
+
+	let mut issues = 0;
+	for (i, (original, forked)) in original.extrinsics.iter().zip(&forked.extrinsics).enumerate() {
+		let failed = !matches!(forked, Ok(Ok(Ok(()))));
+		if original != forked {
+			println!("  extrinsic {i}: {original:?} -> {forked:?}");
+			issues += 1;
+		} else if failed {
+			println!("  extrinsic {i}: {forked:?} (also failed originally)");
+		}
+	}
+
+	if original.events != forked.events {
+		println!(
+			"  events diverged: {} -> {} events",
+			original.event_count(),
+			forked.event_count()
+		);
+		issues += 1;
+	}
+
+	let (ref_time, proof_size) = original.weight;
+	let (new_ref_time, new_proof_size) = forked.weight;
+	if original.weight != forked.weight {
+		println!(
+			"  weight: ref_time {ref_time} -> {new_ref_time}, \
+			proof_size {proof_size} -> {new_proof_size}"
+		);
+	}
+
+	issues
+}
+
+/// Execute `block` with `runtime_code` on top of `state` and `overlay`.
+///
+/// The extrinsics are applied one by one instead of executing the whole block, so that the
+/// outcome of each one is known and a block that does not match the new runtime can be replayed.
+/// If the runtime fails to apply an extrinsic, its changes are discarded and the replay continues
+/// with the next one. If it fails to initialize or finalize the block, all changes of the block
+/// are discarded.
+fn apply_block<B, S, HF>(
+	executor: &WasmExecutor<HF>,
+	state: &S,
+	overlay: &mut OverlayedChanges<HashingFor<B>>,
+	runtime_code: &RuntimeCode,
+	block: &B,
+	system_pallet: &str,
+) -> error::Result<BlockOutcome>
+where
+	B: BlockT,
+	S: StateBackend<HashingFor<B>>,
+	HF: HostFunctions,
+{
+	let mut outcome = BlockOutcome::default();
+	overlay.start_transaction();
+
+	let initialized = call(
+		executor,
+		state,
+		overlay,
+		runtime_code,
+		"Core_initialize_block",
+		&block.header().encode(),
+	);
+	if let Err(e) = initialized {
+		overlay.rollback_transaction().expect("Started above; qed");
+		outcome.failure = Some(e.to_string());
+		return Ok(outcome)
+	}
+
+	for extrinsic in block.extrinsics() {
+		overlay.start_transaction();
+		let result = call(
+			executor,
+			state,
+			overlay,
+			runtime_code,
+			"BlockBuilder_apply_extrinsic",
+			&extrinsic.encode(),
+		)
+		.and_then(|result| {
+			ApplyExtrinsicResult::decode(&mut &result[..])
+				.map_err(|e| format!("Failed to decode the result of an extrinsic: {e}").into())
+		});
+		match result {
+			Ok(result) => {
+				overlay.commit_transaction().expect("Started above; qed");
+				outcome.extrinsics.push(Ok(result));
+			},
+			Err(e) => {
+				overlay.rollback_transaction().expect("Started above; qed");
+				outcome.extrinsics.push(Err(e.to_string()));
+			},
+		}
+	}
+
+	let finalized =
+		call(executor, state, overlay, runtime_code, "BlockBuilder_finalize_block", &[]);
+	if let Err(e) = finalized {
+		overlay.rollback_transaction().expect("Started above; qed");
+		outcome.failure = Some(e.to_string());
+		return Ok(outcome)
+	}
+	overlay.commit_transaction().expect("Started above; qed");
+
+	outcome.events =
+		storage(state, overlay, &system_key(system_pallet, b"Events"))?.unwrap_or_default();
+	outcome.weight = storage(state, overlay, &system_key(system_pallet, b"BlockWeight"))?
+		.and_then(|weight| decode_block_weight(&weight))
+		.unwrap_or_default();
+
+	Ok(outcome)
+}
+
+/// Decode `System::BlockWeight`, a `PerDispatchClass<Weight>`, into the total `ref_time` and
+/// `proof_size` of all dispatch classes.
+fn decode_block_weight(encoded: &[u8]) -> Option<(u64, u64)> {
+	let classes = <[(Compact<u64>, Compact<u64>); 3]>::decode(&mut &encoded[..]).ok()?;
+	Some(classes.iter().fold((0, 0), |(r, p), (ref_time, proof_size)| {
+		(r.saturating_add(ref_time.0), p.saturating_add(proof_size.0))
+	}))
+}
+
+/// Call the runtime function `method` with `data`.
+fn call<H, S, HF>(
+	executor: &WasmExecutor<HF>,
+	state: &S,
+	overlay: &mut OverlayedChanges<H>,
+	runtime_code: &RuntimeCode,
+	method: &str,
+	data: &[u8],
+) -> error::Result<Vec<u8>>
+where
+	H: sp_core::Hasher,
+	H::Out: Ord + codec::Codec + 'static,
+	S: StateBackend<H>,
+	HF: HostFunctions,
+{
+	let mut extensions = Extensions::default();
+	extensions.register(ReadRuntimeVersionExt::new(executor.clone()));
+
+	StateMachine::new(
+		state,
+		overlay,
+		executor,
+		method,
+		data,
+		&mut extensions,
+		runtime_code,
+		CallContext::Onchain,
+	)
+	.execute()
+	.map_err(|e| format!("Calling `{method}` failed: {e}").into())
+}
+
+/// Read `key` from `overlay`, falling back to `state`.
+fn storage<H, S>(
+	state: &S,
+	overlay: &mut OverlayedChanges<H>,
+	key: &[u8],
+) -> error::Result<Option<Vec<u8>>>
+where
+	H: sp_core::Hasher,
+	S: StateBackend<H>,
+{
+	match overlay.storage(key) {
+		Some(value) => Ok(value.map(|v| v.to_vec())),
+		None => state.storage(key).map_err(|e| format!("Failed to read storage: {e}").into()),
+	}
+}
+
+/// The storage key of the `StorageValue` `item` of the `frame_system` pallet named `pallet`.
+fn system_key(pallet: &str, item: &[u8]) -> Vec<u8> {
+	[twox_128(pallet.as_bytes()), twox_128(item)].concat()
+}
+
+impl CliConfiguration for ReplayBlocksCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_runtime::{
+		transaction_validity::{InvalidTransaction, TransactionValidityError},
+		DispatchError,
+	};
+
+	fn outcome(extrinsics: Vec<Result<ApplyExtrinsicResult, String>>) -> BlockOutcome {
+		BlockOutcome { extrinsics, events: vec![0], ..Default::default() }
+	}
+
+	#[test]
+	fn report_counts_diverging_extrinsics() {
+		let original = outcome(vec![
+			Ok(Ok(Ok(()))),
+			Ok(Ok(Err(DispatchError::BadOrigin))),
+			Ok(Ok(Ok(()))),
+			Ok(Ok(Ok(()))),
+		]);
+		let forked = outcome(vec![
+			Ok(Ok(Ok(()))),
+			// Failed the same way before, which is not an issue.
+			Ok(Ok(Err(DispatchError::BadOrigin))),
+			Ok(Err(TransactionValidityError::Invalid(InvalidTransaction::Call))),
+			Err("wasm trap".into()),
+		]);
+
+		assert_eq!(report(&original, &original), 0);
+		assert_eq!(report(&original, &forked), 2);
+	}
+
+	#[test]
+	fn report_compares_events_but_not_weight() {
+		let original = outcome(vec![Ok(Ok(Ok(())))]);
+		let mut forked = outcome(vec![Ok(Ok(Ok(())))]);
+		forked.weight = (1, 1);
+		assert_eq!(report(&original, &forked), 0);
+
+		forked.events = Compact(1u32).encode();
+		assert_eq!(report(&original, &forked), 1);
+	}
+
+	#[test]
+	fn report_compares_block_failures() {
+		let original = outcome(vec![Ok(Ok(Ok(())))]);
+		let failed = BlockOutcome { failure: Some("wasm trap".into()), ..Default::default() };
+
+		assert_eq!(report(&original, &failed), 1);
+		assert_eq!(report(&failed, &original), 1);
+		assert_eq!(report(&failed, &failed), 0);
+	}
+
+	#[test]
+	fn decode_block_weight_sums_dispatch_classes() {
+		// `PerDispatchClass<Weight>` of `normal`, `operational` and `mandatory`, where `Weight`
+		// consists of the compact `ref_time` and `proof_size`.
+		let weight = [
+			(Compact(1u64), Compact(10u64)),
+			(Compact(2u64), Compact(20u64)),
+			(Compact(u64::MAX), Compact(30u64)),
+		];
+		assert_eq!(decode_block_weight(&weight.encode()), Some((u64::MAX, 60)));
+		assert_eq!(decode_block_weight(&[1, 2]), None);
+	}
+
+	#[test]
+	fn system_key_uses_pallet_name() {
+		assert_eq!(
+			system_key("System", b"Events"),
+			array_bytes::hex2bytes_unchecked(
+				"26aa394eea5630e07c48ae0c9558cef780d41e5e16056765bc8461851072c9d7"
+			)
+		);
+		assert_ne!(system_key("Frame", b"Events"), system_key("System", b"Events"));
+	}
+}
@@ -33,7 +33,7 @@ use sc_service::{
 	ChainSpec, Role,
 };
 use sc_telemetry::TelemetryEndpoints;
-use std::num::NonZeroU32;
+use std::{num::NonZeroU32, path::PathBuf};
 
 /// The `run` command used to run a node.
 #[derive(Debug, Clone, Parser)]
@@ -342,6 +342,40 @@ impl CliConfiguration for RunCmd {
 		Ok(self.runtime_params.runtime_cache_size)
 	}
 
+	fn runtime_cache_path(&self) -> Result<Option<PathBuf>> {
+		Ok(self.runtime_params.runtime_cache_path.clone())
+	}
+
+	fn runtime_cache_max_size(&self) -> Result<u64> {
+		Ok(self.runtime_params.runtime_cache_max_size.saturating_mul(1024 * 1024))
+	}
+
+	fn host_function_shims(&self) -> Result<sc_executor::HostFunctionShims> {
+		let shims = self
+			.runtime_params
+			.host_function_shim
+			.iter()
+			.map(|path| {
+				std::fs::read(path).map_err(|e| {
+					Error::Input(format!(
+						"Failed to read the host function shim {}: {e}",
+						path.display()
+					))
+				})
+			})
+			.collect::<Result<Vec<_>>>()?;
+
+		Ok(sc_executor::HostFunctionShims::new(shims))
+	}
+
+	fn precompile_runtime_upgrades(&self) -> Result<bool> {
+		Ok(!self.runtime_params.no_runtime_precompile)
+	}
+
+	fn runtime_api_cache_size(&self) -> Result<usize> {
+		Ok(self.runtime_params.runtime_api_cache_size)
+	}
+
 	fn base_path(&self) -> Result<Option<BasePath>> {
 		Ok(if self.tmp {
 			Some(BasePath::new_temp_dir()?)
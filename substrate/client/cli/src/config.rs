@@ -466,6 +466,42 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(2)
 	}
 
+	/// Get the directory in which the compiled runtimes are persisted
+	///
+	/// By default this is `None`.
+	fn runtime_cache_path(&self) -> Result<Option<PathBuf>> {
+		Ok(None)
+	}
+
+	/// Get the maximum size in bytes of the compiled runtimes persisted in the
+	/// `runtime_cache_path`
+	///
+	/// By default this is [`sc_executor::DEFAULT_RUNTIME_CACHE_MAX_SIZE`].
+	fn runtime_cache_max_size(&self) -> Result<u64> {
+		Ok(sc_executor::DEFAULT_RUNTIME_CACHE_MAX_SIZE)
+	}
+
+	/// Get the wasm modules providing host functions which are not implemented natively
+	///
+	/// By default no shims are used.
+	fn host_function_shims(&self) -> Result<sc_executor::HostFunctionShims> {
+		Ok(Default::default())
+	}
+
+	/// Returns `true` if runtime upgrades should be compiled ahead of time
+	///
+	/// By default this is `true`.
+	fn precompile_runtime_upgrades(&self) -> Result<bool> {
+		Ok(true)
+	}
+
+	/// Get the maximum number of cached results of pure runtime api calls
+	///
+	/// By default this is `0`, which disables the cache.
+	fn runtime_api_cache_size(&self) -> Result<usize> {
+		Ok(0)
+	}
+
 	/// Activate or not the automatic announcing of blocks after import
 	///
 	/// By default this is `false`.
@@ -535,6 +571,12 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 				default_heap_pages: self.default_heap_pages()?,
 				max_runtime_instances,
 				runtime_cache_size,
+				runtime_cache_path: self.runtime_cache_path()?,
+				runtime_cache_max_size: self.runtime_cache_max_size()?,
+				precompile_runtime_upgrades: self.precompile_runtime_upgrades()?,
+				pending_code_storage_keys: Vec::new(),
+				runtime_api_cache_size: self.runtime_api_cache_size()?,
+				host_function_shims: self.host_function_shims()?,
 			},
 			wasm_runtime_overrides: self.wasm_runtime_overrides(),
 			rpc: RpcConfiguration {
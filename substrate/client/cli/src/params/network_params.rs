@@ -23,15 +23,30 @@ use crate::{
 use clap::Args;
 use sc_network::{
 	config::{
-		NetworkConfiguration, NodeKeyConfig, NonReservedPeerMode, SetConfig, TransportConfig,
+		BandwidthLimit, NetworkConfiguration, NodeKeyConfig, NonReservedPeerMode, SetConfig,
+		TransportConfig,
 	},
 	multiaddr::Protocol,
+	ProtocolName,
 };
 use sc_service::{
 	config::{Multiaddr, MultiaddrWithPeerId},
 	ChainSpec, ChainType,
 };
-use std::{borrow::Cow, num::NonZeroUsize, path::PathBuf};
+use std::{
+	borrow::Cow,
+	num::{NonZeroU64, NonZeroUsize},
+	path::PathBuf,
+};
+
+fn parse_bandwidth_limit(s: &str) -> Result<(ProtocolName, BandwidthLimit), String> {
+	let (protocol, bytes_per_second) = s
+		.rsplit_once('=')
+		.ok_or_else(|| "expected `<PROTOCOL>=<BYTES_PER_SECOND>`".to_string())?;
+	let bytes_per_second = bytes_per_second.parse::<NonZeroU64>().map_err(|err| err.to_string())?;
+
+	Ok((protocol.to_string().into(), BandwidthLimit::new(bytes_per_second)))
+}
 
 /// Parameters used to create the network configuration.
 #[derive(Debug, Clone, Args)]
@@ -65,6 +80,12 @@ pub struct NetworkParams {
 	/// By default:
 	/// If `--validator` is passed: `/ip4/0.0.0.0/tcp/<port>` and `/ip6/[::]/tcp/<port>`.
 	/// Otherwise: `/ip4/0.0.0.0/tcp/<port>/ws` and `/ip6/[::]/tcp/<port>/ws`.
+	///
+	/// The litep2p network backend additionally supports QUIC (`/ip4/0.0.0.0/udp/<port>/quic-v1`)
+	/// and WebRTC (`/ip4/0.0.0.0/udp/<port>/webrtc-direct`) listen addresses. These transports
+	/// are only enabled if at least one address of the transport is given, and a node must
+	/// listen on QUIC in order to dial other nodes over QUIC. WebRTC only accepts inbound
+	/// connections, e.g. from browsers.
 	#[arg(long, value_name = "LISTEN_ADDR", num_args = 1..)]
 	pub listen_addr: Vec<Multiaddr>,
 
@@ -152,6 +173,14 @@ pub struct NetworkParams {
 	#[arg(long)]
 	pub ipfs_server: bool,
 
+	/// Gossip transactions by announcing their hashes.
+	///
+	/// Peers supporting it are only sent the hashes of new transactions and request the
+	/// transactions they are missing, which avoids sending the same transaction to a node multiple
+	/// times. Other peers keep receiving full transactions.
+	#[arg(long)]
+	pub transaction_hash_announcements: bool,
+
 	/// Blockchain syncing mode.
 	#[arg(
 		long,
@@ -183,6 +212,15 @@ pub struct NetworkParams {
 		verbatim_doc_comment
 	)]
 	pub network_backend: NetworkBackendType,
+
+	/// Limit the upload rate of a notification or request-response protocol.
+	///
+	/// Given as `<PROTOCOL>=<BYTES_PER_SECOND>`, e.g.
+	/// `--bandwidth-limit /<genesis hash>/transactions/1=1048576`. Can be given multiple times.
+	/// Bursts of one second worth of traffic are allowed. Limits of block announcements and sync
+	/// requests are ignored.
+	#[arg(long, value_name = "PROTOCOL=BYTES_PER_SECOND", value_parser = parse_bandwidth_limit)]
+	pub bandwidth_limit: Vec<(ProtocolName, BandwidthLimit)>,
 }
 
 impl NetworkParams {
@@ -277,8 +315,10 @@ impl NetworkParams {
 			kademlia_replication_factor: self.kademlia_replication_factor,
 			yamux_window_size: None,
 			ipfs_server: self.ipfs_server,
+			transaction_hash_announcements: self.transaction_hash_announcements,
 			sync_mode: self.sync.into(),
 			network_backend: self.network_backend.into(),
+			bandwidth_limits: self.bandwidth_limit.iter().cloned().collect(),
 		}
 	}
 }
@@ -327,10 +367,61 @@ mod tests {
 		assert_eq!(expected, params.network_params.reserved_nodes);
 	}
 
+	#[test]
+	fn bandwidth_limit_multiple_occurrences() {
+		let params = Cli::try_parse_from([
+			"",
+			"--bandwidth-limit",
+			"/genesis/transactions/1=1024",
+			"--bandwidth-limit",
+			"/genesis/statement/1=2048",
+		])
+		.expect("Parses network params");
+
+		assert_eq!(
+			params.network_params.bandwidth_limit,
+			vec![
+				(
+					ProtocolName::from("/genesis/transactions/1"),
+					BandwidthLimit::new(NonZeroU64::new(1024).unwrap()),
+				),
+				(
+					ProtocolName::from("/genesis/statement/1"),
+					BandwidthLimit::new(NonZeroU64::new(2048).unwrap()),
+				),
+			],
+		);
+
+		assert!(Cli::try_parse_from(["", "--bandwidth-limit", "/genesis/transactions/1"]).is_err());
+		assert!(
+			Cli::try_parse_from(["", "--bandwidth-limit", "/genesis/transactions/1=0"]).is_err()
+		);
+	}
+
 	#[test]
 	fn sync_ignores_case() {
 		let params = Cli::try_parse_from(["", "--sync", "wArP"]).expect("Parses network params");
 
 		assert_eq!(SyncMode::Warp, params.network_params.sync);
 	}
+
+	#[test]
+	fn listen_addr_accepts_quic_and_webrtc() {
+		let params = Cli::try_parse_from([
+			"",
+			"--listen-addr",
+			"/ip4/0.0.0.0/tcp/30333",
+			"/ip4/0.0.0.0/udp/30333/quic-v1",
+			"/ip6/::/udp/30334/webrtc-direct",
+		])
+		.expect("Parses network params");
+
+		let expected: Vec<Multiaddr> = vec![
+			"/ip4/0.0.0.0/tcp/30333".parse().unwrap(),
+			"/ip4/0.0.0.0/udp/30333/quic-v1".parse().unwrap(),
+			"/ip6/::/udp/30334/webrtc-direct".parse().unwrap(),
+		];
+
+		assert_eq!(expected, params.network_params.listen_addr);
+	}
 }
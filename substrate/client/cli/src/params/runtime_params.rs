@@ -17,7 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use clap::Args;
-use std::str::FromStr;
+use std::{path::PathBuf, str::FromStr};
 
 /// Parameters used to config runtime.
 #[derive(Debug, Clone, Args)]
@@ -31,6 +31,41 @@ pub struct RuntimeParams {
 	/// Maximum number of different runtimes that can be cached.
 	#[arg(long, default_value_t = 2)]
 	pub runtime_cache_size: u8,
+
+	/// Directory in which the compiled runtimes are persisted.
+	///
+	/// Persisted runtimes don't need to be compiled again after a restart. The directory can be
+	/// shared by several nodes running on the same host, e.g. a collator and an RPC node. The
+	/// least recently used runtimes are evicted once the cache exceeds
+	/// `--runtime-cache-max-size`.
+	#[arg(long, value_name = "PATH")]
+	pub runtime_cache_path: Option<PathBuf>,
+
+	/// Maximum size in MiB of the runtimes persisted in `--runtime-cache-path`.
+	#[arg(long, value_name = "MiB", default_value_t = 1024)]
+	pub runtime_cache_max_size: u64,
+
+	/// Wasm module providing host functions which runtimes import, but the node doesn't
+	/// implement natively.
+	///
+	/// Allows executing historic runtimes that import deprecated host functions. Can be given
+	/// multiple times. The `host-functions` subcommand reports which host functions need shims.
+	#[arg(long, value_name = "PATH")]
+	pub host_function_shim: Vec<PathBuf>,
+
+	/// Don't compile runtime upgrades ahead of time.
+	///
+	/// By default new runtime code is compiled in the background as soon as it lands in the state
+	/// of the best block, so that the first block executed with it doesn't stall.
+	#[arg(long)]
+	pub no_runtime_precompile: bool,
+
+	/// Maximum number of results of pure runtime api calls to cache.
+	///
+	/// Only runtime api functions declared as `#[pure]` are cached, keyed by block hash, function
+	/// and arguments. `0` disables the cache.
+	#[arg(long, value_name = "COUNT", default_value_t = 0)]
+	pub runtime_api_cache_size: usize,
 }
 
 fn parse_max_runtime_instances(s: &str) -> Result<usize, String> {
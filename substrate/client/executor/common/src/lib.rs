@@ -25,7 +25,3 @@ pub mod error;
 pub mod runtime_blob;
 pub mod util;
 pub mod wasm_runtime;
-
-pub(crate) fn is_polkavm_enabled() -> bool {
-	std::env::var_os("SUBSTRATE_ENABLE_POLKAVM").map_or(false, |value| value == "1")
-}
@@ -33,31 +33,49 @@ enum BlobKind {
 }
 
 impl RuntimeBlob {
-	/// Create `RuntimeBlob` from the given WASM or PolkaVM compressed program blob.
+	/// Create `RuntimeBlob` from the given compressed WASM program blob.
 	///
 	/// See [`sp_maybe_compressed_blob`] for details about decompression.
 	pub fn uncompress_if_needed(wasm_code: &[u8]) -> Result<Self, WasmError> {
+		Self::new(&Self::decompress(wasm_code)?)
+	}
+
+	/// Create `RuntimeBlob` from the given WASM or PolkaVM compressed program blob.
+	///
+	/// See [`sp_maybe_compressed_blob`] for details about decompression.
+	pub fn uncompress_if_needed_with_polkavm(code: &[u8]) -> Result<Self, WasmError> {
+		Self::new_with_polkavm(&Self::decompress(code)?)
+	}
+
+	fn decompress(code: &[u8]) -> Result<std::borrow::Cow<[u8]>, WasmError> {
 		use sp_maybe_compressed_blob::CODE_BLOB_BOMB_LIMIT;
-		let wasm_code = sp_maybe_compressed_blob::decompress(wasm_code, CODE_BLOB_BOMB_LIMIT)
-			.map_err(|e| WasmError::Other(format!("Decompression error: {:?}", e)))?;
-		Self::new(&wasm_code)
+		sp_maybe_compressed_blob::decompress(code, CODE_BLOB_BOMB_LIMIT)
+			.map_err(|e| WasmError::Other(format!("Decompression error: {:?}", e)))
+	}
+
+	/// Create `RuntimeBlob` from the given WASM program blob.
+	///
+	/// Returns `Err` if the blob cannot be deserialized or is a PolkaVM program. PolkaVM programs
+	/// are only accepted by [`RuntimeBlob::new_with_polkavm`], which must only be used when PolkaVM
+	/// has been explicitly enabled, e.g. by selecting the PolkaVM execution method.
+	pub fn new(raw_blob: &[u8]) -> Result<Self, WasmError> {
+		if raw_blob.starts_with(b"PVM\0") {
+			return Err(WasmError::Other(
+				"expected a WASM runtime blob, found a PolkaVM runtime blob; PolkaVM must be \
+				 enabled explicitly to execute PolkaVM runtimes"
+					.into(),
+			))
+		}
+
+		Self::new_with_polkavm(raw_blob)
 	}
 
 	/// Create `RuntimeBlob` from the given WASM or PolkaVM program blob.
 	///
 	/// Returns `Err` if the blob cannot be deserialized.
-	///
-	/// Will only accept a PolkaVM program if the `SUBSTRATE_ENABLE_POLKAVM` environment
-	/// variable is set to `1`.
-	pub fn new(raw_blob: &[u8]) -> Result<Self, WasmError> {
+	pub fn new_with_polkavm(raw_blob: &[u8]) -> Result<Self, WasmError> {
 		if raw_blob.starts_with(b"PVM\0") {
-			if crate::is_polkavm_enabled() {
-				return Ok(Self(BlobKind::PolkaVM(
-					polkavm::ProgramBlob::parse(raw_blob)?.into_owned(),
-				)));
-			} else {
-				return Err(WasmError::Other("expected a WASM runtime blob, found a PolkaVM runtime blob; set the 'SUBSTRATE_ENABLE_POLKAVM' environment variable to enable the experimental PolkaVM-based executor".to_string()));
-			}
+			return Ok(Self(BlobKind::PolkaVM(polkavm::ProgramBlob::parse(raw_blob)?.into_owned())))
 		}
 
 		let raw_module: Module = deserialize_buffer(raw_blob)
@@ -187,6 +205,29 @@ impl RuntimeBlob {
 			.map(|cs| cs.payload())
 	}
 
+	/// Returns the names of the functions imported from the `env` module, i.e. the host functions
+	/// the program requires.
+	///
+	/// Only valid for WASM programs; will return an error if the blob is a PolkaVM program.
+	pub fn imported_functions(&self) -> Result<Vec<String>, WasmError> {
+		let imports = self
+			.as_webassembly_blob()?
+			.import_section()
+			.map(|section| {
+				section
+					.entries()
+					.iter()
+					.filter(|entry| {
+						entry.module() == "env" && matches!(entry.external(), External::Function(_))
+					})
+					.map(|entry| entry.field().to_owned())
+					.collect()
+			})
+			.unwrap_or_default();
+
+		Ok(imports)
+	}
+
 	/// Consumes this runtime blob and serializes it.
 	pub fn serialize(self) -> Vec<u8> {
 		match self.0 {
@@ -69,6 +69,13 @@ pub trait WasmInstance: Send {
 	fn call_export(&mut self, method: &str, data: &[u8]) -> Result<Vec<u8>, Error> {
 		self.call(method.into(), data)
 	}
+
+	/// Returns the amount of fuel consumed by the last call.
+	///
+	/// Returns `None` if the execution isn't metered or nothing was called yet.
+	fn fuel_consumed(&self) -> Option<u64> {
+		None
+	}
 }
 
 /// Defines the heap pages allocation strategy the wasm runtime should use.
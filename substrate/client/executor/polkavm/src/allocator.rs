@@ -0,0 +1,274 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Allocator of the guest heap.
+//!
+//! Follows the same strategy as the freeing-bump allocator used for WASM runtimes: allocations
+//! are rounded up to the next power of two and freed blocks are kept in per-size free lists for
+//! reuse. New blocks are carved out of the guest heap with `sbrk`. The bookkeeping is kept on the
+//! host side, so the runtime can't corrupt it.
+
+use sc_executor_common::wasm_runtime::AllocationStats;
+use std::collections::HashMap;
+
+/// The smallest possible allocation, in bytes.
+const MIN_POSSIBLE_ALLOCATION: u32 = 8;
+
+/// The largest possible allocation, in bytes.
+const MAX_POSSIBLE_ALLOCATION: u32 = 32 * 1024 * 1024;
+
+/// Number of different allocation sizes.
+const N_ORDERS: usize = (MAX_POSSIBLE_ALLOCATION.trailing_zeros() -
+	MIN_POSSIBLE_ALLOCATION.trailing_zeros() +
+	1) as usize;
+
+/// Size of a heap page, in bytes.
+const PAGE_SIZE: u32 = 64 * 1024;
+
+/// Error message returned when the heap is exhausted.
+const OUT_OF_SPACE: &str = "Allocator ran out of space";
+
+/// Allocator of the guest heap.
+pub(crate) struct HeapAllocator {
+	/// Free blocks, by order.
+	free_lists: [Vec<u32>; N_ORDERS],
+	/// Orders of the allocated blocks, by address.
+	allocated: HashMap<u32, usize>,
+	/// Maximum number of bytes the allocator is allowed to carve out of the heap.
+	max_heap_size: Option<u32>,
+	/// Allocation statistics.
+	stats: AllocationStats,
+}
+
+impl HeapAllocator {
+	/// Create new [`HeapAllocator`] allowed to use `max_heap_pages` of the guest heap.
+	pub fn new(max_heap_pages: Option<u32>) -> Self {
+		Self {
+			free_lists: Default::default(),
+			allocated: HashMap::new(),
+			max_heap_size: max_heap_pages.map(|pages| pages.saturating_mul(PAGE_SIZE)),
+			stats: AllocationStats::default(),
+		}
+	}
+
+	/// Allocate `size` bytes, extending the guest heap with `sbrk` if needed.
+	///
+	/// `sbrk` grows the heap by the given number of bytes and returns the new end of the heap.
+	pub fn allocate(
+		&mut self,
+		sbrk: impl FnOnce(u32) -> Option<u32>,
+		size: u32,
+	) -> Result<u32, String> {
+		if size > MAX_POSSIBLE_ALLOCATION {
+			return Err(format!("Requested allocation size is too large: {size}"))
+		}
+
+		let order = Self::order(size);
+		let block_size = MIN_POSSIBLE_ALLOCATION << order;
+
+		let ptr = match self.free_lists[order].pop() {
+			Some(ptr) => ptr,
+			None => {
+				let address_space_used = self
+					.stats
+					.address_space_used
+					.checked_add(block_size)
+					.filter(|used| self.max_heap_size.map_or(true, |max| *used <= max))
+					.ok_or_else(|| OUT_OF_SPACE.to_owned())?;
+				let heap_end = sbrk(block_size).ok_or_else(|| OUT_OF_SPACE.to_owned())?;
+
+				self.stats.address_space_used = address_space_used;
+				heap_end - block_size
+			},
+		};
+
+		self.allocated.insert(ptr, order);
+		self.stats.bytes_allocated += block_size;
+		self.stats.bytes_allocated_sum += u128::from(block_size);
+		self.stats.bytes_allocated_peak =
+			self.stats.bytes_allocated_peak.max(self.stats.bytes_allocated);
+
+		Ok(ptr)
+	}
+
+	/// Deallocate the block at `ptr`.
+	pub fn deallocate(&mut self, ptr: u32) -> Result<(), String> {
+		let order = self
+			.allocated
+			.remove(&ptr)
+			.ok_or_else(|| format!("Invalid pointer for deallocation: {ptr}"))?;
+
+		self.free_lists[order].push(ptr);
+		self.stats.bytes_allocated -= MIN_POSSIBLE_ALLOCATION << order;
+
+		Ok(())
+	}
+
+	/// Get the allocation statistics.
+	pub fn stats(&self) -> AllocationStats {
+		self.stats.clone()
+	}
+
+	/// Get the order of the smallest block that fits `size` bytes.
+	fn order(size: u32) -> usize {
+		let size = size.max(MIN_POSSIBLE_ALLOCATION).next_power_of_two();
+		(size.trailing_zeros() - MIN_POSSIBLE_ALLOCATION.trailing_zeros()) as usize
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const HEAP_BASE: u32 = 0x1000;
+
+	/// Mock of the guest heap, grown with [`Heap::sbrk`].
+	struct Heap {
+		end: u32,
+		sbrk_calls: usize,
+	}
+
+	impl Heap {
+		fn new() -> Self {
+			Self { end: HEAP_BASE, sbrk_calls: 0 }
+		}
+
+		fn sbrk(&mut self) -> impl FnOnce(u32) -> Option<u32> + '_ {
+			|size| {
+				self.sbrk_calls += 1;
+				self.end = self.end.checked_add(size)?;
+				Some(self.end)
+			}
+		}
+	}
+
+	#[test]
+	fn order_rounds_up_to_power_of_two() {
+		assert_eq!(HeapAllocator::order(0), 0);
+		assert_eq!(HeapAllocator::order(1), 0);
+		assert_eq!(HeapAllocator::order(8), 0);
+		assert_eq!(HeapAllocator::order(9), 1);
+		assert_eq!(HeapAllocator::order(16), 1);
+		assert_eq!(HeapAllocator::order(17), 2);
+		assert_eq!(HeapAllocator::order(MAX_POSSIBLE_ALLOCATION), N_ORDERS - 1);
+	}
+
+	#[test]
+	fn allocations_do_not_overlap() {
+		let mut heap = Heap::new();
+		let mut allocator = HeapAllocator::new(None);
+
+		let first = allocator.allocate(heap.sbrk(), 5).unwrap();
+		let second = allocator.allocate(heap.sbrk(), 20).unwrap();
+		let third = allocator.allocate(heap.sbrk(), 8).unwrap();
+
+		assert_eq!(first, HEAP_BASE);
+		assert_eq!(second, HEAP_BASE + 8);
+		assert_eq!(third, HEAP_BASE + 8 + 32);
+		assert_eq!(heap.end, HEAP_BASE + 8 + 32 + 8);
+		assert_eq!(heap.sbrk_calls, 3);
+	}
+
+	#[test]
+	fn freed_blocks_are_reused() {
+		let mut heap = Heap::new();
+		let mut allocator = HeapAllocator::new(None);
+
+		let ptr = allocator.allocate(heap.sbrk(), 100).unwrap();
+		allocator.deallocate(ptr).unwrap();
+
+		// A block of the same order is reused without growing the heap.
+		assert_eq!(allocator.allocate(heap.sbrk(), 128).unwrap(), ptr);
+		assert_eq!(heap.sbrk_calls, 1);
+
+		// A block of another order is not.
+		allocator.deallocate(ptr).unwrap();
+		assert_eq!(allocator.allocate(heap.sbrk(), 129).unwrap(), ptr + 128);
+		assert_eq!(heap.sbrk_calls, 2);
+	}
+
+	#[test]
+	fn invalid_deallocations_are_rejected() {
+		let mut heap = Heap::new();
+		let mut allocator = HeapAllocator::new(None);
+
+		let ptr = allocator.allocate(heap.sbrk(), 8).unwrap();
+		assert!(allocator.deallocate(ptr + 1).is_err());
+
+		allocator.deallocate(ptr).unwrap();
+		assert_eq!(
+			allocator.deallocate(ptr),
+			Err(format!("Invalid pointer for deallocation: {ptr}"))
+		);
+	}
+
+	#[test]
+	fn too_large_allocations_are_rejected() {
+		let mut heap = Heap::new();
+		let mut allocator = HeapAllocator::new(None);
+
+		assert!(allocator.allocate(heap.sbrk(), MAX_POSSIBLE_ALLOCATION).is_ok());
+		assert_eq!(
+			allocator.allocate(heap.sbrk(), MAX_POSSIBLE_ALLOCATION + 1),
+			Err(format!("Requested allocation size is too large: {}", MAX_POSSIBLE_ALLOCATION + 1))
+		);
+	}
+
+	#[test]
+	fn heap_limit_is_respected() {
+		let mut heap = Heap::new();
+		let mut allocator = HeapAllocator::new(Some(1));
+
+		let ptr = allocator.allocate(heap.sbrk(), PAGE_SIZE / 2).unwrap();
+		allocator.allocate(heap.sbrk(), PAGE_SIZE / 2).unwrap();
+		assert_eq!(allocator.allocate(heap.sbrk(), 8), Err(OUT_OF_SPACE.to_owned()));
+		// The heap isn't grown past the limit.
+		assert_eq!(heap.sbrk_calls, 2);
+
+		// Freed blocks can still be reused.
+		allocator.deallocate(ptr).unwrap();
+		assert_eq!(allocator.allocate(heap.sbrk(), PAGE_SIZE / 2).unwrap(), ptr);
+	}
+
+	#[test]
+	fn failing_sbrk_is_out_of_space() {
+		let mut allocator = HeapAllocator::new(None);
+
+		assert_eq!(allocator.allocate(|_| None, 8), Err(OUT_OF_SPACE.to_owned()));
+		assert_eq!(allocator.stats().address_space_used, 0);
+		assert_eq!(allocator.stats().bytes_allocated, 0);
+	}
+
+	#[test]
+	fn stats_are_tracked() {
+		let mut heap = Heap::new();
+		let mut allocator = HeapAllocator::new(None);
+
+		let first = allocator.allocate(heap.sbrk(), 8).unwrap();
+		let second = allocator.allocate(heap.sbrk(), 16).unwrap();
+		allocator.deallocate(first).unwrap();
+		allocator.allocate(heap.sbrk(), 8).unwrap();
+		allocator.deallocate(second).unwrap();
+
+		let stats = allocator.stats();
+		assert_eq!(stats.bytes_allocated, 8);
+		assert_eq!(stats.bytes_allocated_peak, 24);
+		assert_eq!(stats.bytes_allocated_sum, 32);
+		assert_eq!(stats.address_space_used, 24);
+	}
+}
@@ -16,24 +16,139 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+//! PolkaVM executor for Substrate.
+//!
+//! Executes runtimes compiled to PolkaVM program blobs, exposing the same host functions and
+//! allocator semantics as the WASM executor.
+
+use parking_lot::Mutex;
 use polkavm::{Caller, Reg};
 use sc_executor_common::{
-	error::{Error, WasmError},
-	wasm_runtime::{AllocationStats, WasmInstance, WasmModule},
+	error::{Error, MessageWithBacktrace, WasmError},
+	wasm_runtime::{AllocationStats, HeapAllocStrategy, WasmInstance, WasmModule},
 };
 use sp_wasm_interface::{
 	Function, FunctionContext, HostFunctions, Pointer, Value, ValueType, WordSize,
 };
+use std::sync::{Arc, OnceLock};
+
+use allocator::HeapAllocator;
+
+mod allocator;
+
+/// The backend used to execute PolkaVM programs.
+#[derive(Debug, Default, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum Backend {
+	/// Use the compiler if it's supported on the current platform, otherwise the interpreter.
+	#[default]
+	Auto,
+	/// Compile the program into native code.
+	Compiler,
+	/// Interpret the program.
+	Interpreter,
+}
+
+impl Backend {
+	fn kind(self) -> Option<polkavm::BackendKind> {
+		match self {
+			Backend::Auto => None,
+			Backend::Compiler => Some(polkavm::BackendKind::Compiler),
+			Backend::Interpreter => Some(polkavm::BackendKind::Interpreter),
+		}
+	}
+}
+
+/// Configuration used to create a PolkaVM runtime.
+#[derive(Clone, Debug)]
+pub struct Config {
+	/// The backend used to execute the program.
+	pub backend: Backend,
+
+	/// The heap allocation strategy.
+	///
+	/// Limits how much of the guest heap the host allocator may use. As PolkaVM programs have no
+	/// notion of initial heap pages, `Static { extra_pages }` limits the heap to `extra_pages`.
+	pub heap_alloc_strategy: HeapAllocStrategy,
+
+	/// If `true`, calls to host functions that are not provided trap at runtime instead of
+	/// failing the runtime creation.
+	pub allow_missing_func_imports: bool,
+
+	/// The maximum number of idle instances kept around for reuse.
+	pub max_idle_instances: usize,
+
+	/// The amount of gas every call is given, or `None` to disable gas metering.
+	///
+	/// Gas is charged deterministically for every executed instruction, and a call which runs
+	/// out of gas is aborted with a trap.
+	pub gas_limit: Option<u64>,
+}
 
-#[repr(transparent)]
-pub struct InstancePre(polkavm::InstancePre<()>);
+/// State of a single call into the runtime, accessible from the host functions.
+struct HostState {
+	/// The allocator of the guest heap.
+	allocator: Option<HeapAllocator>,
+	/// The panic message registered by the runtime.
+	panic_message: Option<String>,
+	/// The reason of a trap raised by the host.
+	trap_message: Option<String>,
+}
+
+/// Instances that are not currently in use, shared between a module and its instances.
+type IdleInstances = Arc<Mutex<Vec<polkavm::Instance<HostState>>>>;
+
+pub struct InstancePre {
+	instance_pre: polkavm::InstancePre<HostState>,
+	idle: IdleInstances,
+	max_heap_pages: Option<u32>,
+	max_idle_instances: usize,
+	gas_limit: Option<polkavm::Gas>,
+}
 
-#[repr(transparent)]
-pub struct Instance(polkavm::Instance<()>);
+pub struct Instance {
+	instance: Option<polkavm::Instance<HostState>>,
+	idle: IdleInstances,
+	max_heap_pages: Option<u32>,
+	max_idle_instances: usize,
+	gas_limit: Option<polkavm::Gas>,
+	/// The amount of gas consumed by the last call, if gas metering is enabled.
+	gas_consumed: Option<u64>,
+	/// Whether the instance failed and must not be reused.
+	failed: bool,
+}
 
 impl WasmModule for InstancePre {
 	fn new_instance(&self) -> Result<Box<dyn WasmInstance>, Error> {
-		Ok(Box::new(Instance(self.0.instantiate()?)))
+		// The memory of an instance is reset at the beginning of every call, so idle instances
+		// can be reused without any further preparation.
+		let instance = match self.idle.lock().pop() {
+			Some(instance) => instance,
+			None => self.instance_pre.instantiate()?,
+		};
+
+		Ok(Box::new(Instance {
+			instance: Some(instance),
+			idle: self.idle.clone(),
+			max_heap_pages: self.max_heap_pages,
+			max_idle_instances: self.max_idle_instances,
+			gas_limit: self.gas_limit,
+			gas_consumed: None,
+			failed: false,
+		}))
+	}
+}
+
+impl Drop for Instance {
+	fn drop(&mut self) {
+		let Some(instance) = self.instance.take() else { return };
+		if self.failed {
+			return
+		}
+
+		let mut idle = self.idle.lock();
+		if idle.len() < self.max_idle_instances {
+			idle.push(instance);
+		}
 	}
 }
 
@@ -43,7 +158,28 @@ impl WasmInstance for Instance {
 		name: &str,
 		raw_data: &[u8],
 	) -> (Result<Vec<u8>, Error>, Option<AllocationStats>) {
-		let Some(method_index) = self.0.module().lookup_export(name) else {
+		let (result, stats) = self.call_impl(name, raw_data);
+		if result.is_err() {
+			self.failed = true;
+		}
+
+		(result, stats)
+	}
+
+	fn fuel_consumed(&self) -> Option<u64> {
+		self.gas_consumed
+	}
+}
+
+impl Instance {
+	fn call_impl(
+		&mut self,
+		name: &str,
+		raw_data: &[u8],
+	) -> (Result<Vec<u8>, Error>, Option<AllocationStats>) {
+		let instance = self.instance.as_ref().expect("instance is only taken on drop; qed");
+
+		let Some(method_index) = instance.module().lookup_export(name) else {
 			return (
 				Err(format!("cannot call into the runtime: export not found: '{name}'").into()),
 				None,
@@ -57,15 +193,17 @@ impl WasmInstance for Instance {
 			);
 		};
 
-		// TODO: This will leak guest memory; find a better solution.
 		let mut state_args = polkavm::StateArgs::new();
 
 		// Make sure the memory is cleared...
 		state_args.reset_memory(true);
 		// ...and allocate space for the input payload.
 		state_args.sbrk(raw_data_length);
+		if let Some(gas_limit) = self.gas_limit {
+			state_args.set_gas(gas_limit);
+		}
 
-		match self.0.update_state(state_args) {
+		match instance.update_state(state_args) {
 			Ok(()) => {},
 			Err(polkavm::ExecutionError::Trap(trap)) => {
 				return (Err(format!("call into the runtime method '{name}' failed: failed to prepare the guest's memory: {trap}").into()), None);
@@ -73,52 +211,75 @@ impl WasmInstance for Instance {
 			Err(polkavm::ExecutionError::Error(error)) => {
 				return (Err(format!("call into the runtime method '{name}' failed: failed to prepare the guest's memory: {error}").into()), None);
 			},
-			Err(polkavm::ExecutionError::OutOfGas) => unreachable!("gas metering is never enabled"),
+			Err(polkavm::ExecutionError::OutOfGas) =>
+				unreachable!("gas is only consumed when executing the program"),
 		}
 
 		// Grab the address of where the guest's heap starts; that's where we've just allocated
 		// the memory for the input payload.
-		let data_pointer = self.0.module().memory_map().heap_base();
+		let data_pointer = instance.module().memory_map().heap_base();
 
-		if let Err(error) = self.0.write_memory(data_pointer, raw_data) {
+		if let Err(error) = instance.write_memory(data_pointer, raw_data) {
 			return (Err(format!("call into the runtime method '{name}': failed to write the input payload into guest memory: {error}").into()), None);
 		}
 
-		let mut state = ();
+		let mut state = HostState {
+			allocator: Some(HeapAllocator::new(self.max_heap_pages)),
+			panic_message: None,
+			trap_message: None,
+		};
 		let mut call_args = polkavm::CallArgs::new(&mut state, method_index);
 		call_args.args_untyped(&[data_pointer, raw_data_length]);
 
-		match self.0.call(Default::default(), call_args) {
+		let result = instance.call(Default::default(), call_args);
+		let stats = state.allocator.as_ref().map(HeapAllocator::stats);
+		self.gas_consumed = self
+			.gas_limit
+			.zip(instance.gas_remaining())
+			.map(|(limit, remaining)| limit.get().saturating_sub(remaining.get()));
+
+		match result {
 			Ok(()) => {},
 			Err(polkavm::ExecutionError::Trap(trap)) => {
-				return (
-					Err(format!("call into the runtime method '{name}' failed: {trap}").into()),
-					None,
-				);
+				let error = if let Some(message) = state.panic_message.take() {
+					Error::AbortedDueToPanic(MessageWithBacktrace { message, backtrace: None })
+				} else if let Some(message) = state.trap_message.take() {
+					Error::AbortedDueToTrap(MessageWithBacktrace { message, backtrace: None })
+				} else {
+					format!("call into the runtime method '{name}' failed: {trap}").into()
+				};
+
+				return (Err(error), stats)
 			},
 			Err(polkavm::ExecutionError::Error(error)) => {
 				return (
 					Err(format!("call into the runtime method '{name}' failed: {error}").into()),
-					None,
+					stats,
 				);
 			},
-			Err(polkavm::ExecutionError::OutOfGas) => unreachable!("gas metering is never enabled"),
+			Err(polkavm::ExecutionError::OutOfGas) => {
+				let message = format!("call into the runtime method '{name}' ran out of gas");
+				return (
+					Err(Error::AbortedDueToTrap(MessageWithBacktrace { message, backtrace: None })),
+					stats,
+				)
+			},
 		}
 
-		let result_pointer = self.0.get_reg(Reg::A0);
-		let result_length = self.0.get_reg(Reg::A1);
-		let output = match self.0.read_memory_into_vec(result_pointer, result_length) {
+		let result_pointer = instance.get_reg(Reg::A0);
+		let result_length = instance.get_reg(Reg::A1);
+		let output = match instance.read_memory_into_vec(result_pointer, result_length) {
 			Ok(output) => output,
 			Err(error) => {
-				return (Err(format!("call into the runtime method '{name}' failed: failed to read the return payload: {error}").into()), None)
+				return (Err(format!("call into the runtime method '{name}' failed: failed to read the return payload: {error}").into()), stats)
 			},
 		};
 
-		(Ok(output), None)
+		(Ok(output), stats)
 	}
 }
 
-struct Context<'r, 'a>(&'r mut polkavm::Caller<'a, ()>);
+struct Context<'r, 'a>(&'r mut polkavm::Caller<'a, HostState>);
 
 impl<'r, 'a> FunctionContext for Context<'r, 'a> {
 	fn read_memory_into(
@@ -137,26 +298,37 @@ impl<'r, 'a> FunctionContext for Context<'r, 'a> {
 	}
 
 	fn allocate_memory(&mut self, size: WordSize) -> sp_wasm_interface::Result<Pointer<u8>> {
-		let pointer = self.0.sbrk(0).expect("fetching the current heap pointer never fails");
+		let mut allocator = self
+			.0
+			.data_mut()
+			.allocator
+			.take()
+			.expect("allocator is not empty when calling a function in the runtime; qed");
+
+		// We can not return on error early, as we need to store back allocator.
+		let res = allocator.allocate(|size| self.0.sbrk(size), size).map(Pointer::new);
 
-		// TODO: This will leak guest memory; find a better solution.
-		self.0.sbrk(size).ok_or_else(|| String::from("allocation failed"))?;
+		self.0.data_mut().allocator = Some(allocator);
 
-		Ok(Pointer::new(pointer))
+		res
 	}
 
-	fn deallocate_memory(&mut self, _ptr: Pointer<u8>) -> sp_wasm_interface::Result<()> {
-		// This is only used by the allocator host function, which is unused under PolkaVM.
-		unimplemented!("'deallocate_memory' is never used when running under PolkaVM");
+	fn deallocate_memory(&mut self, ptr: Pointer<u8>) -> sp_wasm_interface::Result<()> {
+		self.0
+			.data_mut()
+			.allocator
+			.as_mut()
+			.expect("allocator is not empty when calling a function in the runtime; qed")
+			.deallocate(u32::from(ptr))
 	}
 
-	fn register_panic_error_message(&mut self, _message: &str) {
-		unimplemented!("'register_panic_error_message' is never used when running under PolkaVM");
+	fn register_panic_error_message(&mut self, message: &str) {
+		self.0.data_mut().panic_message = Some(message.to_owned());
 	}
 }
 
 fn call_host_function(
-	caller: &mut Caller<()>,
+	caller: &mut Caller<HostState>,
 	function: &dyn Function,
 ) -> Result<(), polkavm::Trap> {
 	let mut args = [Value::I64(0); Reg::ARG_REGS.len()];
@@ -205,6 +377,8 @@ fn call_host_function(
 		Ok(value) => value,
 		Err(error) => {
 			log::warn!("Call into the host function '{}' failed: {error}", function.name());
+			caller.data_mut().trap_message =
+				Some(format!("call into the host function '{}' failed: {error}", function.name()));
 			return Err(polkavm::Trap::default());
 		},
 	};
@@ -231,31 +405,81 @@ fn call_host_function(
 	Ok(())
 }
 
-pub fn create_runtime<H>(blob: &polkavm::ProgramBlob) -> Result<Box<dyn WasmModule>, WasmError>
-where
-	H: HostFunctions,
-{
-	static ENGINE: std::sync::OnceLock<Result<polkavm::Engine, polkavm::Error>> =
-		std::sync::OnceLock::new();
+/// Get the engine for `backend`, creating it on first use.
+fn engine(backend: Backend) -> Result<&'static polkavm::Engine, WasmError> {
+	static AUTO: OnceLock<Result<polkavm::Engine, polkavm::Error>> = OnceLock::new();
+	static COMPILER: OnceLock<Result<polkavm::Engine, polkavm::Error>> = OnceLock::new();
+	static INTERPRETER: OnceLock<Result<polkavm::Engine, polkavm::Error>> = OnceLock::new();
+
+	let engine = match backend {
+		Backend::Auto => &AUTO,
+		Backend::Compiler => &COMPILER,
+		Backend::Interpreter => &INTERPRETER,
+	};
+
+	let engine = engine.get_or_init(|| {
+		let mut config = polkavm::Config::from_env()?;
+		if let Some(kind) = backend.kind() {
+			config.set_backend(Some(kind));
+		}
 
-	let engine = ENGINE.get_or_init(|| {
-		let config = polkavm::Config::from_env()?;
 		polkavm::Engine::new(&config)
 	});
 
-	let engine = match engine {
-		Ok(ref engine) => engine,
-		Err(ref error) => {
-			return Err(WasmError::Other(error.to_string()));
-		},
-	};
+	match engine {
+		Ok(ref engine) => Ok(engine),
+		Err(ref error) => Err(WasmError::Other(error.to_string())),
+	}
+}
 
-	let module = polkavm::Module::from_blob(&engine, &polkavm::ModuleConfig::default(), blob)?;
-	let mut linker = polkavm::Linker::new(&engine);
+/// Create a runtime out of the given PolkaVM program blob.
+pub fn create_runtime<H>(
+	blob: &polkavm::ProgramBlob,
+	config: Config,
+) -> Result<Box<dyn WasmModule>, WasmError>
+where
+	H: HostFunctions,
+{
+	let engine = engine(config.backend)?;
+
+	let gas_limit = config
+		.gas_limit
+		.map(|gas_limit| {
+			polkavm::Gas::new(gas_limit)
+				.ok_or_else(|| WasmError::Other(format!("invalid gas limit: {gas_limit}")))
+		})
+		.transpose()?;
+
+	let mut module_config = polkavm::ModuleConfig::default();
+	if gas_limit.is_some() {
+		module_config.set_gas_metering(Some(polkavm::GasMeteringKind::Sync));
+	}
+
+	let module = polkavm::Module::from_blob(engine, &module_config, blob)?;
+	let mut linker = polkavm::Linker::new(engine);
 	for function in H::host_functions() {
 		linker.func_new(function.name(), |mut caller| call_host_function(&mut caller, function))?;
 	}
 
+	if config.allow_missing_func_imports {
+		linker.func_fallback(|mut caller, symbol| {
+			caller.data_mut().trap_message =
+				Some(format!("call to a missing function {}", String::from_utf8_lossy(symbol)));
+			Err(polkavm::Trap::default())
+		});
+	}
+
+	let max_heap_pages = match config.heap_alloc_strategy {
+		HeapAllocStrategy::Static { extra_pages } => Some(extra_pages),
+		HeapAllocStrategy::Dynamic { maximum_pages } => maximum_pages,
+	};
+
 	let instance_pre = linker.instantiate_pre(&module)?;
-	Ok(Box::new(InstancePre(instance_pre)))
+	Ok(Box::new(InstancePre {
+		instance_pre,
+		idle: Default::default(),
+		max_heap_pages,
+		max_idle_instances: config.max_idle_instances,
+		gas_limit,
+	}))
 }
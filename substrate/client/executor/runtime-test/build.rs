@@ -17,11 +17,12 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 fn main() {
-	// regular build
+	// regular build, always for WASM so that the executor can be tested with wasmtime
 	#[cfg(feature = "std")]
 	{
 		substrate_wasm_builder::WasmBuilder::new()
 			.with_current_project()
+			.with_target(substrate_wasm_builder::RuntimeTarget::Wasm)
 			.export_heap_base()
 			.import_memory()
 			.disable_runtime_version_section_check()
@@ -35,9 +36,21 @@ fn main() {
 			.with_current_project()
 			.export_heap_base()
 			.import_memory()
+			.with_target(substrate_wasm_builder::RuntimeTarget::Wasm)
 			.set_file_name("wasm_binary_with_tracing.rs")
 			.append_to_rust_flags(r#"--cfg feature="with-tracing""#)
 			.disable_runtime_version_section_check()
 			.build();
 	}
+
+	// and building for PolkaVM, which requires the PolkaVM toolchain
+	#[cfg(feature = "riscv")]
+	{
+		substrate_wasm_builder::WasmBuilder::new()
+			.with_current_project()
+			.with_target(substrate_wasm_builder::RuntimeTarget::Riscv)
+			.set_file_name("polkavm_binary.rs")
+			.disable_runtime_version_section_check()
+			.build();
+	}
 }
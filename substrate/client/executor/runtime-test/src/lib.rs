@@ -31,6 +31,20 @@ pub fn wasm_binary_unwrap() -> &'static [u8] {
 	)
 }
 
+#[cfg(all(feature = "std", feature = "riscv"))]
+mod polkavm {
+	include!(concat!(env!("OUT_DIR"), "/polkavm_binary.rs"));
+}
+
+/// PolkaVM binary unwrapped. If built with `SKIP_WASM_BUILD`, the function panics.
+#[cfg(all(feature = "std", feature = "riscv"))]
+pub fn polkavm_binary_unwrap() -> &'static [u8] {
+	polkavm::WASM_BINARY.expect(
+		"Development PolkaVM binary is not available. Testing is only supported with the flag \
+		 disabled.",
+	)
+}
+
 #[cfg(not(feature = "std"))]
 extern crate alloc;
 
@@ -0,0 +1,258 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! On-disk cache of compiled runtimes.
+//!
+//! Compiling a runtime is expensive, so the compiled artifacts are persisted to survive restarts
+//! of the node. The cache is content-addressed: an artifact is stored under a key derived from
+//! the hash of the runtime code and from everything else the compiled code depends on, i.e. the
+//! versions of the compiler, its settings and the semantics it was compiled with. Artifacts are
+//! written atomically, which allows several nodes on the same host to share one cache directory.
+//!
+//! Every artifact is prefixed with a checksum of its content that is verified before the
+//! artifact is used, and the least recently used artifacts are evicted once the total size of
+//! the cache exceeds its limit.
+//!
+//! Only wasmtime artifacts are cached, PolkaVM can't serialize its compiled modules.
+
+use codec::Encode;
+use sp_core::hexdisplay::HexDisplay;
+use std::{
+	ffi::OsStr,
+	fs,
+	io::{self, Write},
+	path::PathBuf,
+	time::SystemTime,
+};
+
+/// The default limit of the total size of the artifacts in the cache, 1 GiB.
+pub const DEFAULT_MAX_SIZE: u64 = 1024 * 1024 * 1024;
+
+/// The extension of the artifact files.
+const ARTIFACT_EXTENSION: &str = "artifact";
+
+/// The length of the checksum prefixing every artifact.
+const CHECKSUM_LEN: usize = 32;
+
+const LOG_TARGET: &str = "wasm-runtime";
+
+/// Identifies a compiled runtime in an [`ArtifactCache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArtifactKey([u8; 32]);
+
+impl ArtifactKey {
+	/// Create the key of the runtime with the given `code_hash`, compiled by a compiler identified
+	/// by `compatibility`.
+	///
+	/// `compatibility` must describe everything besides the code that affects the compiled code,
+	/// see `sc_executor_wasmtime::artifact_compatibility`.
+	pub fn new(code_hash: &[u8], compatibility: &[u8]) -> Self {
+		Self(sp_crypto_hashing::blake2_256(&(code_hash, compatibility).encode()))
+	}
+}
+
+/// On-disk cache of compiled runtimes.
+///
+/// The cache directory must only be writable by trusted processes: the artifacts are native code
+/// which is executed without further validation.
+#[derive(Debug, Clone)]
+pub struct ArtifactCache {
+	path: PathBuf,
+	max_size: u64,
+}
+
+impl ArtifactCache {
+	/// Create a cache storing the artifacts in the directory at `path`.
+	///
+	/// The directory is created when the first artifact is stored. Once the total size of the
+	/// artifacts exceeds `max_size` bytes, the least recently used ones are evicted.
+	pub fn new(path: PathBuf, max_size: u64) -> Self {
+		Self { path, max_size }
+	}
+
+	/// Load the artifact stored under `key`.
+	///
+	/// Returns `None` if there is no such artifact or if it's corrupted, in which case it's removed
+	/// from the cache.
+	pub fn load(&self, key: &ArtifactKey) -> Option<Vec<u8>> {
+		let path = self.artifact_path(key);
+		let mut artifact = match fs::read(&path) {
+			Ok(artifact) => artifact,
+			Err(error) if error.kind() == io::ErrorKind::NotFound => return None,
+			Err(error) => {
+				tracing::warn!(target: LOG_TARGET, ?path, %error, "Failed to read cached runtime");
+				return None
+			},
+		};
+
+		if artifact.len() < CHECKSUM_LEN ||
+			sp_crypto_hashing::blake2_256(&artifact[CHECKSUM_LEN..]) != artifact[..CHECKSUM_LEN]
+		{
+			tracing::warn!(target: LOG_TARGET, ?path, "Removing corrupted cached runtime");
+			let _ = fs::remove_file(&path);
+			return None
+		}
+
+		// Mark the artifact as recently used.
+		if let Err(error) = fs::File::options()
+			.write(true)
+			.open(&path)
+			.and_then(|file| file.set_modified(SystemTime::now()))
+		{
+			tracing::debug!(target: LOG_TARGET, ?path, %error, "Failed to touch cached runtime");
+		}
+
+		artifact.drain(..CHECKSUM_LEN);
+		Some(artifact)
+	}
+
+	/// Store `artifact` under `key`, evicting the least recently used artifacts if the cache
+	/// exceeds its size limit.
+	///
+	/// Failures are logged, as the cache is only an optimization.
+	pub fn store(&self, key: &ArtifactKey, artifact: &[u8]) {
+		if let Err(error) = self.try_store(key, artifact) {
+			tracing::warn!(
+				target: LOG_TARGET,
+				path = ?self.path,
+				%error,
+				"Failed to store compiled runtime",
+			);
+		}
+	}
+
+	fn try_store(&self, key: &ArtifactKey, artifact: &[u8]) -> io::Result<()> {
+		fs::create_dir_all(&self.path)?;
+
+		// Write to a temporary file first, so that other processes sharing the cache never
+		// observe a partially written artifact.
+		let tmp_path =
+			self.path
+				.join(format!("{}.{}.tmp", HexDisplay::from(&key.0), std::process::id()));
+		let result = (|| {
+			let mut file = fs::File::create(&tmp_path)?;
+			file.write_all(&sp_crypto_hashing::blake2_256(artifact))?;
+			file.write_all(artifact)?;
+			file.sync_all()?;
+			fs::rename(&tmp_path, self.artifact_path(key))
+		})();
+		if result.is_err() {
+			let _ = fs::remove_file(&tmp_path);
+		}
+		result?;
+
+		self.evict()
+	}
+
+	fn evict(&self) -> io::Result<()> {
+		let mut artifacts = Vec::new();
+		let mut total_size = 0;
+		for entry in fs::read_dir(&self.path)? {
+			let path = entry?.path();
+			if path.extension() != Some(OsStr::new(ARTIFACT_EXTENSION)) {
+				continue
+			}
+
+			// The artifact may have been removed by another process in the meantime.
+			let Ok(metadata) = fs::metadata(&path) else { continue };
+			total_size += metadata.len();
+			artifacts.push((metadata.modified()?, metadata.len(), path));
+		}
+
+		artifacts.sort_by_key(|(modified, _, _)| *modified);
+		for (_, size, path) in artifacts {
+			if total_size <= self.max_size {
+				break
+			}
+
+			tracing::debug!(target: LOG_TARGET, ?path, "Evicting cached runtime");
+			match fs::remove_file(&path) {
+				Ok(()) => {},
+				Err(error) if error.kind() == io::ErrorKind::NotFound => {},
+				Err(error) => return Err(error),
+			}
+			total_size -= size;
+		}
+
+		Ok(())
+	}
+
+	fn artifact_path(&self, key: &ArtifactKey) -> PathBuf {
+		self.path.join(format!("{}.{ARTIFACT_EXTENSION}", HexDisplay::from(&key.0)))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn stored_artifact_is_loaded() {
+		let dir = tempfile::tempdir().unwrap();
+		let cache = ArtifactCache::new(dir.path().join("runtimes"), DEFAULT_MAX_SIZE);
+		let key = ArtifactKey::new(b"code hash", b"compiler");
+
+		assert_eq!(cache.load(&key), None);
+		cache.store(&key, b"artifact");
+		assert_eq!(cache.load(&key), Some(b"artifact".to_vec()));
+
+		// A different compiler results in a different artifact.
+		assert_eq!(cache.load(&ArtifactKey::new(b"code hash", b"other compiler")), None);
+	}
+
+	#[test]
+	fn corrupted_artifact_is_removed() {
+		let dir = tempfile::tempdir().unwrap();
+		let cache = ArtifactCache::new(dir.path().to_path_buf(), DEFAULT_MAX_SIZE);
+		let key = ArtifactKey::new(b"code hash", b"compiler");
+
+		cache.store(&key, b"artifact");
+		let path = cache.artifact_path(&key);
+		let mut content = fs::read(&path).unwrap();
+		*content.last_mut().unwrap() ^= 1;
+		fs::write(&path, content).unwrap();
+
+		assert_eq!(cache.load(&key), None);
+		assert!(!path.exists());
+	}
+
+	#[test]
+	fn least_recently_used_artifacts_are_evicted() {
+		let dir = tempfile::tempdir().unwrap();
+		let artifact = [0u8; 100];
+		let size = (CHECKSUM_LEN + artifact.len()) as u64;
+		let cache = ArtifactCache::new(dir.path().to_path_buf(), 2 * size);
+		let keys = [b"1", b"2", b"3"].map(|hash| ArtifactKey::new(hash, b"compiler"));
+
+		cache.store(&keys[0], &artifact);
+		cache.store(&keys[1], &artifact);
+		// Make sure the modification times differ.
+		let past = SystemTime::now() - std::time::Duration::from_secs(60);
+		fs::File::options()
+			.write(true)
+			.open(cache.artifact_path(&keys[1]))
+			.unwrap()
+			.set_modified(past)
+			.unwrap();
+		cache.store(&keys[2], &artifact);
+
+		assert!(cache.load(&keys[0]).is_some());
+		assert_eq!(cache.load(&keys[1]), None);
+		assert!(cache.load(&keys[2]).is_some());
+	}
+}
@@ -17,6 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
+	artifact_cache::{self, ArtifactCache},
 	error::{Error, Result},
 	wasm_runtime::{RuntimeCache, WasmExecutionMethod},
 	RuntimeVersionOf,
@@ -36,6 +37,7 @@ use sc_executor_common::{
 		AllocationStats, HeapAllocStrategy, WasmInstance, WasmModule, DEFAULT_HEAP_ALLOC_STRATEGY,
 	},
 };
+use sc_executor_wasmtime::HostFunctionShims;
 use sp_core::traits::{CallContext, CodeExecutor, Externalities, RuntimeCode};
 use sp_version::{GetNativeVersion, NativeVersion, RuntimeVersion};
 use sp_wasm_interface::{ExtendedHostFunctions, HostFunctions};
@@ -91,8 +93,11 @@ pub struct WasmExecutorBuilder<H = sp_io::SubstrateHostFunctions> {
 	ignore_onchain_heap_pages: bool,
 	max_runtime_instances: usize,
 	cache_path: Option<PathBuf>,
+	runtime_cache_path: Option<PathBuf>,
+	runtime_cache_max_size: u64,
 	allow_missing_host_functions: bool,
 	runtime_cache_size: u8,
+	host_function_shims: HostFunctionShims,
 }
 
 impl<H> WasmExecutorBuilder<H> {
@@ -110,6 +115,9 @@ impl<H> WasmExecutorBuilder<H> {
 			runtime_cache_size: 4,
 			allow_missing_host_functions: false,
 			cache_path: None,
+			runtime_cache_path: None,
+			runtime_cache_max_size: artifact_cache::DEFAULT_MAX_SIZE,
+			host_function_shims: HostFunctionShims::default(),
 		}
 	}
 
@@ -170,6 +178,33 @@ impl<H> WasmExecutorBuilder<H> {
 		self
 	}
 
+	/// Create the wasm executor with the given `runtime_cache_path`.
+	///
+	/// The `runtime_cache_path` is a path to a directory where the executor persists the compiled
+	/// runtimes, so that they don't need to be compiled again after a restart. The directory can
+	/// be shared by several nodes running on the same host. Only runtimes executed with
+	/// [`WasmExecutionMethod::Compiled`] are cached. PolkaVM runtimes are compiled again after
+	/// every restart, as PolkaVM provides no way to serialize a compiled module and its single
+	/// pass compiler is cheap compared to wasmtime.
+	///
+	/// By default, or if `None` is given, the compiled runtimes are not persisted.
+	pub fn with_runtime_cache_path(mut self, runtime_cache_path: Option<PathBuf>) -> Self {
+		self.runtime_cache_path = runtime_cache_path;
+		self
+	}
+
+	/// Create the wasm executor with the given `runtime_cache_max_size`.
+	///
+	/// Once the total size of the runtimes persisted in the `runtime_cache_path` exceeds
+	/// `runtime_cache_max_size` bytes, the least recently used ones are evicted.
+	///
+	/// By default this is
+	/// [`DEFAULT_RUNTIME_CACHE_MAX_SIZE`](crate::DEFAULT_RUNTIME_CACHE_MAX_SIZE).
+	pub fn with_runtime_cache_max_size(mut self, runtime_cache_max_size: u64) -> Self {
+		self.runtime_cache_max_size = runtime_cache_max_size;
+		self
+	}
+
 	/// Create the wasm executor and allow/forbid missing host functions.
 	///
 	/// If missing host functions are forbidden, the instantiation of a wasm blob will fail
@@ -193,6 +228,19 @@ impl<H> WasmExecutorBuilder<H> {
 		self
 	}
 
+	/// Create the wasm executor with the given `host_function_shims`.
+	///
+	/// The shims are wasm modules implementing host functions which are imported by a runtime,
+	/// but not provided by the host functions `H`. This allows to drop the native implementation
+	/// of deprecated host functions while still being able to execute historic runtimes. See
+	/// [`HostFunctionShims`] for the requirements on the shims.
+	///
+	/// By default no shims are used.
+	pub fn with_host_function_shims(mut self, host_function_shims: HostFunctionShims) -> Self {
+		self.host_function_shims = host_function_shims;
+		self
+	}
+
 	/// Build the configured [`WasmExecutor`].
 	pub fn build(self) -> WasmExecutor<H> {
 		WasmExecutor {
@@ -208,9 +256,13 @@ impl<H> WasmExecutorBuilder<H> {
 				self.max_runtime_instances,
 				self.cache_path.clone(),
 				self.runtime_cache_size,
+				self.runtime_cache_path
+					.map(|path| ArtifactCache::new(path, self.runtime_cache_max_size)),
+				self.host_function_shims.clone(),
 			)),
 			cache_path: self.cache_path,
 			allow_missing_host_functions: self.allow_missing_host_functions,
+			host_function_shims: self.host_function_shims,
 			phantom: PhantomData,
 		}
 	}
@@ -234,6 +286,8 @@ pub struct WasmExecutor<H = sp_io::SubstrateHostFunctions> {
 	cache_path: Option<PathBuf>,
 	/// Ignore missing function imports.
 	allow_missing_host_functions: bool,
+	/// Wasm modules providing host functions which are not implemented natively.
+	host_function_shims: HostFunctionShims,
 	phantom: PhantomData<H>,
 }
 
@@ -247,6 +301,7 @@ impl<H> Clone for WasmExecutor<H> {
 			cache: self.cache.clone(),
 			cache_path: self.cache_path.clone(),
 			allow_missing_host_functions: self.allow_missing_host_functions,
+			host_function_shims: self.host_function_shims.clone(),
 			phantom: self.phantom,
 		}
 	}
@@ -298,9 +353,12 @@ impl<H> WasmExecutor<H> {
 				max_runtime_instances,
 				cache_path.clone(),
 				runtime_cache_size,
+				None,
+				HostFunctionShims::default(),
 			)),
 			cache_path,
 			allow_missing_host_functions: false,
+			host_function_shims: HostFunctionShims::default(),
 			phantom: PhantomData,
 		}
 	}
@@ -430,6 +488,7 @@ where
 			runtime_blob,
 			allow_missing_host_functions,
 			self.cache_path.as_deref(),
+			&self.host_function_shims,
 		)
 		.map_err(|e| format!("Failed to create module: {}", e))?;
 
@@ -459,7 +518,7 @@ where
 		wasm_code: &[u8],
 		ext: &mut dyn Externalities,
 	) -> std::result::Result<Vec<u8>, String> {
-		let runtime_blob = RuntimeBlob::uncompress_if_needed(wasm_code)
+		let runtime_blob = crate::wasm_runtime::runtime_blob(wasm_code, self.method)
 			.map_err(|e| format!("Failed to create runtime blob: {:?}", e))?;
 
 		if let Some(version) = crate::wasm_runtime::read_embedded_version(&runtime_blob)
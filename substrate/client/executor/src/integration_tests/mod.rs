@@ -44,9 +44,25 @@ pub type TestExternalities = CoreTestExternalities<BlakeTwo256>;
 type HostFunctions = sp_io::SubstrateHostFunctions;
 
 /// Simple macro that runs a given method as test with the available wasm execution methods.
+///
+/// The method is always run with wasmtime and, with the `riscv` feature, also with PolkaVM.
+/// Methods declared with `@wasmtime` are never run with PolkaVM.
 #[macro_export]
 macro_rules! test_wasm_execution {
 	($method_name:ident) => {
+		test_wasm_execution!(@wasmtime $method_name);
+
+		paste::item! {
+			#[test]
+			#[cfg(feature = "riscv")]
+			fn [<$method_name _polkavm>]() {
+				let _ = sp_tracing::try_init_simple();
+				$method_name(WasmExecutionMethod::PolkaVm { backend: Default::default() });
+			}
+		}
+	};
+
+	(@wasmtime $method_name:ident) => {
 		paste::item! {
 			#[test]
 			fn [<$method_name _compiled_recreate_instance_cow>]() {
@@ -83,6 +99,18 @@ macro_rules! test_wasm_execution {
 	};
 }
 
+/// Returns the test runtime matching the given execution method.
+fn test_runtime(wasm_method: WasmExecutionMethod) -> &'static [u8] {
+	match wasm_method {
+		WasmExecutionMethod::Compiled { .. } => wasm_binary_unwrap(),
+		#[cfg(feature = "riscv")]
+		WasmExecutionMethod::PolkaVm { .. } => sc_runtime_test::polkavm_binary_unwrap(),
+		#[cfg(not(feature = "riscv"))]
+		WasmExecutionMethod::PolkaVm { .. } =>
+			panic!("the PolkaVM test runtime is only built with the `riscv` feature"),
+	}
+}
+
 fn call_in_wasm<E: Externalities>(
 	function: &str,
 	call_data: &[u8],
@@ -94,7 +122,8 @@ fn call_in_wasm<E: Externalities>(
 		.build();
 
 	executor.uncached_call(
-		RuntimeBlob::uncompress_if_needed(wasm_binary_unwrap()).unwrap(),
+		crate::wasm_runtime::runtime_blob(test_runtime(execution_method), execution_method)
+			.unwrap(),
 		ext,
 		true,
 		function,
@@ -121,6 +150,8 @@ fn call_not_existing_function(wasm_method: WasmExecutionMethod) {
 			let expected = match wasm_method {
 				WasmExecutionMethod::Compiled { .. } =>
 					"call to a missing function env:missing_external",
+				WasmExecutionMethod::PolkaVm { .. } =>
+					"call to a missing function missing_external",
 			};
 			assert_eq!(error.message, expected);
 		},
@@ -140,6 +171,8 @@ fn call_yet_another_not_existing_function(wasm_method: WasmExecutionMethod) {
 			let expected = match wasm_method {
 				WasmExecutionMethod::Compiled { .. } =>
 					"call to a missing function env:yet_another_missing_external",
+				WasmExecutionMethod::PolkaVm { .. } =>
+					"call to a missing function yet_another_missing_external",
 			};
 			assert_eq!(error.message, expected);
 		},
@@ -407,7 +440,7 @@ fn offchain_http_should_work(wasm_method: WasmExecutionMethod) {
 	);
 }
 
-test_wasm_execution!(should_trap_when_heap_exhausted);
+test_wasm_execution!(@wasmtime should_trap_when_heap_exhausted);
 fn should_trap_when_heap_exhausted(wasm_method: WasmExecutionMethod) {
 	let mut ext = TestExternalities::default();
 
@@ -453,11 +486,12 @@ fn mk_test_runtime(
 		blob,
 		true,
 		None,
+		&Default::default(),
 	)
 	.expect("failed to instantiate wasm runtime")
 }
 
-test_wasm_execution!(returns_mutable_static);
+test_wasm_execution!(@wasmtime returns_mutable_static);
 fn returns_mutable_static(wasm_method: WasmExecutionMethod) {
 	let runtime =
 		mk_test_runtime(wasm_method, HeapAllocStrategy::Dynamic { maximum_pages: Some(1024) });
@@ -473,7 +507,7 @@ fn returns_mutable_static(wasm_method: WasmExecutionMethod) {
 	assert_eq!(33, u64::decode(&mut &res[..]).unwrap());
 }
 
-test_wasm_execution!(returns_mutable_static_bss);
+test_wasm_execution!(@wasmtime returns_mutable_static_bss);
 fn returns_mutable_static_bss(wasm_method: WasmExecutionMethod) {
 	let runtime =
 		mk_test_runtime(wasm_method, HeapAllocStrategy::Dynamic { maximum_pages: Some(1024) });
@@ -493,7 +527,7 @@ fn returns_mutable_static_bss(wasm_method: WasmExecutionMethod) {
 // returned to its initial value and thus the stack space is going to be leaked.
 //
 // See https://github.com/paritytech/substrate/issues/2967 for details
-test_wasm_execution!(restoration_of_globals);
+test_wasm_execution!(@wasmtime restoration_of_globals);
 fn restoration_of_globals(wasm_method: WasmExecutionMethod) {
 	// Allocate 32 pages (of 65536 bytes) which gives the runtime 2048KB of heap to operate on
 	// (plus some additional space unused from the initial pages requested by the wasm runtime
@@ -534,7 +568,11 @@ fn parallel_execution(wasm_method: WasmExecutionMethod) {
 				assert_eq!(
 					executor
 						.uncached_call(
-							RuntimeBlob::uncompress_if_needed(wasm_binary_unwrap()).unwrap(),
+							crate::wasm_runtime::runtime_blob(
+								test_runtime(wasm_method),
+								wasm_method
+							)
+							.unwrap(),
 							&mut ext,
 							true,
 							"test_twox_128",
@@ -552,7 +590,7 @@ fn parallel_execution(wasm_method: WasmExecutionMethod) {
 	}
 }
 
-test_wasm_execution!(wasm_tracing_should_work);
+test_wasm_execution!(@wasmtime wasm_tracing_should_work);
 fn wasm_tracing_should_work(wasm_method: WasmExecutionMethod) {
 	use sc_tracing::{SpanDatum, TraceEvent};
 	use std::sync::Mutex;
@@ -604,7 +642,7 @@ fn wasm_tracing_should_work(wasm_method: WasmExecutionMethod) {
 	assert_eq!(len, 2);
 }
 
-test_wasm_execution!(allocate_two_gigabyte);
+test_wasm_execution!(@wasmtime allocate_two_gigabyte);
 fn allocate_two_gigabyte(wasm_method: WasmExecutionMethod) {
 	let runtime = mk_test_runtime(wasm_method, HeapAllocStrategy::Dynamic { maximum_pages: None });
 
@@ -613,7 +651,7 @@ fn allocate_two_gigabyte(wasm_method: WasmExecutionMethod) {
 	assert_eq!(10 * 1024 * 1024 * 205, u32::decode(&mut &res[..]).unwrap());
 }
 
-test_wasm_execution!(memory_is_cleared_between_invocations);
+test_wasm_execution!(@wasmtime memory_is_cleared_between_invocations);
 fn memory_is_cleared_between_invocations(wasm_method: WasmExecutionMethod) {
 	// This is based on the code generated by compiling a runtime *without*
 	// the `-C link-arg=--import-memory` using the following code and then
@@ -679,6 +717,7 @@ fn memory_is_cleared_between_invocations(wasm_method: WasmExecutionMethod) {
 		RuntimeBlob::uncompress_if_needed(&binary[..]).unwrap(),
 		true,
 		None,
+		&Default::default(),
 	)
 	.unwrap();
 
@@ -720,7 +759,7 @@ fn abort_on_panic(wasm_method: WasmExecutionMethod) {
 	}
 }
 
-test_wasm_execution!(unreachable_intrinsic);
+test_wasm_execution!(@wasmtime unreachable_intrinsic);
 fn unreachable_intrinsic(wasm_method: WasmExecutionMethod) {
 	let mut ext = TestExternalities::default();
 	let mut ext = ext.ext();
@@ -730,6 +769,7 @@ fn unreachable_intrinsic(wasm_method: WasmExecutionMethod) {
 			let expected = match wasm_method {
 				WasmExecutionMethod::Compiled { .. } =>
 					"wasm trap: wasm `unreachable` instruction executed",
+				WasmExecutionMethod::PolkaVm { .. } => unreachable!("only executed with wasmtime"),
 			};
 			assert_eq!(error.message, expected);
 		},
@@ -748,7 +788,7 @@ fn return_value(wasm_method: WasmExecutionMethod) {
 	);
 }
 
-test_wasm_execution!(return_huge_len);
+test_wasm_execution!(@wasmtime return_huge_len);
 fn return_huge_len(wasm_method: WasmExecutionMethod) {
 	let mut ext = TestExternalities::default();
 	let mut ext = ext.ext();
@@ -761,7 +801,7 @@ fn return_huge_len(wasm_method: WasmExecutionMethod) {
 	}
 }
 
-test_wasm_execution!(return_max_memory_offset);
+test_wasm_execution!(@wasmtime return_max_memory_offset);
 fn return_max_memory_offset(wasm_method: WasmExecutionMethod) {
 	let mut ext = TestExternalities::default();
 	let mut ext = ext.ext();
@@ -772,7 +812,7 @@ fn return_max_memory_offset(wasm_method: WasmExecutionMethod) {
 	);
 }
 
-test_wasm_execution!(return_max_memory_offset_plus_one);
+test_wasm_execution!(@wasmtime return_max_memory_offset_plus_one);
 fn return_max_memory_offset_plus_one(wasm_method: WasmExecutionMethod) {
 	let mut ext = TestExternalities::default();
 	let mut ext = ext.ext();
@@ -787,7 +827,7 @@ fn return_max_memory_offset_plus_one(wasm_method: WasmExecutionMethod) {
 	}
 }
 
-test_wasm_execution!(return_overflow);
+test_wasm_execution!(@wasmtime return_overflow);
 fn return_overflow(wasm_method: WasmExecutionMethod) {
 	let mut ext = TestExternalities::default();
 	let mut ext = ext.ext();
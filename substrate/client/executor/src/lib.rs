@@ -30,12 +30,14 @@
 
 #![warn(missing_docs)]
 
+mod artifact_cache;
 #[macro_use]
 mod executor;
 #[cfg(test)]
 mod integration_tests;
 mod wasm_runtime;
 
+pub use artifact_cache::DEFAULT_MAX_SIZE as DEFAULT_RUNTIME_CACHE_MAX_SIZE;
 pub use codec::Codec;
 #[allow(deprecated)]
 pub use executor::NativeElseWasmExecutor;
@@ -46,13 +48,16 @@ pub use sp_version::{NativeVersion, RuntimeVersion};
 #[doc(hidden)]
 pub use sp_wasm_interface;
 pub use sp_wasm_interface::HostFunctions;
-pub use wasm_runtime::{read_embedded_version, WasmExecutionMethod};
+pub use wasm_runtime::{imported_host_functions, read_embedded_version, WasmExecutionMethod};
 
 pub use sc_executor_common::{
 	error,
 	wasm_runtime::{HeapAllocStrategy, DEFAULT_HEAP_ALLOC_PAGES, DEFAULT_HEAP_ALLOC_STRATEGY},
 };
-pub use sc_executor_wasmtime::InstantiationStrategy as WasmtimeInstantiationStrategy;
+pub use sc_executor_polkavm::Backend as PolkaVmBackend;
+pub use sc_executor_wasmtime::{
+	HostFunctionShims, InstantiationStrategy as WasmtimeInstantiationStrategy,
+};
 
 /// Extracts the runtime version of a given runtime code.
 pub trait RuntimeVersionOf {
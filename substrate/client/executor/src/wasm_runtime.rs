@@ -21,7 +21,10 @@
 //! The primary means of accessing the runtimes is through a cache which saves the reusable
 //! components of the runtime that are expensive to initialize.
 
-use crate::error::{Error, WasmError};
+use crate::{
+	artifact_cache::{ArtifactCache, ArtifactKey},
+	error::{Error, WasmError},
+};
 
 use codec::Decode;
 use parking_lot::Mutex;
@@ -29,6 +32,7 @@ use sc_executor_common::{
 	runtime_blob::RuntimeBlob,
 	wasm_runtime::{HeapAllocStrategy, WasmInstance, WasmModule},
 };
+use sc_executor_wasmtime::HostFunctionShims;
 use schnellru::{ByLength, LruMap};
 use sp_core::traits::{Externalities, FetchRuntimeCode, RuntimeCode};
 use sp_version::RuntimeVersion;
@@ -48,6 +52,13 @@ pub enum WasmExecutionMethod {
 		/// The instantiation strategy to use.
 		instantiation_strategy: sc_executor_wasmtime::InstantiationStrategy,
 	},
+	/// Uses PolkaVM to execute runtimes compiled to PolkaVM program blobs.
+	///
+	/// WASM runtimes can't be executed with this method.
+	PolkaVm {
+		/// The backend to execute the runtime with.
+		backend: sc_executor_polkavm::Backend,
+	},
 }
 
 impl Default for WasmExecutionMethod {
@@ -58,6 +69,9 @@ impl Default for WasmExecutionMethod {
 	}
 }
 
+/// The maximum number of idle instances of a PolkaVM runtime kept around for reuse.
+const MAX_IDLE_POLKAVM_INSTANCES: usize = 8;
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 struct VersionedRuntimeId {
 	/// Runtime code hash.
@@ -167,6 +181,10 @@ pub struct RuntimeCache {
 	/// The size of the instances cache for each runtime.
 	max_runtime_instances: usize,
 	cache_path: Option<PathBuf>,
+	/// The on-disk cache of compiled runtimes, shared across restarts and processes.
+	artifact_cache: Option<ArtifactCache>,
+	/// Wasm modules providing host functions which are not implemented natively.
+	host_function_shims: HostFunctionShims,
 }
 
 impl RuntimeCache {
@@ -180,13 +198,27 @@ impl RuntimeCache {
 	///
 	/// `runtime_cache_size` specifies the number of different runtimes versions preserved in an
 	/// in-memory cache, must always be at least 1.
+	///
+	/// `artifact_cache` allows to specify an optional on-disk cache of compiled runtimes, which
+	/// survives restarts and can be shared between nodes.
+	///
+	/// `host_function_shims` are linked into wasm runtimes that import host functions which are
+	/// not implemented natively.
 	pub fn new(
 		max_runtime_instances: usize,
 		cache_path: Option<PathBuf>,
 		runtime_cache_size: u8,
+		artifact_cache: Option<ArtifactCache>,
+		host_function_shims: HostFunctionShims,
 	) -> RuntimeCache {
 		let cap = ByLength::new(runtime_cache_size.max(1) as u32);
-		RuntimeCache { runtimes: Mutex::new(LruMap::new(cap)), max_runtime_instances, cache_path }
+		RuntimeCache {
+			runtimes: Mutex::new(LruMap::new(cap)),
+			max_runtime_instances,
+			cache_path,
+			artifact_cache,
+			host_function_shims,
+		}
 	}
 
 	/// Prepares a WASM module instance and executes given function for it.
@@ -249,12 +281,15 @@ impl RuntimeCache {
 
 			let result = create_versioned_wasm_runtime::<H>(
 				&code,
+				code_hash,
 				ext,
 				wasm_method,
 				heap_alloc_strategy,
 				allow_missing_func_imports,
 				self.max_runtime_instances,
 				self.cache_path.as_deref(),
+				self.artifact_cache.as_ref(),
+				&self.host_function_shims,
 			);
 
 			match result {
@@ -287,44 +322,129 @@ impl RuntimeCache {
 }
 
 /// Create a wasm runtime with the given `code`.
+///
+/// The `host_function_shims` are only used by wasm runtimes, PolkaVM runtimes ignore them.
 pub fn create_wasm_runtime_with_code<H>(
 	wasm_method: WasmExecutionMethod,
 	heap_alloc_strategy: HeapAllocStrategy,
 	blob: RuntimeBlob,
 	allow_missing_func_imports: bool,
 	cache_path: Option<&Path>,
+	host_function_shims: &HostFunctionShims,
 ) -> Result<Box<dyn WasmModule>, WasmError>
 where
 	H: HostFunctions,
 {
 	if let Some(blob) = blob.as_polkavm_blob() {
-		return sc_executor_polkavm::create_runtime::<H>(blob);
+		let WasmExecutionMethod::PolkaVm { backend } = wasm_method else {
+			return Err(WasmError::Other(
+				"PolkaVM runtimes can only be executed with the PolkaVM execution method".into(),
+			))
+		};
+
+		// PolkaVM runtimes aren't cached on disk: PolkaVM compiles the program directly into
+		// state owned by its sandbox and has no API to serialize it, so there is nothing that
+		// could be persisted. Its compiler is single pass, which keeps recompiling cheap.
+		return sc_executor_polkavm::create_runtime::<H>(
+			blob,
+			sc_executor_polkavm::Config {
+				backend,
+				heap_alloc_strategy,
+				allow_missing_func_imports,
+				max_idle_instances: MAX_IDLE_POLKAVM_INSTANCES,
+				gas_limit: None,
+			},
+		)
 	}
 
 	match wasm_method {
+		WasmExecutionMethod::PolkaVm { .. } => Err(WasmError::Other(
+			"the PolkaVM execution method can only execute PolkaVM runtimes".into(),
+		)),
 		WasmExecutionMethod::Compiled { instantiation_strategy } =>
 			sc_executor_wasmtime::create_runtime::<H>(
 				blob,
-				sc_executor_wasmtime::Config {
+				wasmtime_config(
+					instantiation_strategy,
+					heap_alloc_strategy,
 					allow_missing_func_imports,
-					cache_path: cache_path.map(ToOwned::to_owned),
-					semantics: sc_executor_wasmtime::Semantics {
-						heap_alloc_strategy,
-						instantiation_strategy,
-						deterministic_stack_limit: None,
-						canonicalize_nans: false,
-						parallel_compilation: true,
-						wasm_multi_value: false,
-						wasm_bulk_memory: false,
-						wasm_reference_types: false,
-						wasm_simd: false,
-					},
-				},
+					cache_path,
+					host_function_shims,
+				),
 			)
 			.map(|runtime| -> Box<dyn WasmModule> { Box::new(runtime) }),
 	}
 }
 
+fn wasmtime_config(
+	instantiation_strategy: sc_executor_wasmtime::InstantiationStrategy,
+	heap_alloc_strategy: HeapAllocStrategy,
+	allow_missing_func_imports: bool,
+	cache_path: Option<&Path>,
+	host_function_shims: &HostFunctionShims,
+) -> sc_executor_wasmtime::Config {
+	sc_executor_wasmtime::Config {
+		allow_missing_func_imports,
+		cache_path: cache_path.map(ToOwned::to_owned),
+		host_function_shims: host_function_shims.clone(),
+		semantics: sc_executor_wasmtime::Semantics {
+			heap_alloc_strategy,
+			instantiation_strategy,
+			deterministic_stack_limit: None,
+			canonicalize_nans: false,
+			parallel_compilation: true,
+			wasm_multi_value: false,
+			wasm_bulk_memory: false,
+			wasm_reference_types: false,
+			wasm_simd: false,
+			fuel: None,
+		},
+	}
+}
+
+/// Create a wasmtime runtime with the given `code_hash`, reusing the compiled artifact from the
+/// `artifact_cache` if it's there and storing it otherwise.
+fn create_wasmtime_runtime_with_artifact_cache<H>(
+	blob: RuntimeBlob,
+	code_hash: &[u8],
+	config: sc_executor_wasmtime::Config,
+	artifact_cache: &ArtifactCache,
+) -> Result<Box<dyn WasmModule>, WasmError>
+where
+	H: HostFunctions,
+{
+	let key = ArtifactKey::new(
+		code_hash,
+		&sc_executor_wasmtime::artifact_compatibility(&config.semantics)?,
+	);
+
+	if let Some(artifact) = artifact_cache.load(&key) {
+		// SAFETY: The artifact was produced by `prepare_runtime_artifact` below and its integrity
+		//         was checked when loading it. An artifact compiled by an incompatible version of
+		//         wasmtime is rejected deterministically.
+		match unsafe {
+			sc_executor_wasmtime::create_runtime_from_artifact_bytes::<H>(&artifact, config.clone())
+		} {
+			Ok(runtime) => {
+				tracing::debug!(target: "wasm-runtime", "Loaded compiled runtime from the cache");
+				return Ok(Box::new(runtime))
+			},
+			Err(error) => tracing::debug!(
+				target: "wasm-runtime",
+				%error,
+				"Cached runtime can't be used, compiling it again",
+			),
+		}
+	}
+
+	let artifact = sc_executor_wasmtime::prepare_runtime_artifact(blob, &config.semantics)?;
+	artifact_cache.store(&key, &artifact);
+
+	// SAFETY: The artifact was just produced by `prepare_runtime_artifact` with the same config.
+	unsafe { sc_executor_wasmtime::create_runtime_from_artifact_bytes::<H>(&artifact, config) }
+		.map(|runtime| -> Box<dyn WasmModule> { Box::new(runtime) })
+}
+
 fn decode_version(mut version: &[u8]) -> Result<RuntimeVersion, WasmError> {
 	Decode::decode(&mut version).map_err(|_| {
 		WasmError::Instantiation(
@@ -381,34 +501,76 @@ pub fn read_embedded_version(blob: &RuntimeBlob) -> Result<Option<RuntimeVersion
 	}
 }
 
+/// Decompress and parse the runtime `code`.
+///
+/// PolkaVM runtimes are only accepted with [`WasmExecutionMethod::PolkaVm`].
+pub(crate) fn runtime_blob(
+	code: &[u8],
+	wasm_method: WasmExecutionMethod,
+) -> Result<RuntimeBlob, WasmError> {
+	match wasm_method {
+		WasmExecutionMethod::PolkaVm { .. } => RuntimeBlob::uncompress_if_needed_with_polkavm(code),
+		WasmExecutionMethod::Compiled { .. } => RuntimeBlob::uncompress_if_needed(code),
+	}
+}
+
+/// Returns the names of the host functions imported by the given, possibly compressed, runtime
+/// `code`.
+///
+/// Host functions declared with `#[runtime_interface]` are named
+/// `ext_{interface}_{function}_version_{version}`.
+pub fn imported_host_functions(code: &[u8]) -> Result<Vec<String>, WasmError> {
+	RuntimeBlob::uncompress_if_needed_with_polkavm(code)?.imported_functions()
+}
+
 fn create_versioned_wasm_runtime<H>(
 	code: &[u8],
+	code_hash: &[u8],
 	ext: &mut dyn Externalities,
 	wasm_method: WasmExecutionMethod,
 	heap_alloc_strategy: HeapAllocStrategy,
 	allow_missing_func_imports: bool,
 	max_instances: usize,
 	cache_path: Option<&Path>,
+	artifact_cache: Option<&ArtifactCache>,
+	host_function_shims: &HostFunctionShims,
 ) -> Result<VersionedRuntime, WasmError>
 where
 	H: HostFunctions,
 {
 	// The incoming code may be actually compressed. We decompress it here and then work with
 	// the uncompressed code from now on.
-	let blob = sc_executor_common::runtime_blob::RuntimeBlob::uncompress_if_needed(code)?;
+	let blob = runtime_blob(code, wasm_method)?;
 
 	// Use the runtime blob to scan if there is any metadata embedded into the wasm binary
 	// pertaining to runtime version. We do it before consuming the runtime blob for creating the
 	// runtime.
 	let mut version = read_embedded_version(&blob)?;
 
-	let runtime = create_wasm_runtime_with_code::<H>(
-		wasm_method,
-		heap_alloc_strategy,
-		blob,
-		allow_missing_func_imports,
-		cache_path,
-	)?;
+	let runtime = match (wasm_method, artifact_cache) {
+		(WasmExecutionMethod::Compiled { instantiation_strategy }, Some(artifact_cache))
+			if blob.as_polkavm_blob().is_none() =>
+			create_wasmtime_runtime_with_artifact_cache::<H>(
+				blob,
+				code_hash,
+				wasmtime_config(
+					instantiation_strategy,
+					heap_alloc_strategy,
+					allow_missing_func_imports,
+					cache_path,
+					host_function_shims,
+				),
+				artifact_cache,
+			)?,
+		_ => create_wasm_runtime_with_code::<H>(
+			wasm_method,
+			heap_alloc_strategy,
+			blob,
+			allow_missing_func_imports,
+			cache_path,
+			host_function_shims,
+		)?,
+	};
 
 	// If the runtime blob doesn't embed the runtime version then use the legacy version query
 	// mechanism: call the runtime.
@@ -469,6 +631,15 @@ mod tests {
 		assert!(equal, "Host functions are not equal");
 	}
 
+	#[test]
+	fn imported_host_functions_works() {
+		let imports =
+			imported_host_functions(substrate_test_runtime::wasm_binary_unwrap()).unwrap();
+
+		assert!(imports.iter().any(|name| name == "ext_allocator_malloc_version_1"));
+		assert!(imports.iter().all(|name| name.starts_with("ext_")));
+	}
+
 	#[test]
 	fn old_runtime_version_decodes() {
 		let old_runtime_version = OldRuntimeVersion {
@@ -561,4 +732,69 @@ mod tests {
 
 		assert_eq!(runtime_version, read_version);
 	}
+
+	#[cfg(unix)]
+	#[test]
+	fn compiled_runtime_is_loaded_from_the_artifact_cache() {
+		use std::{fs, os::unix::fs::MetadataExt, time::SystemTime};
+
+		let dir = tempfile::tempdir().unwrap();
+		let code = sp_core::traits::WrappedRuntimeCode(
+			substrate_test_runtime::wasm_binary_unwrap().into(),
+		);
+		let runtime_code = RuntimeCode { code_fetcher: &code, heap_pages: None, hash: vec![1; 32] };
+
+		// Every run uses a new in-memory cache, like a restarted node.
+		let run = || {
+			let cache = RuntimeCache::new(
+				1,
+				None,
+				1,
+				Some(ArtifactCache::new(
+					dir.path().to_path_buf(),
+					crate::artifact_cache::DEFAULT_MAX_SIZE,
+				)),
+				HostFunctionShims::default(),
+			);
+			cache
+				.with_instance::<sp_io::SubstrateHostFunctions, _, _>(
+					&runtime_code,
+					&mut sp_state_machine::BasicExternalities::default(),
+					WasmExecutionMethod::Compiled {
+						instantiation_strategy:
+							sc_executor_wasmtime::InstantiationStrategy::RecreateInstance,
+					},
+					sc_executor_common::wasm_runtime::DEFAULT_HEAP_ALLOC_STRATEGY,
+					false,
+					|_, _, version, _| Ok(version.cloned()),
+				)
+				.unwrap()
+				.unwrap()
+		};
+
+		let version = run();
+		assert!(version.is_some());
+		let artifacts =
+			fs::read_dir(dir.path()).unwrap().map(|e| e.unwrap().path()).collect::<Vec<_>>();
+		assert_eq!(artifacts.len(), 1);
+		let stored = fs::metadata(&artifacts[0]).unwrap();
+
+		// Loading the artifact marks it as recently used.
+		let past = SystemTime::now() - std::time::Duration::from_secs(60);
+		fs::File::options()
+			.write(true)
+			.open(&artifacts[0])
+			.unwrap()
+			.set_modified(past)
+			.unwrap();
+
+		assert_eq!(run(), version);
+
+		// The artifact was loaded instead of being compiled and stored again, which would have
+		// replaced the file.
+		let loaded = fs::metadata(&artifacts[0]).unwrap();
+		assert_eq!(loaded.ino(), stored.ino());
+		assert!(loaded.modified().unwrap() > past);
+		assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+	}
 }
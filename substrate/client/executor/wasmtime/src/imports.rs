@@ -17,17 +17,71 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{host::HostContext, runtime::StoreData};
+use parking_lot::Mutex;
 use sc_executor_common::error::WasmError;
 use sp_wasm_interface::{FunctionContext, HostFunctions};
-use std::collections::HashMap;
-use wasmtime::{ExternType, FuncType, ImportType, Linker, Module};
+use std::{
+	collections::{hash_map::DefaultHasher, BTreeMap, HashMap},
+	hash::{Hash, Hasher},
+	sync::Arc,
+};
+use wasmtime::{Engine, ExternType, FuncType, ImportType, InstancePre, Linker, Module};
+
+/// Host function shims precompiled by [`shim_module`], keyed by the compatibility hash of the
+/// engine they were compiled with and their code.
+pub(crate) static COMPILED_SHIMS: Mutex<BTreeMap<(u64, Arc<[u8]>), Arc<[u8]>>> =
+	Mutex::new(BTreeMap::new());
+
+/// Wasm modules providing host functions which are not implemented natively by the node.
+///
+/// This allows a node to drop the native implementation of a deprecated host function version,
+/// while still being able to execute historic runtimes that import it. A shim implements the
+/// deprecated version on top of host functions that are still provided by the node.
+///
+/// A shim may only import functions from the `env` module, which are resolved using the host
+/// functions of the node. It exports functions under the names of the host functions it
+/// provides, e.g. `ext_misc_print_num_version_1`, with the same signature the runtime imports
+/// them with. A shim can't access the memory of the runtime directly, but the host functions it
+/// calls can.
+///
+/// Shims are only consulted for imports the node doesn't provide natively. They are compiled once
+/// per engine configuration and the compiled artifact is reused by all later runtimes. Each runtime
+/// instance instantiates a shim the first time one of its functions is called.
+#[derive(Clone, Default)]
+pub struct HostFunctionShims(Vec<Arc<[u8]>>);
+
+impl std::fmt::Debug for HostFunctionShims {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_tuple("HostFunctionShims").field(&self.0.len()).finish()
+	}
+}
+
+impl HostFunctionShims {
+	/// Create a new set of shims from the given wasm modules.
+	pub fn new(shims: Vec<Vec<u8>>) -> Self {
+		Self(shims.into_iter().map(Into::into).collect())
+	}
+
+	/// Create an empty set of shims.
+	pub const fn empty() -> Self {
+		Self(Vec::new())
+	}
+
+	/// Returns `true` if there are no shims.
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+}
 
 /// Goes over all imports of a module and prepares the given linker for instantiation of the module.
 /// Returns an error if there are imports that cannot be satisfied.
+///
+/// Imports that aren't provided by the host functions `H` are resolved using the given `shims`.
 pub(crate) fn prepare_imports<H>(
 	linker: &mut Linker<StoreData>,
 	module: &Module,
 	allow_missing_func_imports: bool,
+	shims: &HostFunctionShims,
 ) -> Result<(), WasmError>
 where
 	H: HostFunctions,
@@ -59,10 +113,15 @@ where
 
 	let mut registry = Registry { linker, pending_func_imports };
 	H::register_static(&mut registry)?;
+	let mut pending_func_imports = registry.pending_func_imports;
 
-	if !registry.pending_func_imports.is_empty() {
+	if !pending_func_imports.is_empty() && !shims.is_empty() {
+		link_shims::<H>(linker, &mut pending_func_imports, shims)?;
+	}
+
+	if !pending_func_imports.is_empty() {
 		if allow_missing_func_imports {
-			for (name, (import_ty, func_ty)) in registry.pending_func_imports {
+			for (name, (import_ty, func_ty)) in pending_func_imports {
 				let error = format!("call to a missing function {}:{}", import_ty.module(), name);
 				log::debug!("Missing import: '{}' {:?}", name, func_ty);
 				linker
@@ -73,7 +132,7 @@ where
 			}
 		} else {
 			let mut names = Vec::new();
-			for (name, (import_ty, _)) in registry.pending_func_imports {
+			for (name, (import_ty, _)) in pending_func_imports {
 				names.push(format!("'{}:{}'", import_ty.module(), name));
 			}
 			let names = names.join(", ");
@@ -87,6 +146,135 @@ where
 	Ok(())
 }
 
+/// Resolves the pending function imports using the exports of the given `shims`.
+fn link_shims<H>(
+	linker: &mut Linker<StoreData>,
+	pending_func_imports: &mut HashMap<String, (ImportType<'_>, FuncType)>,
+	shims: &HostFunctionShims,
+) -> Result<(), WasmError>
+where
+	H: HostFunctions,
+{
+	for (index, code) in shims.0.iter().enumerate() {
+		let module = shim_module(linker.engine(), code)?;
+
+		let mut provided = Vec::new();
+		for export in module.exports() {
+			let Some(func_ty) = export.ty().func().cloned() else { continue };
+			let Some((_, import_func_ty)) = pending_func_imports.get(export.name()) else {
+				continue
+			};
+
+			if *import_func_ty != func_ty {
+				return Err(WasmError::Other(format!(
+					"host function shim provides '{}' as {:?}, but the runtime imports it as {:?}",
+					export.name(),
+					func_ty,
+					import_func_ty,
+				)))
+			}
+
+			pending_func_imports.remove(export.name());
+			provided.push((export.name().to_owned(), func_ty));
+		}
+
+		if provided.is_empty() {
+			continue
+		}
+
+		// The shim itself may only use host functions which are natively provided.
+		let mut shim_linker = Linker::new(linker.engine());
+		prepare_imports::<H>(&mut shim_linker, &module, false, &HostFunctionShims::empty())?;
+		let instance_pre = Arc::new(shim_linker.instantiate_pre(&module).map_err(|error| {
+			WasmError::Other(format!("cannot preinstantiate host function shim: {:#}", error))
+		})?);
+
+		for (name, func_ty) in provided {
+			link_shim_function(linker, index, instance_pre.clone(), name, func_ty)?;
+		}
+	}
+
+	Ok(())
+}
+
+/// Returns the module of the shim with the given `code`, compiled for `engine`.
+///
+/// The shim is only compiled the first time it is used with an engine configuration, afterwards
+/// the artifact cached in [`COMPILED_SHIMS`] is deserialized.
+fn shim_module(engine: &Engine, code: &Arc<[u8]>) -> Result<Module, WasmError> {
+	let compatibility = {
+		let mut hasher = DefaultHasher::new();
+		engine.precompile_compatibility_hash().hash(&mut hasher);
+		hasher.finish()
+	};
+
+	let artifact = {
+		let mut compiled = COMPILED_SHIMS.lock();
+		match compiled.get(&(compatibility, code.clone())) {
+			Some(artifact) => artifact.clone(),
+			None => {
+				let artifact: Arc<[u8]> = engine
+					.precompile_module(code)
+					.map_err(|error| {
+						WasmError::Other(format!(
+							"cannot compile host function shim module: {:#}",
+							error
+						))
+					})?
+					.into();
+				compiled.insert((compatibility, code.clone()), artifact.clone());
+				artifact
+			},
+		}
+	};
+
+	// SAFETY: The artifact was produced by `Engine::precompile_module` in this process, by an
+	// engine with the same compatibility hash as `engine`.
+	unsafe { Module::deserialize(engine, &artifact[..]) }.map_err(|error| {
+		WasmError::Other(format!("cannot deserialize host function shim module: {:#}", error))
+	})
+}
+
+/// Registers the host function `name`, which forwards the call to the function with the same name
+/// exported by the shim with the given `index`.
+fn link_shim_function(
+	linker: &mut Linker<StoreData>,
+	index: usize,
+	instance_pre: Arc<InstancePre<StoreData>>,
+	name: String,
+	func_ty: FuncType,
+) -> Result<(), WasmError> {
+	let error = |error: anyhow::Error| {
+		WasmError::Other(format!(
+			"failed to register host function shim '{}' with the WASM linker: {:#}",
+			name, error
+		))
+	};
+
+	linker
+		.func_new("env", &name, func_ty, {
+			let name = name.clone();
+			move |mut caller, params, results| {
+				let instance = match caller.data().shim_instances.get(&index) {
+					Some(instance) => *instance,
+					None => {
+						let instance = instance_pre.instantiate(&mut caller)?;
+						caller.data_mut().shim_instances.insert(index, instance);
+						instance
+					},
+				};
+
+				let func = instance
+					.get_func(&mut caller, &name)
+					.expect("the shim exports the function, checked when it was linked; qed");
+				func.call(&mut caller, params, results)
+			}
+		})
+		.map_err(error)?;
+
+	Ok(())
+}
+
 struct Registry<'a, 'b> {
 	linker: &'a mut Linker<StoreData>,
 	pending_func_imports: HashMap<String, (ImportType<'b>, FuncType)>,
@@ -36,10 +36,11 @@ mod util;
 #[cfg(test)]
 mod tests;
 
+pub use imports::HostFunctionShims;
 pub use runtime::{
-	create_runtime, create_runtime_from_artifact, create_runtime_from_artifact_bytes,
-	prepare_runtime_artifact, Config, DeterministicStackLimit, InstantiationStrategy, Semantics,
-	WasmtimeRuntime,
+	artifact_compatibility, create_runtime, create_runtime_from_artifact,
+	create_runtime_from_artifact_bytes, prepare_runtime_artifact, Config, DeterministicStackLimit,
+	InstantiationStrategy, Semantics, WasmtimeRuntime,
 };
 pub use sc_executor_common::{
 	runtime_blob::RuntimeBlob,
@@ -20,6 +20,7 @@
 
 use crate::{
 	host::HostState,
+	imports::HostFunctionShims,
 	instance_wrapper::{EntryPoint, InstanceWrapper, MemoryWrapper},
 	util::{self, replace_strategy_if_broken},
 };
@@ -35,22 +36,32 @@ use sc_executor_common::{
 use sp_runtime_interface::unpack_ptr_and_len;
 use sp_wasm_interface::{HostFunctions, Pointer, WordSize};
 use std::{
+	collections::HashMap,
+	hash::{Hash, Hasher},
 	path::{Path, PathBuf},
 	sync::{
 		atomic::{AtomicBool, Ordering},
 		Arc,
 	},
 };
-use wasmtime::{AsContext, Engine, Memory};
+use wasmtime::{AsContext, Engine, Instance, Memory};
 
 const MAX_INSTANCE_COUNT: u32 = 64;
 
+/// The version of the `wasmtime` dependency, see [`artifact_compatibility`].
+///
+/// `test_wasmtime_version_matches_dependency` makes sure it's bumped together with the dependency.
+pub(crate) const WASMTIME_VERSION: &str = "8.0.1";
+
 #[derive(Default)]
 pub(crate) struct StoreData {
 	/// This will only be set when we call into the runtime.
 	pub(crate) host_state: Option<HostState>,
 	/// This will be always set once the store is initialized.
 	pub(crate) memory: Option<Memory>,
+	/// Instances of the host function shims, indexed by their position in the
+	/// [`HostFunctionShims`]. They are only instantiated when they are called for the first time.
+	pub(crate) shim_instances: HashMap<usize, Instance>,
 }
 
 impl StoreData {
@@ -75,11 +86,17 @@ struct InstanceCreator {
 	engine: Engine,
 	instance_pre: Arc<wasmtime::InstancePre<StoreData>>,
 	instance_counter: Arc<InstanceCounter>,
+	fuel: Option<u64>,
 }
 
 impl InstanceCreator {
 	fn instantiate(&mut self) -> Result<InstanceWrapper> {
-		InstanceWrapper::new(&self.engine, &self.instance_pre, self.instance_counter.clone())
+		InstanceWrapper::new(
+			&self.engine,
+			&self.instance_pre,
+			self.instance_counter.clone(),
+			self.fuel,
+		)
 	}
 }
 
@@ -137,6 +154,7 @@ pub struct WasmtimeRuntime {
 	instance_pre: Arc<wasmtime::InstancePre<StoreData>>,
 	instantiation_strategy: InternalInstantiationStrategy,
 	instance_counter: Arc<InstanceCounter>,
+	fuel: Option<u64>,
 }
 
 impl WasmModule for WasmtimeRuntime {
@@ -146,10 +164,11 @@ impl WasmModule for WasmtimeRuntime {
 				engine: self.engine.clone(),
 				instance_pre: self.instance_pre.clone(),
 				instance_counter: self.instance_counter.clone(),
+				fuel: self.fuel,
 			}),
 		};
 
-		Ok(Box::new(WasmtimeInstance { strategy }))
+		Ok(Box::new(WasmtimeInstance { strategy, fuel_consumed: None }))
 	}
 }
 
@@ -157,6 +176,8 @@ impl WasmModule for WasmtimeRuntime {
 /// to execute the compiled code.
 pub struct WasmtimeInstance {
 	strategy: Strategy,
+	/// The amount of fuel consumed by the last call, if fuel metering is enabled.
+	fuel_consumed: Option<u64>,
 }
 
 impl WasmtimeInstance {
@@ -173,7 +194,16 @@ impl WasmtimeInstance {
 				let entrypoint = instance_wrapper.resolve_entrypoint(method)?;
 				let allocator = FreeingBumpHeapAllocator::new(heap_base);
 
-				perform_call(data, &mut instance_wrapper, entrypoint, allocator, allocation_stats)
+				let result = perform_call(
+					data,
+					&mut instance_wrapper,
+					entrypoint,
+					allocator,
+					allocation_stats,
+				);
+				self.fuel_consumed = instance_wrapper.store().fuel_consumed();
+
+				result
 			},
 		}
 	}
@@ -189,6 +219,10 @@ impl WasmInstance for WasmtimeInstance {
 		let result = self.call_impl(method, data, &mut allocation_stats);
 		(result, allocation_stats)
 	}
+
+	fn fuel_consumed(&self) -> Option<u64> {
+		self.fuel_consumed
+	}
 }
 
 /// Prepare a directory structure and a config file to enable wasmtime caching.
@@ -267,6 +301,7 @@ fn common_config(semantics: &Semantics) -> std::result::Result<wasmtime::Config,
 	config.max_wasm_stack(native_stack_max as usize);
 
 	config.parallel_compilation(semantics.parallel_compilation);
+	config.consume_fuel(semantics.fuel.is_some());
 
 	// Be clear and specific about the extensions we support. If an update brings new features
 	// they should be introduced here as well.
@@ -456,6 +491,14 @@ pub struct Semantics {
 
 	/// Enables WASM Fixed-Width SIMD proposal
 	pub wasm_simd: bool,
+
+	/// Specifying `Some` enables fuel metering: every call is given the specified amount of fuel,
+	/// which is consumed deterministically by the executed instructions, and traps once it runs
+	/// out of fuel.
+	///
+	/// Since fuel metering is compiled into the code, this must be enabled both when preparing an
+	/// artifact and when instantiating it.
+	pub fuel: Option<u64>,
 }
 
 #[derive(Clone)]
@@ -471,6 +514,10 @@ pub struct Config {
 
 	/// Tuning of various semantics of the wasmtime executor.
 	pub semantics: Semantics,
+
+	/// Wasm modules providing the host functions the runtime imports, but which are not provided
+	/// natively.
+	pub host_function_shims: HostFunctionShims,
 }
 
 enum CodeSupplyMode<'a> {
@@ -625,7 +672,12 @@ where
 	};
 
 	let mut linker = wasmtime::Linker::new(&engine);
-	crate::imports::prepare_imports::<H>(&mut linker, &module, config.allow_missing_func_imports)?;
+	crate::imports::prepare_imports::<H>(
+		&mut linker,
+		&module,
+		config.allow_missing_func_imports,
+		&config.host_function_shims,
+	)?;
 
 	let instance_pre = linker
 		.instantiate_pre(&module)
@@ -636,6 +688,7 @@ where
 		instance_pre: Arc::new(instance_pre),
 		instantiation_strategy,
 		instance_counter: Default::default(),
+		fuel: config.semantics.fuel,
 	})
 }
 
@@ -676,6 +729,50 @@ pub fn prepare_runtime_artifact(
 		.map_err(|e| WasmError::Other(format!("cannot precompile module: {:#}", e)))
 }
 
+/// Returns an opaque identifier of the artifacts produced by [`prepare_runtime_artifact`] with the
+/// given `semantics`.
+///
+/// The identifier covers the versions of wasmtime and of this crate, the settings of the wasmtime
+/// engine as reported by [`Engine::precompile_compatibility_hash`] and the semantics which change
+/// the code before it's compiled. Artifacts with the same identifier are interchangeable, so it
+/// can be used to key a cache of artifacts.
+pub fn artifact_compatibility(semantics: &Semantics) -> std::result::Result<Vec<u8>, WasmError> {
+	let mut semantics = semantics.clone();
+	replace_strategy_if_broken(&mut semantics.instantiation_strategy);
+
+	let engine = Engine::new(&common_config(&semantics)?)
+		.map_err(|e| WasmError::Other(format!("cannot create the engine: {:#}", e)))?;
+
+	let mut hasher = BytesHasher::default();
+	WASMTIME_VERSION.hash(&mut hasher);
+	env!("CARGO_PKG_VERSION").hash(&mut hasher);
+	engine.precompile_compatibility_hash().hash(&mut hasher);
+	semantics.instantiation_strategy.hash(&mut hasher);
+	format!("{:?}", semantics.heap_alloc_strategy).hash(&mut hasher);
+	semantics
+		.deterministic_stack_limit
+		.as_ref()
+		.map(|limit| (limit.logical_max, limit.native_stack_max))
+		.hash(&mut hasher);
+
+	Ok(hasher.0)
+}
+
+/// A [`Hasher`] collecting the hashed bytes, to make a [`Hash`] usable outside of the process.
+#[derive(Default)]
+struct BytesHasher(Vec<u8>);
+
+impl Hasher for BytesHasher {
+	fn write(&mut self, bytes: &[u8]) {
+		self.0.extend_from_slice(bytes);
+	}
+
+	fn finish(&self) -> u64 {
+		// Only the collected bytes are used.
+		0
+	}
+}
+
 fn perform_call(
 	data: &[u8],
 	instance_wrapper: &mut InstanceWrapper,
@@ -70,6 +70,8 @@ struct RuntimeBuilder {
 	deterministic_stack: bool,
 	heap_pages: HeapAllocStrategy,
 	precompile_runtime: bool,
+	fuel: Option<u64>,
+	host_function_shims: Vec<String>,
 	tmpdir: Option<tempfile::TempDir>,
 }
 
@@ -82,6 +84,8 @@ impl RuntimeBuilder {
 			deterministic_stack: false,
 			heap_pages: DEFAULT_HEAP_ALLOC_STRATEGY,
 			precompile_runtime: false,
+			fuel: None,
+			host_function_shims: Vec::new(),
 			tmpdir: None,
 		}
 	}
@@ -111,6 +115,16 @@ impl RuntimeBuilder {
 		self
 	}
 
+	fn fuel(mut self, fuel: Option<u64>) -> Self {
+		self.fuel = fuel;
+		self
+	}
+
+	fn host_function_shim(mut self, wat: String) -> Self {
+		self.host_function_shims.push(wat);
+		self
+	}
+
 	fn build(&mut self) -> impl WasmModule + '_ {
 		let blob = {
 			let wasm: Vec<u8>;
@@ -130,6 +144,12 @@ impl RuntimeBuilder {
 		let config = crate::Config {
 			allow_missing_func_imports: true,
 			cache_path: None,
+			host_function_shims: crate::HostFunctionShims::new(
+				self.host_function_shims
+					.iter()
+					.map(|wat| wat::parse_str(wat).expect("wat parsing failed"))
+					.collect(),
+			),
 			semantics: crate::Semantics {
 				instantiation_strategy: self.instantiation_strategy,
 				deterministic_stack_limit: match self.deterministic_stack {
@@ -146,6 +166,7 @@ impl RuntimeBuilder {
 				wasm_bulk_memory: false,
 				wasm_reference_types: false,
 				wasm_simd: false,
+				fuel: self.fuel,
 			},
 		};
 
@@ -303,6 +324,155 @@ fn test_stack_depth_reaching(instantiation_strategy: InstantiationStrategy) {
 	}
 }
 
+test_wasm_execution!(test_fuel_metering);
+fn test_fuel_metering(instantiation_strategy: InstantiationStrategy) {
+	let call = |fuel, precompile_runtime| {
+		let mut builder = RuntimeBuilder::new(instantiation_strategy)
+			.use_wat(deep_call_stack_wat(1000))
+			.fuel(Some(fuel))
+			.precompile_runtime(precompile_runtime);
+
+		let runtime = builder.build();
+		let mut instance = runtime.new_instance().expect("failed to instantiate a runtime");
+		let result = instance.call_export("main", &[]);
+		(result, instance.fuel_consumed())
+	};
+
+	const FUEL: u64 = 1_000_000_000;
+
+	let (result, fuel_consumed) = call(FUEL, false);
+	result.unwrap();
+	let fuel_consumed = fuel_consumed.unwrap();
+	assert!(fuel_consumed > 1000);
+
+	// The fuel consumption is deterministic...
+	let (result, fuel_consumed_precompiled) = call(FUEL, true);
+	result.unwrap();
+	assert_eq!(fuel_consumed_precompiled, Some(fuel_consumed));
+
+	// ...and so is running out of it.
+	match call(fuel_consumed / 2, false).0.unwrap_err() {
+		Error::AbortedDueToTrap(error) => {
+			assert!(error.message.contains("all fuel consumed"), "{}", error.message);
+		},
+		error => panic!("unexpected error: {:?}", error),
+	}
+}
+
+test_wasm_execution!(test_host_function_shims);
+fn test_host_function_shims(instantiation_strategy: InstantiationStrategy) {
+	let runtime_wat = r#"
+		(module
+			(import "env" "ext_test_malloc_version_1" (func $malloc (param i32) (result i32)))
+			(memory $0 32)
+			(export "memory" (memory $0))
+			(global (export "__heap_base") i32 (i32.const 1024))
+			(func (export "main")
+				(param i32 i32) (result i64)
+
+				;; assert(malloc(16) != 0)
+				(if
+					(i32.eqz
+						(call $malloc
+							(i32.const 16)
+						)
+					)
+					(then
+						(unreachable)
+					)
+				)
+
+				(i64.const 0)
+			)
+		)
+	"#;
+
+	// The shim provides the deprecated host function on top of one the node still provides.
+	let shim_wat = r#"
+		(module
+			(import "env" "ext_allocator_malloc_version_1" (func $malloc (param i32) (result i32)))
+			(func (export "ext_test_malloc_version_1")
+				(param i32) (result i32)
+				(call $malloc
+					(local.get 0)
+				)
+			)
+		)
+	"#;
+
+	for precompile_runtime in [false, true] {
+		let mut builder = RuntimeBuilder::new(instantiation_strategy)
+			.use_wat(runtime_wat.to_string())
+			.host_function_shim(shim_wat.to_string())
+			.precompile_runtime(precompile_runtime);
+		let runtime = builder.build();
+		let mut instance = runtime.new_instance().unwrap();
+		instance.call_export("main", &[]).unwrap();
+		// The shim instance is reused by subsequent calls.
+		instance.call_export("main", &[]).unwrap();
+	}
+
+	// Without the shim the missing import is resolved by a trapping stub.
+	let mut builder = RuntimeBuilder::new(instantiation_strategy).use_wat(runtime_wat.to_string());
+	let runtime = builder.build();
+	let mut instance = runtime.new_instance().unwrap();
+	match instance.call_export("main", &[]).unwrap_err() {
+		Error::AbortedDueToTrap(error) => {
+			assert!(error.message.contains("call to a missing function"), "{}", error.message);
+		},
+		error => panic!("unexpected error: {:?}", error),
+	}
+}
+
+#[test]
+fn host_function_shims_are_compiled_once() {
+	let runtime_wat = r#"
+		(module
+			(import "env" "ext_test_compiled_once_version_1" (func $malloc (param i32) (result i32)))
+			(memory $0 32)
+			(export "memory" (memory $0))
+			(global (export "__heap_base") i32 (i32.const 1024))
+			(func (export "main")
+				(param i32 i32) (result i64)
+				(drop
+					(call $malloc
+						(i32.const 16)
+					)
+				)
+				(i64.const 0)
+			)
+		)
+	"#;
+	let shim_wat = r#"
+		(module
+			(import "env" "ext_allocator_malloc_version_1" (func $malloc (param i32) (result i32)))
+			(func (export "ext_test_compiled_once_version_1")
+				(param i32) (result i32)
+				(call $malloc
+					(local.get 0)
+				)
+			)
+		)
+	"#;
+	let shim = wat::parse_str(shim_wat).unwrap();
+	let compiled = || {
+		crate::imports::COMPILED_SHIMS
+			.lock()
+			.keys()
+			.filter(|(_, code)| **code == shim[..])
+			.count()
+	};
+
+	for _ in 0..3 {
+		let mut builder = RuntimeBuilder::new(InstantiationStrategy::RecreateInstanceCopyOnWrite)
+			.use_wat(runtime_wat.to_string())
+			.host_function_shim(shim_wat.to_string());
+		let runtime = builder.build();
+		runtime.new_instance().unwrap().call_export("main", &[]).unwrap();
+		assert_eq!(compiled(), 1);
+	}
+}
+
 test_wasm_execution!(test_max_memory_pages_imported_memory_without_precompilation);
 fn test_max_memory_pages_imported_memory_without_precompilation(
 	instantiation_strategy: InstantiationStrategy,
@@ -463,6 +633,7 @@ fn test_instances_without_reuse_are_not_leaked() {
 		crate::Config {
 			allow_missing_func_imports: true,
 			cache_path: None,
+			host_function_shims: Default::default(),
 			semantics: crate::Semantics {
 				instantiation_strategy: InstantiationStrategy::RecreateInstance,
 				deterministic_stack_limit: None,
@@ -473,6 +644,7 @@ fn test_instances_without_reuse_are_not_leaked() {
 				wasm_bulk_memory: false,
 				wasm_reference_types: false,
 				wasm_simd: false,
+				fuel: None,
 			},
 		},
 	)
@@ -520,3 +692,53 @@ fn test_rustix_version_matches_with_wasmtime() {
 		);
 	}
 }
+
+#[test]
+fn test_wasmtime_version_matches_dependency() {
+	let metadata = cargo_metadata::MetadataCommand::new().exec().unwrap();
+
+	let wasmtime = metadata.packages.iter().find(|pkg| pkg.name == "wasmtime").unwrap();
+
+	if wasmtime.version.to_string() != crate::runtime::WASMTIME_VERSION {
+		panic!(
+			"`WASMTIME_VERSION` ({0}) doesn't match the version of wasmtime ({1}); \
+				bump it in `sc-executor-wasmtime`'s `runtime.rs` to '{1}' and try again",
+			crate::runtime::WASMTIME_VERSION,
+			wasmtime.version,
+		);
+	}
+}
+
+#[test]
+fn artifact_compatibility_depends_on_semantics() {
+	let semantics = |heap_alloc_strategy| crate::Semantics {
+		instantiation_strategy: InstantiationStrategy::RecreateInstance,
+		deterministic_stack_limit: None,
+		canonicalize_nans: false,
+		parallel_compilation: true,
+		heap_alloc_strategy,
+		wasm_multi_value: false,
+		wasm_bulk_memory: false,
+		wasm_reference_types: false,
+		wasm_simd: false,
+		fuel: None,
+	};
+	let dynamic = semantics(HeapAllocStrategy::Dynamic { maximum_pages: None });
+	let r#static = semantics(HeapAllocStrategy::Static { extra_pages: 2048 });
+
+	assert_eq!(
+		crate::artifact_compatibility(&dynamic).unwrap(),
+		crate::artifact_compatibility(&dynamic).unwrap(),
+	);
+	assert_ne!(
+		crate::artifact_compatibility(&dynamic).unwrap(),
+		crate::artifact_compatibility(&r#static).unwrap(),
+	);
+
+	let mut canonicalize_nans = dynamic.clone();
+	canonicalize_nans.canonicalize_nans = true;
+	assert_ne!(
+		crate::artifact_compatibility(&dynamic).unwrap(),
+		crate::artifact_compatibility(&canonicalize_nans).unwrap(),
+	);
+}
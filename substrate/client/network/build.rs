@@ -16,7 +16,11 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-const PROTOS: &[&str] = &["src/schema/bitswap.v1.2.0.proto"];
+const PROTOS: &[&str] = &[
+	"src/schema/bitswap.v1.2.0.proto",
+	"src/schema/dag_pb.proto",
+	"src/schema/unixfs.proto",
+];
 
 fn main() {
 	prost_build::compile_protos(PROTOS, &["src/schema"]).unwrap();
@@ -0,0 +1,660 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Per-protocol and per-peer bandwidth accounting and throttling.
+//!
+//! [`BandwidthAccounting`] is shared by all notification and request-response protocols of a
+//! node. It counts the bytes of notifications, requests and responses per protocol and per peer,
+//! and optionally enforces an upload rate limit ([`BandwidthLimit`]) for each protocol.
+//!
+//! Rate limits are implemented as token buckets which are allowed to go into debt: traffic is
+//! admitted as long as the bucket of the protocol is not in debt and the actual number of bytes
+//! sent is charged afterwards. This allows limiting protocols where the size of the outbound
+//! message is not known at the time of admission, e.g. responses to inbound requests.
+//!
+//! Protocols the node can't do without, like block announcements and sync requests, can be
+//! [exempted](BandwidthAccounting::exempt) from the limits. Their traffic is still accounted for.
+
+use crate::types::ProtocolName;
+
+use futures_timer::Delay;
+use parking_lot::Mutex;
+use prometheus_endpoint::{
+	self as prometheus, MetricSource, Opts, PrometheusError, Registry, SourcedCounter,
+};
+use sc_network_types::PeerId;
+use schnellru::{ByLength, LruMap};
+use serde::{Deserialize, Serialize};
+
+use std::{
+	collections::HashMap,
+	num::NonZeroU64,
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+/// Log target for this file.
+const LOG_TARGET: &str = "sub-libp2p::bandwidth";
+
+/// Maximum number of peers for which bandwidth usage is tracked.
+///
+/// The least recently active peer is forgotten when the limit is reached.
+const MAX_TRACKED_PEERS: u32 = 512;
+
+/// Maximum number of peers exposed through Prometheus.
+///
+/// Only the peers with the most traffic are exposed to keep the cardinality of the `peer` label
+/// bounded. [`BandwidthAccounting::state`] reports all tracked peers.
+const MAX_PEER_METRICS: usize = 16;
+
+/// Upload rate limit of a protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthLimit {
+	/// Sustained upload rate, in bytes per second.
+	pub bytes_per_second: NonZeroU64,
+
+	/// Number of bytes that can be sent in a burst after the protocol has been idle.
+	pub burst: u64,
+}
+
+impl BandwidthLimit {
+	/// Create new [`BandwidthLimit`] allowing bursts of one second worth of traffic.
+	pub fn new(bytes_per_second: NonZeroU64) -> Self {
+		Self { bytes_per_second, burst: bytes_per_second.get() }
+	}
+
+	/// Set the burst size of the limit.
+	pub fn with_burst(mut self, burst: u64) -> Self {
+		self.burst = burst;
+		self
+	}
+}
+
+/// Number of bytes transferred in each direction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthCounters {
+	/// Bytes received.
+	pub inbound: u64,
+
+	/// Bytes sent.
+	pub outbound: u64,
+}
+
+/// Bandwidth usage of a protocol.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolBandwidth {
+	/// Bytes received.
+	pub inbound: u64,
+
+	/// Bytes sent.
+	pub outbound: u64,
+
+	/// Number of notifications or requests delayed, dropped or rejected because of the limit.
+	pub throttled: u64,
+
+	/// Configured upload limit, in bytes per second.
+	pub limit: Option<u64>,
+}
+
+/// Snapshot of the bandwidth usage of the node.
+///
+/// Part of [`NetworkState`](crate::network_state::NetworkState). Unstable.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthState {
+	/// Bandwidth usage by protocol name.
+	pub protocols: HashMap<String, ProtocolBandwidth>,
+
+	/// Bandwidth usage by peer ID.
+	pub peers: HashMap<String, BandwidthCounters>,
+}
+
+/// Token bucket enforcing a [`BandwidthLimit`].
+#[derive(Debug)]
+struct TokenBucket {
+	/// Configured limit.
+	limit: BandwidthLimit,
+
+	/// Available tokens, in bytes. Negative if the bucket is in debt.
+	tokens: f64,
+
+	/// When were the tokens last refilled.
+	last_refill: Instant,
+}
+
+impl TokenBucket {
+	/// Create new full [`TokenBucket`].
+	fn new(limit: BandwidthLimit, now: Instant) -> Self {
+		Self { limit, tokens: limit.burst as f64, last_refill: now }
+	}
+
+	/// Refill the bucket with the tokens accumulated since the last refill.
+	fn refill(&mut self, now: Instant) {
+		let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+
+		self.tokens = (self.tokens + elapsed * self.limit.bytes_per_second.get() as f64)
+			.min(self.limit.burst as f64);
+		self.last_refill = now;
+	}
+
+	/// Get how long traffic must wait before it's admitted.
+	///
+	/// Returns `None` if the traffic can be admitted immediately.
+	fn admission_delay(&mut self, now: Instant) -> Option<Duration> {
+		self.refill(now);
+
+		(self.tokens < 0f64).then(|| {
+			Duration::from_secs_f64(-self.tokens / self.limit.bytes_per_second.get() as f64)
+		})
+	}
+
+	/// Charge `bytes` from the bucket.
+	fn consume(&mut self, bytes: usize, now: Instant) {
+		self.refill(now);
+		self.tokens -= bytes as f64;
+	}
+}
+
+/// Accounting state of a protocol.
+#[derive(Debug, Default)]
+struct ProtocolEntry {
+	/// Transferred bytes.
+	counters: BandwidthCounters,
+
+	/// Number of times traffic was throttled.
+	throttled: u64,
+
+	/// Token bucket, if the protocol is rate limited.
+	bucket: Option<TokenBucket>,
+
+	/// Whether the protocol is exempt from rate limiting.
+	exempt: bool,
+}
+
+/// Inner state of [`BandwidthAccounting`].
+struct Inner {
+	/// Accounting state of protocols.
+	protocols: HashMap<ProtocolName, ProtocolEntry>,
+
+	/// Transferred bytes of recently active peers.
+	peers: LruMap<PeerId, BandwidthCounters, ByLength>,
+}
+
+impl Inner {
+	fn protocol(&mut self, protocol: &ProtocolName) -> &mut ProtocolEntry {
+		self.protocols.entry(protocol.clone()).or_default()
+	}
+
+	fn peer(&mut self, peer: &PeerId) -> Option<&mut BandwidthCounters> {
+		self.peers.get_or_insert(*peer, Default::default)
+	}
+}
+
+/// Bandwidth accounting shared by the protocols of the node.
+///
+/// Cloning the object returns a handle to the same underlying state.
+#[derive(Clone)]
+pub struct BandwidthAccounting {
+	inner: Arc<Mutex<Inner>>,
+}
+
+impl Default for BandwidthAccounting {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl std::fmt::Debug for BandwidthAccounting {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("BandwidthAccounting").finish_non_exhaustive()
+	}
+}
+
+impl BandwidthAccounting {
+	/// Create new [`BandwidthAccounting`] without any limits.
+	pub fn new() -> Self {
+		Self {
+			inner: Arc::new(Mutex::new(Inner {
+				protocols: HashMap::new(),
+				peers: LruMap::new(ByLength::new(MAX_TRACKED_PEERS)),
+			})),
+		}
+	}
+
+	/// Configure upload limits of protocols, replacing any previously configured limits.
+	pub fn set_limits(&self, limits: impl IntoIterator<Item = (ProtocolName, BandwidthLimit)>) {
+		let mut inner = self.inner.lock();
+		let now = Instant::now();
+
+		inner.protocols.values_mut().for_each(|entry| entry.bucket = None);
+
+		for (protocol, limit) in limits {
+			let entry = inner.protocol(&protocol);
+
+			if entry.exempt {
+				log::warn!(
+					target: LOG_TARGET,
+					"{protocol}: protocol can't be throttled, ignoring its upload limit",
+				);
+				continue
+			}
+
+			log::debug!(
+				target: LOG_TARGET,
+				"{protocol}: limit upload to {} bytes/s (burst {} bytes)",
+				limit.bytes_per_second,
+				limit.burst,
+			);
+
+			entry.bucket = Some(TokenBucket::new(limit, now));
+		}
+	}
+
+	/// Exempt `protocols` from rate limiting, removing any limits configured for them.
+	///
+	/// Traffic of exempt protocols is still accounted for.
+	pub fn exempt(&self, protocols: impl IntoIterator<Item = ProtocolName>) {
+		let mut inner = self.inner.lock();
+
+		for protocol in protocols {
+			let entry = inner.protocol(&protocol);
+
+			if entry.bucket.take().is_some() {
+				log::warn!(
+					target: LOG_TARGET,
+					"{protocol}: protocol can't be throttled, ignoring its upload limit",
+				);
+			}
+			entry.exempt = true;
+		}
+	}
+
+	/// Report that `bytes` were received from `peer` over `protocol`.
+	pub fn report_inbound(&self, protocol: &ProtocolName, peer: &PeerId, bytes: usize) {
+		let mut inner = self.inner.lock();
+
+		let counters = &mut inner.protocol(protocol).counters;
+		counters.inbound = counters.inbound.saturating_add(bytes as u64);
+
+		if let Some(counters) = inner.peer(peer) {
+			counters.inbound = counters.inbound.saturating_add(bytes as u64);
+		}
+	}
+
+	/// Report that `bytes` were sent to `peer` over `protocol`.
+	///
+	/// The bytes are charged from the upload limit of `protocol`, if one is configured.
+	pub fn report_outbound(&self, protocol: &ProtocolName, peer: &PeerId, bytes: usize) {
+		let mut inner = self.inner.lock();
+
+		let entry = inner.protocol(protocol);
+		entry.counters.outbound = entry.counters.outbound.saturating_add(bytes as u64);
+
+		if let Some(bucket) = entry.bucket.as_mut() {
+			bucket.consume(bytes, Instant::now());
+		}
+
+		if let Some(counters) = inner.peer(peer) {
+			counters.outbound = counters.outbound.saturating_add(bytes as u64);
+		}
+	}
+
+	/// Check if `protocol` is allowed to send data right now.
+	///
+	/// Returns `false`, and counts the traffic as throttled, if the upload limit of `protocol`
+	/// has been exhausted.
+	pub fn try_admit(&self, protocol: &ProtocolName) -> bool {
+		let mut inner = self.inner.lock();
+		let Some(entry) = inner.protocols.get_mut(protocol) else { return true };
+		let Some(bucket) = entry.bucket.as_mut() else { return true };
+
+		match bucket.admission_delay(Instant::now()) {
+			None => true,
+			Some(_) => {
+				entry.throttled = entry.throttled.saturating_add(1);
+				false
+			},
+		}
+	}
+
+	/// Wait until `protocol` is allowed to send data.
+	///
+	/// Resolves immediately if `protocol` doesn't have an upload limit or if the limit hasn't
+	/// been exhausted.
+	pub async fn wait_for_admission(&self, protocol: &ProtocolName) {
+		let mut throttled = false;
+
+		loop {
+			let delay = {
+				let mut inner = self.inner.lock();
+				let Some(entry) = inner.protocols.get_mut(protocol) else { return };
+				let Some(bucket) = entry.bucket.as_mut() else { return };
+				let delay = bucket.admission_delay(Instant::now());
+
+				if delay.is_some() && !throttled {
+					throttled = true;
+					entry.throttled = entry.throttled.saturating_add(1);
+				}
+
+				delay
+			};
+
+			match delay {
+				None => return,
+				Some(delay) => Delay::new(delay).await,
+			}
+		}
+	}
+
+	/// Get a snapshot of the bandwidth usage.
+	pub fn state(&self) -> BandwidthState {
+		let inner = self.inner.lock();
+
+		BandwidthState {
+			protocols: inner
+				.protocols
+				.iter()
+				.map(|(protocol, entry)| {
+					(
+						protocol.to_string(),
+						ProtocolBandwidth {
+							inbound: entry.counters.inbound,
+							outbound: entry.counters.outbound,
+							throttled: entry.throttled,
+							limit: entry
+								.bucket
+								.as_ref()
+								.map(|bucket| bucket.limit.bytes_per_second.get()),
+						},
+					)
+				})
+				.collect(),
+			peers: inner
+				.peers
+				.iter()
+				.map(|(peer, counters)| (peer.to_base58(), *counters))
+				.collect(),
+		}
+	}
+
+	/// Register Prometheus metrics sourced from the accounting state.
+	pub fn register(&self, registry: &Registry) -> Result<(), PrometheusError> {
+		prometheus::register(
+			SourcedCounter::new(
+				&Opts::new(
+					"substrate_sub_libp2p_protocol_bytes_total",
+					"Total bytes of notifications, requests and responses, by protocol",
+				)
+				.variable_label("direction")
+				.variable_label("protocol"),
+				ProtocolBytesSource(self.clone()),
+			)?,
+			registry,
+		)?;
+		prometheus::register(
+			SourcedCounter::new(
+				&Opts::new(
+					"substrate_sub_libp2p_peer_bytes_total",
+					"Total bytes of notifications, requests and responses of the most active \
+					 peers, by peer",
+				)
+				.variable_label("direction")
+				.variable_label("peer"),
+				PeerBytesSource(self.clone()),
+			)?,
+			registry,
+		)?;
+		prometheus::register(
+			SourcedCounter::new(
+				&Opts::new(
+					"substrate_sub_libp2p_protocol_throttled_total",
+					"Total number of notifications or requests throttled by the protocol's \
+					 bandwidth limit",
+				)
+				.variable_label("protocol"),
+				ThrottledSource(self.clone()),
+			)?,
+			registry,
+		)?;
+
+		Ok(())
+	}
+}
+
+/// Per-protocol byte counters.
+#[derive(Clone)]
+struct ProtocolBytesSource(BandwidthAccounting);
+
+impl MetricSource for ProtocolBytesSource {
+	type N = u64;
+
+	fn collect(&self, mut set: impl FnMut(&[&str], Self::N)) {
+		let inner = self.0.inner.lock();
+
+		for (protocol, entry) in inner.protocols.iter() {
+			set(&["in", protocol], entry.counters.inbound);
+			set(&["out", protocol], entry.counters.outbound);
+		}
+	}
+}
+
+/// Per-peer byte counters.
+#[derive(Clone)]
+struct PeerBytesSource(BandwidthAccounting);
+
+impl MetricSource for PeerBytesSource {
+	type N = u64;
+
+	fn collect(&self, mut set: impl FnMut(&[&str], Self::N)) {
+		let inner = self.0.inner.lock();
+		let mut peers = inner.peers.iter().collect::<Vec<_>>();
+		peers.sort_unstable_by_key(|(_, counters)| {
+			std::cmp::Reverse(counters.inbound.saturating_add(counters.outbound))
+		});
+
+		for (peer, counters) in peers.into_iter().take(MAX_PEER_METRICS) {
+			let peer = peer.to_base58();
+
+			set(&["in", &peer], counters.inbound);
+			set(&["out", &peer], counters.outbound);
+		}
+	}
+}
+
+/// Per-protocol throttling counters.
+#[derive(Clone)]
+struct ThrottledSource(BandwidthAccounting);
+
+impl MetricSource for ThrottledSource {
+	type N = u64;
+
+	fn collect(&self, mut set: impl FnMut(&[&str], Self::N)) {
+		let inner = self.0.inner.lock();
+
+		for (protocol, entry) in inner.protocols.iter() {
+			set(&[protocol], entry.throttled);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn limit(bytes_per_second: u64, burst: u64) -> BandwidthLimit {
+		BandwidthLimit::new(NonZeroU64::new(bytes_per_second).unwrap()).with_burst(burst)
+	}
+
+	#[test]
+	fn token_bucket_refills_up_to_burst() {
+		let now = Instant::now();
+		let mut bucket = TokenBucket::new(limit(1000, 500), now);
+
+		bucket.consume(2000, now);
+		assert_eq!(bucket.admission_delay(now), Some(Duration::from_millis(1500)));
+
+		// half a second later the debt has been halved
+		let later = now + Duration::from_millis(500);
+		assert_eq!(bucket.admission_delay(later), Some(Duration::from_secs(1)));
+
+		// the bucket never holds more than `burst` tokens
+		let much_later = now + Duration::from_secs(60);
+		assert_eq!(bucket.admission_delay(much_later), None);
+		assert_eq!(bucket.tokens, 500f64);
+	}
+
+	#[test]
+	fn counts_bytes_per_protocol_and_peer() {
+		let accounting = BandwidthAccounting::new();
+		let protocol1 = ProtocolName::from("/protocol/1");
+		let protocol2 = ProtocolName::from("/protocol/2");
+		let peer1 = PeerId::random();
+		let peer2 = PeerId::random();
+
+		accounting.report_inbound(&protocol1, &peer1, 100);
+		accounting.report_outbound(&protocol1, &peer2, 200);
+		accounting.report_outbound(&protocol2, &peer1, 300);
+
+		let state = accounting.state();
+		assert_eq!(
+			state.protocols.get("/protocol/1"),
+			Some(&ProtocolBandwidth { inbound: 100, outbound: 200, throttled: 0, limit: None }),
+		);
+		assert_eq!(
+			state.protocols.get("/protocol/2"),
+			Some(&ProtocolBandwidth { inbound: 0, outbound: 300, throttled: 0, limit: None }),
+		);
+		assert_eq!(
+			state.peers.get(&peer1.to_base58()),
+			Some(&BandwidthCounters { inbound: 100, outbound: 300 }),
+		);
+		assert_eq!(
+			state.peers.get(&peer2.to_base58()),
+			Some(&BandwidthCounters { inbound: 0, outbound: 200 }),
+		);
+	}
+
+	#[test]
+	fn protocol_without_limit_is_always_admitted() {
+		let accounting = BandwidthAccounting::new();
+		let protocol = ProtocolName::from("/protocol/1");
+
+		accounting.report_outbound(&protocol, &PeerId::random(), usize::MAX);
+		assert!(accounting.try_admit(&protocol));
+		assert!(accounting.try_admit(&ProtocolName::from("/unknown/1")));
+	}
+
+	#[test]
+	fn exhausted_limit_rejects_traffic() {
+		let accounting = BandwidthAccounting::new();
+		let protocol = ProtocolName::from("/protocol/1");
+		let other = ProtocolName::from("/protocol/2");
+		let peer = PeerId::random();
+
+		accounting.set_limits([(protocol.clone(), limit(1000, 1000))]);
+		assert!(accounting.try_admit(&protocol));
+
+		// the bucket may go into debt once
+		accounting.report_outbound(&protocol, &peer, 5000);
+		assert!(!accounting.try_admit(&protocol));
+		assert!(!accounting.try_admit(&protocol));
+
+		// other protocols are not affected
+		accounting.report_outbound(&other, &peer, 5000);
+		assert!(accounting.try_admit(&other));
+
+		let state = accounting.state();
+		assert_eq!(state.protocols.get("/protocol/1").unwrap().throttled, 2);
+		assert_eq!(state.protocols.get("/protocol/1").unwrap().limit, Some(1000));
+		assert_eq!(state.protocols.get("/protocol/2").unwrap().throttled, 0);
+
+		// removing the limit admits traffic again
+		accounting.set_limits([]);
+		assert!(accounting.try_admit(&protocol));
+	}
+
+	#[test]
+	fn exempt_protocol_is_never_throttled() {
+		let accounting = BandwidthAccounting::new();
+		let limited = ProtocolName::from("/protocol/1");
+		let exempt = ProtocolName::from("/protocol/2");
+		let peer = PeerId::random();
+
+		accounting
+			.set_limits([(limited.clone(), limit(1000, 0)), (exempt.clone(), limit(1000, 0))]);
+		accounting.exempt([exempt.clone()]);
+		accounting.report_outbound(&limited, &peer, 5000);
+		accounting.report_outbound(&exempt, &peer, 5000);
+		assert!(!accounting.try_admit(&limited));
+		assert!(accounting.try_admit(&exempt));
+
+		// limits configured after the exemption are ignored too
+		accounting.set_limits([(exempt.clone(), limit(1000, 0))]);
+		accounting.report_outbound(&exempt, &peer, 5000);
+		assert!(accounting.try_admit(&exempt));
+
+		let state = accounting.state();
+		assert_eq!(
+			state.protocols.get("/protocol/2"),
+			Some(&ProtocolBandwidth { inbound: 0, outbound: 10_000, throttled: 0, limit: None }),
+		);
+	}
+
+	#[test]
+	fn peer_metrics_expose_most_active_peers() {
+		let accounting = BandwidthAccounting::new();
+		let protocol = ProtocolName::from("/protocol/1");
+		let peers = (0..MAX_PEER_METRICS + 4).map(|_| PeerId::random()).collect::<Vec<_>>();
+
+		for (bytes, peer) in peers.iter().enumerate() {
+			accounting.report_inbound(&protocol, peer, bytes + 1);
+		}
+
+		let mut exposed = Vec::new();
+		PeerBytesSource(accounting.clone()).collect(|labels, _| {
+			if labels[0] == "in" {
+				exposed.push(labels[1].to_string());
+			}
+		});
+		exposed.sort();
+
+		let mut expected = peers[4..].iter().map(|peer| peer.to_base58()).collect::<Vec<_>>();
+		expected.sort();
+		assert_eq!(exposed, expected);
+		assert_eq!(accounting.state().peers.len(), MAX_PEER_METRICS + 4);
+	}
+
+	#[tokio::test]
+	async fn wait_for_admission_delays_until_debt_is_paid() {
+		let accounting = BandwidthAccounting::new();
+		let protocol = ProtocolName::from("/protocol/1");
+
+		accounting.set_limits([(protocol.clone(), limit(10_000, 0))]);
+		accounting.report_outbound(&protocol, &PeerId::random(), 2_000);
+
+		let started = Instant::now();
+		accounting.wait_for_admission(&protocol).await;
+
+		assert!(started.elapsed() >= Duration::from_millis(190));
+		assert!(accounting.try_admit(&protocol));
+		assert_eq!(accounting.state().protocols.get("/protocol/1").unwrap().throttled, 1);
+	}
+}
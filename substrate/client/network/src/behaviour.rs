@@ -17,6 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
+	bandwidth::BandwidthAccounting,
 	discovery::{DiscoveryBehaviour, DiscoveryConfig, DiscoveryOut},
 	event::DhtEvent,
 	peer_info,
@@ -184,6 +185,7 @@ impl<B: BlockT> Behaviour<B> {
 		peer_store_handle: Arc<dyn PeerStoreProvider>,
 		external_addresses: Arc<Mutex<HashSet<Multiaddr>>>,
 		connection_limits: ConnectionLimits,
+		bandwidth: BandwidthAccounting,
 	) -> Result<Self, request_responses::RegisterError> {
 		Ok(Self {
 			substrate,
@@ -196,6 +198,7 @@ impl<B: BlockT> Behaviour<B> {
 			request_responses: request_responses::RequestResponsesBehaviour::new(
 				request_response_protocols.into_iter(),
 				peer_store_handle,
+				bandwidth,
 			)?,
 			connection_limits: libp2p::connection_limits::Behaviour::new(connection_limits),
 		})
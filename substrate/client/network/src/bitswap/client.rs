@@ -0,0 +1,447 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Bitswap client.
+
+use super::{
+	dag::{self, Node, MAX_DAG_BLOCKS, MAX_DAG_DEPTH},
+	schema::bitswap::{
+		message::{
+			wantlist::{Entry, WantType},
+			Wantlist,
+		},
+		Message as BitswapMessage,
+	},
+	transaction_hash, BitswapError, BitswapStore, Cid, Prefix, LOG_TARGET, MAX_WANTED_BLOCKS,
+	PROTOCOL_NAME,
+};
+use crate::{service::traits::NetworkRequest, types::ProtocolName, IfDisconnected};
+
+use log::{debug, error, trace};
+use prost::Message;
+use sc_client_api::BlockBackend;
+use sc_network_types::PeerId;
+use sp_runtime::traits::Block as BlockT;
+use std::{
+	collections::{HashMap, HashSet},
+	sync::Arc,
+};
+
+/// Lookup of locally available blocks.
+type LocalBlocks = Arc<dyn Fn(&Cid) -> Option<Vec<u8>> + Send + Sync>;
+
+/// Bitswap client.
+///
+/// Fetches content from the [`BitswapStore`], the indexed transactions of the chain and, if not
+/// available locally, from remote peers. Fetching from remote peers requires the bitswap
+/// protocol to be registered as a request-response protocol, which is the case for the libp2p
+/// network backend when the bitswap server is enabled.
+///
+/// Every block received from a remote peer is verified against its CID.
+#[derive(Clone)]
+pub struct BitswapClient {
+	/// Network service used to send bitswap requests.
+	network: Arc<dyn NetworkRequest + Send + Sync>,
+
+	/// Store of pinned content.
+	store: BitswapStore,
+
+	/// Lookup of locally available blocks.
+	local: LocalBlocks,
+}
+
+impl BitswapClient {
+	/// Create new [`BitswapClient`].
+	pub fn new<B: BlockT>(
+		network: impl NetworkRequest + Send + Sync + 'static,
+		client: Arc<dyn BlockBackend<B> + Send + Sync>,
+		store: BitswapStore,
+	) -> Self {
+		let local: LocalBlocks = {
+			let store = store.clone();
+
+			Arc::new(move |cid: &Cid| {
+				store.get(cid).or_else(|| {
+					let hash = transaction_hash::<B>(cid)?;
+					client.indexed_transaction(hash).unwrap_or_else(|error| {
+						error!(target: LOG_TARGET, "Error retrieving transaction {hash}: {error}");
+						None
+					})
+				})
+			})
+		};
+
+		Self { network: Arc::new(network), store, local }
+	}
+
+	/// Get the store of pinned content.
+	pub fn store(&self) -> &BitswapStore {
+		&self.store
+	}
+
+	/// Fetch `cids`, querying `peers` in order for blocks that are not available locally.
+	///
+	/// The blocks are returned in the order of `cids`.
+	pub async fn fetch_blocks(
+		&self,
+		cids: &[Cid],
+		peers: &[PeerId],
+	) -> Result<Vec<(Cid, Vec<u8>)>, BitswapError> {
+		let mut found = HashMap::new();
+		let mut missing = Vec::new();
+
+		for cid in cids {
+			match (self.local)(cid) {
+				Some(block) => {
+					found.insert(*cid, block);
+				},
+				None => missing.push(*cid),
+			}
+		}
+
+		for chunk in missing.chunks(MAX_WANTED_BLOCKS) {
+			let mut wanted = chunk.iter().copied().collect::<HashSet<_>>();
+
+			for peer in peers {
+				if wanted.is_empty() {
+					break
+				}
+
+				match self.request(*peer, &wanted).await {
+					Ok(blocks) =>
+						for (cid, block) in blocks {
+							if wanted.remove(&cid) {
+								found.insert(cid, block);
+							}
+						},
+					Err(error) => {
+						debug!(target: LOG_TARGET, "Bitswap request to {peer} failed: {error}");
+					},
+				}
+			}
+
+			if let Some(cid) = wanted.into_iter().next() {
+				return Err(BitswapError::NotFound(cid))
+			}
+		}
+
+		Ok(cids
+			.iter()
+			.map(|cid| (*cid, found.get(cid).cloned().expect("all blocks have been found; qed")))
+			.collect())
+	}
+
+	/// Fetch all blocks of the DAG rooted at `root`.
+	///
+	/// The blocks are returned in breadth-first order, starting with `root`.
+	pub async fn fetch_dag(
+		&self,
+		root: Cid,
+		peers: &[PeerId],
+	) -> Result<Vec<(Cid, Vec<u8>)>, BitswapError> {
+		let mut blocks = Vec::new();
+		let mut seen = HashSet::from([root]);
+		let mut level = vec![root];
+
+		for _ in 0..=MAX_DAG_DEPTH {
+			if level.is_empty() {
+				return Ok(blocks)
+			}
+
+			let mut next = Vec::new();
+
+			for (cid, block) in self.fetch_blocks(&level, peers).await? {
+				for link in Node::decode(&cid, &block)?.links {
+					if seen.insert(link) {
+						next.push(link);
+					}
+				}
+
+				blocks.push((cid, block));
+			}
+
+			if seen.len() > MAX_DAG_BLOCKS {
+				return Err(BitswapError::TooManyBlocks)
+			}
+
+			level = next;
+		}
+
+		if level.is_empty() {
+			Ok(blocks)
+		} else {
+			Err(BitswapError::DagTooDeep)
+		}
+	}
+
+	/// Fetch the content of the DAG rooted at `root`.
+	pub async fn fetch(&self, root: Cid, peers: &[PeerId]) -> Result<Vec<u8>, BitswapError> {
+		let blocks = self.fetch_dag(root, peers).await?.into_iter().collect();
+
+		dag::assemble(&root, &blocks)
+	}
+
+	/// Fetch the DAG rooted at `root` and pin it into the store.
+	pub async fn pin(&self, root: Cid, peers: &[PeerId]) -> Result<(), BitswapError> {
+		let blocks = self.fetch_dag(root, peers).await?;
+		trace!(target: LOG_TARGET, "Pinning {root} consisting of {} blocks", blocks.len());
+
+		self.store.pin(root, blocks)
+	}
+
+	/// Unpin the DAG rooted at `root`.
+	///
+	/// Returns `false` if `root` wasn't pinned.
+	pub fn unpin(&self, root: &Cid) -> Result<bool, BitswapError> {
+		self.store.unpin(root)
+	}
+
+	/// List pinned roots.
+	pub fn pins(&self) -> Vec<Cid> {
+		self.store.pins()
+	}
+
+	/// Request `wanted` blocks from `peer`.
+	async fn request(
+		&self,
+		peer: PeerId,
+		wanted: &HashSet<Cid>,
+	) -> Result<Vec<(Cid, Vec<u8>)>, BitswapError> {
+		let request = BitswapMessage {
+			wantlist: Some(Wantlist {
+				entries: wanted
+					.iter()
+					.map(|cid| Entry {
+						block: cid.to_bytes(),
+						priority: 1,
+						cancel: false,
+						want_type: WantType::Block as i32,
+						send_dont_have: true,
+					})
+					.collect(),
+				full: true,
+			}),
+			..Default::default()
+		};
+
+		let (response, _) = self
+			.network
+			.request(
+				peer,
+				ProtocolName::from(PROTOCOL_NAME),
+				request.encode_to_vec(),
+				None,
+				IfDisconnected::TryConnect,
+			)
+			.await?;
+		let response = BitswapMessage::decode(&response[..])?;
+
+		// the CID is computed from the received data, so blocks that don't match the requested
+		// content are simply not wanted
+		response
+			.payload
+			.into_iter()
+			.map(|block| Ok((Prefix::from_bytes(&block.prefix)?.to_cid(&block.data)?, block.data)))
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		bitswap::{
+			dag::tests::dag_pb_node, schema::bitswap::message::Block as MessageBlock,
+			tests::raw_cid, BitswapRequestHandler,
+		},
+		request_responses::RequestFailure,
+	};
+	use futures::channel::oneshot;
+	use parking_lot::Mutex;
+	use sc_block_builder::BlockBuilderBuilder;
+	use sp_consensus::BlockOrigin;
+	use substrate_test_runtime::ExtrinsicBuilder;
+	use substrate_test_runtime_client::{prelude::*, runtime::Block, TestClientBuilder};
+
+	/// Answers the requests sent to a peer.
+	type Responder = Box<dyn Fn(&Vec<u8>) -> Result<Vec<u8>, RequestFailure> + Send + Sync>;
+
+	/// Network delivering bitswap requests to the known peers.
+	#[derive(Default)]
+	struct TestNetwork {
+		peers: HashMap<PeerId, Responder>,
+	}
+
+	impl TestNetwork {
+		/// Serve the content pinned into `store` as `peer`.
+		fn with_server(mut self, peer: PeerId, store: BitswapStore) -> Self {
+			let client = Arc::new(substrate_test_runtime_client::new());
+			let (handler, _) = BitswapRequestHandler::<Block>::new(client, store);
+			let handler = Mutex::new(handler);
+
+			self.peers.insert(
+				peer,
+				Box::new(move |request| {
+					handler
+						.lock()
+						.handle_message(&peer, request)
+						.map_err(|_| RequestFailure::Refused)
+				}),
+			);
+			self
+		}
+
+		/// Answer all requests sent to `peer` with `response`.
+		fn with_response(mut self, peer: PeerId, response: BitswapMessage) -> Self {
+			let response = response.encode_to_vec();
+			self.peers.insert(peer, Box::new(move |_| Ok(response.clone())));
+			self
+		}
+	}
+
+	#[async_trait::async_trait]
+	impl NetworkRequest for TestNetwork {
+		async fn request(
+			&self,
+			target: PeerId,
+			protocol: ProtocolName,
+			request: Vec<u8>,
+			_fallback_request: Option<(Vec<u8>, ProtocolName)>,
+			_connect: IfDisconnected,
+		) -> Result<(Vec<u8>, ProtocolName), RequestFailure> {
+			let responder = self.peers.get(&target).ok_or(RequestFailure::NotConnected)?;
+			Ok((responder(&request)?, protocol))
+		}
+
+		fn start_request(
+			&self,
+			_target: PeerId,
+			_protocol: ProtocolName,
+			_request: Vec<u8>,
+			_fallback_request: Option<(Vec<u8>, ProtocolName)>,
+			_tx: oneshot::Sender<Result<(Vec<u8>, ProtocolName), RequestFailure>>,
+			_connect: IfDisconnected,
+		) {
+			unimplemented!("the bitswap client only sends requests through `request`")
+		}
+	}
+
+	fn bitswap_client(network: TestNetwork) -> BitswapClient {
+		BitswapClient::new::<Block>(
+			network,
+			Arc::new(substrate_test_runtime_client::new()),
+			BitswapStore::in_memory(),
+		)
+	}
+
+	/// A file split into a DAG-PB root linking to two raw leaves.
+	fn file() -> (Cid, Vec<(Cid, Vec<u8>)>) {
+		let (hello, world) = (raw_cid(b"hello"), raw_cid(b" world"));
+		let (root, root_block) = dag_pb_node(b"", &[hello, world]);
+
+		(root, vec![(root, root_block), (hello, b"hello".to_vec()), (world, b" world".to_vec())])
+	}
+
+	#[tokio::test]
+	async fn fetch_and_pin_from_remote_peer() {
+		let (root, blocks) = file();
+		let remote_store = BitswapStore::in_memory();
+		remote_store.pin(root, blocks).unwrap();
+
+		let (unknown, remote) = (PeerId::random(), PeerId::random());
+		let client = bitswap_client(TestNetwork::default().with_server(remote, remote_store));
+
+		// Peers that fail to answer are skipped.
+		assert_eq!(client.fetch(root, &[unknown, remote]).await.unwrap(), b"hello world".to_vec());
+		assert!(client.pins().is_empty());
+
+		client.pin(root, &[remote]).await.unwrap();
+		assert_eq!(client.pins(), vec![root]);
+
+		// Pinned content is available without querying any peer.
+		assert_eq!(client.fetch(root, &[]).await.unwrap(), b"hello world".to_vec());
+
+		assert!(client.unpin(&root).unwrap());
+		assert!(!client.unpin(&root).unwrap());
+		assert!(matches!(
+			client.fetch(root, &[]).await,
+			Err(BitswapError::NotFound(cid)) if cid == root,
+		));
+	}
+
+	#[tokio::test]
+	async fn blocks_not_matching_the_requested_cid_are_ignored() {
+		let cid = raw_cid(b"genuine");
+		let prefix = Prefix {
+			version: cid.version(),
+			codec: cid.codec(),
+			mh_type: cid.hash().code(),
+			mh_len: cid.hash().size(),
+		};
+		let forged = BitswapMessage {
+			payload: vec![MessageBlock { prefix: prefix.to_bytes(), data: b"forged".to_vec() }],
+			..Default::default()
+		};
+
+		let honest_store = BitswapStore::in_memory();
+		honest_store.pin(cid, vec![(cid, b"genuine".to_vec())]).unwrap();
+
+		let (forger, honest) = (PeerId::random(), PeerId::random());
+		let client = bitswap_client(
+			TestNetwork::default()
+				.with_response(forger, forged)
+				.with_server(honest, honest_store),
+		);
+
+		assert!(matches!(
+			client.fetch_blocks(&[cid], &[forger]).await,
+			Err(BitswapError::NotFound(missing)) if missing == cid,
+		));
+		assert_eq!(
+			client.fetch_blocks(&[cid], &[forger, honest]).await.unwrap(),
+			vec![(cid, b"genuine".to_vec())],
+		);
+	}
+
+	#[tokio::test]
+	async fn indexed_transactions_are_fetched_locally() {
+		let client = TestClientBuilder::with_tx_storage(u32::MAX).build();
+		let mut block_builder = BlockBuilderBuilder::new(&client)
+			.on_parent_block(client.chain_info().genesis_hash)
+			.with_parent_block_number(0)
+			.build()
+			.unwrap();
+		let data = vec![0x13, 0x37, 0x13, 0x38];
+		block_builder
+			.push(ExtrinsicBuilder::new_indexed_call(data.clone()).build())
+			.unwrap();
+		let block = block_builder.build().unwrap().block;
+		client.import(BlockOrigin::File, block).await.unwrap();
+
+		let bitswap = BitswapClient::new::<Block>(
+			TestNetwork::default(),
+			Arc::new(client),
+			BitswapStore::in_memory(),
+		);
+		let cid = raw_cid(&data);
+
+		assert_eq!(bitswap.fetch(cid, &[]).await.unwrap(), data);
+
+		bitswap.pin(cid, &[]).await.unwrap();
+		assert_eq!(bitswap.pins(), vec![cid]);
+		assert_eq!(bitswap.store().get(&cid), Some(data));
+	}
+}
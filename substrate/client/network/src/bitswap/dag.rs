@@ -0,0 +1,207 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Decoding and reassembly of DAGs.
+//!
+//! Raw blocks are leaves of the DAG. DAG-PB nodes may link to child nodes and embed data, either
+//! directly or wrapped in UnixFS metadata. The content of a node is its embedded data followed by
+//! the content of its children, in order.
+
+use super::{
+	schema::{dag_pb::PbNode, unixfs::Data as UnixFsData},
+	BitswapError, Cid, DAG_PB_CODEC,
+};
+
+use prost::Message;
+use std::collections::HashMap;
+
+/// Max depth of a DAG.
+pub(crate) const MAX_DAG_DEPTH: usize = 32;
+
+/// Max number of blocks in a DAG.
+///
+/// Blocks linked to more than once are counted every time they are visited, so that DAGs that
+/// reuse their nodes can't blow up the reassembly.
+pub(crate) const MAX_DAG_BLOCKS: usize = 16384;
+
+/// Max size of the content of a DAG, in bytes.
+pub(crate) const MAX_DAG_SIZE: usize = 64 * 1024 * 1024;
+
+/// Decoded DAG node.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Node {
+	/// Data embedded in the node.
+	pub data: Vec<u8>,
+
+	/// Child nodes, in order.
+	pub links: Vec<Cid>,
+}
+
+impl Node {
+	/// Decode `block` referenced by `cid`.
+	///
+	/// Blocks that are not valid DAG-PB nodes are treated as leaves.
+	pub fn decode(cid: &Cid, block: &[u8]) -> Result<Self, BitswapError> {
+		let leaf = || Self { data: block.to_vec(), links: Vec::new() };
+
+		if cid.codec() != DAG_PB_CODEC {
+			return Ok(leaf())
+		}
+
+		let Ok(node) = PbNode::decode(block) else { return Ok(leaf()) };
+
+		let links = node
+			.links
+			.iter()
+			.map(|link| Cid::read_bytes(link.hash.as_deref().unwrap_or_default()))
+			.collect::<Result<Vec<_>, _>>()?;
+		let data = match node.data {
+			Some(data) => match UnixFsData::decode(&data[..]) {
+				Ok(unixfs) => unixfs.data.unwrap_or_default(),
+				Err(_) => data,
+			},
+			None => Vec::new(),
+		};
+
+		Ok(Self { data, links })
+	}
+}
+
+/// Reassemble the content of the DAG rooted at `root` from its `blocks`.
+///
+/// Fails if the DAG is deeper than [`MAX_DAG_DEPTH`], visits more than [`MAX_DAG_BLOCKS`] nodes
+/// or its content exceeds [`MAX_DAG_SIZE`].
+pub(crate) fn assemble(
+	root: &Cid,
+	blocks: &HashMap<Cid, Vec<u8>>,
+) -> Result<Vec<u8>, BitswapError> {
+	struct Assembly<'a> {
+		blocks: &'a HashMap<Cid, Vec<u8>>,
+		visited: usize,
+		content: Vec<u8>,
+	}
+
+	impl Assembly<'_> {
+		fn append(&mut self, cid: &Cid, depth: usize) -> Result<(), BitswapError> {
+			if depth > MAX_DAG_DEPTH {
+				return Err(BitswapError::DagTooDeep)
+			}
+
+			self.visited += 1;
+			if self.visited > MAX_DAG_BLOCKS {
+				return Err(BitswapError::TooManyBlocks)
+			}
+
+			let block = self.blocks.get(cid).ok_or(BitswapError::MissingBlock(*cid))?;
+			let node = Node::decode(cid, block)?;
+			if self.content.len().saturating_add(node.data.len()) > MAX_DAG_SIZE {
+				return Err(BitswapError::DagTooLarge)
+			}
+			self.content.extend(node.data);
+
+			for link in &node.links {
+				self.append(link, depth + 1)?;
+			}
+
+			Ok(())
+		}
+	}
+
+	let mut assembly = Assembly { blocks, visited: 0, content: Vec::new() };
+	assembly.append(root, 0)?;
+	Ok(assembly.content)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+	use crate::bitswap::{
+		schema::{dag_pb::PbLink, unixfs::data::DataType},
+		BLAKE2B_256,
+	};
+	use sp_crypto_hashing::blake2_256;
+
+	/// Encode a DAG-PB node embedding `data` in UnixFS metadata and linking to `links`.
+	pub(crate) fn dag_pb_node(data: &[u8], links: &[Cid]) -> (Cid, Vec<u8>) {
+		let unixfs = UnixFsData {
+			r#type: DataType::File as i32,
+			data: Some(data.to_vec()),
+			..Default::default()
+		};
+		let node = PbNode {
+			links: links
+				.iter()
+				.map(|cid| PbLink { hash: Some(cid.to_bytes()), ..Default::default() })
+				.collect(),
+			data: Some(unixfs.encode_to_vec()),
+		};
+		let block = node.encode_to_vec();
+		let cid = Cid::new_v1(
+			DAG_PB_CODEC,
+			cid::multihash::Multihash::wrap(BLAKE2B_256, &blake2_256(&block)).unwrap(),
+		);
+
+		(cid, block)
+	}
+
+	#[test]
+	fn assemble_file() {
+		let leaf = crate::bitswap::tests::raw_cid(b" world");
+		let (inner, inner_block) = dag_pb_node(b"hello", &[]);
+		let (root, root_block) = dag_pb_node(b"", &[inner, leaf]);
+		let blocks =
+			HashMap::from([(root, root_block), (inner, inner_block), (leaf, b" world".to_vec())]);
+
+		assert_eq!(assemble(&root, &blocks).unwrap(), b"hello world".to_vec());
+	}
+
+	#[test]
+	fn missing_block() {
+		let leaf = crate::bitswap::tests::raw_cid(b"leaf");
+		let (root, root_block) = dag_pb_node(b"", &[leaf]);
+		let blocks = HashMap::from([(root, root_block)]);
+
+		assert!(
+			matches!(assemble(&root, &blocks), Err(BitswapError::MissingBlock(cid)) if cid == leaf)
+		);
+	}
+
+	#[test]
+	fn reused_nodes_are_counted_on_every_visit() {
+		// Every level links twice to the level below, so the DAG consists of 16 blocks but
+		// assembling it visits 2^16 nodes.
+		let (mut cid, block) = dag_pb_node(b"x", &[]);
+		let mut blocks = HashMap::from([(cid, block)]);
+		for _ in 0..15 {
+			let (parent, block) = dag_pb_node(b"", &[cid, cid]);
+			blocks.insert(parent, block);
+			cid = parent;
+		}
+
+		assert!(matches!(assemble(&cid, &blocks), Err(BitswapError::TooManyBlocks)));
+	}
+
+	#[test]
+	fn content_size_is_limited() {
+		// The blocks are not verified against their CIDs during the reassembly.
+		let leaf = crate::bitswap::tests::raw_cid(b"leaf");
+		let links = vec![leaf; MAX_DAG_SIZE / (1024 * 1024) + 1];
+		let (root, root_block) = dag_pb_node(b"", &links);
+		let blocks = HashMap::from([(root, root_block), (leaf, vec![0; 1024 * 1024])]);
+
+		assert!(matches!(assemble(&root, &blocks), Err(BitswapError::DagTooLarge)));
+	}
+}
@@ -14,14 +14,18 @@
 // You should have received a copy of the GNU General Public License
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
-//! Bitswap server for Substrate.
+//! Bitswap server and client for Substrate.
 //!
-//! Allows querying transactions by hash over standard bitswap protocol
-//! Only supports bitswap 1.2.0.
-//! CID is expected to reference 256-bit Blake2b transaction hash.
+//! Allows querying transactions by hash and content pinned in the [`BitswapStore`] over standard
+//! bitswap protocol. Only supports bitswap 1.2.0.
+//! CIDs of indexed transactions are expected to reference 256-bit Blake2b transaction hash.
+//!
+//! [`BitswapClient`] fetches content from remote peers, reassembles files split into DAG-PB
+//! nodes and pins content into the [`BitswapStore`] so that it keeps being served after the
+//! transactions it was stored in have been pruned.
 
 use crate::{
-	request_responses::{IncomingRequest, OutgoingResponse, ProtocolConfig},
+	request_responses::{IncomingRequest, OutgoingResponse, ProtocolConfig, RequestFailure},
 	types::ProtocolName,
 	MAX_RESPONSE_SIZE,
 };
@@ -36,11 +40,18 @@ use schema::bitswap::{
 	message::{wantlist::WantType, Block as MessageBlock, BlockPresence, BlockPresenceType},
 	Message as BitswapMessage,
 };
+use sp_crypto_hashing::{blake2_256, sha2_256};
 use sp_runtime::traits::Block as BlockT;
 use std::{io, sync::Arc, time::Duration};
-use unsigned_varint::encode as varint_encode;
+use unsigned_varint::{decode as varint_decode, encode as varint_encode};
+
+pub use self::{client::BitswapClient, store::BitswapStore};
+pub use cid::Cid;
 
+mod client;
+mod dag;
 mod schema;
+mod store;
 
 const LOG_TARGET: &str = "bitswap";
 
@@ -59,6 +70,51 @@ const MAX_WANTED_BLOCKS: usize = 16;
 /// Bitswap protocol name
 const PROTOCOL_NAME: &'static str = "/ipfs/bitswap/1.2.0";
 
+/// Multicodec of DAG-PB nodes.
+const DAG_PB_CODEC: u64 = 0x70;
+
+/// Multihash code of 256-bit Blake2b.
+const BLAKE2B_256: u64 = 0xb220;
+
+/// Multihash code of SHA2-256.
+const SHA2_256: u64 = 0x12;
+
+/// Hash `data` with the multihash function `code`, if it is supported.
+fn hash_block(code: u64, data: &[u8]) -> Option<[u8; 32]> {
+	match code {
+		BLAKE2B_256 => Some(blake2_256(data)),
+		SHA2_256 => Some(sha2_256(data)),
+		_ => None,
+	}
+}
+
+/// Verify that `data` is the content referenced by `cid`.
+pub(crate) fn verify_block(cid: &Cid, data: &[u8]) -> Result<(), BitswapError> {
+	let digest = hash_block(cid.hash().code(), data).ok_or(BitswapError::UnsupportedCid(*cid))?;
+
+	if cid.hash().digest() != &digest[..] {
+		return Err(BitswapError::InvalidBlock(*cid))
+	}
+
+	Ok(())
+}
+
+/// Get the hash of the indexed transaction referenced by `cid`.
+///
+/// Returns `None` if `cid` can't reference an indexed transaction.
+pub(crate) fn transaction_hash<B: BlockT>(cid: &Cid) -> Option<B::Hash> {
+	if cid.version() != cid::Version::V1 ||
+		cid.hash().code() != BLAKE2B_256 ||
+		cid.hash().size() != 32
+	{
+		return None
+	}
+
+	let mut hash = B::Hash::default();
+	hash.as_mut().copy_from_slice(&cid.hash().digest()[0..32]);
+	Some(hash)
+}
+
 /// Prefix represents all metadata of a CID, without the actual content.
 #[derive(PartialEq, Eq, Clone, Debug)]
 struct Prefix {
@@ -90,17 +146,49 @@ impl Prefix {
 		res.extend_from_slice(mh_len);
 		res
 	}
+
+	/// Decode the prefix from encoded bytes.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, BitswapError> {
+		let (version, rest) = varint_decode::u64(bytes)?;
+		let (codec, rest) = varint_decode::u64(rest)?;
+		let (mh_type, rest) = varint_decode::u64(rest)?;
+		let (mh_len, _) = varint_decode::u64(rest)?;
+
+		Ok(Self {
+			version: Version::try_from(version)?,
+			codec,
+			mh_type,
+			mh_len: u8::try_from(mh_len).map_err(|_| BitswapError::InvalidPrefix)?,
+		})
+	}
+
+	/// Compute the CID of `data` described by the prefix.
+	pub fn to_cid(&self, data: &[u8]) -> Result<Cid, BitswapError> {
+		let digest = hash_block(self.mh_type, data).ok_or(BitswapError::InvalidPrefix)?;
+		if usize::from(self.mh_len) != digest.len() {
+			return Err(BitswapError::InvalidPrefix)
+		}
+
+		let hash = cid::multihash::Multihash::wrap(self.mh_type, &digest)
+			.map_err(|_| BitswapError::InvalidPrefix)?;
+
+		Ok(Cid::new(self.version, self.codec, hash)?)
+	}
 }
 
 /// Bitswap request handler
 pub struct BitswapRequestHandler<B> {
 	client: Arc<dyn BlockBackend<B> + Send + Sync>,
+	store: BitswapStore,
 	request_receiver: async_channel::Receiver<IncomingRequest>,
 }
 
 impl<B: BlockT> BitswapRequestHandler<B> {
 	/// Create a new [`BitswapRequestHandler`].
-	pub fn new(client: Arc<dyn BlockBackend<B> + Send + Sync>) -> (Self, ProtocolConfig) {
+	pub fn new(
+		client: Arc<dyn BlockBackend<B> + Send + Sync>,
+		store: BitswapStore,
+	) -> (Self, ProtocolConfig) {
 		let (tx, request_receiver) = async_channel::bounded(MAX_REQUEST_QUEUE);
 
 		let config = ProtocolConfig {
@@ -112,7 +200,7 @@ impl<B: BlockT> BitswapRequestHandler<B> {
 			inbound_queue: Some(tx),
 		};
 
-		(Self { client, request_receiver }, config)
+		(Self { client, store, request_receiver }, config)
 	}
 
 	/// Run [`BitswapRequestHandler`].
@@ -196,28 +284,36 @@ impl<B: BlockT> BitswapRequestHandler<B> {
 				},
 			};
 
-			if cid.version() != cid::Version::V1 ||
-				cid.hash().code() != u64::from(cid::multihash::Code::Blake2b256) ||
-				cid.hash().size() != 32
-			{
-				debug!(target: LOG_TARGET, "Ignoring unsupported CID {}: {}", peer, cid);
-				continue
-			}
+			let block = match self.store.get(&cid) {
+				Some(block) => {
+					trace!(target: LOG_TARGET, "Found pinned CID {:?}", cid);
+					Some(block)
+				},
+				None => {
+					let Some(hash) = transaction_hash::<B>(&cid) else {
+						debug!(target: LOG_TARGET, "Ignoring unsupported CID {}: {}", peer, cid);
+						continue
+					};
 
-			let mut hash = B::Hash::default();
-			hash.as_mut().copy_from_slice(&cid.hash().digest()[0..32]);
-			let transaction = match self.client.indexed_transaction(hash) {
-				Ok(ex) => ex,
-				Err(e) => {
-					error!(target: LOG_TARGET, "Error retrieving transaction {}: {}", hash, e);
-					None
+					match self.client.indexed_transaction(hash) {
+						Ok(Some(transaction)) => {
+							trace!(target: LOG_TARGET, "Found CID {:?}, hash {:?}", cid, hash);
+							Some(transaction)
+						},
+						Ok(None) => {
+							trace!(target: LOG_TARGET, "Missing CID {:?}, hash {:?}", cid, hash);
+							None
+						},
+						Err(e) => {
+							error!(target: LOG_TARGET, "Error retrieving transaction {}: {}", hash, e);
+							None
+						},
+					}
 				},
 			};
 
-			match transaction {
-				Some(transaction) => {
-					trace!(target: LOG_TARGET, "Found CID {:?}, hash {:?}", cid, hash);
-
+			match block {
+				Some(block) =>
 					if entry.want_type == WantType::Block as i32 {
 						let prefix = Prefix {
 							version: cid.version(),
@@ -227,24 +323,20 @@ impl<B: BlockT> BitswapRequestHandler<B> {
 						};
 						response
 							.payload
-							.push(MessageBlock { prefix: prefix.to_bytes(), data: transaction });
+							.push(MessageBlock { prefix: prefix.to_bytes(), data: block });
 					} else {
 						response.block_presences.push(BlockPresence {
 							r#type: BlockPresenceType::Have as i32,
 							cid: cid.to_bytes(),
 						});
-					}
-				},
-				None => {
-					trace!(target: LOG_TARGET, "Missing CID {:?}, hash {:?}", cid, hash);
-
+					},
+				None =>
 					if entry.send_dont_have {
 						response.block_presences.push(BlockPresence {
 							r#type: BlockPresenceType::DontHave as i32,
 							cid: cid.to_bytes(),
 						});
-					}
-				},
+					},
 			}
 		}
 
@@ -286,6 +378,46 @@ pub enum BitswapError {
 	/// Too many blocks requested.
 	#[error("Too many block entries in the request.")]
 	TooManyEntries,
+
+	/// Varint decoding error.
+	#[error("Failed to decode varint: {0}.")]
+	DecodeVarint(#[from] varint_decode::Error),
+
+	/// Block prefix is malformed or uses an unsupported hash function.
+	#[error("Invalid block prefix.")]
+	InvalidPrefix,
+
+	/// CID uses an unsupported hash function.
+	#[error("Unsupported CID {0}.")]
+	UnsupportedCid(Cid),
+
+	/// Block data doesn't match its CID.
+	#[error("Block data doesn't match CID {0}.")]
+	InvalidBlock(Cid),
+
+	/// Block is missing from the pinned content.
+	#[error("Missing block {0}.")]
+	MissingBlock(Cid),
+
+	/// Block was not found locally nor on any of the queried peers.
+	#[error("Block {0} not found.")]
+	NotFound(Cid),
+
+	/// Bitswap request failed.
+	#[error(transparent)]
+	Request(#[from] RequestFailure),
+
+	/// DAG is deeper than allowed.
+	#[error("DAG is too deep.")]
+	DagTooDeep,
+
+	/// DAG consists of more blocks than allowed.
+	#[error("DAG consists of too many blocks.")]
+	TooManyBlocks,
+
+	/// Content of the DAG is larger than allowed.
+	#[error("DAG content is too large.")]
+	DagTooLarge,
 }
 
 #[cfg(test)]
@@ -302,10 +434,22 @@ mod tests {
 	use substrate_test_runtime::ExtrinsicBuilder;
 	use substrate_test_runtime_client::{self, prelude::*, TestClientBuilder};
 
+	/// Multicodec of raw binary blocks.
+	const RAW_CODEC: u64 = 0x55;
+
+	/// Get the CID of raw `data` hashed with 256-bit Blake2b.
+	pub(crate) fn raw_cid(data: &[u8]) -> Cid {
+		Cid::new_v1(
+			RAW_CODEC,
+			cid::multihash::Multihash::wrap(BLAKE2B_256, &blake2_256(data)).unwrap(),
+		)
+	}
+
 	#[tokio::test]
 	async fn undecodable_message() {
 		let client = substrate_test_runtime_client::new();
-		let (bitswap, config) = BitswapRequestHandler::new(Arc::new(client));
+		let (bitswap, config) =
+			BitswapRequestHandler::new(Arc::new(client), BitswapStore::in_memory());
 
 		tokio::spawn(async move { bitswap.run().await });
 
@@ -333,7 +477,8 @@ mod tests {
 	#[tokio::test]
 	async fn empty_want_list() {
 		let client = substrate_test_runtime_client::new();
-		let (bitswap, mut config) = BitswapRequestHandler::new(Arc::new(client));
+		let (bitswap, mut config) =
+			BitswapRequestHandler::new(Arc::new(client), BitswapStore::in_memory());
 
 		tokio::spawn(async move { bitswap.run().await });
 
@@ -387,7 +532,8 @@ mod tests {
 	#[tokio::test]
 	async fn too_long_want_list() {
 		let client = substrate_test_runtime_client::new();
-		let (bitswap, config) = BitswapRequestHandler::new(Arc::new(client));
+		let (bitswap, config) =
+			BitswapRequestHandler::new(Arc::new(client), BitswapStore::in_memory());
 
 		tokio::spawn(async move { bitswap.run().await });
 
@@ -425,7 +571,8 @@ mod tests {
 	async fn transaction_not_found() {
 		let client = TestClientBuilder::with_tx_storage(u32::MAX).build();
 
-		let (bitswap, config) = BitswapRequestHandler::new(Arc::new(client));
+		let (bitswap, config) =
+			BitswapRequestHandler::new(Arc::new(client), BitswapStore::in_memory());
 		tokio::spawn(async move { bitswap.run().await });
 
 		let (tx, rx) = oneshot::channel();
@@ -485,7 +632,8 @@ mod tests {
 
 		client.import(BlockOrigin::File, block).await.unwrap();
 
-		let (bitswap, config) = BitswapRequestHandler::new(Arc::new(client));
+		let (bitswap, config) =
+			BitswapRequestHandler::new(Arc::new(client), BitswapStore::in_memory());
 
 		tokio::spawn(async move { bitswap.run().await });
 
@@ -530,4 +678,41 @@ mod tests {
 			panic!("invalid event received");
 		}
 	}
+
+	#[tokio::test]
+	async fn pinned_block_found() {
+		let client = substrate_test_runtime_client::new();
+		let store = BitswapStore::in_memory();
+		let cid = raw_cid(b"pinned");
+		store.pin(cid, vec![(cid, b"pinned".to_vec())]).unwrap();
+
+		let (bitswap, config) = BitswapRequestHandler::new(Arc::new(client), store);
+		tokio::spawn(async move { bitswap.run().await });
+
+		let (tx, rx) = oneshot::channel();
+		config
+			.inbound_queue
+			.unwrap()
+			.send(IncomingRequest {
+				peer: PeerId::random(),
+				payload: BitswapMessage {
+					wantlist: Some(Wantlist {
+						entries: vec![Entry { block: cid.to_bytes(), ..Default::default() }],
+						full: false,
+					}),
+					..Default::default()
+				}
+				.encode_to_vec(),
+				pending_response: tx,
+			})
+			.await
+			.unwrap();
+
+		let OutgoingResponse { result, .. } = rx.await.unwrap();
+		let response =
+			schema::bitswap::Message::decode(&result.expect("fetch to succeed")[..]).unwrap();
+		let block = &response.payload[0];
+		assert_eq!(block.data, b"pinned".to_vec());
+		assert_eq!(Prefix::from_bytes(&block.prefix).unwrap().to_cid(&block.data).unwrap(), cid);
+	}
 }
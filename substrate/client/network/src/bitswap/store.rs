@@ -0,0 +1,279 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Store of content pinned by the local node.
+
+use super::{dag, verify_block, BitswapError, Cid, LOG_TARGET};
+
+use parking_lot::RwLock;
+use std::{
+	collections::{HashMap, HashSet},
+	fs, io,
+	path::PathBuf,
+	str::FromStr,
+	sync::Arc,
+};
+
+/// Directory of the stored blocks, relative to the store path.
+const BLOCKS_DIR: &str = "blocks";
+
+/// Directory of the pinned roots, relative to the store path.
+const PINS_DIR: &str = "pins";
+
+#[derive(Default)]
+struct Inner {
+	/// Stored blocks.
+	blocks: HashMap<Cid, Vec<u8>>,
+
+	/// Pinned roots and the blocks of their DAGs.
+	pins: HashMap<Cid, Vec<Cid>>,
+}
+
+/// Store of content pinned by the local node.
+///
+/// Blocks of pinned content are served over bitswap in addition to the indexed transactions of
+/// the chain and, unlike indexed transactions, are not removed when blocks are pruned. If the
+/// store has a path, its content is persisted on disk and survives restarts.
+///
+/// Cloning the store returns a handle to the same underlying state.
+#[derive(Clone, Default)]
+pub struct BitswapStore {
+	inner: Arc<RwLock<Inner>>,
+	path: Option<PathBuf>,
+}
+
+impl std::fmt::Debug for BitswapStore {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("BitswapStore").field("path", &self.path).finish_non_exhaustive()
+	}
+}
+
+impl BitswapStore {
+	/// Create a store that keeps its content only in memory.
+	pub fn in_memory() -> Self {
+		Self::default()
+	}
+
+	/// Open the store persisted at `path`, creating it if it doesn't exist.
+	pub fn open(path: PathBuf) -> io::Result<Self> {
+		fs::create_dir_all(path.join(BLOCKS_DIR))?;
+		fs::create_dir_all(path.join(PINS_DIR))?;
+
+		let mut inner = Inner::default();
+
+		for entry in fs::read_dir(path.join(PINS_DIR))? {
+			let entry = entry?;
+			let Some(root) = Self::parse_file_name(&entry) else { continue };
+			let blocks = fs::read_to_string(entry.path())?
+				.lines()
+				.filter_map(|line| Cid::from_str(line).ok())
+				.collect::<Vec<_>>();
+
+			for cid in &blocks {
+				if inner.blocks.contains_key(cid) {
+					continue
+				}
+
+				match fs::read(path.join(BLOCKS_DIR).join(cid.to_string())) {
+					Ok(data) => {
+						inner.blocks.insert(*cid, data);
+					},
+					Err(error) => log::warn!(
+						target: LOG_TARGET,
+						"failed to load block {cid} of pinned content {root}: {error}",
+					),
+				}
+			}
+
+			inner.pins.insert(root, blocks);
+		}
+
+		log::debug!(target: LOG_TARGET, "loaded {} pinned roots from {path:?}", inner.pins.len());
+
+		Ok(Self { inner: Arc::new(RwLock::new(inner)), path: Some(path) })
+	}
+
+	/// Get stored block.
+	pub fn get(&self, cid: &Cid) -> Option<Vec<u8>> {
+		self.inner.read().blocks.get(cid).cloned()
+	}
+
+	/// Check whether `root` is pinned.
+	pub fn is_pinned(&self, root: &Cid) -> bool {
+		self.inner.read().pins.contains_key(root)
+	}
+
+	/// List pinned roots.
+	pub fn pins(&self) -> Vec<Cid> {
+		let mut pins = self.inner.read().pins.keys().copied().collect::<Vec<_>>();
+		pins.sort();
+		pins
+	}
+
+	/// Pin the DAG rooted at `root`, consisting of `blocks`.
+	///
+	/// The data of each block is verified against its CID and the DAG must be complete and within
+	/// the limits of its reassembly before anything is stored.
+	pub fn pin(&self, root: Cid, blocks: Vec<(Cid, Vec<u8>)>) -> Result<(), BitswapError> {
+		for (cid, data) in &blocks {
+			verify_block(cid, data)?;
+		}
+
+		dag::assemble(&root, &blocks.iter().cloned().collect())?;
+
+		if let Some(path) = &self.path {
+			for (cid, data) in &blocks {
+				fs::write(path.join(BLOCKS_DIR).join(cid.to_string()), data)?;
+			}
+
+			let index = blocks.iter().map(|(cid, _)| cid.to_string()).collect::<Vec<_>>();
+			fs::write(path.join(PINS_DIR).join(root.to_string()), index.join("\n"))?;
+		}
+
+		let mut inner = self.inner.write();
+		let cids = blocks.iter().map(|(cid, _)| *cid).collect();
+		inner.blocks.extend(blocks);
+		inner.pins.insert(root, cids);
+
+		Ok(())
+	}
+
+	/// Unpin the DAG rooted at `root`.
+	///
+	/// Blocks that are not part of any other pinned DAG are removed from the store. Returns
+	/// `false` if `root` wasn't pinned.
+	pub fn unpin(&self, root: &Cid) -> Result<bool, BitswapError> {
+		let mut inner = self.inner.write();
+		let Some(blocks) = inner.pins.remove(root) else { return Ok(false) };
+
+		let referenced = inner.pins.values().flatten().copied().collect::<HashSet<_>>();
+		let removed = blocks.into_iter().filter(|cid| !referenced.contains(cid));
+
+		if let Some(path) = &self.path {
+			fs::remove_file(path.join(PINS_DIR).join(root.to_string()))?;
+
+			for cid in removed {
+				inner.blocks.remove(&cid);
+
+				if let Err(error) = fs::remove_file(path.join(BLOCKS_DIR).join(cid.to_string())) {
+					log::debug!(target: LOG_TARGET, "failed to remove block {cid}: {error}");
+				}
+			}
+		} else {
+			removed.for_each(|cid| {
+				inner.blocks.remove(&cid);
+			});
+		}
+
+		Ok(true)
+	}
+
+	fn parse_file_name(entry: &fs::DirEntry) -> Option<Cid> {
+		entry.file_name().to_str().and_then(|name| Cid::from_str(name).ok())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::bitswap::{
+		schema::dag_pb::{PbLink, PbNode},
+		tests::raw_cid,
+		BLAKE2B_256, DAG_PB_CODEC,
+	};
+	use prost::Message;
+	use sp_crypto_hashing::blake2_256;
+
+	#[test]
+	fn pin_and_unpin() {
+		let store = BitswapStore::in_memory();
+		let (leaf1, leaf2) = (raw_cid(b"leaf1"), raw_cid(b"leaf2"));
+
+		store.pin(leaf1, vec![(leaf1, b"leaf1".to_vec())]).unwrap();
+		store
+			.pin(leaf2, vec![(leaf2, b"leaf2".to_vec()), (leaf1, b"leaf1".to_vec())])
+			.unwrap();
+		assert_eq!(store.pins().len(), 2);
+
+		// `leaf1` is still referenced by the DAG of `leaf2`
+		assert!(store.unpin(&leaf1).unwrap());
+		assert!(!store.is_pinned(&leaf1));
+		assert_eq!(store.get(&leaf1), Some(b"leaf1".to_vec()));
+
+		assert!(store.unpin(&leaf2).unwrap());
+		assert!(!store.unpin(&leaf2).unwrap());
+		assert_eq!(store.get(&leaf1), None);
+		assert_eq!(store.get(&leaf2), None);
+	}
+
+	#[test]
+	fn pinning_verifies_blocks() {
+		let store = BitswapStore::in_memory();
+		let cid = raw_cid(b"data");
+
+		assert!(matches!(
+			store.pin(cid, vec![(cid, b"other data".to_vec())]),
+			Err(BitswapError::InvalidBlock(_)),
+		));
+		assert!(matches!(
+			store.pin(cid, vec![(raw_cid(b"other"), b"other".to_vec())]),
+			Err(BitswapError::MissingBlock(_)),
+		));
+		assert!(store.pins().is_empty());
+	}
+
+	#[test]
+	fn pinning_requires_complete_dag() {
+		let store = BitswapStore::in_memory();
+		let leaf = raw_cid(b"leaf");
+		let node = PbNode {
+			links: vec![PbLink { hash: Some(leaf.to_bytes()), ..Default::default() }],
+			data: None,
+		}
+		.encode_to_vec();
+		let root = Cid::new_v1(
+			DAG_PB_CODEC,
+			cid::multihash::Multihash::wrap(BLAKE2B_256, &blake2_256(&node)).unwrap(),
+		);
+
+		assert!(matches!(
+			store.pin(root, vec![(root, node.clone())]),
+			Err(BitswapError::MissingBlock(cid)) if cid == leaf,
+		));
+		assert!(store.pins().is_empty());
+
+		store.pin(root, vec![(root, node), (leaf, b"leaf".to_vec())]).unwrap();
+		assert_eq!(store.pins(), vec![root]);
+	}
+
+	#[test]
+	fn persisted_content_is_loaded() {
+		let dir = tempfile::tempdir().unwrap();
+		let cid = raw_cid(b"data");
+
+		let store = BitswapStore::open(dir.path().to_path_buf()).unwrap();
+		store.pin(cid, vec![(cid, b"data".to_vec())]).unwrap();
+		drop(store);
+
+		let store = BitswapStore::open(dir.path().to_path_buf()).unwrap();
+		assert_eq!(store.pins(), vec![cid]);
+		assert_eq!(store.get(&cid), Some(b"data".to_vec()));
+
+		store.unpin(&cid).unwrap();
+		let store = BitswapStore::open(dir.path().to_path_buf()).unwrap();
+		assert!(store.pins().is_empty());
+	}
+}
@@ -22,6 +22,8 @@
 //! See the documentation of [`Params`].
 
 pub use crate::{
+	bandwidth::BandwidthLimit,
+	bitswap::BitswapStore,
 	discovery::DEFAULT_KADEMLIA_REPLICATION_FACTOR,
 	peer_store::PeerStoreProvider,
 	protocol::{notification_service, NotificationsSink, ProtocolHandlePair},
@@ -55,6 +57,7 @@ pub use sc_network_common::{
 use sp_runtime::traits::Block as BlockT;
 
 use std::{
+	collections::HashMap,
 	error::Error,
 	fmt, fs,
 	future::Future,
@@ -648,8 +651,14 @@ pub struct NetworkConfiguration {
 	pub kademlia_replication_factor: NonZeroUsize,
 
 	/// Enable serving block data over IPFS bitswap.
+	///
+	/// Content pinned through the bitswap client is persisted under `net_config_path`, if set.
 	pub ipfs_server: bool,
 
+	/// Announce transaction hashes instead of pushing full transactions to peers supporting
+	/// version 2 of the transactions protocol.
+	pub transaction_hash_announcements: bool,
+
 	/// Size of Yamux receive window of all substreams. `None` for the default (256kiB).
 	/// Any value less than 256kiB is invalid.
 	///
@@ -673,6 +682,15 @@ pub struct NetworkConfiguration {
 
 	/// Networking backend used for P2P communication.
 	pub network_backend: NetworkBackendType,
+
+	/// Upload bandwidth limits of individual protocols.
+	///
+	/// Outbound notifications of a protocol that exceeds its limit are delayed (or dropped, if
+	/// sent synchronously) and inbound requests are rejected until the limit is replenished.
+	/// Protocols without an entry are not throttled, but their traffic is still accounted for.
+	///
+	/// Limits of block announcements and sync requests are ignored, these are never throttled.
+	pub bandwidth_limits: HashMap<ProtocolName, BandwidthLimit>,
 }
 
 impl NetworkConfiguration {
@@ -705,7 +723,9 @@ impl NetworkConfiguration {
 				.expect("value is a constant; constant is non-zero; qed."),
 			yamux_window_size: None,
 			ipfs_server: false,
+			transaction_hash_announcements: false,
 			network_backend: NetworkBackendType::Libp2p,
+			bandwidth_limits: HashMap::new(),
 		}
 	}
 
@@ -793,6 +813,9 @@ pub struct FullNetworkConfiguration<B: BlockT + 'static, H: ExHashT, N: NetworkB
 
 	/// Registry for recording prometheus metrics to.
 	pub metrics_registry: Option<Registry>,
+
+	/// Store of content pinned by the local node and served over bitswap.
+	bitswap_store: BitswapStore,
 }
 
 impl<B: BlockT + 'static, H: ExHashT, N: NetworkBackend<B, H>> FullNetworkConfiguration<B, H, N> {
@@ -801,6 +824,17 @@ impl<B: BlockT + 'static, H: ExHashT, N: NetworkBackend<B, H>> FullNetworkConfig
 		let bootnodes = network_config.boot_nodes.iter().map(|bootnode| bootnode.peer_id).collect();
 		let peer_store = N::peer_store(bootnodes, metrics_registry.clone());
 		let peer_store_handle = peer_store.handle();
+		let bitswap_store = match &network_config.net_config_path {
+			Some(path) if network_config.ipfs_server => BitswapStore::open(path.join("bitswap"))
+				.unwrap_or_else(|error| {
+					log::warn!(
+						target: "sub-libp2p",
+						"Failed to open bitswap store, pinned content won't be persisted: {error}",
+					);
+					BitswapStore::in_memory()
+				}),
+			_ => BitswapStore::in_memory(),
+		};
 
 		Self {
 			peer_store: Some(peer_store),
@@ -809,6 +843,7 @@ impl<B: BlockT + 'static, H: ExHashT, N: NetworkBackend<B, H>> FullNetworkConfig
 			request_response_protocols: Vec::new(),
 			network_config: network_config.clone(),
 			metrics_registry,
+			bitswap_store,
 		}
 	}
 
@@ -832,6 +867,11 @@ impl<B: BlockT + 'static, H: ExHashT, N: NetworkBackend<B, H>> FullNetworkConfig
 		Arc::clone(&self.peer_store_handle)
 	}
 
+	/// Get handle to the store of content served over bitswap.
+	pub fn bitswap_store(&self) -> BitswapStore {
+		self.bitswap_store.clone()
+	}
+
 	/// Take [`PeerStore`].
 	///
 	/// `PeerStore` is created when `FullNetworkConfig` is initialized so that `PeerStoreHandle`s
@@ -243,13 +243,14 @@
 //! More precise usage details are still being worked on and will likely change in the future.
 
 mod behaviour;
-mod bitswap;
 mod litep2p;
 mod protocol;
 
 #[cfg(test)]
 mod mock;
 
+pub mod bandwidth;
+pub mod bitswap;
 pub mod config;
 pub mod discovery;
 pub mod error;
@@ -19,6 +19,7 @@
 //! `NetworkBackend` implementation for `litep2p`.
 
 use crate::{
+	bitswap::BitswapStore,
 	config::{
 		FullNetworkConfiguration, IncomingRequest, NodeKeyConfig, NotificationHandshake, Params,
 		SetConfig, TransportConfig,
@@ -63,7 +64,8 @@ use litep2p::{
 		request_response::ConfigBuilder as RequestResponseConfigBuilder,
 	},
 	transport::{
-		tcp::config::Config as TcpTransportConfig,
+		quic::config::Config as QuicTransportConfig, tcp::config::Config as TcpTransportConfig,
+		webrtc::config::Config as WebRtcTransportConfig,
 		websocket::config::Config as WebSocketTransportConfig, ConnectionLimitsConfig, Endpoint,
 	},
 	types::{
@@ -100,6 +102,9 @@ mod peerstore;
 mod service;
 mod shim;
 
+#[cfg(test)]
+mod tests;
+
 /// Litep2p bandwidth sink.
 struct Litep2pBandwidthSink {
 	sink: litep2p::BandwidthSink,
@@ -317,64 +322,98 @@ impl Litep2pNetworkBackend {
 			yamux_config
 		};
 
-		let (tcp, websocket): (Vec<Option<_>>, Vec<Option<_>>) = config
-			.network_config
-			.listen_addresses
-			.iter()
-			.filter_map(|address| {
-				use sc_network_types::multiaddr::Protocol;
-
-				let mut iter = address.iter();
-
-				match iter.next() {
-					Some(Protocol::Ip4(_) | Protocol::Ip6(_)) => {},
-					protocol => {
-						log::error!(
-							target: LOG_TARGET,
-							"unknown protocol {protocol:?}, ignoring {address:?}",
-						);
-
-						return None
-					},
-				}
-
-				match iter.next() {
-					Some(Protocol::Tcp(_)) => match iter.next() {
-						Some(Protocol::Ws(_) | Protocol::Wss(_)) =>
-							Some((None, Some(address.clone()))),
-						Some(Protocol::P2p(_)) | None => Some((Some(address.clone()), None)),
-						protocol => {
-							log::error!(
-								target: LOG_TARGET,
-								"unknown protocol {protocol:?}, ignoring {address:?}",
-							);
-							None
-						},
-					},
-					protocol => {
-						log::error!(
-							target: LOG_TARGET,
-							"unknown protocol {protocol:?}, ignoring {address:?}",
-						);
-						None
-					},
-				}
-			})
-			.unzip();
+		let mut tcp = Vec::new();
+		let mut websocket = Vec::new();
+		let mut quic = Vec::new();
+		let mut webrtc = Vec::new();
+
+		for address in config.network_config.listen_addresses.iter() {
+			match ListenTransport::from_address(address) {
+				Some(ListenTransport::Tcp) => tcp.push(address.clone().into()),
+				Some(ListenTransport::WebSocket) => websocket.push(address.clone().into()),
+				Some(ListenTransport::Quic) => quic.push(address.clone().into()),
+				Some(ListenTransport::WebRtc) => webrtc.push(address.clone().into()),
+				None => log::error!(
+					target: LOG_TARGET,
+					"unsupported listen address, ignoring {address:?}",
+				),
+			}
+		}
 
-		config_builder
+		let mut config_builder = config_builder
 			.with_websocket(WebSocketTransportConfig {
-				listen_addresses: websocket.into_iter().flatten().map(Into::into).collect(),
+				listen_addresses: websocket,
 				yamux_config: yamux_config.clone(),
 				nodelay: true,
 				..Default::default()
 			})
 			.with_tcp(TcpTransportConfig {
-				listen_addresses: tcp.into_iter().flatten().map(Into::into).collect(),
+				listen_addresses: tcp,
 				yamux_config,
 				nodelay: true,
 				..Default::default()
-			})
+			});
+
+		// QUIC and WebRTC are opt-in and only enabled if the node was instructed to listen on
+		// at least one address of the transport.
+		if !quic.is_empty() {
+			config_builder = config_builder
+				.with_quic(QuicTransportConfig { listen_addresses: quic, ..Default::default() });
+		}
+
+		if !webrtc.is_empty() {
+			config_builder = config_builder.with_webrtc(WebRtcTransportConfig {
+				listen_addresses: webrtc,
+				..Default::default()
+			});
+		}
+
+		config_builder
+	}
+}
+
+/// Transport which is used to listen on a multiaddress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ListenTransport {
+	/// `/ip4/.../tcp/...`
+	Tcp,
+
+	/// `/ip4/.../tcp/.../ws` or `/ip4/.../tcp/.../wss`
+	WebSocket,
+
+	/// `/ip4/.../udp/.../quic-v1`
+	Quic,
+
+	/// `/ip4/.../udp/.../webrtc-direct`
+	WebRtc,
+}
+
+impl ListenTransport {
+	/// Get the transport `address` must be bound to.
+	///
+	/// Returns `None` if `litep2p` doesn't support listening on `address`.
+	fn from_address(address: &sc_network_types::multiaddr::Multiaddr) -> Option<Self> {
+		use sc_network_types::multiaddr::Protocol;
+
+		let mut iter = address.iter();
+
+		if !std::matches!(iter.next(), Some(Protocol::Ip4(_) | Protocol::Ip6(_))) {
+			return None
+		}
+
+		let (transport, next) = match (iter.next(), iter.next()) {
+			(Some(Protocol::Tcp(_)), Some(Protocol::Ws(_) | Protocol::Wss(_))) =>
+				(Self::WebSocket, iter.next()),
+			(Some(Protocol::Tcp(_)), next) => (Self::Tcp, next),
+			(Some(Protocol::Udp(_)), Some(Protocol::QuicV1)) => (Self::Quic, iter.next()),
+			(Some(Protocol::Udp(_)), Some(Protocol::WebRTC)) => (Self::WebRtc, iter.next()),
+			_ => return None,
+		};
+
+		match next {
+			Some(Protocol::P2p(_)) | None => Some(transport),
+			_ => None,
+		}
 	}
 }
 
@@ -435,6 +474,9 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkBackend<B, H> for Litep2pNetworkBac
 		let known_addresses = params.network_config.known_addresses();
 		let peer_store_handle = params.network_config.peer_store_handle();
 		let executor = Arc::new(Litep2pExecutor { executor: params.executor });
+		let bandwidth_accounting = params.notification_metrics.bandwidth().clone();
+		bandwidth_accounting
+			.set_limits(params.network_config.network_config.bandwidth_limits.clone());
 
 		let FullNetworkConfiguration {
 			notification_protocols,
@@ -508,6 +550,7 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkBackend<B, H> for Litep2pNetworkBac
 						.expect("receiver exists as it was just added and there are no duplicate protocols; qed"),
 					request_response_senders.clone(),
 					metrics.clone(),
+					bandwidth_accounting.clone(),
 				);
 
 				executor.run(Box::pin(async move {
@@ -524,8 +567,13 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkBackend<B, H> for Litep2pNetworkBac
 				use sc_network_types::multiaddr::Protocol;
 
 				let address = match address.iter().last() {
-					Some(Protocol::Ws(_) | Protocol::Wss(_) | Protocol::Tcp(_)) =>
-						address.with(Protocol::P2p(peer.into())),
+					Some(
+						Protocol::Ws(_) |
+						Protocol::Wss(_) |
+						Protocol::Tcp(_) |
+						Protocol::QuicV1 |
+						Protocol::Certhash(_),
+					) => address.with(Protocol::P2p(peer.into())),
 					Some(Protocol::P2p(_)) => address,
 					_ => return acc,
 				};
@@ -597,6 +645,7 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkBackend<B, H> for Litep2pNetworkBac
 			request_response_senders,
 			Arc::clone(&listen_addresses),
 			public_addresses,
+			bandwidth_accounting,
 		));
 
 		// register rest of the metrics now that `Litep2p` has been created
@@ -643,8 +692,9 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkBackend<B, H> for Litep2pNetworkBac
 	/// Create Bitswap server.
 	fn bitswap_server(
 		client: Arc<dyn BlockBackend<B> + Send + Sync>,
+		store: BitswapStore,
 	) -> (Pin<Box<dyn Future<Output = ()> + Send>>, Self::BitswapConfig) {
-		BitswapServer::new(client)
+		BitswapServer::new(client, store)
 	}
 
 	/// Create notification protocol configuration for `protocol`.
@@ -1066,6 +1116,7 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkBackend<B, H> for Litep2pNetworkBac
 									NegotiationError::ParseError(_) => "parse-error",
 									NegotiationError::IoError(_) => "io-error",
 									NegotiationError::WebSocket(_) => "webscoket-error",
+									NegotiationError::Quic(_) => "quic-error",
 									NegotiationError::BadSignature => "bad-signature",
 								}
 							};
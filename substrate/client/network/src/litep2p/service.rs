@@ -19,6 +19,7 @@
 //! `NetworkService` implementation for `litep2p`.
 
 use crate::{
+	bandwidth::BandwidthAccounting,
 	config::MultiaddrWithPeerId,
 	litep2p::shim::{
 		notification::{config::ProtocolControlHandle, peerset::PeersetCommand},
@@ -200,6 +201,9 @@ pub struct Litep2pNetworkService {
 
 	/// External addresses.
 	external_addresses: PublicAddresses,
+
+	/// Per-protocol and per-peer bandwidth accounting.
+	bandwidth: BandwidthAccounting,
 }
 
 impl Litep2pNetworkService {
@@ -214,6 +218,7 @@ impl Litep2pNetworkService {
 		request_response_protocols: HashMap<ProtocolName, TracingUnboundedSender<OutboundRequest>>,
 		listen_addresses: Arc<RwLock<HashSet<LiteP2pMultiaddr>>>,
 		external_addresses: PublicAddresses,
+		bandwidth: BandwidthAccounting,
 	) -> Self {
 		Self {
 			local_peer_id,
@@ -225,6 +230,7 @@ impl Litep2pNetworkService {
 			request_response_protocols,
 			listen_addresses,
 			external_addresses,
+			bandwidth,
 		}
 	}
 }
@@ -332,6 +338,7 @@ impl NetworkStatusProvider for Litep2pNetworkService {
 			peerset: serde_json::json!(
 				"Unimplemented. See https://github.com/paritytech/substrate/issues/14160."
 			),
+			bandwidth: self.bandwidth.state(),
 		})
 	}
 }
@@ -18,6 +18,8 @@
 
 //! Shim for litep2p's Bitswap implementation to make it work with `sc-network`.
 
+use crate::bitswap::{transaction_hash, BitswapStore, Cid};
+
 use futures::StreamExt;
 use litep2p::protocol::libp2p::bitswap::{
 	BitswapEvent, BitswapHandle, BlockPresenceType, Config, ResponseType, WantType,
@@ -37,19 +39,47 @@ pub struct BitswapServer<Block: BlockT> {
 
 	/// Blockchain client.
 	client: Arc<dyn BlockBackend<Block> + Send + Sync>,
+
+	/// Store of pinned content.
+	store: BitswapStore,
 }
 
 impl<Block: BlockT> BitswapServer<Block> {
 	/// Create new [`BitswapServer`].
 	pub fn new(
 		client: Arc<dyn BlockBackend<Block> + Send + Sync>,
+		store: BitswapStore,
 	) -> (Pin<Box<dyn Future<Output = ()> + Send>>, Config) {
 		let (config, handle) = Config::new();
-		let bitswap = Self { client, handle };
+		let bitswap = Self { client, store, handle };
 
 		(Box::pin(async move { bitswap.run().await }), config)
 	}
 
+	/// Get block referenced by `cid`, either from the pinned content or the indexed transactions.
+	fn block(&self, cid: &Cid) -> Option<Vec<u8>> {
+		if let Some(block) = self.store.get(cid) {
+			log::trace!(target: LOG_TARGET, "found pinned cid {cid:?}");
+			return Some(block)
+		}
+
+		let hash = transaction_hash::<Block>(cid)?;
+		match self.client.indexed_transaction(hash) {
+			Ok(Some(transaction)) => {
+				log::trace!(target: LOG_TARGET, "found cid {cid:?}, hash {hash:?}");
+				Some(transaction)
+			},
+			Ok(None) => {
+				log::trace!(target: LOG_TARGET, "missing cid {cid:?}, hash {hash:?}");
+				None
+			},
+			Err(error) => {
+				log::error!(target: LOG_TARGET, "error retrieving transaction {hash}: {error}");
+				None
+			},
+		}
+	}
+
 	async fn run(mut self) {
 		log::debug!(target: LOG_TARGET, "starting bitswap server");
 
@@ -61,36 +91,22 @@ impl<Block: BlockT> BitswapServer<Block> {
 					let response: Vec<ResponseType> = cids
 						.into_iter()
 						.map(|(cid, want_type)| {
-							let mut hash = Block::Hash::default();
-							hash.as_mut().copy_from_slice(&cid.hash().digest()[0..32]);
-							let transaction = match self.client.indexed_transaction(hash) {
-								Ok(ex) => ex,
-								Err(error) => {
-									log::error!(target: LOG_TARGET, "error retrieving transaction {hash}: {error}");
-									None
-								},
-							};
-
-							match transaction {
-								Some(transaction) => {
-									log::trace!(target: LOG_TARGET, "found cid {cid:?}, hash {hash:?}");
-
-									match want_type {
-										WantType::Block =>
-											ResponseType::Block { cid, block: transaction },
-										_ => ResponseType::Presence {
-											cid,
-											presence: BlockPresenceType::Have,
-										},
-									}
-								},
-								None => {
-									log::trace!(target: LOG_TARGET, "missing cid {cid:?}, hash {hash:?}");
-
-									ResponseType::Presence {
+							// `litep2p` and `sc-network` depend on different versions of `cid`
+							let block = Cid::read_bytes(cid.to_bytes().as_slice())
+								.ok()
+								.and_then(|cid| self.block(&cid));
+
+							match block {
+								Some(block) => match want_type {
+									WantType::Block => ResponseType::Block { cid, block },
+									_ => ResponseType::Presence {
 										cid,
-										presence: BlockPresenceType::DontHave,
-									}
+										presence: BlockPresenceType::Have,
+									},
+								},
+								None => ResponseType::Presence {
+									cid,
+									presence: BlockPresenceType::DontHave,
 								},
 							}
 						})
@@ -82,8 +82,18 @@ impl MessageSink for Litep2pMessageSink {
 	fn send_sync_notification(&self, notification: Vec<u8>) {
 		let size = notification.len();
 
+		if !self.metrics.bandwidth().try_admit(&self.protocol) {
+			log::trace!(
+				target: LOG_TARGET,
+				"{}: bandwidth limit exceeded, dropping notification to {:?}",
+				self.protocol,
+				self.peer,
+			);
+			return
+		}
+
 		match self.sink.send_sync_notification(notification) {
-			Ok(_) => self.metrics.register_notification_sent(&self.protocol, size),
+			Ok(_) => self.metrics.register_notification_sent(&self.protocol, &self.peer, size),
 			Err(error) => log::trace!(
 				target: LOG_TARGET,
 				"{}: failed to send sync notification to {:?}: {error:?}",
@@ -99,10 +109,11 @@ impl MessageSink for Litep2pMessageSink {
 	/// Returns an error if the peer does not exist.
 	async fn send_async_notification(&self, notification: Vec<u8>) -> Result<(), Error> {
 		let size = notification.len();
+		self.metrics.bandwidth().wait_for_admission(&self.protocol).await;
 
 		match self.sink.send_async_notification(notification).await {
 			Ok(_) => {
-				self.metrics.register_notification_sent(&self.protocol, size);
+				self.metrics.register_notification_sent(&self.protocol, &self.peer, size);
 				Ok(())
 			},
 			Err(error) => {
@@ -201,8 +212,17 @@ impl NotificationService for NotificationProtocol {
 	fn send_sync_notification(&mut self, peer: &PeerId, notification: Vec<u8>) {
 		let size = notification.len();
 
+		if !self.metrics.bandwidth().try_admit(&self.protocol) {
+			log::trace!(
+				target: LOG_TARGET,
+				"{}: bandwidth limit exceeded, dropping notification to {peer:?}",
+				self.protocol,
+			);
+			return
+		}
+
 		if let Ok(_) = self.handle.send_sync_notification(peer.into(), notification) {
-			self.metrics.register_notification_sent(&self.protocol, size);
+			self.metrics.register_notification_sent(&self.protocol, peer, size);
 		}
 	}
 
@@ -212,10 +232,11 @@ impl NotificationService for NotificationProtocol {
 		notification: Vec<u8>,
 	) -> Result<(), Error> {
 		let size = notification.len();
+		self.metrics.bandwidth().wait_for_admission(&self.protocol).await;
 
 		match self.handle.send_async_notification(peer.into(), notification).await {
 			Ok(_) => {
-				self.metrics.register_notification_sent(&self.protocol, size);
+				self.metrics.register_notification_sent(&self.protocol, peer, size);
 				Ok(())
 			},
 			Err(_) => Err(Error::ChannelClosed),
@@ -345,7 +366,11 @@ impl NotificationService for NotificationProtocol {
 						peer,
 						notification,
 					} => {
-						self.metrics.register_notification_received(&self.protocol, notification.len());
+						self.metrics.register_notification_received(
+							&self.protocol,
+							&peer.into(),
+							notification.len(),
+						);
 
 						if !self.pending_cancels.contains(&peer) {
 							return Some(SubstrateNotificationEvent::NotificationReceived {
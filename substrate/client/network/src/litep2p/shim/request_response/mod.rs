@@ -20,6 +20,7 @@
 //! request-response API.
 
 use crate::{
+	bandwidth::BandwidthAccounting,
 	litep2p::shim::request_response::metrics::RequestResponseMetrics,
 	peer_store::PeerStoreProvider,
 	request_responses::{IncomingRequest, OutgoingResponse},
@@ -196,6 +197,9 @@ pub struct RequestResponseProtocol {
 
 	/// Metrics, if enabled.
 	metrics: RequestResponseMetrics,
+
+	/// Per-protocol and per-peer bandwidth accounting.
+	bandwidth: BandwidthAccounting,
 }
 
 impl RequestResponseProtocol {
@@ -208,6 +212,7 @@ impl RequestResponseProtocol {
 		request_rx: TracingUnboundedReceiver<OutboundRequest>,
 		request_tx: HashMap<ProtocolName, TracingUnboundedSender<OutboundRequest>>,
 		metrics: Option<Metrics>,
+		bandwidth: BandwidthAccounting,
 	) -> Self {
 		Self {
 			handle,
@@ -219,6 +224,7 @@ impl RequestResponseProtocol {
 			pending_inbound_responses: HashMap::new(),
 			pending_outbound_responses: FuturesUnordered::new(),
 			metrics: RequestResponseMetrics::new(metrics, protocol),
+			bandwidth,
 		}
 	}
 
@@ -245,8 +251,11 @@ impl RequestResponseProtocol {
 			dial_options,
 		);
 
+		let request_len = request.len();
+
 		match self.handle.try_send_request(peer.into(), request, dial_options) {
 			Ok(request_id) => {
+				self.bandwidth.report_outbound(&self.protocol, &peer, request_len);
 				self.pending_inbound_responses
 					.insert(request_id, PendingRequest::new(tx, Instant::now(), fallback_request));
 			},
@@ -265,7 +274,8 @@ impl RequestResponseProtocol {
 
 	/// Handle inbound request from `peer`
 	///
-	/// If the protocol is configured outbound only, reject the request immediately.
+	/// If the protocol is configured outbound only or its bandwidth limit has been exceeded,
+	/// reject the request immediately.
 	fn on_inbound_request(
 		&mut self,
 		peer: litep2p::PeerId,
@@ -273,6 +283,8 @@ impl RequestResponseProtocol {
 		request_id: RequestId,
 		request: Vec<u8>,
 	) {
+		self.bandwidth.report_inbound(&self.protocol, &peer.into(), request.len());
+
 		let Some(inbound_queue) = &self.inbound_queue else {
 			log::trace!(
 				target: LOG_TARGET,
@@ -284,6 +296,18 @@ impl RequestResponseProtocol {
 			return;
 		};
 
+		if !self.bandwidth.try_admit(&self.protocol) {
+			log::debug!(
+				target: LOG_TARGET,
+				"{}: rejecting inbound request from {peer:?}, bandwidth limit exceeded",
+				self.protocol,
+			);
+
+			self.handle.reject_request(request_id);
+			self.metrics.register_inbound_request_failure("throttled");
+			return;
+		}
+
 		log::trace!(
 			target: LOG_TARGET,
 			"{}: request received from {peer:?} ({fallback:?} {request_id:?}), request size {:?}",
@@ -336,6 +360,7 @@ impl RequestResponseProtocol {
 					self.protocol,
 					response.len(),
 				);
+				self.bandwidth.report_inbound(&self.protocol, &peer.into(), response.len());
 
 				let _ = tx.send(Ok((response, self.protocol.clone())));
 				self.metrics.register_outbound_request_success(started.elapsed());
@@ -498,6 +523,7 @@ impl RequestResponseProtocol {
 					self.protocol,
 					response.len(),
 				);
+				self.bandwidth.report_outbound(&self.protocol, &peer.into(), response.len());
 
 				match sent_feedback {
 					None => self.handle.send_response(request_id, response),
@@ -17,6 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
+	bandwidth::{BandwidthAccounting, BandwidthLimit},
 	litep2p::{
 		peerstore::peerstore_handle_test,
 		shim::request_response::{OutboundRequest, RequestResponseProtocol},
@@ -110,6 +111,7 @@ async fn dial_failure() {
 		outbound_rx,
 		senders,
 		None,
+		BandwidthAccounting::new(),
 	);
 
 	tokio::spawn(protocol.run());
@@ -146,6 +148,7 @@ async fn send_request_to_disconnected_peer() {
 		outbound_rx,
 		senders,
 		None,
+		BandwidthAccounting::new(),
 	);
 
 	tokio::spawn(protocol.run());
@@ -192,6 +195,7 @@ async fn send_request_to_disconnected_peer_and_dial() {
 		outbound_rx1,
 		senders,
 		None,
+		BandwidthAccounting::new(),
 	);
 
 	let (outbound_tx2, outbound_rx2) = tracing_unbounded("outbound-request", 1000);
@@ -206,6 +210,7 @@ async fn send_request_to_disconnected_peer_and_dial() {
 		outbound_rx2,
 		senders,
 		None,
+		BandwidthAccounting::new(),
 	);
 
 	tokio::spawn(protocol1.run());
@@ -253,6 +258,7 @@ async fn too_many_inbound_requests() {
 		outbound_rx,
 		senders,
 		None,
+		BandwidthAccounting::new(),
 	);
 
 	tokio::spawn(protocol.run());
@@ -289,6 +295,65 @@ async fn too_many_inbound_requests() {
 	.await;
 }
 
+#[tokio::test]
+async fn inbound_request_rejected_when_bandwidth_limit_exceeded() {
+	let (mut litep2p1, handle1) = make_litep2p().await;
+	let (mut litep2p2, mut handle2) = make_litep2p().await;
+	let peer1 = *litep2p1.local_peer_id();
+	let peer2 = *litep2p2.local_peer_id();
+
+	connect_peers(&mut litep2p1, &mut litep2p2).await;
+
+	// exhaust the upload budget of the protocol so inbound requests are rejected
+	let protocol_name = ProtocolName::from("/protocol/1");
+	let bandwidth = BandwidthAccounting::new();
+	bandwidth.set_limits([(
+		protocol_name.clone(),
+		BandwidthLimit::new(std::num::NonZeroU64::new(1).unwrap()),
+	)]);
+	bandwidth.report_outbound(&protocol_name, &PeerId::random(), 1024);
+
+	let (outbound_tx, outbound_rx) = tracing_unbounded("outbound-request", 1000);
+	let senders = HashMap::from_iter([(protocol_name.clone(), outbound_tx)]);
+	let (tx, rx) = async_channel::bounded(4);
+
+	let protocol = RequestResponseProtocol::new(
+		protocol_name.clone(),
+		handle1,
+		Arc::new(peerstore_handle_test()),
+		Some(tx),
+		outbound_rx,
+		senders,
+		None,
+		bandwidth.clone(),
+	);
+
+	tokio::spawn(protocol.run());
+	tokio::spawn(async move { while let Some(_) = litep2p1.next_event().await {} });
+	tokio::spawn(async move { while let Some(_) = litep2p2.next_event().await {} });
+
+	handle2
+		.send_request(peer1, vec![1, 2, 3, 4], DialOptions::Reject)
+		.await
+		.unwrap();
+
+	match handle2.next().await {
+		Some(RequestResponseEvent::RequestFailed { peer, error, .. }) => {
+			assert_eq!(peer, peer1);
+			assert!(std::matches!(error, RequestResponseError::Rejected(_)));
+		},
+		event => panic!("invalid event: {event:?}"),
+	}
+
+	// the request was never passed on to the request handler
+	assert!(rx.try_recv().is_err());
+
+	let state = bandwidth.state();
+	assert_eq!(state.protocols[&*protocol_name].throttled, 1);
+	assert_eq!(state.protocols[&*protocol_name].inbound, 4);
+	assert_eq!(state.peers[&PeerId::from(peer2).to_base58()].inbound, 4);
+}
+
 #[tokio::test]
 async fn feedback_works() {
 	let (mut litep2p1, handle1) = make_litep2p().await;
@@ -311,6 +376,7 @@ async fn feedback_works() {
 		outbound_rx,
 		senders,
 		None,
+		BandwidthAccounting::new(),
 	);
 
 	tokio::spawn(protocol.run());
@@ -428,6 +494,7 @@ async fn fallback_request_compatible_peers() {
 		outbound_rx1,
 		senders1.clone(),
 		None,
+		BandwidthAccounting::new(),
 	);
 
 	let (tx_fallback, _rx_fallback) = async_channel::bounded(4);
@@ -439,6 +506,7 @@ async fn fallback_request_compatible_peers() {
 		outbound_rx_fallback,
 		senders1,
 		None,
+		BandwidthAccounting::new(),
 	);
 
 	let (outbound_tx2, outbound_rx2) = tracing_unbounded("outbound-request", 1000);
@@ -453,6 +521,7 @@ async fn fallback_request_compatible_peers() {
 		outbound_rx2,
 		senders2,
 		None,
+		BandwidthAccounting::new(),
 	);
 
 	tokio::spawn(protocol1.run());
@@ -570,6 +639,7 @@ async fn fallback_request_old_peer_receives() {
 		outbound_rx1,
 		senders1.clone(),
 		None,
+		BandwidthAccounting::new(),
 	);
 
 	let (tx_fallback, _rx_fallback) = async_channel::bounded(4);
@@ -581,6 +651,7 @@ async fn fallback_request_old_peer_receives() {
 		outbound_rx_fallback,
 		senders1,
 		None,
+		BandwidthAccounting::new(),
 	);
 
 	let (outbound_tx2, outbound_rx2) = tracing_unbounded("outbound-request", 1000);
@@ -595,6 +666,7 @@ async fn fallback_request_old_peer_receives() {
 		outbound_rx2,
 		senders2,
 		None,
+		BandwidthAccounting::new(),
 	);
 
 	tokio::spawn(protocol1.run());
@@ -712,6 +784,7 @@ async fn fallback_request_old_peer_sends() {
 		outbound_rx1,
 		senders1.clone(),
 		None,
+		BandwidthAccounting::new(),
 	);
 
 	let (tx_fallback, rx_fallback) = async_channel::bounded(4);
@@ -723,6 +796,7 @@ async fn fallback_request_old_peer_sends() {
 		outbound_rx_fallback,
 		senders1,
 		None,
+		BandwidthAccounting::new(),
 	);
 
 	let (outbound_tx2, outbound_rx2) = tracing_unbounded("outbound-request", 1000);
@@ -737,6 +811,7 @@ async fn fallback_request_old_peer_sends() {
 		outbound_rx2,
 		senders2,
 		None,
+		BandwidthAccounting::new(),
 	);
 
 	tokio::spawn(protocol1.run());
@@ -853,6 +928,7 @@ async fn old_protocol_supported_but_no_fallback_provided() {
 		outbound_rx1,
 		senders1.clone(),
 		None,
+		BandwidthAccounting::new(),
 	);
 
 	let (tx_fallback, _rx_fallback) = async_channel::bounded(4);
@@ -864,6 +940,7 @@ async fn old_protocol_supported_but_no_fallback_provided() {
 		outbound_rx_fallback,
 		senders1,
 		None,
+		BandwidthAccounting::new(),
 	);
 
 	let (outbound_tx2, outbound_rx2) = tracing_unbounded("outbound-request", 1000);
@@ -878,6 +955,7 @@ async fn old_protocol_supported_but_no_fallback_provided() {
 		outbound_rx2,
 		senders2,
 		None,
+		BandwidthAccounting::new(),
 	);
 
 	tokio::spawn(protocol1.run());
@@ -0,0 +1,234 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	config::{
+		FullNetworkConfiguration, MultiaddrWithPeerId, NetworkConfiguration, NonReservedPeerMode,
+		NotificationHandshake, Params, ProtocolId, Role, SetConfig,
+	},
+	litep2p::{ListenTransport, Litep2pNetworkBackend},
+	service::traits::{NetworkBackend, NetworkService, NotificationEvent, ValidationResult},
+	NotificationMetrics, NotificationService, Roles,
+};
+
+use sc_network_common::sync::message::BlockAnnouncesHandshake;
+use sc_network_types::multiaddr::{Multiaddr, Protocol};
+use sp_runtime::traits::Zero;
+use substrate_test_runtime_client::runtime;
+
+use std::{sync::Arc, time::Duration};
+
+type Backend = Litep2pNetworkBackend;
+
+/// Create `Litep2pNetworkBackend` which listens on `listen_addresses`.
+fn make_backend(
+	listen_addresses: Vec<Multiaddr>,
+) -> (Backend, Arc<dyn NetworkService>, Box<dyn NotificationService>) {
+	let role = Role::Full;
+	let net_conf = NetworkConfiguration { listen_addresses, ..NetworkConfiguration::new_local() };
+	let network_config =
+		FullNetworkConfiguration::<runtime::Block, runtime::Hash, Backend>::new(&net_conf, None);
+	let genesis_hash = runtime::Hash::zero();
+	let (block_announce_config, notification_service) =
+		<Backend as NetworkBackend<runtime::Block, runtime::Hash>>::notification_config(
+			"/block-announces/1".into(),
+			vec![],
+			1024 * 1024,
+			Some(NotificationHandshake::new(BlockAnnouncesHandshake::<runtime::Block>::build(
+				Roles::from(&role),
+				Zero::zero(),
+				genesis_hash,
+				genesis_hash,
+			))),
+			SetConfig {
+				in_peers: 1,
+				out_peers: 1,
+				reserved_nodes: vec![],
+				non_reserved_mode: NonReservedPeerMode::Accept,
+			},
+			NotificationMetrics::new(None),
+			network_config.peer_store_handle(),
+		);
+	let backend = <Backend as NetworkBackend<runtime::Block, runtime::Hash>>::new(Params {
+		block_announce_config,
+		role,
+		executor: Box::new(|f| {
+			tokio::spawn(f);
+		}),
+		genesis_hash,
+		network_config,
+		protocol_id: ProtocolId::from("litep2p-transport-test"),
+		fork_id: None,
+		metrics_registry: None,
+		bitswap_config: None,
+		notification_metrics: NotificationMetrics::new(None),
+	})
+	.unwrap();
+	let network_service =
+		<Backend as NetworkBackend<runtime::Block, runtime::Hash>>::network_service(&backend);
+
+	(backend, network_service, notification_service)
+}
+
+/// Wait until `service` reports a listen address which satisfies `predicate`.
+async fn wait_for_listen_address(
+	service: &Arc<dyn NetworkService>,
+	predicate: impl Fn(&Multiaddr) -> bool,
+) -> Multiaddr {
+	loop {
+		if let Some(address) = service.listen_addresses().into_iter().find(|a| predicate(a)) {
+			return address;
+		}
+		tokio::time::sleep(Duration::from_millis(10)).await;
+	}
+}
+
+/// Connect two nodes over the listen address of `listener` selected by `predicate` and wait
+/// until the block announce substream has been opened.
+async fn connect_over(
+	dialer: (&Arc<dyn NetworkService>, &mut Box<dyn NotificationService>),
+	listener: (&Arc<dyn NetworkService>, &mut Box<dyn NotificationService>),
+	predicate: impl Fn(&Multiaddr) -> bool,
+) {
+	let (dialer_service, dialer_notifications) = dialer;
+	let (listener_service, listener_notifications) = listener;
+
+	let address = wait_for_listen_address(listener_service, predicate).await;
+	dialer_service
+		.add_reserved_peer(MultiaddrWithPeerId {
+			multiaddr: address,
+			peer_id: listener_service.local_peer_id(),
+		})
+		.unwrap();
+
+	loop {
+		tokio::select! {
+			Some(event) = dialer_notifications.next_event() => {
+				if let NotificationEvent::NotificationStreamOpened { .. } = event {
+					break
+				}
+			},
+			Some(event) = listener_notifications.next_event() => {
+				if let NotificationEvent::ValidateInboundSubstream { result_tx, .. } = event {
+					result_tx.send(ValidationResult::Accept).unwrap();
+				}
+			},
+		}
+	}
+}
+
+fn is_quic(address: &Multiaddr) -> bool {
+	address.iter().any(|protocol| std::matches!(protocol, Protocol::QuicV1))
+}
+
+fn is_webrtc(address: &Multiaddr) -> bool {
+	address.iter().any(|protocol| std::matches!(protocol, Protocol::WebRTC))
+}
+
+#[test]
+fn listen_transport_from_address() {
+	let cases = [
+		("/ip4/127.0.0.1/tcp/30333", Some(ListenTransport::Tcp)),
+		("/ip6/::1/tcp/30333", Some(ListenTransport::Tcp)),
+		("/ip4/127.0.0.1/tcp/30333/ws", Some(ListenTransport::WebSocket)),
+		("/ip4/127.0.0.1/tcp/30333/wss", Some(ListenTransport::WebSocket)),
+		("/ip4/127.0.0.1/udp/30333/quic-v1", Some(ListenTransport::Quic)),
+		("/ip6/::1/udp/30333/quic-v1", Some(ListenTransport::Quic)),
+		("/ip4/127.0.0.1/udp/30333/webrtc-direct", Some(ListenTransport::WebRtc)),
+		(
+			"/ip4/127.0.0.1/udp/30333/quic-v1/p2p/12D3KooWRpzRTivvJ5ySvgbFnPeEE6rDhitQKL1fFJvvBGhnenSk",
+			Some(ListenTransport::Quic),
+		),
+		("/ip4/127.0.0.1/udp/30333", None),
+		("/ip4/127.0.0.1/udp/30333/quic", None),
+		("/dns/example.com/tcp/30333", None),
+		("/ip4/127.0.0.1/tcp/30333/ws/tcp/1", None),
+	];
+
+	for (address, expected) in cases {
+		let address: Multiaddr = address.parse().unwrap();
+		assert_eq!(ListenTransport::from_address(&address), expected, "{address}");
+	}
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn nodes_connect_over_quic() {
+	let (backend1, service1, mut notifications1) =
+		make_backend(vec!["/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap()]);
+	let (backend2, service2, mut notifications2) =
+		make_backend(vec!["/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap()]);
+
+	tokio::spawn(<Backend as NetworkBackend<runtime::Block, runtime::Hash>>::run(backend1));
+	tokio::spawn(<Backend as NetworkBackend<runtime::Block, runtime::Hash>>::run(backend2));
+
+	// all listen addresses must be QUIC as TCP and WebSocket were not requested
+	let address = wait_for_listen_address(&service2, |_| true).await;
+	assert!(is_quic(&address), "{address}");
+
+	tokio::time::timeout(
+		Duration::from_secs(20),
+		connect_over((&service1, &mut notifications1), (&service2, &mut notifications2), is_quic),
+	)
+	.await
+	.expect("nodes to connect over QUIC");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn nodes_connect_over_tcp_with_quic_enabled() {
+	let listen_addresses = || {
+		vec![
+			"/ip4/127.0.0.1/tcp/0".parse().unwrap(),
+			"/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap(),
+		]
+	};
+	let (backend1, service1, mut notifications1) = make_backend(listen_addresses());
+	let (backend2, service2, mut notifications2) = make_backend(listen_addresses());
+
+	tokio::spawn(<Backend as NetworkBackend<runtime::Block, runtime::Hash>>::run(backend1));
+	tokio::spawn(<Backend as NetworkBackend<runtime::Block, runtime::Hash>>::run(backend2));
+
+	tokio::time::timeout(
+		Duration::from_secs(20),
+		connect_over((&service1, &mut notifications1), (&service2, &mut notifications2), |a| {
+			a.iter().any(|protocol| std::matches!(protocol, Protocol::Tcp(_)))
+		}),
+	)
+	.await
+	.expect("nodes to connect over TCP");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn webrtc_listen_address_is_advertised() {
+	let (backend, service, _notifications) = make_backend(vec![
+		"/ip4/127.0.0.1/tcp/0".parse().unwrap(),
+		"/ip4/127.0.0.1/udp/0/webrtc-direct".parse().unwrap(),
+	]);
+
+	tokio::spawn(<Backend as NetworkBackend<runtime::Block, runtime::Hash>>::run(backend));
+
+	// browsers need the certificate hash to dial the node
+	let address =
+		tokio::time::timeout(Duration::from_secs(20), wait_for_listen_address(&service, is_webrtc))
+			.await
+			.expect("WebRTC listen address to be reported");
+
+	assert!(
+		address.iter().any(|protocol| std::matches!(protocol, Protocol::Certhash(_))),
+		"{address}",
+	);
+}
@@ -20,6 +20,8 @@
 //!
 //! **Warning**: These APIs are not stable.
 
+use crate::bandwidth::BandwidthState;
+
 use libp2p::{
 	core::{ConnectedPoint, Endpoint as CoreEndpoint},
 	Multiaddr,
@@ -50,6 +52,9 @@ pub struct NetworkState {
 	pub not_connected_peers: HashMap<String, NotConnectedPeer>,
 	/// State of the peerset manager.
 	pub peerset: serde_json::Value,
+	/// Bandwidth used by each protocol and peer.
+	#[serde(default)]
+	pub bandwidth: BandwidthState,
 }
 
 /// Part of the `NetworkState` struct. Unstable.
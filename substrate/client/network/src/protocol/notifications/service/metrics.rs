@@ -18,6 +18,8 @@
 
 use crate::{service::metrics::NotificationMetrics, types::ProtocolName};
 
+use libp2p::PeerId;
+
 /// Register opened substream to Prometheus.
 pub fn register_substream_opened(metrics: &Option<NotificationMetrics>, protocol: &ProtocolName) {
 	if let Some(metrics) = metrics {
@@ -36,10 +38,11 @@ pub fn register_substream_closed(metrics: &Option<NotificationMetrics>, protocol
 pub fn register_notification_sent(
 	metrics: &Option<std::sync::Arc<NotificationMetrics>>,
 	protocol: &ProtocolName,
+	peer: &PeerId,
 	size: usize,
 ) {
 	if let Some(metrics) = metrics {
-		metrics.register_notification_sent(protocol, size);
+		metrics.register_notification_sent(protocol, &(*peer).into(), size);
 	}
 }
 
@@ -47,9 +50,28 @@ pub fn register_notification_sent(
 pub fn register_notification_received(
 	metrics: &Option<NotificationMetrics>,
 	protocol: &ProtocolName,
+	peer: &PeerId,
 	size: usize,
 ) {
 	if let Some(metrics) = metrics {
-		metrics.register_notification_received(protocol, size);
+		metrics.register_notification_received(protocol, &(*peer).into(), size);
+	}
+}
+
+/// Check if the bandwidth limit of `protocol` allows sending a notification right now.
+pub fn try_admit_notification(
+	metrics: &Option<std::sync::Arc<NotificationMetrics>>,
+	protocol: &ProtocolName,
+) -> bool {
+	metrics.as_ref().map_or(true, |metrics| metrics.bandwidth().try_admit(protocol))
+}
+
+/// Wait until the bandwidth limit of `protocol` allows sending a notification.
+pub async fn wait_for_notification_admission(
+	metrics: &Option<std::sync::Arc<NotificationMetrics>>,
+	protocol: &ProtocolName,
+) {
+	if let Some(metrics) = metrics {
+		metrics.bandwidth().wait_for_admission(protocol).await;
 	}
 }
@@ -69,7 +69,22 @@ impl MessageSink for NotificationSink {
 	fn send_sync_notification(&self, notification: Vec<u8>) {
 		let sink = self.lock();
 
-		metrics::register_notification_sent(sink.0.metrics(), &sink.1, notification.len());
+		if !metrics::try_admit_notification(sink.0.metrics(), &sink.1) {
+			log::trace!(
+				target: LOG_TARGET,
+				"{}: bandwidth limit exceeded, dropping notification to {:?}",
+				sink.1,
+				sink.0.peer_id(),
+			);
+			return
+		}
+
+		metrics::register_notification_sent(
+			sink.0.metrics(),
+			&sink.1,
+			sink.0.peer_id(),
+			notification.len(),
+		);
 		sink.0.send_sync_notification(notification);
 	}
 
@@ -83,6 +98,7 @@ impl MessageSink for NotificationSink {
 		// method is also used by `NetworkService` when sending notifications.
 		let notification_len = notification.len();
 		let sink = self.lock().clone();
+		metrics::wait_for_notification_admission(sink.0.metrics(), &sink.1).await;
 		let permit = sink
 			.0
 			.reserve_notification()
@@ -90,7 +106,12 @@ impl MessageSink for NotificationSink {
 			.map_err(|_| error::Error::ConnectionClosed)?;
 
 		permit.send(notification).map_err(|_| error::Error::ChannelClosed).inspect(|_| {
-			metrics::register_notification_sent(sink.0.metrics(), &sink.1, notification_len);
+			metrics::register_notification_sent(
+				sink.0.metrics(),
+				&sink.1,
+				sink.0.peer_id(),
+				notification_len,
+			);
 		})
 	}
 }
@@ -234,9 +255,19 @@ impl NotificationService for NotificationHandle {
 	/// Send synchronous `notification` to `peer`.
 	fn send_sync_notification(&mut self, peer: &sc_network_types::PeerId, notification: Vec<u8>) {
 		if let Some(info) = self.peers.get(&((*peer).into())) {
+			if !metrics::try_admit_notification(info.sink.metrics(), &self.protocol) {
+				log::trace!(
+					target: LOG_TARGET,
+					"{}: bandwidth limit exceeded, dropping notification to {peer:?}",
+					self.protocol,
+				);
+				return
+			}
+
 			metrics::register_notification_sent(
 				info.sink.metrics(),
 				&self.protocol,
+				info.sink.peer_id(),
 				notification.len(),
 			);
 
@@ -257,6 +288,7 @@ impl NotificationService for NotificationHandle {
 			.ok_or_else(|| error::Error::PeerDoesntExist((*peer).into()))?
 			.sink;
 
+		metrics::wait_for_notification_admission(sink.metrics(), &self.protocol).await;
 		sink.reserve_notification()
 			.await
 			.map_err(|_| error::Error::ConnectionClosed)?
@@ -266,6 +298,7 @@ impl NotificationService for NotificationHandle {
 				metrics::register_notification_sent(
 					sink.metrics(),
 					&self.protocol,
+					sink.peer_id(),
 					notification_len,
 				);
 			})
@@ -577,7 +610,12 @@ impl ProtocolHandle {
 		peer: PeerId,
 		notification: Vec<u8>,
 	) -> Result<(), ()> {
-		metrics::register_notification_received(&self.metrics, &self.protocol, notification.len());
+		metrics::register_notification_received(
+			&self.metrics,
+			&self.protocol,
+			&peer,
+			notification.len(),
+		);
 
 		let mut subscribers = self.subscribers.lock();
 		log::trace!(target: LOG_TARGET, "{}: notification received from {peer:?}", self.protocol);
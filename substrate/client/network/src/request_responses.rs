@@ -35,6 +35,7 @@
 //! is used to handle incoming requests.
 
 use crate::{
+	bandwidth::BandwidthAccounting,
 	peer_store::{PeerStoreProvider, BANNED_THRESHOLD},
 	service::traits::RequestResponseConfig as RequestResponseConfigT,
 	types::ProtocolName,
@@ -359,6 +360,9 @@ pub struct RequestResponsesBehaviour {
 
 	/// Primarily used to get a reputation of a node.
 	peer_store: Arc<dyn PeerStoreProvider>,
+
+	/// Per-protocol and per-peer bandwidth accounting.
+	bandwidth: BandwidthAccounting,
 }
 
 /// Generated by the response builder and waiting to be processed.
@@ -376,6 +380,7 @@ impl RequestResponsesBehaviour {
 	pub fn new(
 		list: impl Iterator<Item = ProtocolConfig>,
 		peer_store: Arc<dyn PeerStoreProvider>,
+		bandwidth: BandwidthAccounting,
 	) -> Result<Self, RegisterError> {
 		let mut protocols = HashMap::new();
 		for protocol in list {
@@ -412,6 +417,7 @@ impl RequestResponsesBehaviour {
 			pending_responses_arrival_time: Default::default(),
 			send_feedback: Default::default(),
 			peer_store,
+			bandwidth,
 		})
 	}
 
@@ -436,6 +442,7 @@ impl RequestResponsesBehaviour {
 			Self::send_request_inner(
 				protocol,
 				&mut self.pending_requests,
+				&self.bandwidth,
 				target,
 				protocol_name,
 				request,
@@ -456,6 +463,7 @@ impl RequestResponsesBehaviour {
 	fn send_request_inner(
 		behaviour: &mut Behaviour<GenericCodec>,
 		pending_requests: &mut HashMap<ProtocolRequestId, PendingRequest>,
+		bandwidth: &BandwidthAccounting,
 		target: &PeerId,
 		protocol_name: ProtocolName,
 		request: Vec<u8>,
@@ -464,6 +472,8 @@ impl RequestResponsesBehaviour {
 		connect: IfDisconnected,
 	) {
 		if behaviour.is_connected(target) || connect.should_connect() {
+			bandwidth.report_outbound(&protocol_name, &(*target).into(), request.len());
+
 			let request_id = behaviour.send_request(target, request);
 			let prev_req_id = pending_requests.insert(
 				(protocol_name.to_string().into(), request_id).into(),
@@ -677,6 +687,7 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
 				if let Ok(payload) = result {
 					if let Some((protocol, _)) = self.protocols.get_mut(&*protocol_name) {
 						log::trace!(target: "sub-libp2p", "send response to {peer} ({protocol_name:?}), {} bytes", payload.len());
+						self.bandwidth.report_outbound(&protocol_name, &peer.into(), payload.len());
 
 						if protocol.send_response(inner_channel, Ok(payload)).is_err() {
 							// Note: Failure is handled further below when receiving
@@ -751,6 +762,8 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
 							self.pending_responses_arrival_time
 								.insert((protocol.clone(), request_id).into(), Instant::now());
 
+							self.bandwidth.report_inbound(protocol, &peer.into(), request.len());
+
 							let reputation = self.peer_store.peer_reputation(&peer.into());
 
 							if reputation < BANNED_THRESHOLD {
@@ -763,6 +776,16 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
 								continue 'poll_protocol
 							}
 
+							// Dropping `channel` is reported as `InboundFailure::ResponseOmission`
+							// by the request-response [`Behaviour`].
+							if !self.bandwidth.try_admit(protocol) {
+								log::debug!(
+									target: "sub-libp2p",
+									"Bandwidth limit of {protocol:?} exceeded, dropping request from {peer}",
+								);
+								continue 'poll_protocol
+							}
+
 							let (tx, rx) = oneshot::channel();
 
 							// Submit the request to the "response builder" passed by the user at
@@ -816,10 +839,18 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
 								.remove(&(protocol.clone(), request_id).into())
 							{
 								Some(PendingRequest { started_at, response_tx, .. }) => {
+									let response_len =
+										response.as_ref().map_or(0usize, |response| response.len());
+
 									log::trace!(
 										target: "sub-libp2p",
 										"received response from {peer} ({protocol:?}), {} bytes",
-										response.as_ref().map_or(0usize, |response| response.len()),
+										response_len,
+									);
+									self.bandwidth.report_inbound(
+										protocol,
+										&peer.into(),
+										response_len,
 									);
 
 									let delivered = response_tx
@@ -980,6 +1011,7 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
 					Self::send_request_inner(
 						behaviour,
 						&mut self.pending_requests,
+						&self.bandwidth,
 						&peer,
 						protocol,
 						request,
@@ -1176,7 +1208,12 @@ mod tests {
 			.multiplex(libp2p::yamux::Config::default())
 			.boxed();
 
-		let behaviour = RequestResponsesBehaviour::new(list, Arc::new(MockPeerStore {})).unwrap();
+		let behaviour = RequestResponsesBehaviour::new(
+			list,
+			Arc::new(MockPeerStore {}),
+			BandwidthAccounting::new(),
+		)
+		.unwrap();
 
 		let runtime = tokio::runtime::Runtime::new().unwrap();
 
@@ -28,8 +28,9 @@
 //! which is then processed by [`NetworkWorker::next_action`].
 
 use crate::{
+	bandwidth::BandwidthAccounting,
 	behaviour::{self, Behaviour, BehaviourOut},
-	bitswap::BitswapRequestHandler,
+	bitswap::{BitswapRequestHandler, BitswapStore},
 	config::{
 		parse_addr, FullNetworkConfiguration, IncomingRequest, MultiaddrWithPeerId,
 		NonDefaultSetConfig, NotificationHandshake, Params, SetConfig, TransportConfig,
@@ -199,8 +200,9 @@ where
 
 	fn bitswap_server(
 		client: Arc<dyn BlockBackend<B> + Send + Sync>,
+		store: BitswapStore,
 	) -> (Pin<Box<dyn Future<Output = ()> + Send>>, Self::BitswapConfig) {
-		let (handler, protocol_config) = BitswapRequestHandler::new(client.clone());
+		let (handler, protocol_config) = BitswapRequestHandler::new(client.clone(), store);
 
 		(Box::pin(async move { handler.run().await }), protocol_config)
 	}
@@ -495,6 +497,9 @@ where
 		let num_connected = Arc::new(AtomicUsize::new(0));
 		let external_addresses = Arc::new(Mutex::new(HashSet::new()));
 
+		let bandwidth_accounting = params.notification_metrics.bandwidth().clone();
+		bandwidth_accounting.set_limits(network_config.bandwidth_limits.clone());
+
 		let (protocol, notif_protocol_handles) = Protocol::new(
 			From::from(&params.role),
 			params.notification_metrics,
@@ -563,6 +568,7 @@ where
 						.with_max_established_incoming(Some(
 							crate::MAX_CONNECTIONS_ESTABLISHED_INCOMING,
 						)),
+					bandwidth_accounting.clone(),
 				);
 
 				match result {
@@ -649,6 +655,7 @@ where
 			reported_invalid_boot_nodes: Default::default(),
 			peer_store_handle: Arc::clone(&peer_store_handle),
 			notif_protocol_handles,
+			bandwidth_accounting,
 			_marker: Default::default(),
 			_block: Default::default(),
 		})
@@ -810,6 +817,7 @@ where
 			peerset: serde_json::json!(
 				"Unimplemented. See https://github.com/paritytech/substrate/issues/14160."
 			),
+			bandwidth: self.bandwidth_accounting.state(),
 		}
 	}
 
@@ -1383,6 +1391,8 @@ where
 	peer_store_handle: Arc<dyn PeerStoreProvider>,
 	/// Notification protocol handles.
 	notif_protocol_handles: Vec<protocol::ProtocolHandle>,
+	/// Per-protocol and per-peer bandwidth accounting.
+	bandwidth_accounting: BandwidthAccounting,
 	/// Marker to pin the `H` generic. Serves no purpose except to not break backwards
 	/// compatibility.
 	_marker: PhantomData<H>,
@@ -16,12 +16,13 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{service::traits::BandwidthSink, ProtocolName};
+use crate::{bandwidth::BandwidthAccounting, service::traits::BandwidthSink, ProtocolName};
 
 use prometheus_endpoint::{
 	self as prometheus, Counter, CounterVec, Gauge, GaugeVec, HistogramOpts, MetricSource, Opts,
 	PrometheusError, Registry, SourcedCounter, SourcedGauge, U64,
 };
+use sc_network_types::PeerId;
 
 use std::{
 	str,
@@ -304,21 +305,42 @@ impl MetricSource for NumConnectedGauge {
 /// Notification metrics.
 ///
 /// Wrapper over `Option<InnerNotificationMetrics>` to make metrics reporting code cleaner.
+///
+/// The object also carries the [`BandwidthAccounting`] of the node which is shared by all
+/// notification and request-response protocols that were given a clone of the same object.
 #[derive(Debug, Clone)]
 pub struct NotificationMetrics {
 	/// Metrics, if enabled.
 	metrics: Option<InnerNotificationMetrics>,
+
+	/// Per-protocol and per-peer bandwidth accounting.
+	bandwidth: BandwidthAccounting,
 }
 
 impl NotificationMetrics {
 	/// Create new [`NotificationMetrics`].
 	pub fn new(registry: Option<&Registry>) -> NotificationMetrics {
+		let bandwidth = BandwidthAccounting::new();
 		let metrics = match registry {
-			Some(registry) => InnerNotificationMetrics::register(registry).ok(),
+			Some(registry) => {
+				if let Err(error) = bandwidth.register(registry) {
+					log::debug!(
+						target: "sub-libp2p",
+						"failed to register bandwidth accounting metrics: {error:?}",
+					);
+				}
+
+				InnerNotificationMetrics::register(registry).ok()
+			},
 			None => None,
 		};
 
-		Self { metrics }
+		Self { metrics, bandwidth }
+	}
+
+	/// Get the bandwidth accounting of the node.
+	pub fn bandwidth(&self) -> &BandwidthAccounting {
+		&self.bandwidth
 	}
 
 	/// Register opened substream to Prometheus.
@@ -338,8 +360,10 @@ impl NotificationMetrics {
 		}
 	}
 
-	/// Register sent notification to Prometheus.
-	pub fn register_notification_sent(&self, protocol: &ProtocolName, size: usize) {
+	/// Register notification sent to `peer` to Prometheus.
+	pub fn register_notification_sent(&self, protocol: &ProtocolName, peer: &PeerId, size: usize) {
+		self.bandwidth.report_outbound(protocol, peer, size);
+
 		if let Some(metrics) = &self.metrics {
 			metrics
 				.notifications_sizes
@@ -348,8 +372,15 @@ impl NotificationMetrics {
 		}
 	}
 
-	/// Register received notification to Prometheus.
-	pub fn register_notification_received(&self, protocol: &ProtocolName, size: usize) {
+	/// Register notification received from `peer` to Prometheus.
+	pub fn register_notification_received(
+		&self,
+		protocol: &ProtocolName,
+		peer: &PeerId,
+		size: usize,
+	) {
+		self.bandwidth.report_inbound(protocol, peer, size);
+
 		if let Some(metrics) = &self.metrics {
 			metrics
 				.notifications_sizes
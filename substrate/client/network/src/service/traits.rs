@@ -21,6 +21,7 @@
 //! Traits defined by `sc-network`.
 
 use crate::{
+	bitswap::BitswapStore,
 	config::{IncomingRequest, MultiaddrWithPeerId, NotificationHandshake, Params, SetConfig},
 	error::{self, Error},
 	event::Event,
@@ -144,8 +145,11 @@ pub trait NetworkBackend<B: BlockT + 'static, H: ExHashT>: Send + 'static {
 	fn register_notification_metrics(registry: Option<&Registry>) -> NotificationMetrics;
 
 	/// Create Bitswap server.
+	///
+	/// The server serves the indexed transactions of `client` and the content pinned in `store`.
 	fn bitswap_server(
 		client: Arc<dyn BlockBackend<B> + Send + Sync>,
+		store: BitswapStore,
 	) -> (Pin<Box<dyn Future<Output = ()> + Send>>, Self::BitswapConfig);
 
 	/// Create notification protocol configuration and an associated `NotificationService`
@@ -0,0 +1,160 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Bloom filter of the statement topics a peer is interested in.
+
+use codec::{Decode, Encode};
+use sp_statement_store::{Statement, Topic, MAX_TOPICS};
+
+/// Maximum size of a topic filter, in bytes.
+pub const MAX_TOPIC_FILTER_SIZE: usize = 64 * 1024;
+
+/// Maximum number of hash functions of a topic filter.
+const MAX_HASH_FUNCTIONS: u8 = 16;
+
+/// Number of bits allocated per expected topic.
+///
+/// Together with [`DEFAULT_HASH_FUNCTIONS`] this results in a false positive rate of about 1%.
+const BITS_PER_TOPIC: usize = 10;
+
+/// Number of hash functions used by [`TopicFilter::with_capacity`].
+const DEFAULT_HASH_FUNCTIONS: u8 = 7;
+
+/// Bloom filter of statement topics.
+///
+/// Peers advertise a topic filter to only receive statements with at least one topic in the
+/// filter. Like any bloom filter it can produce false positives, so peers may still receive some
+/// statements they are not interested in, but never miss a statement that matches the filter.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct TopicFilter {
+	/// Bit array of the filter.
+	bits: Vec<u8>,
+	/// Number of hash functions.
+	hash_functions: u8,
+}
+
+impl TopicFilter {
+	/// Create an empty filter sized for `expected_topics` topics.
+	pub fn with_capacity(expected_topics: usize) -> Self {
+		let bytes = (expected_topics.max(1) * BITS_PER_TOPIC).div_ceil(8);
+
+		Self {
+			bits: vec![0u8; bytes.min(MAX_TOPIC_FILTER_SIZE)],
+			hash_functions: DEFAULT_HASH_FUNCTIONS,
+		}
+	}
+
+	/// Create a filter matching `topics`.
+	pub fn from_topics<'a>(topics: impl IntoIterator<Item = &'a Topic>) -> Self {
+		let topics = topics.into_iter().collect::<Vec<_>>();
+		let mut filter = Self::with_capacity(topics.len());
+		topics.into_iter().for_each(|topic| filter.insert(topic));
+		filter
+	}
+
+	/// Add `topic` to the filter.
+	pub fn insert(&mut self, topic: &Topic) {
+		for bit in self.bit_indices(topic) {
+			self.bits[bit / 8] |= 1 << (bit % 8);
+		}
+	}
+
+	/// Check whether `topic` may be in the filter.
+	pub fn contains(&self, topic: &Topic) -> bool {
+		self.bit_indices(topic).all(|bit| self.bits[bit / 8] & (1 << (bit % 8)) != 0)
+	}
+
+	/// Check whether any topic of `statement` may be in the filter.
+	///
+	/// Statements without topics never match.
+	pub fn matches(&self, statement: &Statement) -> bool {
+		(0..MAX_TOPICS)
+			.map_while(|index| statement.topic(index))
+			.any(|topic| self.contains(&topic))
+	}
+
+	/// Check that a filter received from the network is well-formed.
+	pub fn is_valid(&self) -> bool {
+		!self.bits.is_empty() &&
+			self.bits.len() <= MAX_TOPIC_FILTER_SIZE &&
+			(1..=MAX_HASH_FUNCTIONS).contains(&self.hash_functions)
+	}
+
+	/// Indices of the bits corresponding to `topic`, using double hashing.
+	fn bit_indices(&self, topic: &Topic) -> impl Iterator<Item = usize> {
+		let hash = sp_crypto_hashing::blake2_256(topic);
+		let h1 = u64::from_le_bytes(hash[..8].try_into().expect("slice is 8 bytes long; qed"));
+		let h2 = u64::from_le_bytes(hash[8..16].try_into().expect("slice is 8 bytes long; qed"));
+		let num_bits = (self.bits.len() * 8) as u64;
+
+		(0..self.hash_functions as u64)
+			.map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % num_bits) as usize)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn topic(n: u8) -> Topic {
+		[n; 32]
+	}
+
+	#[test]
+	fn inserted_topics_are_contained() {
+		let topics = (0..100).map(topic).collect::<Vec<_>>();
+		let filter = TopicFilter::from_topics(&topics);
+
+		assert!(filter.is_valid());
+		assert!(topics.iter().all(|topic| filter.contains(topic)));
+	}
+
+	#[test]
+	fn false_positive_rate_is_low() {
+		let filter = TopicFilter::from_topics(&(0..100).map(topic).collect::<Vec<_>>());
+		let false_positives = (100..=255).filter(|n| filter.contains(&topic(*n))).count();
+
+		assert!(false_positives < 10, "too many false positives: {false_positives}");
+	}
+
+	#[test]
+	fn statement_matching() {
+		let filter = TopicFilter::from_topics(&[topic(1)]);
+
+		let mut statement = Statement::new();
+		assert!(!filter.matches(&statement));
+
+		statement.set_topic(0, topic(2));
+		assert!(!filter.matches(&statement));
+
+		statement.set_topic(1, topic(1));
+		assert!(filter.matches(&statement));
+	}
+
+	#[test]
+	fn invalid_filters_are_detected() {
+		assert!(!TopicFilter { bits: Vec::new(), hash_functions: 1 }.is_valid());
+		assert!(!TopicFilter { bits: vec![0; 8], hash_functions: 0 }.is_valid());
+		assert!(
+			!TopicFilter { bits: vec![0; 8], hash_functions: MAX_HASH_FUNCTIONS + 1 }.is_valid()
+		);
+		assert!(
+			!TopicFilter { bits: vec![0; MAX_TOPIC_FILTER_SIZE + 1], hash_functions: 1 }.is_valid()
+		);
+	}
+}
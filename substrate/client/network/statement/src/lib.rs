@@ -25,8 +25,19 @@
 //!   configuration as an extra peers set.
 //! - Use [`StatementHandlerPrototype::build`] then [`StatementHandler::run`] to obtain a
 //! `Future` that processes statements.
+//!
+//! # Topic filters
+//!
+//! Version 2 of the protocol allows peers to advertise a [`TopicFilter`] of the topics they are
+//! interested in (see [`StatementMessage`]). Statements are then only forwarded to a peer if at
+//! least one of their topics matches its filter. Peers that advertise no filter, or that only
+//! support version 1 of the protocol, receive all statements.
+//!
+//! A node configured with a filter using [`StatementHandlerPrototype::with_topic_filter`] also
+//! ignores statements not matching it, as they may be sent by peers that only support version 1.
 
 use crate::config::*;
+pub use crate::filter::TopicFilter;
 
 use codec::{Decode, Encode};
 use futures::{channel::oneshot, prelude::*, stream::FuturesUnordered, FutureExt};
@@ -59,12 +70,35 @@ use std::{
 };
 
 pub mod config;
+mod filter;
 
 /// A set of statements.
 pub type Statements = Vec<Statement>;
 /// Future resolving to statement import result.
 pub type StatementImportFuture = oneshot::Receiver<SubmitResult>;
 
+/// Message of version 2 of the statement protocol.
+///
+/// Version 1 of the protocol only ever sends [`Statements`].
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub enum StatementMessage {
+	/// Statements.
+	Statements(Statements),
+	/// Topics the sender is interested in, replacing any previously advertised filter.
+	///
+	/// `None` if the sender wants to receive all statements.
+	TopicFilter(Option<TopicFilter>),
+}
+
+/// Version of the statement protocol negotiated with a peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProtocolVersion {
+	/// Only statements are exchanged.
+	V1,
+	/// Topic filters are exchanged in addition to statements.
+	V2,
+}
+
 mod rep {
 	use sc_network::ReputationChange as Rep;
 	/// Reputation change when a peer sends us any statement.
@@ -82,12 +116,15 @@ mod rep {
 	pub const DUPLICATE_STATEMENT: Rep = Rep::new(-(1 << 7), "Duplicate statement");
 	/// Reputation change when a peer sends us particularly useful statement
 	pub const EXCELLENT_STATEMENT: Rep = Rep::new(1 << 8, "High priority statement");
+	/// Reputation change when a peer sends us a malformed topic filter.
+	pub const BAD_TOPIC_FILTER: Rep = Rep::new(-(1 << 12), "Bad topic filter");
 }
 
 const LOG_TARGET: &str = "statement-gossip";
 
 struct Metrics {
 	propagated_statements: Counter<U64>,
+	filtered_statements: Counter<U64>,
 }
 
 impl Metrics {
@@ -100,6 +137,13 @@ impl Metrics {
 				)?,
 				r,
 			)?,
+			filtered_statements: register(
+				Counter::new(
+					"substrate_sync_filtered_statements",
+					"Number of statements not sent to a peer because they didn't match its topic filter",
+				)?,
+				r,
+			)?,
 		})
 	}
 }
@@ -108,6 +152,7 @@ impl Metrics {
 pub struct StatementHandlerPrototype {
 	protocol_name: ProtocolName,
 	notification_service: Box<dyn NotificationService>,
+	topic_filter: Option<TopicFilter>,
 }
 
 impl StatementHandlerPrototype {
@@ -123,14 +168,22 @@ impl StatementHandlerPrototype {
 		peer_store_handle: Arc<dyn PeerStoreProvider>,
 	) -> (Self, Net::NotificationProtocolConfig) {
 		let genesis_hash = genesis_hash.as_ref();
-		let protocol_name = if let Some(fork_id) = fork_id {
-			format!("/{}/{}/statement/1", array_bytes::bytes2hex("", genesis_hash), fork_id)
-		} else {
-			format!("/{}/statement/1", array_bytes::bytes2hex("", genesis_hash))
+		let protocol_name_of = |version: u32| -> ProtocolName {
+			if let Some(fork_id) = fork_id {
+				format!(
+					"/{}/{}/statement/{version}",
+					array_bytes::bytes2hex("", genesis_hash),
+					fork_id
+				)
+			} else {
+				format!("/{}/statement/{version}", array_bytes::bytes2hex("", genesis_hash))
+			}
+			.into()
 		};
+		let protocol_name = protocol_name_of(2);
 		let (config, notification_service) = Net::notification_config(
-			protocol_name.clone().into(),
-			Vec::new(),
+			protocol_name.clone(),
+			vec![protocol_name_of(1)],
 			MAX_STATEMENT_SIZE,
 			None,
 			SetConfig {
@@ -143,7 +196,16 @@ impl StatementHandlerPrototype {
 			peer_store_handle,
 		);
 
-		(Self { protocol_name: protocol_name.into(), notification_service }, config)
+		(Self { protocol_name, notification_service, topic_filter: None }, config)
+	}
+
+	/// Only receive statements matching `topic_filter`.
+	///
+	/// The filter is advertised to peers supporting topic filters, statements not matching it that
+	/// are still received are ignored. By default all statements are received.
+	pub fn with_topic_filter(mut self, topic_filter: TopicFilter) -> Self {
+		self.topic_filter = Some(topic_filter);
+		self
 	}
 
 	/// Turns the prototype into the actual handler.
@@ -195,6 +257,7 @@ impl StatementHandlerPrototype {
 				.fuse(),
 			pending_statements: FuturesUnordered::new(),
 			pending_statements_peers: HashMap::new(),
+			topic_filter: self.topic_filter,
 			network,
 			sync,
 			sync_event_stream: sync_event_stream.fuse(),
@@ -228,6 +291,8 @@ pub struct StatementHandler<
 	/// imported. This prevents that we import the same statement
 	/// multiple times concurrently.
 	pending_statements_peers: HashMap<Hash, HashSet<PeerId>>,
+	/// Topic filter advertised to peers, if any.
+	topic_filter: Option<TopicFilter>,
 	/// Network service to use to send messages and manage peers.
 	network: N,
 	/// Syncing service.
@@ -250,6 +315,10 @@ struct Peer {
 	/// Holds a set of statements known to this peer.
 	known_statements: LruHashSet<Hash>,
 	role: ObservedRole,
+	/// Negotiated version of the protocol.
+	version: ProtocolVersion,
+	/// Topics the peer is interested in. `None` if the peer wants all statements.
+	topic_filter: Option<TopicFilter>,
 }
 
 impl<N, S> StatementHandler<N, S>
@@ -329,12 +398,31 @@ where
 					.map_or(ValidationResult::Reject, |_| ValidationResult::Accept);
 				let _ = result_tx.send(result);
 			},
-			NotificationEvent::NotificationStreamOpened { peer, handshake, .. } => {
+			NotificationEvent::NotificationStreamOpened {
+				peer,
+				handshake,
+				negotiated_fallback,
+				..
+			} => {
 				let Some(role) = self.network.peer_role(peer, handshake) else {
 					log::debug!(target: LOG_TARGET, "role for {peer} couldn't be determined");
 					return
 				};
 
+				// version 2 is the main protocol so any negotiated fallback means version 1
+				let version = match negotiated_fallback {
+					None => ProtocolVersion::V2,
+					Some(_) => ProtocolVersion::V1,
+				};
+				log::trace!(target: LOG_TARGET, "{peer} opened substream, protocol version {version:?}");
+
+				if let (ProtocolVersion::V2, Some(topic_filter)) = (version, &self.topic_filter) {
+					self.notification_service.send_sync_notification(
+						&peer,
+						StatementMessage::TopicFilter(Some(topic_filter.clone())).encode(),
+					);
+				}
+
 				let _was_in = self.peers.insert(
 					peer,
 					Peer {
@@ -342,6 +430,8 @@ where
 							NonZeroUsize::new(MAX_KNOWN_STATEMENTS).expect("Constant is nonzero"),
 						),
 						role,
+						version,
+						topic_filter: None,
 					},
 				);
 				debug_assert!(_was_in.is_none());
@@ -351,6 +441,32 @@ where
 				debug_assert!(_peer.is_some());
 			},
 			NotificationEvent::NotificationReceived { peer, notification } => {
+				let Some(version) = self.peers.get(&peer).map(|peer| peer.version) else {
+					log::trace!(target: LOG_TARGET, "Received notification from unknown peer {peer}");
+					return
+				};
+
+				let statements = match version {
+					ProtocolVersion::V1 =>
+						match <Statements as Decode>::decode(&mut notification.as_ref()) {
+							Ok(statements) => statements,
+							Err(_) => {
+								log::debug!(target: LOG_TARGET, "Failed to decode statement list from {peer}");
+								return
+							},
+						},
+					ProtocolVersion::V2 =>
+						match <StatementMessage as Decode>::decode(&mut notification.as_ref()) {
+							Ok(StatementMessage::Statements(statements)) => statements,
+							Ok(StatementMessage::TopicFilter(topic_filter)) =>
+								return self.on_topic_filter(peer, topic_filter),
+							Err(_) => {
+								log::debug!(target: LOG_TARGET, "Failed to decode statement message from {peer}");
+								return
+							},
+						},
+				};
+
 				// Accept statements only when node is not major syncing
 				if self.sync.is_major_syncing() {
 					log::trace!(
@@ -360,15 +476,32 @@ where
 					return
 				}
 
-				if let Ok(statements) = <Statements as Decode>::decode(&mut notification.as_ref()) {
-					self.on_statements(peer, statements);
-				} else {
-					log::debug!(target: LOG_TARGET, "Failed to decode statement list from {peer}");
-				}
+				self.on_statements(peer, statements);
 			},
 		}
 	}
 
+	/// Called when peer advertises the topics it is interested in.
+	fn on_topic_filter(&mut self, who: PeerId, topic_filter: Option<TopicFilter>) {
+		if topic_filter.as_ref().is_some_and(|topic_filter| !topic_filter.is_valid()) {
+			log::debug!(target: LOG_TARGET, "{who} sent an invalid topic filter");
+			self.network.report_peer(who, rep::BAD_TOPIC_FILTER);
+			return
+		}
+
+		if let Some(peer) = self.peers.get_mut(&who) {
+			log::trace!(
+				target: LOG_TARGET,
+				"{who} updated topic filter (filtered: {})",
+				topic_filter.is_some(),
+			);
+			peer.topic_filter = topic_filter;
+			peer.known_statements = LruHashSet::new(
+				NonZeroUsize::new(MAX_KNOWN_STATEMENTS).expect("Constant is nonzero"),
+			);
+		}
+	}
+
 	/// Called when peer sends us new statements
 	fn on_statements(&mut self, who: PeerId, statements: Statements) {
 		log::trace!(target: LOG_TARGET, "Received {} statements from {}", statements.len(), who);
@@ -386,6 +519,12 @@ where
 				let hash = s.hash();
 				peer.known_statements.insert(hash);
 
+				if self.topic_filter.as_ref().is_some_and(|topic_filter| !topic_filter.matches(&s))
+				{
+					log::trace!(target: LOG_TARGET, "Ignoring statement not matching topic filter");
+					continue
+				}
+
 				self.network.report_peer(who, rep::ANY_STATEMENT);
 
 				match self.pending_statements_peers.entry(hash) {
@@ -456,6 +595,7 @@ where
 
 	fn do_propagate_statements(&mut self, statements: &[(Hash, Statement)]) {
 		let mut propagated_statements = 0;
+		let mut filtered_statements = 0;
 
 		for (who, peer) in self.peers.iter_mut() {
 			// never send statements to light nodes
@@ -463,21 +603,42 @@ where
 				continue
 			}
 
+			// statements not matching the topic filter are marked as known to the peer so they are
+			// not checked again, the known statements are reset when the filter changes
 			let to_send = statements
 				.iter()
-				.filter_map(|(hash, stmt)| peer.known_statements.insert(*hash).then(|| stmt))
+				.filter_map(|(hash, stmt)| {
+					if !peer.known_statements.insert(*hash) {
+						return None
+					}
+
+					match &peer.topic_filter {
+						Some(topic_filter) if !topic_filter.matches(stmt) => {
+							filtered_statements += 1;
+							None
+						},
+						_ => Some(stmt),
+					}
+				})
 				.collect::<Vec<_>>();
 
 			propagated_statements += to_send.len();
 
 			if !to_send.is_empty() {
 				log::trace!(target: LOG_TARGET, "Sending {} statements to {}", to_send.len(), who);
-				self.notification_service.send_sync_notification(who, to_send.encode());
+				let notification = match peer.version {
+					ProtocolVersion::V1 => to_send.encode(),
+					// `StatementMessage::Statements` is encoded as the variant index followed by
+					// the statements, avoid cloning them by encoding the two separately.
+					ProtocolVersion::V2 => (0u8, to_send).encode(),
+				};
+				self.notification_service.send_sync_notification(who, notification);
 			}
 		}
 
 		if let Some(ref metrics) = self.metrics {
-			metrics.propagated_statements.inc_by(propagated_statements as _)
+			metrics.propagated_statements.inc_by(propagated_statements as _);
+			metrics.filtered_statements.inc_by(filtered_statements);
 		}
 	}
 
@@ -494,3 +655,204 @@ where
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_network::{service::traits::Direction, ReputationChange};
+	use sc_network_sync::service::mock::{MockNetwork, MockNotificationService, MockSyncService};
+	use sp_statement_store::Topic;
+	use std::sync::Mutex;
+
+	#[derive(Default)]
+	struct TestStatementStore {
+		statements: Vec<(Hash, Statement)>,
+	}
+
+	impl StatementStore for TestStatementStore {
+		fn statements(&self) -> sp_statement_store::Result<Vec<(Hash, Statement)>> {
+			Ok(self.statements.clone())
+		}
+
+		fn statement(&self, hash: &Hash) -> sp_statement_store::Result<Option<Statement>> {
+			Ok(self.statements.iter().find(|(h, _)| h == hash).map(|(_, s)| s.clone()))
+		}
+
+		fn broadcasts(&self, _: &[Topic]) -> sp_statement_store::Result<Vec<Vec<u8>>> {
+			unimplemented!();
+		}
+
+		fn posted(&self, _: &[Topic], _: [u8; 32]) -> sp_statement_store::Result<Vec<Vec<u8>>> {
+			unimplemented!();
+		}
+
+		fn posted_clear(
+			&self,
+			_: &[Topic],
+			_: [u8; 32],
+		) -> sp_statement_store::Result<Vec<Vec<u8>>> {
+			unimplemented!();
+		}
+
+		fn submit(&self, _: Statement, _: StatementSource) -> SubmitResult {
+			unimplemented!();
+		}
+
+		fn remove(&self, _: &Hash) -> sp_statement_store::Result<()> {
+			unimplemented!();
+		}
+	}
+
+	type TestHandler = StatementHandler<Arc<MockNetwork>, MockSyncService>;
+
+	struct TestSetup {
+		handler: TestHandler,
+		sent: Arc<Mutex<Vec<(PeerId, Vec<u8>)>>>,
+		reports: Arc<Mutex<Vec<(PeerId, ReputationChange)>>>,
+		// Keeps the validation queue open, the validation task itself is never polled.
+		_validator: Arc<Mutex<Vec<Pin<Box<dyn Future<Output = ()> + Send>>>>>,
+	}
+
+	fn setup(topic_filter: Option<TopicFilter>, statements: Vec<Statement>) -> TestSetup {
+		let sent = Arc::new(Mutex::new(Vec::new()));
+		let mut notification_service = MockNotificationService::new();
+		notification_service.expect_send_sync_notification().returning({
+			let sent = sent.clone();
+			move |peer, notification| sent.lock().unwrap().push((*peer, notification))
+		});
+
+		let reports = Arc::new(Mutex::new(Vec::new()));
+		let mut network = MockNetwork::new();
+		network.expect_peer_role().returning(|_, _| Some(ObservedRole::Full));
+		network.expect_report_peer().returning({
+			let reports = reports.clone();
+			move |peer, change| reports.lock().unwrap().push((peer, change))
+		});
+
+		let mut sync = MockSyncService::new();
+		sync.expect_event_stream().returning(|_| Box::pin(stream::pending()));
+		sync.expect_is_major_syncing().return_const(false);
+
+		let validator = Arc::new(Mutex::new(Vec::new()));
+		let statement_store = TestStatementStore {
+			statements: statements.into_iter().map(|s| (s.hash(), s)).collect(),
+		};
+
+		let prototype = StatementHandlerPrototype {
+			protocol_name: "/statement/2".into(),
+			notification_service: Box::new(notification_service),
+			topic_filter: None,
+		};
+		let prototype = match topic_filter {
+			Some(topic_filter) => prototype.with_topic_filter(topic_filter),
+			None => prototype,
+		};
+		let handler = prototype
+			.build(Arc::new(network), sync, Arc::new(statement_store), None, {
+				let validator = validator.clone();
+				move |task| validator.lock().unwrap().push(task)
+			})
+			.unwrap();
+
+		TestSetup { handler, sent, reports, _validator: validator }
+	}
+
+	fn open_substream(handler: &mut TestHandler, peer: PeerId, version: ProtocolVersion) {
+		handler.handle_notification_event(NotificationEvent::NotificationStreamOpened {
+			peer,
+			direction: Direction::Inbound,
+			handshake: Vec::new(),
+			negotiated_fallback: match version {
+				ProtocolVersion::V1 => Some("/statement/1".into()),
+				ProtocolVersion::V2 => None,
+			},
+		});
+	}
+
+	fn statement_with_topic(topic: u8) -> Statement {
+		let mut statement = Statement::new();
+		statement.set_topic(0, [topic; 32]);
+		statement
+	}
+
+	#[test]
+	fn statements_not_matching_peer_filter_are_not_propagated() {
+		let matching = statement_with_topic(1);
+		let not_matching = statement_with_topic(2);
+		let TestSetup { mut handler, sent, .. } =
+			setup(None, vec![matching.clone(), not_matching.clone()]);
+
+		let filtered_peer = PeerId::random();
+		let v1_peer = PeerId::random();
+		open_substream(&mut handler, filtered_peer, ProtocolVersion::V2);
+		open_substream(&mut handler, v1_peer, ProtocolVersion::V1);
+		handler.handle_notification_event(NotificationEvent::NotificationReceived {
+			peer: filtered_peer,
+			notification: StatementMessage::TopicFilter(Some(TopicFilter::from_topics(&[[1; 32]])))
+				.encode(),
+		});
+
+		handler.propagate_statements();
+
+		let mut notifications = std::mem::take(&mut *sent.lock().unwrap());
+		notifications.sort_by_key(|(peer, _)| *peer != filtered_peer);
+		assert_eq!(
+			notifications,
+			vec![
+				(filtered_peer, StatementMessage::Statements(vec![matching.clone()]).encode()),
+				(v1_peer, vec![matching, not_matching].encode()),
+			],
+		);
+
+		// filtered statements are not checked again
+		handler.propagate_statements();
+		assert!(sent.lock().unwrap().is_empty());
+	}
+
+	#[test]
+	fn statements_not_matching_own_filter_are_not_accepted() {
+		let matching = statement_with_topic(1);
+		let not_matching = statement_with_topic(2);
+		let topic_filter = TopicFilter::from_topics(&[[1; 32]]);
+		let TestSetup { mut handler, sent, reports, .. } =
+			setup(Some(topic_filter.clone()), vec![]);
+
+		let v2_peer = PeerId::random();
+		let v1_peer = PeerId::random();
+		open_substream(&mut handler, v2_peer, ProtocolVersion::V2);
+		open_substream(&mut handler, v1_peer, ProtocolVersion::V1);
+
+		// the filter is only advertised to peers supporting it
+		assert_eq!(
+			*sent.lock().unwrap(),
+			vec![(v2_peer, StatementMessage::TopicFilter(Some(topic_filter)).encode())],
+		);
+
+		handler.handle_notification_event(NotificationEvent::NotificationReceived {
+			peer: v2_peer,
+			notification: StatementMessage::Statements(vec![not_matching.clone()]).encode(),
+		});
+		handler.handle_notification_event(NotificationEvent::NotificationReceived {
+			peer: v1_peer,
+			notification: vec![matching.clone(), not_matching.clone()].encode(),
+		});
+
+		assert_eq!(
+			handler.pending_statements_peers,
+			HashMap::from([(matching.hash(), HashSet::from([v1_peer]))]),
+		);
+		assert_eq!(*reports.lock().unwrap(), vec![(v1_peer, rep::ANY_STATEMENT)]);
+	}
+
+	#[test]
+	fn statements_message_encoding() {
+		let mut statement = Statement::new();
+		statement.set_topic(0, [1u8; 32]);
+		let statements = vec![statement];
+
+		assert_eq!(
+			(0u8, statements.iter().collect::<Vec<_>>()).encode(),
+			StatementMessage::Statements(statements).encode(),
+		);
+	}
+}
@@ -96,7 +96,10 @@ pub fn generate_protocol_config<
 }
 
 /// Generate the block protocol name from the genesis hash and fork id.
-fn generate_protocol_name<Hash: AsRef<[u8]>>(genesis_hash: Hash, fork_id: Option<&str>) -> String {
+pub(crate) fn generate_protocol_name<Hash: AsRef<[u8]>>(
+	genesis_hash: Hash,
+	fork_id: Option<&str>,
+) -> String {
 	let genesis_hash = genesis_hash.as_ref();
 	if let Some(fork_id) = fork_id {
 		format!("/{}/{}/sync/2", array_bytes::bytes2hex("", genesis_hash), fork_id)
@@ -106,7 +109,7 @@ fn generate_protocol_name<Hash: AsRef<[u8]>>(genesis_hash: Hash, fork_id: Option
 }
 
 /// Generate the legacy block protocol name from chain specific protocol identifier.
-fn generate_legacy_protocol_name(protocol_id: &ProtocolId) -> String {
+pub(crate) fn generate_legacy_protocol_name(protocol_id: &ProtocolId) -> String {
 	format!("/{}/sync/2", protocol_id.as_ref())
 }
 
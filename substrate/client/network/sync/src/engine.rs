@@ -23,14 +23,16 @@ use crate::{
 	block_announce_validator::{
 		BlockAnnounceValidationResult, BlockAnnounceValidator as BlockAnnounceValidatorStream,
 	},
+	block_request_handler,
 	pending_responses::{PendingResponses, ResponseEvent},
 	service::{
 		self,
 		syncing_service::{SyncingService, ToServiceCommand},
 	},
+	state_request_handler,
 	strategy::{SyncingAction, SyncingStrategy},
 	types::{BadPeer, ExtendedPeerInfo, SyncEvent},
-	LOG_TARGET,
+	warp_request_handler, LOG_TARGET,
 };
 
 use codec::{Decode, DecodeAll, Encode};
@@ -333,6 +335,19 @@ where
 
 		let info = client.info();
 
+		// Falling behind the chain is worse than any traffic a limit could save, so block
+		// announcements and sync requests are never throttled.
+		let bandwidth = network_metrics.bandwidth().clone();
+		bandwidth.exempt([
+			block_request_handler::generate_protocol_name(info.genesis_hash, fork_id).into(),
+			block_request_handler::generate_legacy_protocol_name(&protocol_id).into(),
+			state_request_handler::generate_protocol_name(info.genesis_hash, fork_id).into(),
+			state_request_handler::generate_legacy_protocol_name(&protocol_id).into(),
+			warp_request_handler::generate_protocol_name(info.genesis_hash, fork_id).into(),
+			warp_request_handler::generate_legacy_protocol_name(protocol_id.clone()).into(),
+			format!("/{}/block-announces/1", protocol_id.as_ref()).into(),
+		]);
+
 		let (block_announce_config, notification_service) =
 			Self::get_block_announce_proto_config::<N>(
 				protocol_id,
@@ -347,6 +362,8 @@ where
 			);
 
 		let block_announce_protocol_name = block_announce_config.protocol_name().clone();
+		bandwidth.exempt([block_announce_protocol_name.clone()]);
+
 		let (tx, service_rx) = tracing_unbounded("mpsc_chain_sync", 100_000);
 		let num_connected = Arc::new(AtomicUsize::new(0));
 		let is_major_syncing = Arc::new(AtomicBool::new(false));
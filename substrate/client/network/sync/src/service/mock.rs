@@ -16,20 +16,25 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use futures::channel::oneshot;
+use crate::types::{SyncEvent, SyncEventStream};
+
+use futures::{channel::oneshot, Stream};
 
 use sc_consensus::{BlockImportError, BlockImportStatus};
 use sc_network::{
 	config::MultiaddrWithPeerId,
+	error,
 	request_responses::{IfDisconnected, RequestFailure},
+	service::traits::{MessageSink, NotificationEvent, NotificationService},
 	types::ProtocolName,
-	NetworkPeers, NetworkRequest, NetworkSyncForkRequest, ReputationChange,
+	Event, NetworkEventStream, NetworkPeers, NetworkRequest, NetworkSyncForkRequest,
+	ReputationChange,
 };
 use sc_network_common::role::ObservedRole;
 use sc_network_types::{multiaddr::Multiaddr, PeerId};
 use sp_runtime::traits::{Block as BlockT, NumberFor};
 
-use std::collections::HashSet;
+use std::{collections::HashSet, pin::Pin};
 
 mockall::mock! {
 	pub ChainSyncInterface<B: BlockT> {
@@ -131,4 +136,47 @@ mockall::mock! {
 			connect: IfDisconnected,
 		);
 	}
+
+	impl NetworkEventStream for Network {
+		fn event_stream(&self, name: &'static str) -> Pin<Box<dyn Stream<Item = Event> + Send>>;
+	}
+}
+
+// Mocked syncing service for the tests of protocols following the sync state
+mockall::mock! {
+	pub SyncService {}
+
+	impl SyncEventStream for SyncService {
+		fn event_stream(&self, name: &'static str)
+			-> Pin<Box<dyn Stream<Item = SyncEvent> + Send>>;
+	}
+
+	impl sp_consensus::SyncOracle for SyncService {
+		fn is_major_syncing(&self) -> bool;
+		fn is_offline(&self) -> bool;
+	}
+}
+
+// Mocked notification service for the tests of notification protocols
+mockall::mock! {
+	#[derive(Debug)]
+	pub NotificationService {}
+
+	#[async_trait::async_trait]
+	impl NotificationService for NotificationService {
+		async fn open_substream(&mut self, peer: PeerId) -> Result<(), ()>;
+		async fn close_substream(&mut self, peer: PeerId) -> Result<(), ()>;
+		fn send_sync_notification(&mut self, peer: &PeerId, notification: Vec<u8>);
+		async fn send_async_notification(
+			&mut self,
+			peer: &PeerId,
+			notification: Vec<u8>,
+		) -> Result<(), error::Error>;
+		async fn set_handshake(&mut self, handshake: Vec<u8>) -> Result<(), ()>;
+		fn try_set_handshake(&mut self, handshake: Vec<u8>) -> Result<(), ()>;
+		async fn next_event(&mut self) -> Option<NotificationEvent>;
+		fn clone(&mut self) -> Result<Box<dyn NotificationService>, ()>;
+		fn protocol(&self) -> &ProtocolName;
+		fn message_sink(&self, peer: &PeerId) -> Option<Box<dyn MessageSink>>;
+	}
 }
@@ -59,7 +59,10 @@ pub fn generate_request_response_config<
 }
 
 /// Generate the grandpa warp sync protocol name from the genesis hash and fork id.
-fn generate_protocol_name<Hash: AsRef<[u8]>>(genesis_hash: Hash, fork_id: Option<&str>) -> String {
+pub(crate) fn generate_protocol_name<Hash: AsRef<[u8]>>(
+	genesis_hash: Hash,
+	fork_id: Option<&str>,
+) -> String {
 	let genesis_hash = genesis_hash.as_ref();
 	if let Some(fork_id) = fork_id {
 		format!("/{}/{}/sync/warp", array_bytes::bytes2hex("", genesis_hash), fork_id)
@@ -69,7 +72,7 @@ fn generate_protocol_name<Hash: AsRef<[u8]>>(genesis_hash: Hash, fork_id: Option
 }
 
 /// Generate the legacy grandpa warp sync protocol name from chain specific protocol identifier.
-fn generate_legacy_protocol_name(protocol_id: ProtocolId) -> String {
+pub(crate) fn generate_legacy_protocol_name(protocol_id: ProtocolId) -> String {
 	format!("/{}/sync/warp", protocol_id.as_ref())
 }
 
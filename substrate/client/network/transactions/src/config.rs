@@ -38,6 +38,15 @@ pub(crate) const MAX_TRANSACTIONS_SIZE: u64 = MAX_RESPONSE_SIZE;
 /// Maximum number of transaction validation request we keep at any moment.
 pub(crate) const MAX_PENDING_TRANSACTIONS: usize = 8192;
 
+/// Maximum number of transaction hashes in a single announcement or request.
+pub(crate) const MAX_ANNOUNCED_HASHES: usize = 4096; // ~128kb per notification.
+
+/// Maximum number of announced transactions we are fetching at any moment.
+pub(crate) const MAX_REQUESTED_TRANSACTIONS: usize = 8192;
+
+/// Time after which an announced transaction that wasn't received can be requested again.
+pub(crate) const TRANSACTION_REQUEST_TIMEOUT: time::Duration = time::Duration::from_secs(10);
+
 /// Result of the transaction import.
 #[derive(Clone, Copy, Debug)]
 pub enum TransactionImport {
@@ -68,6 +77,12 @@ pub trait TransactionPool<H: ExHashT, B: BlockT>: Send + Sync {
 	fn on_broadcasted(&self, propagations: HashMap<H, Vec<String>>);
 	/// Get transaction by hash.
 	fn transaction(&self, hash: &H) -> Option<B::Extrinsic>;
+	/// Check whether the pool already knows the transaction.
+	///
+	/// Announced transactions known by the pool are not requested from peers.
+	fn contains(&self, hash: &H) -> bool {
+		self.transaction(hash).is_some()
+	}
 }
 
 /// Dummy implementation of the [`TransactionPool`] trait for a transaction pool that is always
@@ -25,6 +25,14 @@
 //!   network configuration as an extra peers set.
 //! - Use [`TransactionsHandlerPrototype::build`] then [`TransactionsHandler::run`] to obtain a
 //! `Future` that processes transactions.
+//!
+//! # Hash announcements
+//!
+//! By default full transactions are pushed to every peer that doesn't know them yet. If hash
+//! announcements are enabled, version 2 of the protocol is offered to peers, with version 1 as
+//! a fallback. Peers that negotiate version 2 are only sent the hashes of new transactions (see
+//! [`TransactionsMessage`]) and request the bodies of the transactions they are missing. Peers
+//! that negotiate version 1 keep receiving full transactions.
 
 use crate::config::*;
 
@@ -58,6 +66,7 @@ use std::{
 	pin::Pin,
 	sync::Arc,
 	task::Poll,
+	time::Instant,
 };
 
 pub mod config;
@@ -65,6 +74,28 @@ pub mod config;
 /// A set of transactions.
 pub type Transactions<E> = Vec<E>;
 
+/// Message of version 2 of the transactions protocol.
+///
+/// Version 1 of the protocol only ever sends [`Transactions`].
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub enum TransactionsMessage<E, H> {
+	/// Full transactions, either pushed or sent in response to a [`Self::Request`].
+	Transactions(Transactions<E>),
+	/// Hashes of transactions available at the sender.
+	Announcements(Vec<H>),
+	/// Request for the bodies of previously announced transactions.
+	Request(Vec<H>),
+}
+
+/// Version of the transactions protocol negotiated with a peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProtocolVersion {
+	/// Full transactions are pushed to the peer.
+	V1,
+	/// Transaction hashes are announced to the peer, which then requests missing transactions.
+	V2,
+}
+
 /// Logging target for the file.
 const LOG_TARGET: &str = "sync";
 
@@ -81,10 +112,16 @@ mod rep {
 	pub const GOOD_TRANSACTION: Rep = Rep::new(1 << 7, "Good transaction");
 	/// Reputation change when a peer sends us a bad transaction.
 	pub const BAD_TRANSACTION: Rep = Rep::new(-(1 << 12), "Bad transaction");
+	/// Reputation change when a peer requests too many transactions at once.
+	pub const EXCESSIVE_REQUEST: Rep = Rep::new(-(1 << 10), "Excessive transaction request");
 }
 
 struct Metrics {
 	propagated_transactions: Counter<U64>,
+	announced_transactions: Counter<U64>,
+	requested_transactions: Counter<U64>,
+	known_announced_transactions: Counter<U64>,
+	duplicate_transactions: Counter<U64>,
 }
 
 impl Metrics {
@@ -97,6 +134,34 @@ impl Metrics {
 				)?,
 				r,
 			)?,
+			announced_transactions: register(
+				Counter::new(
+					"substrate_sync_announced_transactions",
+					"Number of transaction hashes announced to peers instead of full transactions",
+				)?,
+				r,
+			)?,
+			requested_transactions: register(
+				Counter::new(
+					"substrate_sync_requested_transactions",
+					"Number of announced transactions requested from peers",
+				)?,
+				r,
+			)?,
+			known_announced_transactions: register(
+				Counter::new(
+					"substrate_sync_known_announced_transactions",
+					"Number of announced transactions not requested because they were already known",
+				)?,
+				r,
+			)?,
+			duplicate_transactions: register(
+				Counter::new(
+					"substrate_sync_duplicate_transactions",
+					"Number of full transactions received that were already known",
+				)?,
+				r,
+			)?,
 		})
 	}
 }
@@ -127,10 +192,16 @@ pub struct TransactionsHandlerPrototype {
 
 	/// Handle that is used to communicate with `sc_network::Notifications`.
 	notification_service: Box<dyn NotificationService>,
+
+	/// Whether transaction hashes are announced to peers supporting version 2 of the protocol.
+	hash_announcements: bool,
 }
 
 impl TransactionsHandlerPrototype {
 	/// Create a new instance.
+	///
+	/// If `hash_announcements` is `true`, version 2 of the protocol is offered to peers with
+	/// version 1 as a fallback.
 	pub fn new<
 		Hash: AsRef<[u8]>,
 		Block: BlockT,
@@ -141,17 +212,31 @@ impl TransactionsHandlerPrototype {
 		fork_id: Option<&str>,
 		metrics: NotificationMetrics,
 		peer_store_handle: Arc<dyn PeerStoreProvider>,
+		hash_announcements: bool,
 	) -> (Self, Net::NotificationProtocolConfig) {
 		let genesis_hash = genesis_hash.as_ref();
-		let protocol_name: ProtocolName = if let Some(fork_id) = fork_id {
-			format!("/{}/{}/transactions/1", array_bytes::bytes2hex("", genesis_hash), fork_id)
+		let protocol_name_of = |version: u32| -> ProtocolName {
+			if let Some(fork_id) = fork_id {
+				format!(
+					"/{}/{}/transactions/{version}",
+					array_bytes::bytes2hex("", genesis_hash),
+					fork_id
+				)
+			} else {
+				format!("/{}/transactions/{version}", array_bytes::bytes2hex("", genesis_hash))
+			}
+			.into()
+		};
+		let legacy_protocol_name: ProtocolName =
+			format!("/{}/transactions/1", protocol_id.as_ref()).into();
+		let (protocol_name, fallback_names) = if hash_announcements {
+			(protocol_name_of(2), vec![protocol_name_of(1), legacy_protocol_name])
 		} else {
-			format!("/{}/transactions/1", array_bytes::bytes2hex("", genesis_hash))
-		}
-		.into();
+			(protocol_name_of(1), vec![legacy_protocol_name])
+		};
 		let (config, notification_service) = Net::notification_config(
 			protocol_name.clone(),
-			vec![format!("/{}/transactions/1", protocol_id.as_ref()).into()],
+			fallback_names,
 			MAX_TRANSACTIONS_SIZE,
 			None,
 			SetConfig {
@@ -164,7 +249,7 @@ impl TransactionsHandlerPrototype {
 			peer_store_handle,
 		);
 
-		(Self { protocol_name, notification_service }, config)
+		(Self { protocol_name, notification_service, hash_announcements }, config)
 	}
 
 	/// Turns the prototype into the actual handler. Returns a controller that allows controlling
@@ -174,7 +259,7 @@ impl TransactionsHandlerPrototype {
 	/// Gossiping is enabled when major syncing is done.
 	pub fn build<
 		B: BlockT + 'static,
-		H: ExHashT,
+		H: ExHashT + Encode + Decode,
 		N: NetworkPeers + NetworkEventStream,
 		S: SyncEventStream + sp_consensus::SyncOracle,
 	>(
@@ -195,6 +280,8 @@ impl TransactionsHandlerPrototype {
 				.fuse(),
 			pending_transactions: FuturesUnordered::new(),
 			pending_transactions_peers: HashMap::new(),
+			requested_transactions: HashMap::new(),
+			hash_announcements: self.hash_announcements,
 			network,
 			sync,
 			sync_event_stream: sync_event_stream.fuse(),
@@ -259,6 +346,12 @@ pub struct TransactionsHandler<
 	/// imported. This prevents that we import the same transaction
 	/// multiple times concurrently.
 	pending_transactions_peers: HashMap<H, Vec<PeerId>>,
+	/// Announced transactions that have been requested from peers, with the time of the request.
+	///
+	/// Used to request each announced transaction only from one peer at a time.
+	requested_transactions: HashMap<H, Instant>,
+	/// Whether transaction hashes are announced to peers supporting version 2 of the protocol.
+	hash_announcements: bool,
 	/// Network service to use to send messages and manage peers.
 	network: N,
 	/// Syncing service.
@@ -281,12 +374,14 @@ struct Peer<H: ExHashT> {
 	/// Holds a set of transactions known to this peer.
 	known_transactions: LruHashSet<H>,
 	role: ObservedRole,
+	/// Negotiated version of the protocol.
+	version: ProtocolVersion,
 }
 
 impl<B, H, N, S> TransactionsHandler<B, H, N, S>
 where
 	B: BlockT + 'static,
-	H: ExHashT,
+	H: ExHashT + Encode + Decode,
 	N: NetworkPeers + NetworkEventStream,
 	S: SyncEventStream + sp_consensus::SyncOracle,
 {
@@ -296,6 +391,7 @@ where
 		loop {
 			futures::select! {
 				_ = self.propagate_timeout.next() => {
+					self.expire_transaction_requests();
 					self.propagate_transactions();
 				},
 				(tx_hash, result) = self.pending_transactions.select_next_some() => {
@@ -341,12 +437,25 @@ where
 					.map_or(ValidationResult::Reject, |_| ValidationResult::Accept);
 				let _ = result_tx.send(result);
 			},
-			NotificationEvent::NotificationStreamOpened { peer, handshake, .. } => {
+			NotificationEvent::NotificationStreamOpened {
+				peer,
+				handshake,
+				negotiated_fallback,
+				..
+			} => {
 				let Some(role) = self.network.peer_role(peer, handshake) else {
 					log::debug!(target: "sub-libp2p", "role for {peer} couldn't be determined");
 					return
 				};
 
+				// version 2 is only offered if hash announcements are enabled, and it's the main
+				// protocol, so any negotiated fallback means the peer speaks version 1
+				let version = match (self.hash_announcements, negotiated_fallback) {
+					(true, None) => ProtocolVersion::V2,
+					_ => ProtocolVersion::V1,
+				};
+				trace!(target: LOG_TARGET, "{peer} opened substream, protocol version {version:?}");
+
 				let _was_in = self.peers.insert(
 					peer,
 					Peer {
@@ -354,6 +463,7 @@ where
 							NonZeroUsize::new(MAX_KNOWN_TRANSACTIONS).expect("Constant is nonzero"),
 						),
 						role,
+						version,
 					},
 				);
 				debug_assert!(_was_in.is_none());
@@ -363,13 +473,37 @@ where
 				debug_assert!(_peer.is_some());
 			},
 			NotificationEvent::NotificationReceived { peer, notification } => {
-				if let Ok(m) =
-					<Transactions<B::Extrinsic> as Decode>::decode(&mut notification.as_ref())
-				{
-					self.on_transactions(peer, m);
-				} else {
-					warn!(target: "sub-libp2p", "Failed to decode transactions list from peer {peer}");
-					self.network.report_peer(peer, rep::BAD_TRANSACTION);
+				let Some(version) = self.peers.get(&peer).map(|peer| peer.version) else {
+					trace!(target: LOG_TARGET, "Received notification from unknown peer {peer}");
+					return
+				};
+
+				match version {
+					ProtocolVersion::V1 => {
+						if let Ok(m) = <Transactions<B::Extrinsic> as Decode>::decode(
+							&mut notification.as_ref(),
+						) {
+							self.on_transactions(peer, m);
+						} else {
+							warn!(target: "sub-libp2p", "Failed to decode transactions list from peer {peer}");
+							self.network.report_peer(peer, rep::BAD_TRANSACTION);
+						}
+					},
+					ProtocolVersion::V2 =>
+						match <TransactionsMessage<B::Extrinsic, H> as Decode>::decode(
+							&mut notification.as_ref(),
+						) {
+							Ok(TransactionsMessage::Transactions(transactions)) =>
+								self.on_transactions(peer, transactions),
+							Ok(TransactionsMessage::Announcements(hashes)) =>
+								self.on_announcements(peer, hashes),
+							Ok(TransactionsMessage::Request(hashes)) =>
+								self.on_transaction_request(peer, hashes),
+							Err(_) => {
+								warn!(target: "sub-libp2p", "Failed to decode transactions message from peer {peer}");
+								self.network.report_peer(peer, rep::BAD_TRANSACTION);
+							},
+						},
 				}
 			},
 		}
@@ -422,11 +556,18 @@ where
 
 				let hash = self.transaction_pool.hash_of(&t);
 				peer.known_transactions.insert(hash.clone());
+				self.requested_transactions.remove(&hash);
 
 				self.network.report_peer(who, rep::ANY_TRANSACTION);
 
 				match self.pending_transactions_peers.entry(hash.clone()) {
 					Entry::Vacant(entry) => {
+						if let Some(ref metrics) = self.metrics {
+							if self.transaction_pool.contains(&hash) {
+								metrics.duplicate_transactions.inc();
+							}
+						}
+
 						self.pending_transactions.push(PendingTransaction {
 							validation: self.transaction_pool.import(t),
 							tx_hash: hash,
@@ -434,6 +575,10 @@ where
 						entry.insert(vec![who]);
 					},
 					Entry::Occupied(mut entry) => {
+						if let Some(ref metrics) = self.metrics {
+							metrics.duplicate_transactions.inc();
+						}
+
 						entry.get_mut().push(who);
 					},
 				}
@@ -441,6 +586,94 @@ where
 		}
 	}
 
+	/// Called when peer announces hashes of transactions it has.
+	///
+	/// Transactions that are neither known nor already being fetched are requested from the peer.
+	fn on_announcements(&mut self, who: PeerId, hashes: Vec<H>) {
+		// Accept transactions only when node is not major syncing
+		if self.sync.is_major_syncing() {
+			trace!(target: LOG_TARGET, "{} Ignoring announcements while major syncing", who);
+			return
+		}
+
+		trace!(target: LOG_TARGET, "Received {} transaction announcements from {}", hashes.len(), who);
+		let Some(peer) = self.peers.get_mut(&who) else { return };
+		let now = Instant::now();
+		let mut request = Vec::new();
+		let mut known = 0;
+
+		for hash in hashes.into_iter().take(MAX_ANNOUNCED_HASHES) {
+			peer.known_transactions.insert(hash.clone());
+
+			if self.pending_transactions_peers.contains_key(&hash) ||
+				self.requested_transactions.contains_key(&hash) ||
+				self.transaction_pool.contains(&hash)
+			{
+				known += 1;
+				continue
+			}
+
+			if self.requested_transactions.len() >= MAX_REQUESTED_TRANSACTIONS {
+				debug!(
+					target: LOG_TARGET,
+					"Ignoring any further announcements that exceed `MAX_REQUESTED_TRANSACTIONS`({}) limit",
+					MAX_REQUESTED_TRANSACTIONS,
+				);
+				break
+			}
+
+			self.requested_transactions.insert(hash.clone(), now);
+			request.push(hash);
+		}
+
+		if let Some(ref metrics) = self.metrics {
+			metrics.known_announced_transactions.inc_by(known);
+			metrics.requested_transactions.inc_by(request.len() as _);
+		}
+
+		if !request.is_empty() {
+			trace!(target: LOG_TARGET, "Requesting {} transactions from {}", request.len(), who);
+			let _ = self.notification_service.send_sync_notification(
+				&who,
+				TransactionsMessage::<B::Extrinsic, H>::Request(request).encode(),
+			);
+		}
+	}
+
+	/// Called when peer requests bodies of transactions we have announced.
+	fn on_transaction_request(&mut self, who: PeerId, hashes: Vec<H>) {
+		let Some(peer) = self.peers.get_mut(&who) else { return };
+
+		if hashes.len() > MAX_ANNOUNCED_HASHES {
+			debug!(target: LOG_TARGET, "{} requested {} transactions at once", who, hashes.len());
+			self.network.report_peer(who, rep::EXCESSIVE_REQUEST);
+			return
+		}
+
+		trace!(target: LOG_TARGET, "{} requested {} transactions", who, hashes.len());
+		for hash in hashes {
+			let Some(transaction) = self.transaction_pool.transaction(&hash) else { continue };
+			peer.known_transactions.insert(hash);
+
+			// Same as when pushing transactions, only a single transaction is sent per
+			// notification.
+			let _ = self.notification_service.send_sync_notification(
+				&who,
+				TransactionsMessage::<B::Extrinsic, H>::Transactions(vec![transaction]).encode(),
+			);
+		}
+	}
+
+	/// Forget about requests that weren't answered in time, allowing the transactions to be
+	/// requested from other peers that announce them.
+	fn expire_transaction_requests(&mut self) {
+		let now = Instant::now();
+
+		self.requested_transactions.retain(|_, requested_at| {
+			now.duration_since(*requested_at) < TRANSACTION_REQUEST_TIMEOUT
+		});
+	}
+
 	fn on_handle_transaction_import(&mut self, who: PeerId, import: TransactionImport) {
 		match import {
 			TransactionImport::KnownGood =>
@@ -473,6 +706,7 @@ where
 	) -> HashMap<H, Vec<String>> {
 		let mut propagated_to = HashMap::<_, Vec<_>>::new();
 		let mut propagated_transactions = 0;
+		let mut announced_transactions = 0;
 
 		for (who, peer) in self.peers.iter_mut() {
 			// never send transactions to the light node
@@ -489,9 +723,25 @@ where
 			propagated_transactions += hashes.len();
 
 			if !to_send.is_empty() {
-				for hash in hashes {
-					propagated_to.entry(hash).or_default().push(who.to_base58());
+				for hash in &hashes {
+					propagated_to.entry(hash.clone()).or_default().push(who.to_base58());
 				}
+
+				if peer.version == ProtocolVersion::V2 {
+					trace!(target: "sync", "Announcing {} transactions to {}", hashes.len(), who);
+					announced_transactions += hashes.len();
+
+					for chunk in hashes.chunks(MAX_ANNOUNCED_HASHES) {
+						let _ = self.notification_service.send_sync_notification(
+							who,
+							TransactionsMessage::<B::Extrinsic, H>::Announcements(chunk.to_vec())
+								.encode(),
+						);
+					}
+
+					continue
+				}
+
 				trace!(target: "sync", "Sending {} transactions to {}", to_send.len(), who);
 				// Historically, the format of a notification of the transactions protocol
 				// consisted in a (SCALE-encoded) `Vec<Transaction>`.
@@ -511,7 +761,8 @@ where
 		}
 
 		if let Some(ref metrics) = self.metrics {
-			metrics.propagated_transactions.inc_by(propagated_transactions as _)
+			metrics.propagated_transactions.inc_by(propagated_transactions as _);
+			metrics.announced_transactions.inc_by(announced_transactions as _);
 		}
 
 		propagated_to
@@ -536,3 +787,263 @@ where
 		self.transaction_pool.on_broadcasted(propagated_to);
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_network::{service::traits::Direction, ReputationChange};
+	use sc_network_sync::service::mock::{MockNetwork, MockNotificationService, MockSyncService};
+	use sp_runtime::{
+		testing::Block as RawBlock,
+		traits::{BlakeTwo256, Hash as HashT},
+		OpaqueExtrinsic,
+	};
+	use std::sync::Mutex;
+
+	type Block = RawBlock<OpaqueExtrinsic>;
+	type Hash = <BlakeTwo256 as HashT>::Output;
+
+	#[derive(Default)]
+	struct TestTransactionPool {
+		transactions: Vec<(Hash, OpaqueExtrinsic)>,
+		broadcasted: Mutex<Vec<HashMap<Hash, Vec<String>>>>,
+	}
+
+	impl TransactionPool<Hash, Block> for TestTransactionPool {
+		fn transactions(&self) -> Vec<(Hash, OpaqueExtrinsic)> {
+			self.transactions.clone()
+		}
+
+		fn hash_of(&self, transaction: &OpaqueExtrinsic) -> Hash {
+			BlakeTwo256::hash_of(transaction)
+		}
+
+		fn import(&self, _: OpaqueExtrinsic) -> TransactionImportFuture {
+			Box::pin(future::ready(TransactionImport::NewGood))
+		}
+
+		fn on_broadcasted(&self, propagations: HashMap<Hash, Vec<String>>) {
+			self.broadcasted.lock().unwrap().push(propagations);
+		}
+
+		fn transaction(&self, hash: &Hash) -> Option<OpaqueExtrinsic> {
+			self.transactions.iter().find(|(h, _)| h == hash).map(|(_, t)| t.clone())
+		}
+	}
+
+	type TestHandler = TransactionsHandler<Block, Hash, Arc<MockNetwork>, MockSyncService>;
+
+	struct TestSetup {
+		handler: TestHandler,
+		pool: Arc<TestTransactionPool>,
+		sent: Arc<Mutex<Vec<(PeerId, Vec<u8>)>>>,
+		reports: Arc<Mutex<Vec<(PeerId, ReputationChange)>>>,
+	}
+
+	fn setup(hash_announcements: bool, transactions: Vec<OpaqueExtrinsic>) -> TestSetup {
+		let sent = Arc::new(Mutex::new(Vec::new()));
+		let mut notification_service = MockNotificationService::new();
+		notification_service.expect_send_sync_notification().returning({
+			let sent = sent.clone();
+			move |peer, notification| sent.lock().unwrap().push((*peer, notification))
+		});
+
+		let reports = Arc::new(Mutex::new(Vec::new()));
+		let mut network = MockNetwork::new();
+		network.expect_peer_role().returning(|_, _| Some(ObservedRole::Full));
+		network.expect_report_peer().returning({
+			let reports = reports.clone();
+			move |peer, change| reports.lock().unwrap().push((peer, change))
+		});
+
+		let mut sync = MockSyncService::new();
+		sync.expect_event_stream().returning(|_| Box::pin(stream::pending()));
+		sync.expect_is_major_syncing().return_const(false);
+
+		let pool = Arc::new(TestTransactionPool {
+			transactions: transactions.into_iter().map(|t| (BlakeTwo256::hash_of(&t), t)).collect(),
+			..Default::default()
+		});
+
+		let (handler, _controller) = TransactionsHandlerPrototype {
+			protocol_name: "/transactions/2".into(),
+			notification_service: Box::new(notification_service),
+			hash_announcements,
+		}
+		.build::<Block, Hash, _, _>(Arc::new(network), sync, pool.clone(), None)
+		.unwrap();
+
+		TestSetup { handler, pool, sent, reports }
+	}
+
+	fn open_substream(handler: &mut TestHandler, peer: PeerId, fallback: bool) {
+		handler.handle_notification_event(NotificationEvent::NotificationStreamOpened {
+			peer,
+			direction: Direction::Inbound,
+			handshake: Vec::new(),
+			negotiated_fallback: fallback.then(|| "/transactions/1".into()),
+		});
+	}
+
+	fn receive(
+		handler: &mut TestHandler,
+		peer: PeerId,
+		message: TransactionsMessage<OpaqueExtrinsic, Hash>,
+	) {
+		handler.handle_notification_event(NotificationEvent::NotificationReceived {
+			peer,
+			notification: message.encode(),
+		});
+	}
+
+	fn transaction(n: u8) -> OpaqueExtrinsic {
+		OpaqueExtrinsic::from_bytes(&vec![n].encode()).unwrap()
+	}
+
+	fn take_sent(sent: &Mutex<Vec<(PeerId, Vec<u8>)>>) -> Vec<(PeerId, Vec<u8>)> {
+		std::mem::take(&mut *sent.lock().unwrap())
+	}
+
+	#[test]
+	fn transactions_are_announced_to_peers_supporting_announcements() {
+		let transactions = vec![transaction(1), transaction(2)];
+		let hashes = transactions.iter().map(BlakeTwo256::hash_of).collect::<Vec<_>>();
+		let TestSetup { mut handler, pool, sent, .. } = setup(true, transactions.clone());
+
+		let v2_peer = PeerId::random();
+		let v1_peer = PeerId::random();
+		open_substream(&mut handler, v2_peer, false);
+		open_substream(&mut handler, v1_peer, true);
+
+		handler.propagate_transactions();
+
+		let mut notifications = take_sent(&sent);
+		notifications.sort_by_key(|(peer, _)| *peer != v2_peer);
+		assert_eq!(
+			notifications,
+			vec![
+				(
+					v2_peer,
+					TransactionsMessage::<OpaqueExtrinsic, Hash>::Announcements(hashes.clone())
+						.encode()
+				),
+				(v1_peer, vec![transactions[0].clone()].encode()),
+				(v1_peer, vec![transactions[1].clone()].encode()),
+			],
+		);
+
+		let broadcasted = pool.broadcasted.lock().unwrap();
+		for hash in &hashes {
+			let mut propagated_to = broadcasted[0][hash].clone();
+			propagated_to.sort();
+			let mut expected = vec![v1_peer.to_base58(), v2_peer.to_base58()];
+			expected.sort();
+			assert_eq!(propagated_to, expected);
+		}
+	}
+
+	#[test]
+	fn full_transactions_are_pushed_without_announcements() {
+		let transactions = vec![transaction(1)];
+		let TestSetup { mut handler, sent, .. } = setup(false, transactions.clone());
+
+		// without announcements version 1 is the main protocol
+		let peer = PeerId::random();
+		open_substream(&mut handler, peer, false);
+
+		handler.propagate_transactions();
+
+		assert_eq!(take_sent(&sent), vec![(peer, transactions.encode())]);
+	}
+
+	#[test]
+	fn announced_transactions_are_requested_once() {
+		let known = transaction(1);
+		let unknown = transaction(2);
+		let unknown_hash = BlakeTwo256::hash_of(&unknown);
+		let TestSetup { mut handler, sent, .. } = setup(true, vec![known.clone()]);
+
+		let first_peer = PeerId::random();
+		let second_peer = PeerId::random();
+		open_substream(&mut handler, first_peer, false);
+		open_substream(&mut handler, second_peer, false);
+
+		// transactions known by the pool are not requested
+		receive(
+			&mut handler,
+			first_peer,
+			TransactionsMessage::Announcements(vec![BlakeTwo256::hash_of(&known), unknown_hash]),
+		);
+		assert_eq!(
+			take_sent(&sent),
+			vec![(
+				first_peer,
+				TransactionsMessage::<OpaqueExtrinsic, Hash>::Request(vec![unknown_hash]).encode()
+			)],
+		);
+
+		// transactions being requested are not requested again
+		receive(&mut handler, second_peer, TransactionsMessage::Announcements(vec![unknown_hash]));
+		assert!(take_sent(&sent).is_empty());
+
+		// unless the request timed out
+		handler.requested_transactions.insert(
+			unknown_hash,
+			Instant::now() - TRANSACTION_REQUEST_TIMEOUT - std::time::Duration::from_secs(1),
+		);
+		handler.expire_transaction_requests();
+		receive(&mut handler, second_peer, TransactionsMessage::Announcements(vec![unknown_hash]));
+		assert_eq!(
+			take_sent(&sent),
+			vec![(
+				second_peer,
+				TransactionsMessage::<OpaqueExtrinsic, Hash>::Request(vec![unknown_hash]).encode()
+			)],
+		);
+
+		// transactions being imported are not requested either
+		receive(&mut handler, second_peer, TransactionsMessage::Transactions(vec![unknown]));
+		assert!(handler.requested_transactions.is_empty());
+		assert!(handler.pending_transactions_peers.contains_key(&unknown_hash));
+
+		receive(&mut handler, first_peer, TransactionsMessage::Announcements(vec![unknown_hash]));
+		assert!(take_sent(&sent).is_empty());
+	}
+
+	#[test]
+	fn requested_transactions_are_sent() {
+		let transaction = transaction(1);
+		let hash = BlakeTwo256::hash_of(&transaction);
+		let TestSetup { mut handler, sent, reports, .. } = setup(true, vec![transaction.clone()]);
+
+		let peer = PeerId::random();
+		open_substream(&mut handler, peer, false);
+
+		// unknown transactions are skipped
+		receive(
+			&mut handler,
+			peer,
+			TransactionsMessage::Request(vec![hash, BlakeTwo256::hash_of(&[0u8])]),
+		);
+		assert_eq!(
+			take_sent(&sent),
+			vec![(
+				peer,
+				TransactionsMessage::<OpaqueExtrinsic, Hash>::Transactions(vec![transaction])
+					.encode()
+			)],
+		);
+
+		// the requested transaction is known to the peer and not announced again
+		handler.propagate_transactions();
+		assert!(take_sent(&sent).is_empty());
+
+		receive(
+			&mut handler,
+			peer,
+			TransactionsMessage::Request(vec![hash; MAX_ANNOUNCED_HASHES + 1]),
+		);
+		assert!(take_sent(&sent).is_empty());
+		assert_eq!(*reports.lock().unwrap(), vec![(peer, rep::EXCESSIVE_REQUEST)]);
+	}
+}
@@ -0,0 +1,50 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Substrate bitswap RPC API.
+
+use error::Error;
+use jsonrpsee::proc_macros::rpc;
+use sp_core::Bytes;
+
+pub mod error;
+
+/// Substrate bitswap RPC API.
+///
+/// CIDs are passed in their string representation and peers as base58-encoded peer IDs.
+#[rpc(client, server)]
+pub trait BitswapApi {
+	/// Fetch the content of the DAG rooted at `cid`.
+	///
+	/// Blocks that are not available locally are requested from `peers`, in order.
+	#[method(name = "bitswap_fetch", with_extensions)]
+	async fn fetch(&self, cid: String, peers: Vec<String>) -> Result<Bytes, Error>;
+
+	/// Fetch the DAG rooted at `cid` from `peers` and pin it, so that it keeps being served over
+	/// bitswap.
+	#[method(name = "bitswap_pin", with_extensions)]
+	async fn pin(&self, cid: String, peers: Vec<String>) -> Result<(), Error>;
+
+	/// Unpin the DAG rooted at `cid`. Returns `false` if it wasn't pinned.
+	#[method(name = "bitswap_unpin", with_extensions)]
+	fn unpin(&self, cid: String) -> Result<bool, Error>;
+
+	/// Return the CIDs of all pinned DAGs.
+	#[method(name = "bitswap_pins")]
+	fn pins(&self) -> Result<Vec<String>, Error>;
+}
@@ -300,4 +300,34 @@ pub trait StateApi<Hash> {
 		storage_keys: Option<String>,
 		methods: Option<String>,
 	) -> Result<sp_rpc::tracing::TraceBlockResponse, Error>;
+
+	/// The `traceBlockChrome` RPC re-executes a single block like [`Self::trace_block`], but
+	/// returns a timeline of the execution in the [Chrome trace event format][1] that can be
+	/// loaded into `chrome://tracing` or [Perfetto](https://ui.perfetto.dev).
+	///
+	/// The storage proof is recorded during the re-execution. Every time a span is entered and
+	/// exited is reported as a complete event carrying the time spent in it and the number of
+	/// bytes it added to the proof (`proof_size` argument). Storage accesses are reported as
+	/// instant events.
+	///
+	/// [1]: https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU
+	///
+	/// ### Params
+	///
+	/// - `block` (param index 0): Hash of the block to trace.
+	/// - `targets` (param index 1): String of comma separated (no spaces) targets, see
+	/// `state_traceBlock`. Defaults to `pallet,frame,state,sp_io`, which covers dispatch,
+	/// hooks, storage accesses and host functions.
+	/// - `storage_keys` (param index 2): If given, only storage events whose key starts with
+	/// one of the comma separated hex encoded prefixes are included.
+	/// - `methods` (param index 3): If given, only events with one of the comma separated
+	/// methods are included.
+	#[method(name = "state_traceBlockChrome", blocking, with_extensions)]
+	fn trace_block_chrome(
+		&self,
+		block: Hash,
+		targets: Option<String>,
+		storage_keys: Option<String>,
+		methods: Option<String>,
+	) -> Result<sp_rpc::tracing::ChromeTrace, Error>;
 }
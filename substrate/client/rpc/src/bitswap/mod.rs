@@ -0,0 +1,94 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Substrate bitswap API.
+
+#[cfg(test)]
+mod tests;
+
+use jsonrpsee::{core::async_trait, Extensions};
+use sc_network::{
+	bitswap::{BitswapClient, Cid},
+	PeerId,
+};
+/// Re-export the API for backward compatibility.
+pub use sc_rpc_api::bitswap::{error::Error, BitswapApiServer};
+use sc_rpc_api::check_if_safe;
+use sp_core::Bytes;
+use std::str::FromStr;
+
+/// Bitswap API.
+pub struct Bitswap {
+	client: BitswapClient,
+}
+
+impl Bitswap {
+	/// Create new instance of Bitswap API.
+	pub fn new(client: BitswapClient) -> Self {
+		Bitswap { client }
+	}
+}
+
+fn parse_cid(cid: &str) -> Result<Cid, Error> {
+	Cid::from_str(cid).map_err(|e| Error::InvalidCid(e.to_string()))
+}
+
+fn parse_peers(peers: &[String]) -> Result<Vec<PeerId>, Error> {
+	peers
+		.iter()
+		.map(|peer| PeerId::from_str(peer).map_err(|e| Error::InvalidPeerId(e.to_string())))
+		.collect()
+}
+
+#[async_trait]
+impl BitswapApiServer for Bitswap {
+	async fn fetch(
+		&self,
+		ext: &Extensions,
+		cid: String,
+		peers: Vec<String>,
+	) -> Result<Bytes, Error> {
+		check_if_safe(ext)?;
+
+		let content = self
+			.client
+			.fetch(parse_cid(&cid)?, &parse_peers(&peers)?)
+			.await
+			.map_err(|e| Error::Bitswap(e.to_string()))?;
+		Ok(content.into())
+	}
+
+	async fn pin(&self, ext: &Extensions, cid: String, peers: Vec<String>) -> Result<(), Error> {
+		check_if_safe(ext)?;
+
+		self.client
+			.pin(parse_cid(&cid)?, &parse_peers(&peers)?)
+			.await
+			.map_err(|e| Error::Bitswap(e.to_string()))
+	}
+
+	fn unpin(&self, ext: &Extensions, cid: String) -> Result<bool, Error> {
+		check_if_safe(ext)?;
+
+		self.client.unpin(&parse_cid(&cid)?).map_err(|e| Error::Bitswap(e.to_string()))
+	}
+
+	fn pins(&self) -> Result<Vec<String>, Error> {
+		Ok(self.client.pins().iter().map(ToString::to_string).collect())
+	}
+}
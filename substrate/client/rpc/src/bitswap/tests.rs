@@ -0,0 +1,157 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+use crate::DenyUnsafe;
+use assert_matches::assert_matches;
+use futures::channel::oneshot;
+use jsonrpsee::{
+	core::EmptyServerParams as EmptyParams, rpc_params, MethodsError as RpcError, RpcModule,
+};
+use sc_network::{
+	bitswap::BitswapStore, IfDisconnected, NetworkRequest, ProtocolName, RequestFailure,
+};
+use sc_rpc_api::error::base::BITSWAP;
+use sp_crypto_hashing::blake2_256;
+use std::sync::Arc;
+use substrate_test_runtime_client::runtime::Block;
+
+/// Network without any reachable peer.
+struct NoPeers;
+
+#[async_trait]
+impl NetworkRequest for NoPeers {
+	async fn request(
+		&self,
+		_target: PeerId,
+		_protocol: ProtocolName,
+		_request: Vec<u8>,
+		_fallback_request: Option<(Vec<u8>, ProtocolName)>,
+		_connect: IfDisconnected,
+	) -> Result<(Vec<u8>, ProtocolName), RequestFailure> {
+		Err(RequestFailure::NotConnected)
+	}
+
+	fn start_request(
+		&self,
+		_target: PeerId,
+		_protocol: ProtocolName,
+		_request: Vec<u8>,
+		_fallback_request: Option<(Vec<u8>, ProtocolName)>,
+		tx: oneshot::Sender<Result<(Vec<u8>, ProtocolName), RequestFailure>>,
+		_connect: IfDisconnected,
+	) {
+		let _ = tx.send(Err(RequestFailure::NotConnected));
+	}
+}
+
+/// CID of raw `data` hashed with 256-bit Blake2b.
+fn raw_cid(data: &[u8]) -> Cid {
+	// CIDv1, raw binary codec, 32 bytes long Blake2b-256 multihash.
+	let mut bytes = vec![0x01, 0x55, 0xa0, 0xe4, 0x02, 0x20];
+	bytes.extend(blake2_256(data));
+	Cid::read_bytes(&bytes[..]).unwrap()
+}
+
+fn setup(deny_unsafe: DenyUnsafe) -> (BitswapStore, RpcModule<Bitswap>) {
+	let store = BitswapStore::in_memory();
+	let client = BitswapClient::new::<Block>(
+		NoPeers,
+		Arc::new(substrate_test_runtime_client::new()),
+		store.clone(),
+	);
+	let mut api = Bitswap::new(client).into_rpc();
+	api.extensions_mut().insert(deny_unsafe);
+
+	(store, api)
+}
+
+#[tokio::test]
+async fn pinned_content_is_fetched_and_unpinned() {
+	let (store, api) = setup(DenyUnsafe::No);
+	let cid = raw_cid(b"pinned");
+	store.pin(cid, vec![(cid, b"pinned".to_vec())]).unwrap();
+
+	assert_eq!(
+		api.call::<_, Vec<String>>("bitswap_pins", EmptyParams::new()).await.unwrap(),
+		vec![cid.to_string()],
+	);
+	assert_eq!(
+		api.call::<_, Bytes>("bitswap_fetch", rpc_params![cid.to_string(), Vec::<String>::new()])
+			.await
+			.unwrap(),
+		Bytes(b"pinned".to_vec()),
+	);
+
+	assert!(api.call::<_, bool>("bitswap_unpin", [cid.to_string()]).await.unwrap());
+	assert!(!api.call::<_, bool>("bitswap_unpin", [cid.to_string()]).await.unwrap());
+	assert!(api
+		.call::<_, Vec<String>>("bitswap_pins", EmptyParams::new())
+		.await
+		.unwrap()
+		.is_empty());
+
+	// Unpinned content has to be fetched from peers again.
+	assert_matches!(
+		api.call::<_, ()>(
+			"bitswap_pin",
+			rpc_params![cid.to_string(), vec![PeerId::random().to_string()]],
+		)
+		.await,
+		Err(RpcError::JsonRpc(err)) if err.code() == BITSWAP + 3
+	);
+}
+
+#[tokio::test]
+async fn malformed_parameters_are_rejected() {
+	let (_, api) = setup(DenyUnsafe::No);
+	let cid = raw_cid(b"content");
+
+	assert_matches!(
+		api.call::<_, Bytes>("bitswap_fetch", rpc_params!["not a CID", Vec::<String>::new()]).await,
+		Err(RpcError::JsonRpc(err)) if err.code() == BITSWAP + 1
+	);
+	assert_matches!(
+		api.call::<_, Bytes>("bitswap_fetch", rpc_params![cid.to_string(), vec!["not a peer"]])
+			.await,
+		Err(RpcError::JsonRpc(err)) if err.code() == BITSWAP + 2
+	);
+}
+
+#[tokio::test]
+async fn deny_unsafe_works() {
+	let (store, api) = setup(DenyUnsafe::Yes);
+	let cid = raw_cid(b"pinned");
+	store.pin(cid, vec![(cid, b"pinned".to_vec())]).unwrap();
+
+	assert_matches!(
+		api.call::<_, Bytes>("bitswap_fetch", rpc_params![cid.to_string(), Vec::<String>::new()])
+			.await,
+		Err(RpcError::JsonRpc(err)) if err.message().contains("RPC call is unsafe to be called externally")
+	);
+	assert_matches!(
+		api.call::<_, bool>("bitswap_unpin", [cid.to_string()]).await,
+		Err(RpcError::JsonRpc(err)) if err.message().contains("RPC call is unsafe to be called externally")
+	);
+
+	// Listing pins is safe.
+	assert_eq!(
+		api.call::<_, Vec<String>>("bitswap_pins", EmptyParams::new()).await.unwrap(),
+		vec![cid.to_string()],
+	);
+}
@@ -148,6 +148,15 @@ where
 		methods: Option<String>,
 	) -> Result<sp_rpc::tracing::TraceBlockResponse, Error>;
 
+	/// Trace the execution timeline of block
+	fn trace_block_chrome(
+		&self,
+		block: Block::Hash,
+		targets: Option<String>,
+		storage_keys: Option<String>,
+		methods: Option<String>,
+	) -> Result<sp_rpc::tracing::ChromeTrace, Error>;
+
 	/// New runtime version subscription
 	fn subscribe_runtime_version(&self, pending: PendingSubscriptionSink);
 
@@ -325,6 +334,25 @@ where
 			.map_err(Into::into)
 	}
 
+	/// Re-execute the given block with the tracing targets given in `targets`
+	/// and capture a timeline of its execution.
+	///
+	/// Note: requires the node to run with `--rpc-methods=Unsafe`.
+	/// Note: requires runtimes compiled with wasm tracing support, `--features with-tracing`.
+	fn trace_block_chrome(
+		&self,
+		ext: &Extensions,
+		block: Block::Hash,
+		targets: Option<String>,
+		storage_keys: Option<String>,
+		methods: Option<String>,
+	) -> Result<sp_rpc::tracing::ChromeTrace, Error> {
+		check_if_safe(ext)?;
+		self.backend
+			.trace_block_chrome(block, targets, storage_keys, methods)
+			.map_err(Into::into)
+	}
+
 	fn subscribe_runtime_version(&self, pending: PendingSubscriptionSink) {
 		self.backend.subscribe_runtime_version(pending)
 	}
@@ -538,3 +538,67 @@ async fn concrete_storage_subscriptions_are_rpc_safe() {
 
 	assert!(sub.is_ok());
 }
+
+#[tokio::test]
+async fn should_trace_block_chrome() {
+	let client = Arc::new(substrate_test_runtime_client::new());
+	let mut builder = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(client.chain_info().best_hash)
+		.with_parent_block_number(client.chain_info().best_number)
+		.build()
+		.unwrap();
+	builder
+		.push(ExtrinsicBuilder::new_storage_change(vec![1], Some(vec![1])).build())
+		.unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = block.header.hash();
+	client.import(BlockOrigin::Own, block).await.unwrap();
+
+	let (api, _child) = new_full(client, test_executor());
+	let mut api_rpc = api.into_rpc();
+	api_rpc.extensions_mut().insert(DenyUnsafe::No);
+
+	let trace: serde_json::Value =
+		api_rpc.call("state_traceBlockChrome", [block_hash]).await.unwrap();
+
+	assert_eq!(trace["displayTimeUnit"], "ns");
+	assert_eq!(trace["otherData"]["tracing_targets"], "pallet,frame,state,sp_io");
+	assert_eq!(
+		trace["otherData"]["block_hash"],
+		sp_core::hexdisplay::HexDisplay::from(&block_hash.as_bytes()).to_string()
+	);
+
+	let events = trace["traceEvents"].as_array().unwrap();
+	let mut last_ts = 0.0;
+	for event in events {
+		assert!(event["name"].is_string(), "{event}");
+		let ts = event["ts"].as_f64().unwrap();
+		assert!(ts >= last_ts, "events are not ordered: {event}");
+		last_ts = ts;
+
+		match event["ph"].as_str().unwrap() {
+			"X" => assert!(event["dur"].as_f64().is_some(), "{event}"),
+			"i" | "C" => assert!(event.get("dur").is_none(), "{event}"),
+			ph => panic!("unexpected phase {ph}"),
+		}
+	}
+
+	// storage accesses are reported as instant events named after the access
+	assert!(events
+		.iter()
+		.any(|e| e["ph"] == "i" && e["cat"] == "state" && e["name"] == "Get"));
+}
+
+#[tokio::test]
+async fn trace_block_chrome_is_rpc_unsafe() {
+	let client = Arc::new(substrate_test_runtime_client::new());
+	let genesis_hash = client.genesis_hash();
+	let (api, _child) = new_full(client, test_executor());
+	let mut api_rpc = api.into_rpc();
+	api_rpc.extensions_mut().insert(DenyUnsafe::Yes);
+
+	let err = api_rpc
+		.call::<_, serde_json::Value>("state_traceBlockChrome", [genesis_hash])
+		.await;
+	assert_matches!(err, Err(RpcError::JsonRpc(e)) if e.message() == "RPC call is unsafe to be called externally");
+}
@@ -91,6 +91,7 @@ fn api<T: Into<Option<Status>>>(sync: T) -> RpcModule<System<Block>> {
 							connected_peers: Default::default(),
 							not_connected_peers: Default::default(),
 							peerset: serde_json::Value::Null,
+							bandwidth: Default::default(),
 						})
 						.unwrap(),
 					);
@@ -282,6 +283,7 @@ async fn system_network_state() {
 			connected_peers: Default::default(),
 			not_connected_peers: Default::default(),
 			peerset: serde_json::Value::Null,
+			bandwidth: Default::default(),
 		}
 	);
 }
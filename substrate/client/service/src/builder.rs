@@ -260,6 +260,7 @@ where
 				no_genesis: config.no_genesis(),
 				wasm_runtime_substitutes,
 				enable_import_proof_recording,
+				runtime_api_cache_size: config.executor.runtime_api_cache_size,
 			},
 		)?;
 
@@ -291,6 +292,9 @@ pub fn new_wasm_executor<H: HostFunctions>(config: &ExecutorConfiguration) -> Wa
 		.with_offchain_heap_alloc_strategy(strategy)
 		.with_max_runtime_instances(config.max_runtime_instances)
 		.with_runtime_cache_size(config.runtime_cache_size)
+		.with_runtime_cache_path(config.runtime_cache_path.clone())
+		.with_runtime_cache_max_size(config.runtime_cache_max_size)
+		.with_host_function_shims(config.host_function_shims.clone())
 		.build()
 }
 
@@ -468,6 +472,19 @@ where
 		sc_transaction_pool::notification_future(client.clone(), transaction_pool.clone()),
 	);
 
+	// Compile runtime upgrades before they become active.
+	if config.executor.precompile_runtime_upgrades {
+		spawn_handle.spawn(
+			"runtime-precompile",
+			Some("executor"),
+			crate::precompile::precompile_runtime_upgrades(
+				client.clone(),
+				spawn_handle.clone(),
+				config.executor.pending_code_storage_keys.clone(),
+			),
+		);
+	}
+
 	spawn_handle.spawn(
 		"on-transaction-imported",
 		Some("transaction-pool"),
@@ -1057,7 +1074,7 @@ where
 	net_config.add_request_response_protocol(light_client_request_protocol_config);
 
 	let bitswap_config = ipfs_server.then(|| {
-		let (handler, config) = Net::bitswap_server(client.clone());
+		let (handler, config) = Net::bitswap_server(client.clone(), net_config.bitswap_store());
 		spawn_handle.spawn("bitswap-request-handler", Some("networking"), handler);
 
 		config
@@ -1071,6 +1088,7 @@ where
 			fork_id,
 			metrics.clone(),
 			net_config.peer_store_handle(),
+			net_config.network_config.transaction_hash_announcements,
 		);
 	net_config.add_notification_protocol(transactions_config);
 
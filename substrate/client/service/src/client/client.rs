@@ -40,6 +40,7 @@ use sc_client_api::{
 	},
 	execution_extensions::ExecutionExtensions,
 	notifications::{StorageEventStream, StorageNotifications},
+	runtime_api_cache::RuntimeApiCache,
 	CallExecutor, ExecutorProvider, KeysIter, OnFinalityAction, OnImportAction, PairsIter,
 	ProofProvider, UnpinWorkerMessage, UsageProvider,
 };
@@ -114,6 +115,7 @@ where
 	telemetry: Option<TelemetryHandle>,
 	unpin_worker_sender: TracingUnboundedSender<UnpinWorkerMessage<Block>>,
 	code_provider: CodeProvider<Block, B, E>,
+	runtime_api_cache: Option<RuntimeApiCache<Block>>,
 	_phantom: PhantomData<RA>,
 }
 
@@ -166,6 +168,8 @@ pub struct ClientConfig<Block: BlockT> {
 	pub wasm_runtime_substitutes: HashMap<NumberFor<Block>, Vec<u8>>,
 	/// Enable recording of storage proofs during block import
 	pub enable_import_proof_recording: bool,
+	/// Maximum number of results of pure runtime api calls to cache. `0` disables the cache.
+	pub runtime_api_cache_size: usize,
 }
 
 impl<Block: BlockT> Default for ClientConfig<Block> {
@@ -177,6 +181,7 @@ impl<Block: BlockT> Default for ClientConfig<Block> {
 			no_genesis: false,
 			wasm_runtime_substitutes: HashMap::new(),
 			enable_import_proof_recording: false,
+			runtime_api_cache_size: 0,
 		}
 	}
 }
@@ -405,6 +410,12 @@ where
 		let unpin_worker = NotificationPinningWorker::new(rx, backend.clone());
 		spawn_handle.spawn("notification-pinning-worker", None, Box::pin(unpin_worker.run()));
 		let code_provider = CodeProvider::new(&config, executor.clone(), backend.clone())?;
+		let runtime_api_cache = (config.runtime_api_cache_size > 0).then(|| {
+			RuntimeApiCache::new(
+				config.runtime_api_cache_size.try_into().unwrap_or(u32::MAX),
+				prometheus_registry.as_ref(),
+			)
+		});
 
 		Ok(Client {
 			backend,
@@ -421,6 +432,7 @@ where
 			telemetry,
 			unpin_worker_sender,
 			code_provider,
+			runtime_api_cache,
 			_phantom: Default::default(),
 		})
 	}
@@ -1662,7 +1674,23 @@ where
 	type StateBackend = B::State;
 
 	fn call_api_at(&self, params: CallApiAtParams<Block>) -> Result<Vec<u8>, sp_api::ApiError> {
-		self.executor
+		// The result of a pure function only depends on the state and the arguments as long as
+		// nothing was changed on top of the state and no proof needs to be recorded.
+		let cache = self.runtime_api_cache.as_ref().filter(|_| {
+			params.pure &&
+				params.recorder.is_none() &&
+				params.call_context == CallContext::Offchain &&
+				params.overlayed_changes.borrow().is_empty()
+		});
+
+		if let Some(result) =
+			cache.and_then(|c| c.get(params.at, params.function, &params.arguments))
+		{
+			return Ok(result)
+		}
+
+		let result: Result<_, sp_api::ApiError> = self
+			.executor
 			.contextual_call(
 				params.at,
 				params.function,
@@ -1672,7 +1700,13 @@ where
 				params.call_context,
 				params.extensions,
 			)
-			.map_err(Into::into)
+			.map_err(Into::into);
+
+		if let (Some(cache), Ok(result)) = (cache, &result) {
+			cache.insert(params.at, params.function, params.arguments, result.clone());
+		}
+
+		result
 	}
 
 	fn runtime_version_at(&self, hash: Block::Hash) -> Result<RuntimeVersion, sp_api::ApiError> {
@@ -336,6 +336,24 @@ pub struct ExecutorConfiguration {
 	pub default_heap_pages: Option<u64>,
 	/// Maximum number of different runtime versions that can be cached.
 	pub runtime_cache_size: u8,
+	/// Directory in which the compiled runtimes are persisted, shared across restarts and nodes.
+	pub runtime_cache_path: Option<PathBuf>,
+	/// Maximum size in bytes of the compiled runtimes persisted in the `runtime_cache_path`.
+	pub runtime_cache_max_size: u64,
+	/// Compile runtime upgrades ahead of time, as soon as they land in the state of the best
+	/// block.
+	pub precompile_runtime_upgrades: bool,
+	/// Storage keys under which runtime code scheduled to replace `:code` is stored, as SCALE
+	/// encoded `Vec<u8>`.
+	///
+	/// The code under these keys is compiled ahead of time along with the code under `:code`, if
+	/// `precompile_runtime_upgrades` is enabled.
+	pub pending_code_storage_keys: Vec<Vec<u8>>,
+	/// Maximum number of results of pure runtime api calls to cache. `0` disables the cache.
+	pub runtime_api_cache_size: usize,
+	/// Wasm modules providing host functions which are imported by a runtime, but not
+	/// implemented natively.
+	pub host_function_shims: sc_executor::HostFunctionShims,
 }
 
 impl Default for ExecutorConfiguration {
@@ -345,6 +363,12 @@ impl Default for ExecutorConfiguration {
 			max_runtime_instances: 8,
 			default_heap_pages: None,
 			runtime_cache_size: 2,
+			runtime_cache_path: None,
+			runtime_cache_max_size: sc_executor::DEFAULT_RUNTIME_CACHE_MAX_SIZE,
+			precompile_runtime_upgrades: true,
+			pending_code_storage_keys: Vec::new(),
+			runtime_api_cache_size: 0,
+			host_function_shims: sc_executor::HostFunctionShims::default(),
 		}
 	}
 }
@@ -29,6 +29,7 @@ pub mod error;
 
 mod builder;
 mod metrics;
+mod precompile;
 mod task_manager;
 
 use crate::config::Multiaddr;
@@ -87,7 +88,7 @@ pub use sc_chain_spec::{
 use crate::config::RpcConfiguration;
 use prometheus_endpoint::Registry;
 pub use sc_consensus::ImportQueue;
-pub use sc_executor::NativeExecutionDispatch;
+pub use sc_executor::{HostFunctionShims, NativeExecutionDispatch};
 pub use sc_network_sync::WarpSyncConfig;
 #[doc(hidden)]
 pub use sc_network_transactions::config::{TransactionImport, TransactionImportFuture};
@@ -565,6 +566,12 @@ where
 			|tx| if tx.is_propagable() { Some((**tx.data()).clone()) } else { None },
 		)
 	}
+
+	fn contains(&self, hash: &H) -> bool {
+		self.pool.ready_transaction(hash).is_some() ||
+			(self.pool.status().future > 0 &&
+				self.pool.futures().iter().any(|tx| tx.hash() == hash))
+	}
 }
 
 #[cfg(test)]
@@ -616,4 +623,44 @@ mod tests {
 		assert_eq!(transactions.len(), 1);
 		assert!(TransferData::try_from(&transactions[0].1).is_ok());
 	}
+
+	#[test]
+	fn pool_adapter_knows_ready_and_future_transactions() {
+		use sc_network_transactions::config::TransactionPool as _;
+
+		// given
+		let (client, longest_chain) = TestClientBuilder::new().build_with_longest_chain();
+		let client = Arc::new(client);
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let pool = Arc::from(BasicPool::new_full(
+			Default::default(),
+			true.into(),
+			None,
+			spawner,
+			client.clone(),
+		));
+		let source = sp_runtime::transaction_validity::TransactionSource::External;
+		let best = block_on(longest_chain.best_chain()).unwrap();
+		let transfer = |nonce| {
+			Transfer {
+				amount: 5,
+				nonce,
+				from: AccountKeyring::Alice.into(),
+				to: AccountKeyring::Bob.into(),
+			}
+			.into_unchecked_extrinsic()
+		};
+		let ready = block_on(pool.submit_one(best.hash(), source, transfer(0))).unwrap();
+		let future = block_on(pool.submit_one(best.hash(), source, transfer(2))).unwrap();
+		assert_eq!(pool.status().ready, 1);
+		assert_eq!(pool.status().future, 1);
+
+		// when
+		let adapter = TransactionPoolAdapter::new(pool.clone(), client);
+
+		// then
+		assert!(adapter.contains(&ready));
+		assert!(adapter.contains(&future));
+		assert!(!adapter.contains(&pool.hash_of(&transfer(1))));
+	}
 }
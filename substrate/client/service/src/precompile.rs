@@ -0,0 +1,252 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Speculative compilation of runtime upgrades.
+//!
+//! Compiling a runtime takes a while, so the first block executed with a new runtime would stall
+//! if the runtime was only compiled when the block is executed. Instead, new runtime code is
+//! compiled in the background as soon as it lands in the state of the best block, which puts it
+//! into the runtime cache of the executor ahead of time.
+
+use codec::{Decode, Encode};
+use futures::StreamExt;
+use log::{debug, warn};
+use sc_client_api::{BlockchainEvents, CallExecutor, ExecutorProvider, StorageProvider};
+use sc_executor::RuntimeVersionOf;
+use sp_consensus::BlockOrigin;
+use sp_core::{
+	storage::{well_known_keys, StorageKey},
+	traits::{RuntimeCode, WrappedRuntimeCode},
+};
+use sp_runtime::traits::{Block as BlockT, Hash as HashT, HashingFor};
+use sp_state_machine::BasicExternalities;
+use std::{collections::HashMap, sync::Arc, time::Instant};
+
+use crate::SpawnTaskHandle;
+
+const LOG_TARGET: &str = "runtime-precompile";
+
+/// Compile runtime upgrades in the background before they become active.
+///
+/// On every new best block the storage hashes of `:code` and of each of the `pending_code_keys`
+/// are checked, and the code under the keys whose storage hash changed is read from the state and
+/// compiled. The values under `pending_code_keys` are expected to be SCALE encoded `Vec<u8>`, like
+/// the validation code scheduled by Cumulus' `PendingValidationCode`.
+pub(crate) async fn precompile_runtime_upgrades<Block, Client, Backend>(
+	client: Arc<Client>,
+	spawn_handle: SpawnTaskHandle,
+	pending_code_keys: Vec<Vec<u8>>,
+) where
+	Block: BlockT,
+	Client: BlockchainEvents<Block>
+		+ ExecutorProvider<Block>
+		+ StorageProvider<Block, Backend>
+		+ Send
+		+ Sync
+		+ 'static,
+	Backend: sc_client_api::Backend<Block>,
+{
+	let mut watcher = CodeWatcher::new(pending_code_keys);
+	let mut notifications = client.import_notification_stream();
+
+	while let Some(notification) = notifications.next().await {
+		// Blocks imported during the initial sync are executed one after another anyway.
+		if !notification.is_new_best || notification.origin == BlockOrigin::NetworkInitialSync {
+			continue
+		}
+
+		let at = notification.hash;
+		let storage_hash =
+			|key: &[u8]| log_error(key, at, client.storage_hash(at, &StorageKey(key.to_vec())));
+		let storage = |key: &[u8]| {
+			log_error(key, at, client.storage(at, &StorageKey(key.to_vec()))).map(|data| data.0)
+		};
+
+		let changed_code = watcher.changed_code(storage_hash, storage);
+		if changed_code.is_empty() {
+			continue
+		}
+
+		let heap_pages = storage(well_known_keys::HEAP_PAGES)
+			.and_then(|pages| u64::decode(&mut &pages[..]).ok());
+
+		for code in changed_code {
+			let code_hash = HashingFor::<Block>::hash(&code);
+			let client = client.clone();
+			spawn_handle.spawn_blocking("runtime-precompile", Some("executor"), async move {
+				precompile::<Block, _>(client.executor(), code, code_hash, heap_pages)
+			});
+		}
+	}
+}
+
+fn log_error<T, Hash: std::fmt::Display>(
+	key: &[u8],
+	at: Hash,
+	result: sp_blockchain::Result<Option<T>>,
+) -> Option<T> {
+	result.unwrap_or_else(|error| {
+		debug!(target: LOG_TARGET, "Failed to read {key:?} at {at}: {error}");
+		None
+	})
+}
+
+/// Keeps track of the storage hashes of the keys holding runtime code.
+struct CodeWatcher<Hash> {
+	/// The watched keys and whether their value is SCALE encoded.
+	keys: Vec<(Vec<u8>, bool)>,
+	/// The storage hashes of the watched keys seen last.
+	storage_hashes: HashMap<Vec<u8>, Hash>,
+}
+
+impl<Hash: PartialEq> CodeWatcher<Hash> {
+	fn new(pending_code_keys: Vec<Vec<u8>>) -> Self {
+		let keys = std::iter::once((well_known_keys::CODE.to_vec(), false))
+			.chain(pending_code_keys.into_iter().map(|key| (key, true)))
+			.collect();
+
+		Self { keys, storage_hashes: HashMap::new() }
+	}
+
+	/// Returns the code under the watched keys whose storage hash changed since the last call.
+	///
+	/// The code is only read with `storage` for keys whose storage hash changed.
+	fn changed_code(
+		&mut self,
+		storage_hash: impl Fn(&[u8]) -> Option<Hash>,
+		storage: impl Fn(&[u8]) -> Option<Vec<u8>>,
+	) -> Vec<Vec<u8>> {
+		let mut changed_code = Vec::new();
+
+		for (key, encoded) in &self.keys {
+			let Some(hash) = storage_hash(key) else {
+				self.storage_hashes.remove(key);
+				continue
+			};
+			if self.storage_hashes.get(key) == Some(&hash) {
+				continue
+			}
+			self.storage_hashes.insert(key.clone(), hash);
+
+			let code = storage(key).and_then(|value| match encoded {
+				true => Vec::<u8>::decode(&mut &value[..]).ok(),
+				false => Some(value),
+			});
+			changed_code.extend(code.filter(|code| !code.is_empty()));
+		}
+
+		changed_code
+	}
+}
+
+fn precompile<Block: BlockT, Executor: CallExecutor<Block>>(
+	executor: &Executor,
+	code: Vec<u8>,
+	code_hash: Block::Hash,
+	heap_pages: Option<u64>,
+) {
+	let runtime_code = RuntimeCode {
+		code_fetcher: &WrappedRuntimeCode(code.into()),
+		// Must match the hash used when executing the code from the state.
+		hash: code_hash.encode(),
+		heap_pages,
+	};
+
+	let started = Instant::now();
+	// Fetching the version of the runtime compiles it and puts it into the runtime cache.
+	match RuntimeVersionOf::runtime_version(
+		executor,
+		&mut BasicExternalities::default(),
+		&runtime_code,
+	) {
+		Ok(version) => debug!(
+			target: LOG_TARGET,
+			"Compiled runtime {code_hash:?} ({}-{}) in {} ms",
+			version.spec_name,
+			version.spec_version,
+			started.elapsed().as_millis(),
+		),
+		Err(error) => warn!(target: LOG_TARGET, "Failed to compile runtime {code_hash:?}: {error}"),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::cell::RefCell;
+
+	const PENDING_CODE: &[u8] = b"pending_code";
+
+	#[derive(Default)]
+	struct State {
+		values: HashMap<Vec<u8>, Vec<u8>>,
+		reads: RefCell<usize>,
+	}
+
+	impl State {
+		fn set(&mut self, key: &[u8], value: Vec<u8>) {
+			self.values.insert(key.to_vec(), value);
+		}
+
+		fn changed_code(&self, watcher: &mut CodeWatcher<Vec<u8>>) -> Vec<Vec<u8>> {
+			watcher.changed_code(
+				|key| self.values.get(key).map(|value| sp_core::blake2_256(value).to_vec()),
+				|key| {
+					*self.reads.borrow_mut() += 1;
+					self.values.get(key).cloned()
+				},
+			)
+		}
+	}
+
+	#[test]
+	fn new_code_is_compiled_once() {
+		let mut watcher = CodeWatcher::new(vec![PENDING_CODE.to_vec()]);
+		let mut state = State::default();
+		state.set(well_known_keys::CODE, b"code_a".to_vec());
+
+		assert_eq!(state.changed_code(&mut watcher), vec![b"code_a".to_vec()]);
+		assert_eq!(*state.reads.borrow(), 1);
+
+		state.set(PENDING_CODE, b"code_b".to_vec().encode());
+		assert_eq!(state.changed_code(&mut watcher), vec![b"code_b".to_vec()]);
+		assert_eq!(*state.reads.borrow(), 2);
+
+		state.set(well_known_keys::CODE, b"code_b".to_vec());
+		state.values.remove(PENDING_CODE);
+		assert_eq!(state.changed_code(&mut watcher), vec![b"code_b".to_vec()]);
+		assert_eq!(*state.reads.borrow(), 3);
+	}
+
+	#[test]
+	fn unchanged_code_is_not_compiled() {
+		let mut watcher = CodeWatcher::new(vec![PENDING_CODE.to_vec()]);
+		let mut state = State::default();
+		state.set(well_known_keys::CODE, b"code_a".to_vec());
+		state.set(PENDING_CODE, b"code_b".to_vec().encode());
+
+		assert_eq!(state.changed_code(&mut watcher).len(), 2);
+		assert_eq!(*state.reads.borrow(), 2);
+
+		for _ in 0..3 {
+			assert!(state.changed_code(&mut watcher).is_empty());
+		}
+		// The code is not read again when its storage hash didn't change.
+		assert_eq!(*state.reads.borrow(), 2);
+	}
+}
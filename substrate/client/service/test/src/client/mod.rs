@@ -2307,3 +2307,131 @@ fn finalize_after_best_block_updates_best() {
 	assert_eq!(client.chain_info().finalized_hash, a3.hash());
 	assert_eq!(client.chain_info().best_hash, a3.hash());
 }
+
+fn new_client_with_runtime_api_cache(
+	registry: &prometheus_endpoint::Registry,
+) -> Client<
+	in_mem::Backend<Block>,
+	LocalCallExecutor<Block, in_mem::Backend<Block>, WasmExecutor>,
+	Block,
+	RuntimeApi,
+> {
+	let backend = Arc::new(sc_client_api::in_mem::Backend::new());
+	let executor = WasmExecutor::default();
+	let client_config =
+		sc_service::ClientConfig { runtime_api_cache_size: 16, ..Default::default() };
+
+	let genesis_block_builder = sc_service::GenesisBlockBuilder::new(
+		&substrate_test_runtime_client::GenesisParameters::default().genesis_storage(),
+		!client_config.no_genesis,
+		backend.clone(),
+		executor.clone(),
+	)
+	.unwrap();
+
+	new_with_backend::<_, _, Block, _, RuntimeApi>(
+		backend,
+		executor,
+		genesis_block_builder,
+		Box::new(TaskExecutor::new()),
+		Some(registry.clone()),
+		None,
+		client_config,
+	)
+	.unwrap()
+}
+
+/// Returns the number of hits and misses of the runtime api cache.
+fn runtime_api_cache_requests(registry: &prometheus_endpoint::Registry) -> (u64, u64) {
+	let requests = |result: &str| {
+		registry
+			.gather()
+			.iter()
+			.filter(|f| f.get_name() == "substrate_runtime_api_cache_requests_total")
+			.flat_map(|f| f.get_metric())
+			.filter(|m| m.get_label().iter().any(|l| l.get_value() == result))
+			.map(|m| m.get_counter().get_value() as u64)
+			.sum()
+	};
+
+	(requests("hit"), requests("miss"))
+}
+
+#[test]
+fn pure_runtime_api_calls_are_cached() {
+	let registry = prometheus_endpoint::Registry::new();
+	let client = new_client_with_runtime_api_cache(&registry);
+	let genesis_hash = client.chain_info().genesis_hash;
+
+	let balance = client
+		.runtime_api()
+		.balance_of(genesis_hash, AccountKeyring::Alice.into())
+		.unwrap();
+	assert_eq!(runtime_api_cache_requests(&registry), (0, 1));
+
+	assert_eq!(
+		client
+			.runtime_api()
+			.balance_of(genesis_hash, AccountKeyring::Alice.into())
+			.unwrap(),
+		balance,
+	);
+	assert_eq!(runtime_api_cache_requests(&registry), (1, 1));
+
+	// Different arguments are a different entry.
+	client
+		.runtime_api()
+		.balance_of(genesis_hash, AccountKeyring::Bob.into())
+		.unwrap();
+	assert_eq!(runtime_api_cache_requests(&registry), (1, 2));
+}
+
+#[test]
+fn impure_runtime_api_calls_bypass_the_cache() {
+	let registry = prometheus_endpoint::Registry::new();
+	let client = new_client_with_runtime_api_cache(&registry);
+	let genesis_hash = client.chain_info().genesis_hash;
+
+	client.runtime_api().get_block_number(genesis_hash).unwrap();
+	client.runtime_api().get_block_number(genesis_hash).unwrap();
+
+	assert_eq!(runtime_api_cache_requests(&registry), (0, 0));
+}
+
+#[test]
+fn pure_runtime_api_calls_on_top_of_changes_bypass_the_cache() {
+	let registry = prometheus_endpoint::Registry::new();
+	let client = new_client_with_runtime_api_cache(&registry);
+	let genesis_hash = client.chain_info().genesis_hash;
+
+	let runtime_api = client.runtime_api();
+	runtime_api
+		.write_key_value(genesis_hash, b"key".to_vec(), b"value".to_vec(), false)
+		.unwrap();
+	runtime_api.balance_of(genesis_hash, AccountKeyring::Alice.into()).unwrap();
+	runtime_api.balance_of(genesis_hash, AccountKeyring::Alice.into()).unwrap();
+
+	assert_eq!(runtime_api_cache_requests(&registry), (0, 0));
+}
+
+#[test]
+fn pure_runtime_api_calls_recording_a_proof_bypass_the_cache() {
+	let registry = prometheus_endpoint::Registry::new();
+	let client = new_client_with_runtime_api_cache(&registry);
+	let genesis_hash = client.chain_info().genesis_hash;
+
+	// Populate the cache.
+	client
+		.runtime_api()
+		.balance_of(genesis_hash, AccountKeyring::Alice.into())
+		.unwrap();
+	assert_eq!(runtime_api_cache_requests(&registry), (0, 1));
+
+	let mut runtime_api = client.runtime_api();
+	runtime_api.record_proof();
+	runtime_api.balance_of(genesis_hash, AccountKeyring::Alice.into()).unwrap();
+
+	assert_eq!(runtime_api_cache_requests(&registry), (0, 1));
+	// The call was executed, so the proof contains the accessed state.
+	assert!(!runtime_api.extract_proof().unwrap().is_empty());
+}
@@ -22,7 +22,7 @@ use std::{
 		atomic::{AtomicU64, Ordering},
 		Arc,
 	},
-	time::Instant,
+	time::{Duration, Instant},
 };
 
 use codec::Encode;
@@ -35,18 +35,23 @@ use tracing::{
 
 use crate::{SpanDatum, TraceEvent, Values};
 use sc_client_api::BlockBackend;
-use sp_api::{Core, Metadata, ProvideRuntimeApi};
+use sp_api::{ApiExt, Core, Metadata, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_core::hexdisplay::HexDisplay;
-use sp_rpc::tracing::{BlockTrace, Span, TraceBlockResponse};
+use sp_rpc::tracing::{
+	BlockTrace, ChromeTrace, ChromeTraceArg, ChromeTraceEvent, TraceBlockResponse,
+};
 use sp_runtime::{
 	generic::BlockId,
 	traits::{Block as BlockT, Header},
 };
 use sp_tracing::{WASM_NAME_KEY, WASM_TARGET_KEY, WASM_TRACE_IDENTIFIER};
+use sp_trie::ProofSizeProvider;
 
 // Default to only pallet, frame support and state related traces
 const DEFAULT_TARGETS: &str = "pallet,frame,state";
+// For timelines also include the host functions
+const DEFAULT_CHROME_TARGETS: &str = "pallet,frame,state,sp_io";
 const TRACE_TARGET: &str = "block_trace";
 // The name of a field required for all events.
 const REQUIRED_EVENT_FIELD: &str = "method";
@@ -67,15 +72,30 @@ pub enum Error {
 	Dispatch(String),
 }
 
+/// Point in the execution of the traced block.
+#[derive(Clone, Copy, Debug)]
+struct Mark {
+	/// Time elapsed since the start of the trace.
+	time: Duration,
+	/// Estimated size of the storage proof recorded so far, if the proof is recorded.
+	proof_size: Option<usize>,
+}
+
 struct BlockSubscriber {
 	targets: Vec<(String, Level)>,
 	next_id: AtomicU64,
 	spans: Mutex<HashMap<Id, SpanDatum>>,
-	events: Mutex<Vec<TraceEvent>>,
+	events: Mutex<Vec<(Mark, TraceEvent)>>,
+	// When the currently entered spans were entered
+	entered: Mutex<HashMap<Id, Vec<Mark>>>,
+	// When spans were entered and exited, one entry per enter/exit pair
+	intervals: Mutex<Vec<(Id, Mark, Mark)>>,
+	started: Instant,
+	proof_size: Option<Box<dyn ProofSizeProvider + Send + Sync>>,
 }
 
 impl BlockSubscriber {
-	fn new(targets: &str) -> Self {
+	fn new(targets: &str, proof_size: Option<Box<dyn ProofSizeProvider + Send + Sync>>) -> Self {
 		let next_id = AtomicU64::new(1);
 		let mut targets: Vec<_> = targets.split(',').map(crate::parse_target).collect();
 		// Ensure that WASM traces are always enabled
@@ -86,7 +106,85 @@ impl BlockSubscriber {
 			next_id,
 			spans: Mutex::new(HashMap::new()),
 			events: Mutex::new(Vec::new()),
+			entered: Mutex::new(HashMap::new()),
+			intervals: Mutex::new(Vec::new()),
+			started: Instant::now(),
+			proof_size,
+		}
+	}
+
+	fn mark(&self) -> Mark {
+		Mark {
+			time: self.started.elapsed(),
+			proof_size: self.proof_size.as_ref().map(|p| p.estimate_encoded_size()),
+		}
+	}
+
+	/// Drain the recorded spans and events into Chrome trace events ordered by timestamp.
+	///
+	/// Events are filtered by `storage_keys` and `methods` only if those are given.
+	fn chrome_events(
+		&self,
+		targets: &str,
+		storage_keys: Option<&str>,
+		methods: Option<&str>,
+	) -> Vec<ChromeTraceEvent> {
+		let spans: HashMap<_, _> = self
+			.spans
+			.lock()
+			.drain()
+			.filter_map(|(id, s)| Some((id, patch_and_filter(s, targets)?)))
+			.collect();
+		let mut trace_events = Vec::new();
+		for (id, entered, exited) in self.intervals.lock().drain(..) {
+			let Some(span) = spans.get(&id) else { continue };
+
+			let mut args = chrome_args(span.values.clone());
+			if let (Some(start), Some(end)) = (entered.proof_size, exited.proof_size) {
+				args.insert(
+					"proof_size".into(),
+					ChromeTraceArg::U64(end.saturating_sub(start) as u64),
+				);
+			}
+			trace_events.push(ChromeTraceEvent {
+				name: span.name.clone(),
+				cat: span.target.clone(),
+				ph: "X".into(),
+				ts: micros(entered.time),
+				dur: Some(micros(exited.time.saturating_sub(entered.time))),
+				args,
+				..chrome_event()
+			});
+			if let Some(proof_size) = exited.proof_size {
+				trace_events.push(proof_size_counter(exited.time, proof_size));
+			}
 		}
+		for (mark, event) in self.events.lock().drain(..) {
+			let filtered_out = |filter: Option<&str>, kind| {
+				filter.is_some_and(|values| !event_values_filter(&event, kind, values))
+			};
+			if filtered_out(storage_keys, "key") || filtered_out(methods, "method") {
+				continue
+			}
+
+			let name = event
+				.values
+				.string_values
+				.get(REQUIRED_EVENT_FIELD)
+				.cloned()
+				.unwrap_or(event.name);
+			trace_events.push(ChromeTraceEvent {
+				name,
+				cat: event.target,
+				ph: "i".into(),
+				ts: micros(mark.time),
+				s: Some("t".into()),
+				args: chrome_args(event.values),
+				..chrome_event()
+			});
+		}
+		trace_events.sort_by(|a, b| a.ts.total_cmp(&b.ts));
+		trace_events
 	}
 }
 
@@ -147,12 +245,21 @@ impl Subscriber for BlockSubscriber {
 			values,
 			parent_id,
 		};
-		self.events.lock().push(trace_event);
+		let mark = self.mark();
+		self.events.lock().push((mark, trace_event));
 	}
 
-	fn enter(&self, _id: &Id) {}
+	fn enter(&self, span: &Id) {
+		let mark = self.mark();
+		self.entered.lock().entry(span.clone()).or_default().push(mark);
+	}
 
-	fn exit(&self, _span: &Id) {}
+	fn exit(&self, span: &Id) {
+		let exited = self.mark();
+		if let Some(entered) = self.entered.lock().get_mut(span).and_then(|marks| marks.pop()) {
+			self.intervals.lock().push((span.clone(), entered, exited));
+		}
+	}
 }
 
 /// Holds a reference to the client in order to execute the given block.
@@ -194,6 +301,88 @@ where
 	/// and filter out events which do not have keys starting with one of the
 	/// prefixes in `Self::storage_keys`.
 	pub fn trace_block(&self) -> TraceBlockResult<TraceBlockResponse> {
+		let targets = if let Some(t) = &self.targets { t } else { DEFAULT_TARGETS };
+		let (parent_hash, dispatch) = self.execute_block(targets, false)?;
+		let block_subscriber = downcast_subscriber(&dispatch)?;
+
+		let spans: Vec<_> = block_subscriber
+			.spans
+			.lock()
+			.drain()
+			// Patch wasm identifiers
+			.filter_map(|(_, s)| patch_and_filter(s, targets))
+			.map(Into::into)
+			.collect();
+		let events: Vec<_> = block_subscriber
+			.events
+			.lock()
+			.drain(..)
+			.map(|(_, e)| e)
+			.filter(|e| {
+				self.storage_keys
+					.as_ref()
+					.map(|keys| event_values_filter(e, "key", keys))
+					.unwrap_or(false)
+			})
+			.filter(|e| {
+				self.methods
+					.as_ref()
+					.map(|methods| event_values_filter(e, "method", methods))
+					.unwrap_or(false)
+			})
+			.map(|s| s.into())
+			.collect();
+		tracing::debug!(target: "state_tracing", "Captured {} spans and {} events", spans.len(), events.len());
+
+		Ok(TraceBlockResponse::BlockTrace(BlockTrace {
+			block_hash: block_id_as_string(BlockId::<Block>::Hash(self.block)),
+			parent_hash: block_id_as_string(BlockId::<Block>::Hash(parent_hash)),
+			tracing_targets: targets.to_string(),
+			storage_keys: self.storage_keys.clone().unwrap_or_default(),
+			methods: self.methods.clone().unwrap_or_default(),
+			spans,
+			events,
+		}))
+	}
+
+	/// Execute block and record a timeline of all spans and events belonging to
+	/// `Self::targets` in the Chrome trace event format.
+	///
+	/// Unlike [`Self::trace_block`], the storage proof is recorded while executing the block.
+	/// Every time a span is entered and exited results in one complete event carrying the time
+	/// spent in it and the size it added to the proof. Events are filtered by
+	/// `Self::storage_keys` and `Self::methods` only if those are given.
+	pub fn trace_block_chrome(&self) -> TraceBlockResult<ChromeTrace> {
+		let targets = if let Some(t) = &self.targets { t } else { DEFAULT_CHROME_TARGETS };
+		let (parent_hash, dispatch) = self.execute_block(targets, true)?;
+		let block_subscriber = downcast_subscriber(&dispatch)?;
+
+		let trace_events = block_subscriber.chrome_events(
+			targets,
+			self.storage_keys.as_deref(),
+			self.methods.as_deref(),
+		);
+		tracing::debug!(target: "state_tracing", "Captured {} trace events", trace_events.len());
+
+		let other_data = [
+			("block_hash", block_id_as_string(BlockId::<Block>::Hash(self.block))),
+			("parent_hash", block_id_as_string(BlockId::<Block>::Hash(parent_hash))),
+			("tracing_targets", targets.to_string()),
+		];
+		Ok(ChromeTrace {
+			trace_events,
+			display_time_unit: "ns".into(),
+			other_data: other_data.into_iter().map(|(k, v)| (k.to_owned(), v)).collect(),
+		})
+	}
+
+	/// Execute block with a [`BlockSubscriber`] recording the given `targets`, optionally
+	/// recording the storage proof. Returns the parent hash and the dispatch of the subscriber.
+	fn execute_block(
+		&self,
+		targets: &str,
+		record_proof: bool,
+	) -> TraceBlockResult<(Block::Hash, Dispatch)> {
 		tracing::debug!(target: "state_tracing", "Tracing block: {}", self.block);
 		// Prepare the block
 		let mut header = self
@@ -213,8 +402,14 @@ where
 		header.digest_mut().logs.retain(|d| d.as_seal().is_none());
 		let block = Block::new(header, extrinsics);
 
-		let targets = if let Some(t) = &self.targets { t } else { DEFAULT_TARGETS };
-		let block_subscriber = BlockSubscriber::new(targets);
+		let mut runtime_api = self.client.runtime_api();
+		if record_proof {
+			runtime_api.record_proof();
+		}
+		let proof_size = runtime_api
+			.proof_recorder()
+			.map(|r| Box::new(r) as Box<dyn ProofSizeProvider + Send + Sync>);
+		let block_subscriber = BlockSubscriber::new(targets, proof_size);
 		let dispatch = Dispatch::new(block_subscriber);
 
 		{
@@ -227,7 +422,7 @@ where
 			if let Err(e) = dispatcher::with_default(&dispatch, || {
 				let span = tracing::info_span!(target: TRACE_TARGET, "trace_block");
 				let _enter = span.enter();
-				self.client.runtime_api().execute_block(parent_hash, block)
+				runtime_api.execute_block(parent_hash, block)
 			}) {
 				return Err(Error::Dispatch(format!(
 					"Failed to collect traces and execute block: {}",
@@ -236,47 +431,47 @@ where
 			}
 		}
 
-		let block_subscriber = dispatch.downcast_ref::<BlockSubscriber>().ok_or_else(|| {
-			Error::Dispatch(
-				"Cannot downcast Dispatch to BlockSubscriber after tracing block".to_string(),
-			)
-		})?;
-		let spans: Vec<_> = block_subscriber
-			.spans
-			.lock()
-			.drain()
-			// Patch wasm identifiers
-			.filter_map(|(_, s)| patch_and_filter(s, targets))
-			.collect();
-		let events: Vec<_> = block_subscriber
-			.events
-			.lock()
-			.drain(..)
-			.filter(|e| {
-				self.storage_keys
-					.as_ref()
-					.map(|keys| event_values_filter(e, "key", keys))
-					.unwrap_or(false)
-			})
-			.filter(|e| {
-				self.methods
-					.as_ref()
-					.map(|methods| event_values_filter(e, "method", methods))
-					.unwrap_or(false)
-			})
-			.map(|s| s.into())
-			.collect();
-		tracing::debug!(target: "state_tracing", "Captured {} spans and {} events", spans.len(), events.len());
+		Ok((parent_hash, dispatch))
+	}
+}
 
-		Ok(TraceBlockResponse::BlockTrace(BlockTrace {
-			block_hash: block_id_as_string(BlockId::<Block>::Hash(self.block)),
-			parent_hash: block_id_as_string(BlockId::<Block>::Hash(parent_hash)),
-			tracing_targets: targets.to_string(),
-			storage_keys: self.storage_keys.clone().unwrap_or_default(),
-			methods: self.methods.clone().unwrap_or_default(),
-			spans,
-			events,
-		}))
+fn downcast_subscriber(dispatch: &Dispatch) -> TraceBlockResult<&BlockSubscriber> {
+	dispatch.downcast_ref::<BlockSubscriber>().ok_or_else(|| {
+		Error::Dispatch(
+			"Cannot downcast Dispatch to BlockSubscriber after tracing block".to_string(),
+		)
+	})
+}
+
+fn micros(duration: Duration) -> f64 {
+	duration.as_nanos() as f64 / 1_000.0
+}
+
+fn chrome_event() -> ChromeTraceEvent {
+	ChromeTraceEvent { pid: 1, tid: 1, ..Default::default() }
+}
+
+fn chrome_args(values: Values) -> rustc_hash::FxHashMap<String, ChromeTraceArg> {
+	let Values { bool_values, i64_values, u64_values, string_values } = values;
+	bool_values
+		.into_iter()
+		.map(|(k, v)| (k, ChromeTraceArg::Bool(v)))
+		.chain(i64_values.into_iter().map(|(k, v)| (k, ChromeTraceArg::I64(v))))
+		.chain(u64_values.into_iter().map(|(k, v)| (k, ChromeTraceArg::U64(v))))
+		.chain(string_values.into_iter().map(|(k, v)| (k, ChromeTraceArg::String(v))))
+		.collect()
+}
+
+fn proof_size_counter(time: Duration, proof_size: usize) -> ChromeTraceEvent {
+	ChromeTraceEvent {
+		name: "proof_size".into(),
+		cat: TRACE_TARGET.into(),
+		ph: "C".into(),
+		ts: micros(time),
+		args: [("bytes".to_owned(), ChromeTraceArg::U64(proof_size as u64))]
+			.into_iter()
+			.collect(),
+		..chrome_event()
 	}
 }
 
@@ -297,7 +492,7 @@ fn event_values_filter(event: &TraceEvent, filter_kind: &str, values: &str) -> b
 // (normally they would be in the static metadata assembled at compile time). Here, if a special
 // WASM `name` or `target` key is found in the `values` we remove it and put the key value pair in
 // the span's metadata, making it consistent with spans that come from native code.
-fn patch_and_filter(mut span: SpanDatum, targets: &str) -> Option<Span> {
+fn patch_and_filter(mut span: SpanDatum, targets: &str) -> Option<SpanDatum> {
 	if span.name == WASM_TRACE_IDENTIFIER {
 		span.values.bool_values.insert("wasm".to_owned(), true);
 		if let Some(n) = span.values.string_values.remove(WASM_NAME_KEY) {
@@ -310,7 +505,7 @@ fn patch_and_filter(mut span: SpanDatum, targets: &str) -> Option<Span> {
 			return None
 		}
 	}
-	Some(span.into())
+	Some(span)
 }
 
 /// Check if a `target` matches any `targets` by prefix
@@ -329,3 +524,66 @@ fn block_id_as_string<T: BlockT>(block_id: BlockId<T>) -> String {
 		BlockId::Number(n) => HexDisplay::from(&n.encode()).to_string(),
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn record(targets: &str, f: impl FnOnce()) -> Dispatch {
+		let dispatch = Dispatch::new(BlockSubscriber::new(targets, None));
+		dispatcher::with_default(&dispatch, f);
+		dispatch
+	}
+
+	#[test]
+	fn chrome_events_are_emitted_per_enter_and_exit() {
+		let dispatch = record("pallet", || {
+			let span = tracing::info_span!(target: "pallet", "on_initialize", weight = 10u64);
+			span.in_scope(|| {
+				tracing::trace!(target: "pallet", method = "Get", key = "00");
+				std::thread::sleep(std::time::Duration::from_millis(1));
+			});
+			std::thread::sleep(std::time::Duration::from_millis(5));
+			span.in_scope(|| std::thread::sleep(std::time::Duration::from_millis(1)));
+
+			// not recorded
+			tracing::info_span!(target: "frame", "ignored").in_scope(|| ());
+			tracing::info_span!(target: "pallet", "never_entered");
+		});
+		let events = downcast_subscriber(&dispatch).unwrap().chrome_events("pallet", None, None);
+
+		let phases = events.iter().map(|e| (e.ph.as_str(), e.name.as_str())).collect::<Vec<_>>();
+		assert_eq!(phases, vec![("X", "on_initialize"), ("i", "Get"), ("X", "on_initialize")]);
+
+		let (first, event, second) = (&events[0], &events[1], &events[2]);
+		assert_eq!(first.cat, "pallet");
+		assert_eq!(first.args.get("weight"), Some(&ChromeTraceArg::U64(10)));
+		assert!(first.dur.unwrap() >= 1_000.0);
+		assert!(second.dur.unwrap() >= 1_000.0);
+		// the pause between the two enters is not part of the span
+		assert!(second.ts >= first.ts + first.dur.unwrap() + 5_000.0);
+
+		assert!(event.ts >= first.ts && event.ts <= first.ts + first.dur.unwrap());
+		assert_eq!(event.dur, None);
+		assert_eq!(event.s.as_deref(), Some("t"));
+		assert_eq!(event.args.get("key"), Some(&ChromeTraceArg::String("00".into())));
+	}
+
+	#[test]
+	fn chrome_events_are_filtered() {
+		let dispatch = record("pallet", || {
+			tracing::trace!(target: "pallet", method = "Get", key = "0011");
+			tracing::trace!(target: "pallet", method = "Put", key = "0011");
+			tracing::trace!(target: "pallet", method = "Get", key = "2233");
+		});
+		let events = downcast_subscriber(&dispatch).unwrap().chrome_events(
+			"pallet",
+			Some("00"),
+			Some("Get"),
+		);
+
+		assert_eq!(events.len(), 1);
+		assert_eq!(events[0].name, "Get");
+		assert_eq!(events[0].args.get("key"), Some(&ChromeTraceArg::String("0011".into())));
+	}
+}
@@ -21,11 +21,13 @@
 
 use crate::{mock_helpers::*, Event, Historic};
 
+use core::marker::PhantomData;
 use frame_support::{
 	derive_impl,
 	migrations::*,
 	traits::{OnFinalize, OnInitialize},
-	weights::Weight,
+	weights::{Weight, WeightMeter},
+	Twox64Concat,
 };
 use frame_system::EventRecord;
 use sp_core::H256;
@@ -56,7 +58,7 @@ impl crate::Config for Test {
 	#[cfg(feature = "runtime-benchmarks")]
 	type Migrations = crate::mock_helpers::MockedMigrations;
 	#[cfg(not(feature = "runtime-benchmarks"))]
-	type Migrations = MockedMigrations;
+	type Migrations = (MockedMigrations, Toggled<KitMigrations>);
 	type MigrationStatusHandler = MockedMigrationStatusHandler;
 	type FailedMigrationHandler = MockedFailedMigrationHandler;
 }
@@ -97,6 +99,108 @@ impl FailedMigrationHandler for MockedFailedMigrationHandler {
 	}
 }
 
+frame_support::parameter_types! {
+	/// Whether [`KitMigrations`] are part of the migrations to run.
+	pub static KitMigrationsEnabled: bool = false;
+	/// The weight of migrating a single item in [`KitMigrations`].
+	pub static KitWeightPerItem: Weight = Weight::from_parts(1, 1);
+	pub KitClearId: MockedIdentifier = b"KitClear".to_vec().try_into().unwrap();
+	pub KitPrefix: Vec<u8> = b"KitPrefix".to_vec();
+}
+
+/// The map before the migration.
+pub mod v0 {
+	use super::*;
+
+	#[frame_support::storage_alias(verbatim)]
+	pub type KitMap = StorageMap<Kit, Twox64Concat, u32, u32>;
+}
+
+#[frame_support::storage_alias(verbatim)]
+pub type KitMap = StorageMap<Kit, Twox64Concat, u32, u64>;
+
+/// Doubles all values of [`KitMap`] and widens them to `u64`. Zero values are removed.
+pub struct KitTranslation;
+impl MapTranslation for KitTranslation {
+	type Identifier = MockedIdentifier;
+	type Key = u32;
+	type OldValue = u32;
+	type Value = u64;
+	type Map = KitMap;
+
+	fn id() -> Self::Identifier {
+		b"KitTranslation".to_vec().try_into().unwrap()
+	}
+
+	fn weight_per_item() -> Weight {
+		KitWeightPerItem::get()
+	}
+
+	fn translate(_key: &u32, old: u32) -> Option<u64> {
+		(old != 0).then(|| old as u64 * 2)
+	}
+}
+
+/// Migrations built with the building blocks of `frame_support::migrations`.
+pub type KitMigrations = (
+	TranslateMapInSteps<KitTranslation>,
+	ClearPrefixInSteps<KitClearId, KitPrefix, KitWeightPerItem>,
+);
+
+/// Only exposes the migrations `M` if [`KitMigrationsEnabled`] is set.
+pub struct Toggled<M>(PhantomData<M>);
+impl<M: SteppedMigrations> SteppedMigrations for Toggled<M> {
+	fn len() -> u32 {
+		if KitMigrationsEnabled::get() {
+			M::len()
+		} else {
+			0
+		}
+	}
+
+	fn nth_id(n: u32) -> Option<Vec<u8>> {
+		M::nth_id(n)
+	}
+
+	fn nth_max_steps(n: u32) -> Option<Option<u32>> {
+		M::nth_max_steps(n)
+	}
+
+	fn nth_step(
+		n: u32,
+		cursor: Option<Vec<u8>>,
+		meter: &mut WeightMeter,
+	) -> Option<Result<Option<Vec<u8>>, SteppedMigrationError>> {
+		M::nth_step(n, cursor, meter)
+	}
+
+	fn nth_transactional_step(
+		n: u32,
+		cursor: Option<Vec<u8>>,
+		meter: &mut WeightMeter,
+	) -> Option<Result<Option<Vec<u8>>, SteppedMigrationError>> {
+		M::nth_transactional_step(n, cursor, meter)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn nth_pre_upgrade(n: u32) -> Option<Result<Vec<u8>, sp_runtime::TryRuntimeError>> {
+		M::nth_pre_upgrade(n)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn nth_post_upgrade(n: u32, state: Vec<u8>) -> Option<Result<(), sp_runtime::TryRuntimeError>> {
+		M::nth_post_upgrade(n, state)
+	}
+
+	fn cursor_max_encoded_len() -> usize {
+		M::cursor_max_encoded_len()
+	}
+
+	fn identifier_max_encoded_len() -> usize {
+		M::identifier_max_encoded_len()
+	}
+}
+
 /// Returns the number of `(started, completed, failed)` upgrades and resets their numbers.
 pub fn upgrades_started_completed_failed() -> (u32, u32, u32) {
 	(UpgradesStarted::take(), UpgradesCompleted::take(), UpgradesFailed::take().len() as u32)
@@ -17,7 +17,16 @@
 
 #![cfg(test)]
 
-use frame_support::{pallet_prelude::Weight, traits::OnRuntimeUpgrade};
+use codec::Encode;
+use frame_support::{
+	migrations::{
+		ClearPrefixInSteps, MapTranslation, MultiBlockMigrationHarness, SteppedMigration,
+		SteppedMigrationError, TranslateMapInSteps,
+	},
+	pallet_prelude::Weight,
+	traits::OnRuntimeUpgrade,
+	weights::WeightMeter,
+};
 
 use crate::{
 	mock::{Test as T, *},
@@ -454,3 +463,110 @@ fn try_runtime_no_migrations() {
 		assert_eq!(System::events().len(), 0);
 	});
 }
+
+/// Fill the storage touched by [`KitMigrations`].
+fn setup_kit_storage() {
+	for i in 0..10 {
+		v0::KitMap::insert(i, i);
+	}
+	for i in 0u32..5 {
+		frame_support::storage::unhashed::put(&[&KitPrefix::get()[..], &i.encode()].concat(), &i);
+	}
+}
+
+fn assert_kit_storage_migrated() {
+	assert_eq!(KitMap::get(0), None);
+	for i in 1..10 {
+		assert_eq!(KitMap::get(i), Some(i as u64 * 2));
+	}
+	assert!(!frame_support::storage::unhashed::contains_prefixed_key(&KitPrefix::get()));
+}
+
+#[test]
+fn kit_migrations_work() {
+	use Event::*;
+	test_closure(|| {
+		MockedMigrations::set(vec![]);
+		KitMigrationsEnabled::set(true);
+		// Only three items fit into a block.
+		KitWeightPerItem::set(MaxServiceWeight::get().div(4));
+		setup_kit_storage();
+
+		System::set_block_number(1);
+		Migrations::on_runtime_upgrade();
+		run_to_block(2);
+		// The migrations are not done after a single block.
+		assert!(historic().is_empty());
+
+		run_to_block(20);
+
+		assert_kit_storage_migrated();
+		// The historic identifiers are the encoded identifiers of the migrations.
+		let encoded =
+			|id: MockedIdentifier| -> MockedIdentifier { id.encode().try_into().unwrap() };
+		assert_eq!(historic(), vec![encoded(KitClearId::get()), encoded(KitTranslation::id())]);
+		// The translation takes four steps and the clearing starts in the block the translation
+		// completed in.
+		let events = System::events().into_iter().map(|r| r.event).collect::<Vec<_>>();
+		for event in [
+			MigrationCompleted { index: 0, took: 4 },
+			MigrationCompleted { index: 1, took: 2 },
+			UpgradeCompleted,
+		] {
+			assert!(events.contains(&RuntimeEvent::Migrations(event)));
+		}
+	});
+}
+
+#[test]
+fn migration_harness_works() {
+	test_closure(|| {
+		setup_kit_storage();
+		let per_item = KitWeightPerItem::get();
+
+		let report =
+			MultiBlockMigrationHarness::<TranslateMapInSteps<KitTranslation>>::run_to_completion(
+				per_item.mul(3),
+			)
+			.unwrap();
+		// Three items per step and the last step also detects the end of the map.
+		assert_eq!(report.steps, 4);
+		assert_eq!(report.weight_consumed, per_item.mul(11));
+		assert_ne!(report.pre_checksum, report.post_checksum);
+
+		let report = MultiBlockMigrationHarness::<
+			ClearPrefixInSteps<KitClearId, KitPrefix, KitWeightPerItem>,
+		>::run_to_completion(per_item.mul(3))
+		.unwrap();
+		assert_eq!(report.steps, 2);
+
+		assert_kit_storage_migrated();
+	});
+}
+
+#[test]
+fn migration_harness_detects_stuck_migration() {
+	struct Stuck;
+	impl SteppedMigration for Stuck {
+		type Cursor = u32;
+		type Identifier = u32;
+
+		fn id() -> u32 {
+			0
+		}
+
+		fn step(
+			_cursor: Option<u32>,
+			_meter: &mut WeightMeter,
+		) -> Result<Option<u32>, SteppedMigrationError> {
+			Ok(Some(0))
+		}
+	}
+
+	test_closure(|| {
+		assert_eq!(
+			MultiBlockMigrationHarness::<Stuck>::run_to_completion(Weight::MAX),
+			Err("Migration step made no progress".into())
+		);
+	});
+}
@@ -45,11 +45,14 @@ pub fn expand_runtime_metadata(
 			let name = &decl.name;
 			let index = &decl.index;
 			let storage = expand_pallet_metadata_storage(&filtered_names, runtime, decl);
+			let (storage_layout_fingerprint, storage_version) =
+				expand_pallet_metadata_storage_layout(&filtered_names, runtime, scrate, decl);
 			let calls = expand_pallet_metadata_calls(&filtered_names, runtime, decl);
 			let event = expand_pallet_metadata_events(&filtered_names, runtime, decl);
 			let constants = expand_pallet_metadata_constants(runtime, decl);
 			let errors = expand_pallet_metadata_errors(runtime, decl);
 			let associated_types = expand_pallet_metadata_associated_types(runtime, decl);
+			let view_functions = expand_pallet_metadata_view_functions(runtime, decl);
 			let docs = expand_pallet_metadata_docs(runtime, decl);
 			let attr = decl.cfg_pattern.iter().fold(TokenStream::new(), |acc, pattern| {
 				let attr = TokenStream::from_str(&format!("#[cfg({})]", pattern.original()))
@@ -66,12 +69,15 @@ pub fn expand_runtime_metadata(
 					name: stringify!(#name),
 					index: #index,
 					storage: #storage,
+					storage_layout_fingerprint: #storage_layout_fingerprint,
+					storage_version: #storage_version,
 					calls: #calls,
 					event: #event,
 					constants: #constants,
 					error: #errors,
 					docs: #docs,
 					associated_types: #associated_types,
+					view_functions: #view_functions,
 					deprecation_info: #deprecation_info,
 				}
 			}
@@ -189,6 +195,32 @@ fn expand_pallet_metadata_storage(
 	}
 }
 
+/// Returns the expressions of the storage layout fingerprint and of the storage version.
+fn expand_pallet_metadata_storage_layout(
+	filtered_names: &[&'static str],
+	runtime: &Ident,
+	scrate: &TokenStream,
+	decl: &Pallet,
+) -> (TokenStream, TokenStream) {
+	if filtered_names.contains(&"Storage") {
+		let instance = decl.instance.as_ref().into_iter();
+		let path = &decl.path;
+		let pallet = quote! {
+			<
+				#path::Pallet::<#runtime #(, #path::#instance)*>
+				as #scrate::traits::StorageLayoutFingerprint
+			>
+		};
+
+		(
+			quote!(Some(#pallet::storage_layout_fingerprint())),
+			quote!(Some(#pallet::storage_layout_version().into())),
+		)
+	} else {
+		(quote!(None), quote!(None))
+	}
+}
+
 fn expand_pallet_metadata_calls(
 	filtered_names: &[&'static str],
 	runtime: &Ident,
@@ -278,3 +310,12 @@ fn expand_pallet_metadata_associated_types(runtime: &Ident, decl: &Pallet) -> To
 		#path::Pallet::<#runtime #(, #path::#instance)*>::pallet_associated_types_metadata()
 	}
 }
+
+fn expand_pallet_metadata_view_functions(runtime: &Ident, decl: &Pallet) -> TokenStream {
+	let path = &decl.path;
+	let instance = decl.instance.as_ref().into_iter();
+
+	quote! {
+		#path::Pallet::<#runtime #(, #path::#instance)*>::pallet_view_functions_metadata()
+	}
+}
@@ -80,6 +80,12 @@ fn counter_prefix(prefix: &str) -> String {
 	format!("CounterFor{}", prefix)
 }
 
+/// Generate the paged_meta_prefix related to the storage.
+/// paged_meta_prefix is used by paged storage value and map.
+fn paged_meta_prefix(prefix: &str) -> String {
+	format!("PagedMetaFor{}", prefix)
+}
+
 /// Construct a runtime, with the given name and the given pallets.
 ///
 /// NOTE: A new version of this macro is available at `frame_support::runtime`. This macro will
@@ -954,6 +960,43 @@ pub fn extra_constants(_: TokenStream, _: TokenStream) -> TokenStream {
 	pallet_macro_stub()
 }
 
+///
+/// ---
+///
+/// Documentation for this macro can be found at `frame_support::pallet_macros::authorize`.
+#[proc_macro_attribute]
+pub fn authorize(_: TokenStream, _: TokenStream) -> TokenStream {
+	pallet_macro_stub()
+}
+
+///
+/// ---
+///
+/// Documentation for this macro can be found at
+/// `frame_support::pallet_macros::weight_of_authorize`.
+#[proc_macro_attribute]
+pub fn weight_of_authorize(_: TokenStream, _: TokenStream) -> TokenStream {
+	pallet_macro_stub()
+}
+
+///
+/// ---
+///
+/// Documentation for this macro can be found at `frame_support::pallet_macros::weight_refund`.
+#[proc_macro_attribute]
+pub fn weight_refund(_: TokenStream, _: TokenStream) -> TokenStream {
+	pallet_macro_stub()
+}
+
+///
+/// ---
+///
+/// Documentation for this macro can be found at `frame_support::pallet_macros::view_functions`.
+#[proc_macro_attribute]
+pub fn view_functions(_: TokenStream, _: TokenStream) -> TokenStream {
+	pallet_macro_stub()
+}
+
 ///
 /// ---
 ///
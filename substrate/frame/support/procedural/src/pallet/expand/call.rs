@@ -18,7 +18,10 @@
 use crate::{
 	pallet::{
 		expand::warnings::{weight_constant_warning, weight_witness_warning},
-		parse::{call::CallWeightDef, helper::CallReturnType},
+		parse::{
+			call::{CallWeightDef, WeightRefundDef},
+			helper::CallReturnType,
+		},
 		Def,
 	},
 	COUNTER,
@@ -253,6 +256,64 @@ pub fn expand_call(def: &mut Def) -> proc_macro2::TokenStream {
 		})
 		.collect::<Vec<_>>();
 
+	let dispatch_call = methods
+		.iter()
+		.zip(args_name.iter())
+		.map(|(method, args_name)| {
+			let fn_name = &method.name;
+			let call = quote::quote!(
+				<#pallet_ident<#type_use_gen>>::#fn_name(origin, #( #args_name, )* )
+					.map(Into::into).map_err(Into::into)
+			);
+			if method.weight_refund.is_some() {
+				quote::quote!(
+					#frame_support::weight_refund::dispatch_with_auto_refund::<
+						<T as #frame_system::Config>::StorageProofSize,
+					>(
+						__pre_dispatch_weight,
+						__declared_storage_ops,
+						<<T as #frame_system::Config>::DbWeight as #frame_support::traits::Get<_>>::get(),
+						|| #call,
+					)
+				)
+			} else {
+				call
+			}
+		})
+		.collect::<Vec<_>>();
+
+	// The pre-dispatch weight and the declared storage operations are only computed for calls
+	// with an automatic weight refund, before `self` is consumed by the dispatch.
+	let pre_dispatch_weight = if methods.iter().any(|method| method.weight_refund.is_some()) {
+		let auto_calls = methods
+			.iter()
+			.zip(cfg_attrs.iter())
+			.zip(args_name_pattern_ref.iter())
+			.filter_map(|((method, cfg_attrs), args_name_pattern_ref)| {
+				let fn_name = &method.name;
+				let WeightRefundDef { reads, writes } = method.weight_refund.as_ref()?;
+				Some(quote::quote!(
+					#cfg_attrs
+					#[allow(unused_variables)]
+					Self::#fn_name { #( #args_name_pattern_ref, )* } => (
+						<Self as #frame_support::dispatch::GetDispatchInfo>::get_dispatch_info(
+							&self,
+						)
+						.call_weight,
+						#frame_support::weight_refund::StorageOps { reads: #reads, writes: #writes },
+					),
+				))
+			});
+		quote::quote!(
+			let (__pre_dispatch_weight, __declared_storage_ops) = match self {
+				#( #auto_calls )*
+				_ => Default::default(),
+			};
+		)
+	} else {
+		quote::quote!()
+	};
+
 	let feeless_checks = methods.iter().map(|method| &method.feeless_check).collect::<Vec<_>>();
 	let feeless_check =
 		feeless_checks.iter().zip(args_name.iter()).map(|(feeless_check, arg_name)| {
@@ -263,6 +324,42 @@ pub fn expand_call(def: &mut Def) -> proc_macro2::TokenStream {
 			}
 		});
 
+	let authorize_fn = methods.iter().zip(args_name.iter()).map(|(method, arg_name)| {
+		if let Some(authorize) = &method.authorize {
+			let expr = &authorize.expr;
+			quote::quote_spanned!(span => {
+				let authorize_fn = #expr;
+				let res = authorize_fn(source, #( #arg_name, )*);
+				Some(res)
+			})
+		} else {
+			quote::quote_spanned!(span => None)
+		}
+	});
+
+	let weight_of_authorize = methods.iter().map(|method| match &method.authorize {
+		Some(authorize) => match &authorize.weight {
+			CallWeightDef::Immediate(e) => e.into_token_stream(),
+			CallWeightDef::DevModeDefault =>
+				quote::quote!(#frame_support::pallet_prelude::Weight::zero()),
+			CallWeightDef::Inherited => {
+				let pallet_weight = def
+					.call
+					.as_ref()
+					.expect("we have methods; we have calls; qed")
+					.inherited_call_weight
+					.as_ref()
+					.expect("the parser prevents this");
+
+				// Expand `<<T as Config>::WeightInfo>::authorize_call_name()`.
+				let t = &pallet_weight.typename;
+				let n = quote::format_ident!("authorize_{}", method.name);
+				quote!({ < #t > :: #n () })
+			},
+		},
+		None => quote::quote!(#frame_support::pallet_prelude::Weight::zero()),
+	});
+
 	let deprecation = match crate::deprecation::get_deprecation_enum(
 		&quote::quote! {#frame_support},
 		def.call.as_ref().map(|call| call.attrs.as_ref()).unwrap_or(&[]),
@@ -403,6 +500,31 @@ pub fn expand_call(def: &mut Def) -> proc_macro2::TokenStream {
 			}
 		}
 
+		impl<#type_impl_gen> #frame_support::traits::Authorize for #call_ident<#type_use_gen>
+			#where_clause
+		{
+			#[allow(unused_variables)]
+			fn authorize(
+				&self,
+				source: #frame_support::pallet_prelude::TransactionSource,
+			) -> Option<#frame_support::pallet_prelude::TransactionValidityWithRefund> {
+				match *self {
+					#(
+						#cfg_attrs
+						Self::#fn_name { #( #args_name_pattern_ref, )* } => #authorize_fn,
+					)*
+					Self::__Ignore(_, _) => unreachable!("__Ignore cannot be used"),
+				}
+			}
+
+			fn weight_of_authorize(&self) -> #frame_support::pallet_prelude::Weight {
+				match *self {
+					#( #cfg_attrs Self::#fn_name { .. } => #weight_of_authorize, )*
+					Self::__Ignore(_, _) => unreachable!("__Ignore cannot be used"),
+				}
+			}
+		}
+
 		impl<#type_impl_gen> #frame_support::traits::GetCallName for #call_ident<#type_use_gen>
 			#where_clause
 		{
@@ -443,6 +565,7 @@ pub fn expand_call(def: &mut Def) -> proc_macro2::TokenStream {
 				origin: Self::RuntimeOrigin
 			) -> #frame_support::dispatch::DispatchResultWithPostInfo {
 				#frame_support::dispatch_context::run_in_context(|| {
+					#pre_dispatch_weight
 					match self {
 						#(
 							#cfg_attrs
@@ -451,8 +574,7 @@ pub fn expand_call(def: &mut Def) -> proc_macro2::TokenStream {
 									#frame_support::__private::sp_tracing::trace_span!(stringify!(#fn_name))
 								);
 								#maybe_allow_attrs
-								<#pallet_ident<#type_use_gen>>::#fn_name(origin, #( #args_name, )* )
-									.map(Into::into).map_err(Into::into)
+								#dispatch_call
 							},
 						)*
 						Self::__Ignore(_, _) => {
@@ -27,6 +27,7 @@ use frame_support_procedural_tools::get_doc_literals;
 /// * replace the first field type of `struct Pallet` with `PhantomData` if it is `_`
 /// * implementation of `PalletInfoAccess` information
 /// * implementation of `StorageInfoTrait` on Pallet
+/// * implementation of `StorageLayoutFingerprint` and `StorageLayouts` on Pallet
 pub fn expand_pallet_struct(def: &mut Def) -> proc_macro2::TokenStream {
 	let frame_support = &def.frame_support;
 	let frame_system = &def.frame_system;
@@ -185,6 +186,41 @@ pub fn expand_pallet_struct(def: &mut Def) -> proc_macro2::TokenStream {
 			}
 		}
 	];
+	let storage_layout_impl = quote::quote!(
+		impl<#type_impl_gen> #frame_support::traits::StorageLayoutFingerprint
+			for #pallet_ident<#type_use_gen>
+			#storages_where_clauses
+		{
+			fn storage_layout_fingerprint() -> [u8; 32] {
+				#frame_support::traits::storage_layout_fingerprint(Self::storage_metadata())
+			}
+
+			fn storage_layout_version() -> #frame_support::traits::StorageVersion {
+				#storage_version
+			}
+		}
+
+		impl<#type_impl_gen> #frame_support::traits::StorageLayouts
+			for #pallet_ident<#type_use_gen>
+			#storages_where_clauses
+		{
+			fn storage_layouts()
+				-> #frame_support::__private::Vec<#frame_support::traits::PalletStorageLayout>
+			{
+				use #frame_support::traits::{
+					GetStorageVersion, PalletInfoAccess, StorageLayoutFingerprint,
+				};
+				#frame_support::__private::vec![#frame_support::traits::PalletStorageLayout {
+					pallet: <Self as PalletInfoAccess>::name().into(),
+					in_code_storage_version:
+						<Self as StorageLayoutFingerprint>::storage_layout_version(),
+					on_chain_storage_version: <Self as GetStorageVersion>::on_chain_storage_version(),
+					fingerprint: <Self as StorageLayoutFingerprint>::storage_layout_fingerprint(),
+				}]
+			}
+		}
+	);
+
 	let deprecation_status =
 		match crate::deprecation::get_deprecation(&quote::quote! {#frame_support}, &def.item.attrs)
 		{
@@ -289,6 +325,7 @@ pub fn expand_pallet_struct(def: &mut Def) -> proc_macro2::TokenStream {
 
 		#storage_info
 		#whitelisted_storage_keys_impl
+		#storage_layout_impl
 
 		impl<#type_use_gen> #pallet_ident<#type_use_gen> {
 			#[allow(dead_code)]
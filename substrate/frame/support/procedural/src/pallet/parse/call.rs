@@ -33,6 +33,12 @@ mod keyword {
 	syn::custom_keyword!(T);
 	syn::custom_keyword!(pallet);
 	syn::custom_keyword!(feeless_if);
+	syn::custom_keyword!(authorize);
+	syn::custom_keyword!(weight_of_authorize);
+	syn::custom_keyword!(weight_refund);
+	syn::custom_keyword!(auto);
+	syn::custom_keyword!(reads);
+	syn::custom_keyword!(writes);
 }
 
 /// Definition of dispatchables typically `impl<T: Config> Pallet<T> { ... }`
@@ -70,6 +76,45 @@ pub enum CallWeightDef {
 	Inherited,
 }
 
+/// The authorization of a call, given by `#[pallet::authorize(…)]`.
+#[derive(Clone)]
+pub struct AuthorizeDef {
+	/// The function authorizing the call, called with the transaction source and references to
+	/// the call arguments.
+	pub expr: syn::Expr,
+	/// The weight of the authorization.
+	///
+	/// `Inherited` means the weight is `authorize_$call_name` of the `WeightInfo` given to
+	/// `#[pallet::call(weight = …)]`.
+	pub weight: CallWeightDef,
+}
+
+/// Definition of the `#[pallet::weight_refund(auto(reads = $expr, writes = $expr))]` attribute.
+#[derive(Clone)]
+pub struct WeightRefundDef {
+	/// The number of storage reads accounted for in the weight of the call.
+	pub reads: syn::Expr,
+	/// The number of storage writes accounted for in the weight of the call.
+	pub writes: syn::Expr,
+}
+
+impl syn::parse::Parse for WeightRefundDef {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		input.parse::<keyword::auto>()?;
+		let content;
+		syn::parenthesized!(content in input);
+		content.parse::<keyword::reads>()?;
+		content.parse::<syn::Token![=]>()?;
+		let reads = content.parse::<syn::Expr>()?;
+		content.parse::<syn::Token![,]>()?;
+		content.parse::<keyword::writes>()?;
+		content.parse::<syn::Token![=]>()?;
+		let writes = content.parse::<syn::Expr>()?;
+		let _ = content.parse::<Option<syn::Token![,]>>()?;
+		Ok(Self { reads, writes })
+	}
+}
+
 /// Definition of dispatchable typically: `#[weight...] fn foo(origin .., param1: ...) -> ..`
 #[derive(Clone)]
 pub struct CallVariantDef {
@@ -91,6 +136,10 @@ pub struct CallVariantDef {
 	pub cfg_attrs: Vec<syn::Attribute>,
 	/// The optional `feeless_if` attribute on the `pallet::call`.
 	pub feeless_check: Option<syn::ExprClosure>,
+	/// The optional `authorize` attribute on the `pallet::call`.
+	pub authorize: Option<AuthorizeDef>,
+	/// The optional `weight_refund` attribute on the `pallet::call`.
+	pub weight_refund: Option<WeightRefundDef>,
 	/// The return type of the call: `DispatchInfo` or `DispatchResultWithPostInfo`.
 	pub return_type: helper::CallReturnType,
 }
@@ -103,6 +152,12 @@ pub enum FunctionAttr {
 	Weight(syn::Expr),
 	/// Parse for `#[pallet::feeless_if(expr)]`
 	FeelessIf(Span, syn::ExprClosure),
+	/// Parse for `#[pallet::authorize(expr)]`
+	Authorize(Span, syn::Expr),
+	/// Parse for `#[pallet::weight_of_authorize(expr)]`
+	WeightOfAuthorize(Span, syn::Expr),
+	/// Parse for `#[pallet::weight_refund(auto(reads = $expr, writes = $expr))]`
+	WeightRefund(Span, WeightRefundDef),
 }
 
 impl syn::parse::Parse for FunctionAttr {
@@ -142,6 +197,21 @@ impl syn::parse::Parse for FunctionAttr {
 					err
 				})?,
 			))
+		} else if lookahead.peek(keyword::authorize) {
+			let span = content.parse::<keyword::authorize>()?.span();
+			let authorize_content;
+			syn::parenthesized!(authorize_content in content);
+			Ok(FunctionAttr::Authorize(span, authorize_content.parse::<syn::Expr>()?))
+		} else if lookahead.peek(keyword::weight_of_authorize) {
+			let span = content.parse::<keyword::weight_of_authorize>()?.span();
+			let weight_content;
+			syn::parenthesized!(weight_content in content);
+			Ok(FunctionAttr::WeightOfAuthorize(span, weight_content.parse::<syn::Expr>()?))
+		} else if lookahead.peek(keyword::weight_refund) {
+			let span = content.parse::<keyword::weight_refund>()?.span();
+			let refund_content;
+			syn::parenthesized!(refund_content in content);
+			Ok(FunctionAttr::WeightRefund(span, refund_content.parse()?))
 		} else {
 			Err(lookahead.error())
 		}
@@ -269,6 +339,9 @@ impl CallDef {
 				let mut call_idx_attrs = vec![];
 				let mut weight_attrs = vec![];
 				let mut feeless_attrs = vec![];
+				let mut authorize_attrs = vec![];
+				let mut weight_of_authorize_attrs = vec![];
+				let mut weight_refund_attrs = vec![];
 				for attr in helper::take_item_pallet_attrs(&mut method.attrs)?.into_iter() {
 					match attr {
 						FunctionAttr::CallIndex(_) => {
@@ -280,6 +353,15 @@ impl CallDef {
 						FunctionAttr::FeelessIf(span, _) => {
 							feeless_attrs.push((span, attr));
 						},
+						FunctionAttr::Authorize(span, expr) => {
+							authorize_attrs.push((span, expr));
+						},
+						FunctionAttr::WeightOfAuthorize(span, expr) => {
+							weight_of_authorize_attrs.push((span, expr));
+						},
+						FunctionAttr::WeightRefund(span, def) => {
+							weight_refund_attrs.push((span, def));
+						},
 					}
 				}
 
@@ -435,6 +517,43 @@ impl CallDef {
 					}
 				}
 
+				if authorize_attrs.len() > 1 {
+					let msg = "Invalid pallet::call, there can only be one authorize attribute";
+					return Err(syn::Error::new(authorize_attrs[1].0, msg));
+				}
+				if weight_of_authorize_attrs.len() > 1 {
+					let msg =
+						"Invalid pallet::call, there can only be one weight_of_authorize attribute";
+					return Err(syn::Error::new(weight_of_authorize_attrs[1].0, msg));
+				}
+				if weight_refund_attrs.len() > 1 {
+					let msg = "Invalid pallet::call, there can only be one weight_refund attribute";
+					return Err(syn::Error::new(weight_refund_attrs[1].0, msg));
+				}
+				let weight_refund = weight_refund_attrs.pop().map(|(_, def)| def);
+
+				let authorize = match (authorize_attrs.pop(), weight_of_authorize_attrs.pop()) {
+					(None, None) => None,
+					(None, Some((span, _))) => {
+						let msg = "Invalid pallet::call, weight_of_authorize attribute requires \
+							an authorize attribute";
+						return Err(syn::Error::new(span, msg));
+					},
+					(Some((_, expr)), Some((_, weight))) =>
+						Some(AuthorizeDef { expr, weight: CallWeightDef::Immediate(weight) }),
+					(Some((_, expr)), None) if inherited_call_weight.is_some() =>
+						Some(AuthorizeDef { expr, weight: CallWeightDef::Inherited }),
+					(Some((_, expr)), None) if dev_mode =>
+						Some(AuthorizeDef { expr, weight: CallWeightDef::DevModeDefault }),
+					(Some((span, _)), None) => {
+						let msg = "Invalid pallet::call, an authorize attribute requires either a \
+							concrete `#[pallet::weight_of_authorize($expr)]` or an inherited \
+							weight from the `#[pallet:call(weight($type))]` attribute, but none \
+							were given.";
+						return Err(syn::Error::new(span, msg));
+					},
+				};
+
 				methods.push(CallVariantDef {
 					name: method.sig.ident.clone(),
 					weight,
@@ -445,6 +564,8 @@ impl CallDef {
 					attrs: method.attrs.clone(),
 					cfg_attrs,
 					feeless_check,
+					authorize,
+					weight_refund,
 					return_type,
 				});
 			} else {
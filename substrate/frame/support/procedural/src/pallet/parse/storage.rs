@@ -156,6 +156,8 @@ pub enum Metadata {
 	DoubleMap { value: syn::Type, key1: syn::Type, key2: syn::Type },
 	NMap { keys: Vec<syn::Type>, keygen: syn::Type, value: syn::Type },
 	CountedNMap { keys: Vec<syn::Type>, keygen: syn::Type, value: syn::Type },
+	PagedValue { value: syn::Type },
+	PagedMap { value: syn::Type, key: syn::Type },
 }
 
 pub enum QueryKind {
@@ -259,6 +261,18 @@ pub enum StorageGenerics {
 		on_empty: Option<syn::Type>,
 		max_values: Option<syn::Type>,
 	},
+	PagedValue {
+		value: syn::Type,
+		values_per_page: syn::Type,
+		max_pages: Option<syn::Type>,
+	},
+	PagedMap {
+		hasher: syn::Type,
+		key: syn::Type,
+		value: syn::Type,
+		values_per_page: syn::Type,
+		max_pages: Option<syn::Type>,
+	},
 }
 
 impl StorageGenerics {
@@ -273,6 +287,8 @@ impl StorageGenerics {
 				Metadata::NMap { keys: collect_keys(&keygen)?, keygen, value },
 			Self::CountedNMap { keygen, value, .. } =>
 				Metadata::CountedNMap { keys: collect_keys(&keygen)?, keygen, value },
+			Self::PagedValue { value, .. } => Metadata::PagedValue { value },
+			Self::PagedMap { key, value, .. } => Metadata::PagedMap { key, value },
 		};
 
 		Ok(res)
@@ -287,6 +303,7 @@ impl StorageGenerics {
 			Self::Value { query_kind, .. } |
 			Self::NMap { query_kind, .. } |
 			Self::CountedNMap { query_kind, .. } => query_kind.clone(),
+			Self::PagedValue { .. } | Self::PagedMap { .. } => None,
 		}
 	}
 }
@@ -298,6 +315,8 @@ enum StorageKind {
 	DoubleMap,
 	NMap,
 	CountedNMap,
+	PagedValue,
+	PagedMap,
 }
 
 /// Check the generics in the `map` contains the generics in `gen` may contains generics in
@@ -549,6 +568,64 @@ fn process_named_generics(
 				max_values: parsed.remove("MaxValues").map(|binding| binding.ty),
 			}
 		},
+		StorageKind::PagedValue => {
+			check_generics(
+				&parsed,
+				&["Value", "ValuesPerPage"],
+				&["MaxPages"],
+				"StoragePagedValue",
+				args_span,
+			)?;
+
+			StorageGenerics::PagedValue {
+				value: parsed
+					.remove("Value")
+					.map(|binding| binding.ty)
+					.expect("checked above as mandatory generic"),
+				values_per_page: parsed
+					.remove("ValuesPerPage")
+					.map(|binding| binding.ty)
+					.expect("checked above as mandatory generic"),
+				max_pages: parsed.remove("MaxPages").map(|binding| binding.ty),
+			}
+		},
+		StorageKind::PagedMap => {
+			let mut paged_map_mandatory_generics = vec!["Key", "Value", "ValuesPerPage"];
+			let mut paged_map_optional_generics = vec!["MaxPages"];
+			if dev_mode {
+				paged_map_optional_generics.push("Hasher");
+			} else {
+				paged_map_mandatory_generics.push("Hasher");
+			}
+
+			check_generics(
+				&parsed,
+				&paged_map_mandatory_generics,
+				&paged_map_optional_generics,
+				"StoragePagedMap",
+				args_span,
+			)?;
+
+			StorageGenerics::PagedMap {
+				hasher: parsed
+					.remove("Hasher")
+					.map(|binding| binding.ty)
+					.unwrap_or(syn::parse_quote!(Blake2_128Concat)),
+				key: parsed
+					.remove("Key")
+					.map(|binding| binding.ty)
+					.expect("checked above as mandatory generic"),
+				value: parsed
+					.remove("Value")
+					.map(|binding| binding.ty)
+					.expect("checked above as mandatory generic"),
+				values_per_page: parsed
+					.remove("ValuesPerPage")
+					.map(|binding| binding.ty)
+					.expect("checked above as mandatory generic"),
+				max_pages: parsed.remove("MaxPages").map(|binding| binding.ty),
+			}
+		},
 	};
 
 	let metadata = generics.metadata()?;
@@ -644,6 +721,19 @@ fn process_unnamed_generics(
 				false,
 			)
 		},
+		StorageKind::PagedValue => {
+			retrieve_arg(2)?;
+			(None, Metadata::PagedValue { value: retrieve_arg(1)? }, None, false)
+		},
+		StorageKind::PagedMap => {
+			retrieve_arg(4)?;
+			(
+				None,
+				Metadata::PagedMap { key: retrieve_arg(2)?, value: retrieve_arg(3)? },
+				None,
+				use_default_hasher(1)?,
+			)
+		},
 	};
 
 	Ok(res)
@@ -661,11 +751,13 @@ fn process_generics(
 		"StorageDoubleMap" => StorageKind::DoubleMap,
 		"StorageNMap" => StorageKind::NMap,
 		"CountedStorageNMap" => StorageKind::CountedNMap,
+		"StoragePagedValue" => StorageKind::PagedValue,
+		"StoragePagedMap" => StorageKind::PagedMap,
 		found => {
 			let msg = format!(
 				"Invalid pallet::storage, expected ident: `StorageValue` or \
 				`StorageMap` or `CountedStorageMap` or `StorageDoubleMap` or `StorageNMap` or `CountedStorageNMap` \
-				in order to expand metadata, found `{}`.",
+				or `StoragePagedValue` or `StoragePagedMap` in order to expand metadata, found `{}`.",
 				found,
 			);
 			return Err(syn::Error::new(segment.ident.span(), msg));
@@ -823,6 +915,13 @@ impl StorageDef {
 		let (named_generics, metadata, query_kind, use_default_hasher) =
 			process_generics(&typ.path.segments[0], dev_mode)?;
 
+		let is_paged = matches!(metadata, Metadata::PagedValue { .. } | Metadata::PagedMap { .. });
+		if let (true, Some(getter)) = (is_paged, getter.as_ref()) {
+			let msg = "Invalid pallet::storage, cannot generate getter for paged storage, use the \
+				iterators of `StoragePagedValue` or `StoragePagedMap` instead.";
+			return Err(syn::Error::new(getter.span(), msg));
+		}
+
 		let query_kind = query_kind
 			.map(|query_kind| {
 				use syn::{
@@ -917,7 +1016,7 @@ impl StorageDef {
 				}
 			})
 			.transpose()?
-			.unwrap_or(Some(QueryKind::OptionQuery));
+			.unwrap_or(if is_paged { None } else { Some(QueryKind::OptionQuery) });
 
 		if let (None, Some(getter)) = (query_kind.as_ref(), getter.as_ref()) {
 			let msg = "Invalid pallet::storage, cannot generate getter because QueryKind is not \
@@ -0,0 +1,161 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::helper;
+use frame_support_procedural_tools::get_doc_literals;
+use inflector::Inflector;
+use syn::spanned::Spanned;
+
+/// Definition of view functions typically `impl<T: Config> Pallet<T> { ... }`
+pub struct ViewFunctionsImplDef {
+	/// The where_clause used.
+	pub where_clause: Option<syn::WhereClause>,
+	/// A set of usage of instance, must be check for consistency with trait.
+	pub instances: Vec<helper::InstanceUsage>,
+	/// The view functions defined.
+	pub view_functions: Vec<ViewFunctionDef>,
+}
+
+/// Input definition for a view function in pallet.
+pub struct ViewFunctionDef {
+	/// Name of the function
+	pub name: syn::Ident,
+	/// The arguments of the function, as `(name, type)`
+	pub args: Vec<(syn::Ident, Box<syn::Type>)>,
+	/// The type returned by the function
+	pub return_type: syn::Type,
+	/// The doc associated
+	pub docs: Vec<syn::Expr>,
+	/// Attributes
+	pub attrs: Vec<syn::Attribute>,
+}
+
+impl ViewFunctionsImplDef {
+	pub fn try_from(item: &mut syn::Item) -> syn::Result<Self> {
+		let item = if let syn::Item::Impl(item) = item {
+			item
+		} else {
+			return Err(syn::Error::new(
+				item.span(),
+				"Invalid pallet::view_functions, expected item impl",
+			));
+		};
+
+		let instances = vec![
+			helper::check_impl_gen(&item.generics, item.impl_token.span())?,
+			helper::check_pallet_struct_usage(&item.self_ty)?,
+		];
+
+		if let Some((_, _, for_)) = item.trait_ {
+			let msg = "Invalid pallet::view_functions, expected no trait ident as in \
+				`impl<..> Pallet<..> { .. }`";
+			return Err(syn::Error::new(for_.span(), msg));
+		}
+
+		let mut view_functions = vec![];
+		for impl_item in &item.items {
+			let method = if let syn::ImplItem::Fn(method) = impl_item {
+				method
+			} else {
+				let msg = "Invalid pallet::view_functions, only method accepted";
+				return Err(syn::Error::new(impl_item.span(), msg));
+			};
+
+			view_functions.push(ViewFunctionDef::try_from(method)?);
+		}
+
+		Ok(Self { instances, where_clause: item.generics.where_clause.clone(), view_functions })
+	}
+}
+
+impl ViewFunctionDef {
+	fn try_from(method: &syn::ImplItemFn) -> syn::Result<Self> {
+		if !matches!(method.vis, syn::Visibility::Public(_)) {
+			let msg = "Invalid pallet::view_functions, view function must be public: \
+				`pub fn`";
+			return Err(syn::Error::new(method.sig.span(), msg));
+		}
+
+		if !method.sig.generics.params.is_empty() {
+			let msg = "Invalid pallet::view_functions, method must have 0 generics";
+			return Err(syn::Error::new(method.sig.generics.params[0].span(), msg));
+		}
+
+		if method.sig.generics.where_clause.is_some() {
+			let msg = "Invalid pallet::view_functions, method must have no where clause";
+			return Err(syn::Error::new(method.sig.generics.where_clause.span(), msg));
+		}
+
+		let return_type = match &method.sig.output {
+			syn::ReturnType::Default => {
+				let msg = "Invalid pallet::view_functions, method must have a return type";
+				return Err(syn::Error::new(method.sig.span(), msg));
+			},
+			syn::ReturnType::Type(_, type_) => *type_.clone(),
+		};
+
+		let mut args = vec![];
+		for arg in &method.sig.inputs {
+			let arg = match arg {
+				syn::FnArg::Typed(arg) => arg,
+				syn::FnArg::Receiver(_) => {
+					let msg = "Invalid pallet::view_functions, method must not have a receiver";
+					return Err(syn::Error::new(arg.span(), msg));
+				},
+			};
+			let name = match &*arg.pat {
+				syn::Pat::Ident(ident) => ident.ident.clone(),
+				_ => {
+					let msg = "Invalid pallet::view_functions, argument must be ident";
+					return Err(syn::Error::new(arg.pat.span(), msg));
+				},
+			};
+			args.push((name, arg.ty.clone()));
+		}
+
+		Ok(Self {
+			name: method.sig.ident.clone(),
+			args,
+			return_type,
+			docs: get_doc_literals(&method.attrs),
+			attrs: method.attrs.clone(),
+		})
+	}
+
+	/// The name of the struct generated to hold the arguments of the view function.
+	pub fn view_function_struct_ident(&self) -> syn::Ident {
+		syn::Ident::new(
+			&format!("{}ViewFunction", self.name.to_string().to_pascal_case()),
+			self.name.span(),
+		)
+	}
+
+	/// The signature of the view function, hashed to compute its `ViewFunctionIdSuffix`.
+	pub fn view_function_id_suffix_bytes(&self) -> [u8; 16] {
+		let arg_types = self
+			.args
+			.iter()
+			.map(|(_, ty)| quote::quote!(#ty).to_string().replace(' ', ""))
+			.collect::<Vec<_>>()
+			.join(",");
+		let return_type = &self.return_type;
+		let return_type = quote::quote!(#return_type).to_string().replace(' ', "");
+		let signature = format!("{}({}) -> {}", self.name, arg_types, return_type);
+
+		sp_crypto_hashing::twox_128(signature.as_bytes())
+	}
+}
@@ -229,6 +229,7 @@ fn construct_runtime_final_expansion(
 
 	let all_pallets = decl_all_pallets(&name, pallets.iter(), &features);
 	let pallet_to_index = decl_pallet_runtime_setup(&name, &pallets, &scrate);
+	let view_functions = expand::expand_outer_view_function(&name, &pallets, &scrate);
 
 	let metadata = expand::expand_runtime_metadata(
 		&name,
@@ -301,6 +302,8 @@ fn construct_runtime_final_expansion(
 
 		#task
 
+		#view_functions
+
 		#metadata
 
 		#outer_config
@@ -82,6 +82,12 @@ pub enum RawOrigin<AccountId> {
 	/// * included and agreed upon by the validators anyway,
 	/// * or unsigned transaction validated by a pallet.
 	None,
+	/// It is signed by nobody, the extrinsic is authorized by the runtime.
+	///
+	/// Authorization logic is defined by pallets.
+	/// See trait [`Authorize`](crate::traits::Authorize) and attribute macro
+	/// [`authorize`](crate::pallet_macros::authorize) for more details.
+	Authorized,
 }
 
 impl<AccountId> From<Option<AccountId>> for RawOrigin<AccountId> {
@@ -111,6 +117,11 @@ impl<AccountId> RawOrigin<AccountId> {
 	pub fn is_none(&self) -> bool {
 		matches!(&self, Self::None)
 	}
+
+	/// Returns `true` if `self` is `Authorized`, `false` otherwise.
+	pub fn is_authorized(&self) -> bool {
+		matches!(&self, Self::Authorized)
+	}
 }
 
 /// A type that can be used as a parameter in a dispatchable function.
@@ -86,6 +86,8 @@ pub mod storage;
 #[cfg(test)]
 mod tests;
 pub mod traits;
+pub mod view_functions;
+pub mod weight_refund;
 pub mod weights;
 #[doc(hidden)]
 pub mod unsigned {
@@ -898,7 +900,8 @@ pub mod pallet_prelude {
 			bounded_vec::BoundedVec,
 			types::{
 				CountedStorageMap, CountedStorageNMap, Key as NMapKey, OptionQuery, ResultQuery,
-				StorageDoubleMap, StorageMap, StorageNMap, StorageValue, ValueQuery,
+				StorageDoubleMap, StorageMap, StorageNMap, StoragePagedMap, StoragePagedValue,
+				StorageValue, ValueQuery,
 			},
 			weak_bounded_vec::WeakBoundedVec,
 			StorageList,
@@ -906,7 +909,7 @@ pub mod pallet_prelude {
 		traits::{
 			BuildGenesisConfig, ConstU32, ConstUint, EnsureOrigin, Get, GetDefault,
 			GetStorageVersion, Hooks, IsType, PalletInfoAccess, StorageInfoTrait, StorageVersion,
-			Task, TypedGet,
+			Task, TransactionValidityWithRefund, TypedGet,
 		},
 		Blake2_128, Blake2_128Concat, Blake2_256, CloneNoBound, DebugNoBound, EqNoBound, Identity,
 		PartialEqNoBound, RuntimeDebugNoBound, Twox128, Twox256, Twox64Concat,
@@ -1419,6 +1422,62 @@ pub mod pallet_macros {
 	/// args, 0 generics, and some return type.
 	pub use frame_support_procedural::extra_constants;
 
+	/// Declares read-only queries of the pallet, called view functions.
+	///
+	/// View functions are aggregated by `construct_runtime!` into a single implementation of
+	/// [`DispatchViewFunction`](crate::view_functions::DispatchViewFunction) for the runtime,
+	/// and described in the pallet metadata with their typed inputs and output. Exposing
+	/// [`RuntimeViewFunction`](crate::view_functions::runtime_api::RuntimeViewFunction) in the
+	/// runtime makes all of them callable by clients without any pallet specific runtime API.
+	///
+	/// Must be defined like:
+	///
+	/// ```
+	/// #[frame_support::pallet]
+	/// mod pallet {
+	/// # 	use frame_support::pallet_prelude::*;
+	/// #
+	/// 	#[pallet::pallet]
+	/// 	pub struct Pallet<T>(_);
+	///
+	/// # 	#[pallet::config]
+	/// # 	pub trait Config: frame_system::Config {}
+	/// #
+	/// 	#[pallet::storage]
+	/// 	pub type SomeMap<T> = StorageMap<_, Twox64Concat, u32, u32>;
+	///
+	/// 	#[pallet::view_functions]
+	/// 	impl<T: Config> Pallet<T> // $optional_where_clause
+	/// 	{
+	/// 		/// Get a value from the map.
+	/// 		pub fn get_value(key: u32) -> Option<u32> {
+	/// 			SomeMap::<T>::get(key)
+	/// 		}
+	/// 	}
+	/// }
+	/// ```
+	///
+	/// I.e. a regular rust `impl` block with some optional where clause and public functions
+	/// with no receiver, 0 generics and some return type. Arguments and return type must
+	/// implement `Encode`, `Decode` and `TypeInfo`.
+	///
+	/// Each view function is identified by a
+	/// [`ViewFunctionId`](crate::view_functions::ViewFunctionId), made of the twox128 hash of
+	/// the pallet name and the twox128 hash of the function signature. The runtime can then
+	/// expose them with:
+	///
+	/// ```ignore
+	/// impl frame_support::view_functions::runtime_api::RuntimeViewFunction<Block> for Runtime {
+	/// 	fn execute_view_function(
+	/// 		id: frame_support::view_functions::ViewFunctionId,
+	/// 		input: Vec<u8>,
+	/// 	) -> Result<Vec<u8>, frame_support::view_functions::ViewFunctionDispatchError> {
+	/// 		Runtime::execute_view_function(id, input)
+	/// 	}
+	/// }
+	/// ```
+	pub use frame_support_procedural::view_functions;
+
 	#[rustfmt::skip]
 	/// Allows bypassing the `frame_system::Config` supertrait check.
 	///
@@ -1669,6 +1728,11 @@ pub mod pallet_macros {
 
 	/// Allows the pallet to validate unsigned transactions.
 	///
+	/// New pallets should prefer
+	/// [`pallet::authorize`](`frame_support::pallet_macros::authorize`) which keeps the
+	/// validation logic next to the call it authorizes and gives the call a
+	/// dedicated `Authorized` origin. `ValidateUnsigned` is kept for existing pallets.
+	///
 	/// Item must be defined as:
 	///
 	/// ```
@@ -1701,6 +1765,13 @@ pub mod pallet_macros {
 	/// NOTE: There is also the [`sp_runtime::traits::TransactionExtension`] trait that can be
 	/// used to add some specific logic for transaction validation.
 	///
+	/// ## Deprecation
+	///
+	/// New pallets should authorize their unsigned calls with [`pallet::authorize`](authorize)
+	/// instead, which is checked by the `frame_system::AuthorizeCall` transaction extension and
+	/// gives the call the `Authorized` origin. This attribute isn't marked `#[deprecated]` yet,
+	/// as many pallets and runtimes still rely on it; migrating them is left to follow-up work.
+	///
 	/// ## Macro expansion
 	///
 	/// The macro currently makes no use of this information, but it might use this information
@@ -1902,6 +1973,131 @@ pub mod pallet_macros {
 	/// [`pallet_skip_feeless_payment::CheckIfFeeless`]: ../../pallet_skip_feeless_payment/struct.SkipCheckIfFeeless.html
 	pub use frame_support_procedural::feeless_if;
 
+	/// Allows to authorize some general transactions with a specific dispatchable function
+	/// (dispatchable functions are defined using the `pallet::call` attribute).
+	///
+	/// The attribute takes a function (or a closure) as argument. It is called with the
+	/// transaction source and references to the arguments of the call, and must return a
+	/// [`TransactionValidityWithRefund`](crate::traits::TransactionValidityWithRefund): the
+	/// validity of the transaction and the weight unspent from the worst case weight of the
+	/// authorization.
+	///
+	/// The worst case weight of the authorization is given by
+	/// [`pallet::weight_of_authorize`](`frame_support::pallet_macros::weight_of_authorize`),
+	/// or inherited from the `WeightInfo` given to `#[pallet::call(weight = ...)]` as
+	/// `authorize_$call_name`. In dev mode it defaults to zero.
+	///
+	/// ```
+	/// #[frame_support::pallet(dev_mode)]
+	/// mod pallet {
+	/// # 	use frame_support::pallet_prelude::*;
+	/// # 	use frame_system::pallet_prelude::*;
+	/// #
+	/// 	#[pallet::pallet]
+	/// 	pub struct Pallet<T>(_);
+	///
+	/// 	#[pallet::call]
+	/// 	impl<T: Config> Pallet<T> {
+	/// 		#[pallet::call_index(0)]
+	/// 		#[pallet::authorize(|_source, foo: &u32| -> TransactionValidityWithRefund {
+	/// 			if *foo == 42 {
+	/// 				let refund = Weight::zero();
+	/// 				let validity = ValidTransaction::default();
+	/// 				Ok((validity, refund))
+	/// 			} else {
+	/// 				Err(InvalidTransaction::Call.into())
+	/// 			}
+	/// 		})]
+	/// 		#[pallet::weight_of_authorize(Weight::from_parts(10, 0))]
+	/// 		pub fn some_call(origin: OriginFor<T>, foo: u32) -> DispatchResult {
+	/// 			frame_system::ensure_authorized(origin)?;
+	/// 			// Your implementation details here
+	/// 			Ok(())
+	/// 		}
+	/// 	}
+	/// #
+	/// # 	#[pallet::config]
+	/// # 	pub trait Config: frame_system::Config {}
+	/// }
+	/// ```
+	///
+	/// The authorization is only taken into account if the runtime uses the
+	/// `frame_system::AuthorizeCall` transaction extension. For a general transaction (i.e.
+	/// with no signer) of an authorized call, this extension sets the validity of the
+	/// transaction to the one returned by the authorize function and dispatches the call with
+	/// the [`RawOrigin::Authorized`](crate::dispatch::RawOrigin::Authorized) origin.
+	///
+	/// The dispatchable function must check the origin with `frame_system::ensure_authorized`:
+	/// the call can still be dispatched with any other origin, e.g. by a signed transaction.
+	///
+	/// ### Macro expansion
+	///
+	/// The macro implements the [`Authorize`](crate::traits::Authorize) trait on the `Call`
+	/// enum of the pallet, and `construct_runtime` implements it on the `RuntimeCall` enum.
+	pub use frame_support_procedural::authorize;
+
+	/// Allows to define the worst case weight of the function given to
+	/// [`pallet::authorize`](`frame_support::pallet_macros::authorize`).
+	///
+	/// The attribute takes an expression evaluating to a `Weight`, see `pallet::authorize` for
+	/// an example.
+	pub use frame_support_procedural::weight_of_authorize;
+
+	/// Automatically refund the unused weight of a dispatchable.
+	///
+	/// The only accepted form is `#[pallet::weight_refund(auto(reads = $expr, writes =
+	/// $expr))]`, put on a dispatchable of the
+	/// [`pallet::call`](`frame_support::pallet_macros::call`) impl block. `reads` and
+	/// `writes` are the number of storage reads and writes accounted for in the weight of the
+	/// call. They can use the arguments of the call, like the weight expression.
+	///
+	/// The dispatchable is executed with
+	/// [`dispatch_with_auto_refund`](crate::weight_refund::dispatch_with_auto_refund). The
+	/// storage reads and writes of the call are counted, and the declared reads and writes
+	/// that were not done are refunded at the price of the `DbWeight` of `frame_system`. The
+	/// rest of the ref time of the weight is never refunded. The growth of the storage proof
+	/// is measured with the `StorageProofSize` of `frame_system`. The result, capped by the
+	/// pre-dispatch weight, is returned as the actual weight of the call. An actual weight
+	/// returned explicitly by the dispatchable takes precedence.
+	///
+	/// Storage iteration, streaming reads and direct calls to `sp_io` are not counted, see
+	/// [`crate::weight_refund`] for the full list of metered operations. Such operations must
+	/// not be part of the declared `reads` and `writes`.
+	///
+	/// ```
+	/// #[frame_support::pallet]
+	/// mod pallet {
+	/// 	use frame_support::pallet_prelude::*;
+	/// 	use frame_system::pallet_prelude::*;
+	///
+	/// 	#[pallet::pallet]
+	/// 	pub struct Pallet<T>(_);
+	///
+	/// 	#[pallet::config]
+	/// 	pub trait Config: frame_system::Config {}
+	///
+	/// 	#[pallet::storage]
+	/// 	pub type Values<T> = StorageMap<_, Twox64Concat, u32, u32>;
+	///
+	/// 	#[pallet::call]
+	/// 	impl<T: Config> Pallet<T> {
+	/// 		#[pallet::call_index(0)]
+	/// 		#[pallet::weight(
+	/// 			Weight::from_parts(10_000, 0).saturating_add(T::DbWeight::get().writes(10))
+	/// 		)]
+	/// 		#[pallet::weight_refund(auto(reads = 0, writes = 10))]
+	/// 		pub fn set_values(origin: OriginFor<T>, values: Vec<(u32, u32)>) -> DispatchResult {
+	/// 			ensure_signed(origin)?;
+	/// 			for (key, value) in values.into_iter().take(10) {
+	/// 				Values::<T>::insert(key, value);
+	/// 			}
+	/// 			Ok(())
+	/// 		}
+	/// 	}
+	/// }
+	/// ```
+	pub use frame_support_procedural::weight_refund;
+
 	/// Allows defining an error enum that will be returned from the dispatchable when an error
 	/// occurs.
 	///
@@ -2311,6 +2507,8 @@ pub mod pallet_macros {
 	/// * [`StorageDoubleMap`](crate::storage::types::StorageDoubleMap)
 	/// * [`StorageNMap`](crate::storage::types::StorageNMap)
 	/// * [`CountedStorageNMap`](crate::storage::types::CountedStorageNMap)
+	/// * [`StoragePagedValue`](crate::storage::types::StoragePagedValue)
+	/// * [`StoragePagedMap`](crate::storage::types::StoragePagedMap)
 	///
 	/// ## Storage Type Usage
 	///
@@ -2325,7 +2523,10 @@ pub mod pallet_macros {
 	/// * [`QueryKind`](#querykind) - Used to configure how to handle queries to the underlying
 	///   storage,
 	/// * `OnEmpty` - Used to handle missing values when querying the underlying storage,
-	/// * `MaxValues` - _not currently used_.
+	/// * `MaxValues` - _not currently used_,
+	/// * `ValuesPerPage` - The maximum number of values stored in a single page of a paged
+	///   storage type,
+	/// * `MaxPages` - Only reported in the storage info of paged storage types.
 	///
 	/// Each `Key` type requires its own designated `Hasher` declaration, so that
 	/// [`StorageDoubleMap`](frame_support::storage::types::StorageDoubleMap) needs two of
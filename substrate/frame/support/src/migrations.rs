@@ -33,6 +33,12 @@ use sp_core::Get;
 use sp_io::{hashing::twox_128, storage::clear_prefix, KillStorageResult};
 use sp_runtime::traits::Zero;
 
+mod stepped;
+pub use stepped::{
+	step_keys, ClearPrefixInSteps, MapTranslation, MigrationReport, MultiBlockMigrationHarness,
+	TranslateMapInSteps,
+};
+
 /// Handles storage migration pallet versioning.
 ///
 /// [`VersionedMigration`] allows developers to write migrations without worrying about checking and
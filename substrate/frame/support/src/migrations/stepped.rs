@@ -0,0 +1,296 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reusable building blocks for writing [`SteppedMigration`]s.
+//!
+//! - [`step_keys`] handles the cursor bookkeeping and weight metering of a migration that walks
+//!   over a key space.
+//! - [`TranslateMapInSteps`] translates the values of a storage map in steps.
+//! - [`ClearPrefixInSteps`] removes all keys under a storage prefix in steps.
+//! - [`MultiBlockMigrationHarness`] runs a migration to completion in tests or `try-runtime`,
+//!   checking the pre- and post-upgrade hooks and that every step makes progress.
+
+use super::{SteppedMigration, SteppedMigrationError};
+use crate::{
+	storage::{unhashed, IterableStorageMap},
+	weights::{Weight, WeightMeter},
+};
+use alloc::vec::Vec;
+use codec::{Decode, Encode, FullCodec, MaxEncodedLen};
+use core::marker::PhantomData;
+use sp_core::{Get, TypedGet};
+use sp_runtime::{Saturating, StateVersion};
+
+/// Walk over keys in a [`SteppedMigration::step`], consuming `weight_per_item` for every item.
+///
+/// `iter_from` is called once with the cursor of the previous step and must return an iterator
+/// over the keys *after* that cursor. `process` is called for every key that fits into `meter`.
+/// The returned cursor is the last processed key, or `None` once the iterator is exhausted.
+///
+/// Returns [`SteppedMigrationError::InsufficientWeight`] if not a single item fits into `meter`.
+/// Otherwise at least one item is processed per step, which guarantees progress.
+pub fn step_keys<K, I>(
+	cursor: Option<K>,
+	meter: &mut WeightMeter,
+	weight_per_item: Weight,
+	iter_from: impl FnOnce(Option<K>) -> I,
+	mut process: impl FnMut(&K) -> Result<(), SteppedMigrationError>,
+) -> Result<Option<K>, SteppedMigrationError>
+where
+	I: Iterator<Item = K>,
+{
+	if !meter.can_consume(weight_per_item) {
+		return Err(SteppedMigrationError::InsufficientWeight { required: weight_per_item })
+	}
+
+	let mut iter = iter_from(cursor);
+	let mut last = None;
+	while meter.try_consume(weight_per_item).is_ok() {
+		let Some(key) = iter.next() else { return Ok(None) };
+		process(&key)?;
+		last = Some(key);
+	}
+
+	Ok(last)
+}
+
+/// Describes how the values of a storage map are translated by [`TranslateMapInSteps`].
+pub trait MapTranslation {
+	/// The unique identifier type of the migration.
+	type Identifier: FullCodec + MaxEncodedLen;
+	/// The key of the map. It is used as cursor.
+	type Key: FullCodec + MaxEncodedLen;
+	/// The type of the values before the migration.
+	type OldValue: Decode;
+	/// The type of the values after the migration.
+	type Value: FullCodec;
+	/// The map that is translated.
+	type Map: IterableStorageMap<Self::Key, Self::Value>;
+
+	/// The unique identifier of the migration.
+	fn id() -> Self::Identifier;
+
+	/// The worst case weight of translating a single value.
+	fn weight_per_item() -> Weight;
+
+	/// Translate the value stored under `key`.
+	///
+	/// Returning `None` removes the entry from the map.
+	fn translate(key: &Self::Key, old: Self::OldValue) -> Option<Self::Value>;
+
+	/// Hook that is run before the migration is started, see [`SteppedMigration::pre_upgrade`].
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+		Ok(Vec::new())
+	}
+
+	/// Hook that is run after the migration is completed, see [`SteppedMigration::post_upgrade`].
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(_state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+		Ok(())
+	}
+}
+
+/// A [`SteppedMigration`] that translates the values of a storage map in steps.
+///
+/// The map is iterated in the order of its keys and every value is translated with
+/// [`MapTranslation::translate`]. The last translated key is used as cursor, so this only works for
+/// maps with a reversible hasher. A value that cannot be decoded as
+/// [`MapTranslation::OldValue`] fails the migration.
+pub struct TranslateMapInSteps<T>(PhantomData<T>);
+
+impl<T: MapTranslation> SteppedMigration for TranslateMapInSteps<T> {
+	type Cursor = T::Key;
+	type Identifier = T::Identifier;
+
+	fn id() -> Self::Identifier {
+		T::id()
+	}
+
+	fn step(
+		cursor: Option<Self::Cursor>,
+		meter: &mut WeightMeter,
+	) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+		step_keys(
+			cursor,
+			meter,
+			T::weight_per_item(),
+			|cursor| match cursor {
+				Some(last) => T::Map::iter_keys_from(T::Map::hashed_key_for(last)),
+				None => T::Map::iter_keys(),
+			},
+			|key| {
+				let old = unhashed::get::<T::OldValue>(&T::Map::hashed_key_for(key))
+					.ok_or(SteppedMigrationError::Failed)?;
+				match T::translate(key, old) {
+					Some(new) => T::Map::insert(key, new),
+					None => T::Map::remove(key),
+				}
+				Ok(())
+			},
+		)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+		T::pre_upgrade()
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+		T::post_upgrade(state)
+	}
+}
+
+/// A [`SteppedMigration`] that removes all keys under `Prefix` in steps.
+///
+/// Every removed key consumes `WeightPerKey`. The cursor is the number of keys removed so far and
+/// the migration is completed once no key is left under `Prefix`.
+pub struct ClearPrefixInSteps<Id, Prefix, WeightPerKey>(PhantomData<(Id, Prefix, WeightPerKey)>);
+
+impl<Id, Prefix, WeightPerKey> SteppedMigration for ClearPrefixInSteps<Id, Prefix, WeightPerKey>
+where
+	Id: TypedGet,
+	Id::Type: FullCodec + MaxEncodedLen,
+	Prefix: Get<Vec<u8>>,
+	WeightPerKey: Get<Weight>,
+{
+	type Cursor = u64;
+	type Identifier = Id::Type;
+
+	fn id() -> Self::Identifier {
+		Id::get()
+	}
+
+	fn step(
+		cursor: Option<Self::Cursor>,
+		meter: &mut WeightMeter,
+	) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+		let prefix = Prefix::get();
+		let mut removed = cursor.unwrap_or_default();
+		// Keys are removed while iterating, so the next key is always the first one left.
+		let keys = core::iter::from_fn(|| {
+			sp_io::storage::next_key(&prefix).filter(|key| key.starts_with(&prefix))
+		});
+
+		let done = step_keys(
+			None,
+			meter,
+			WeightPerKey::get(),
+			|_| keys,
+			|key| {
+				unhashed::kill(key);
+				removed.saturating_inc();
+				Ok(())
+			},
+		)?
+		.is_none();
+
+		Ok((!done).then_some(removed))
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(_state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+		if unhashed::contains_prefixed_key(&Prefix::get()) {
+			return Err("Keys left under the cleared prefix".into())
+		}
+		Ok(())
+	}
+}
+
+/// The outcome of [`MultiBlockMigrationHarness::run_to_completion`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationReport {
+	/// The number of steps it took to complete the migration.
+	pub steps: u32,
+	/// The total weight consumed by all steps.
+	pub weight_consumed: Weight,
+	/// The storage root before the migration was started.
+	pub pre_checksum: Vec<u8>,
+	/// The storage root after the migration was completed.
+	pub post_checksum: Vec<u8>,
+}
+
+/// Runs a [`SteppedMigration`] to completion within a single externalities.
+///
+/// Meant to be used in tests and `try-runtime` checks of migrations. The harness:
+/// - runs [`SteppedMigration::pre_upgrade`] and [`SteppedMigration::post_upgrade`] when the
+///   `try-runtime` feature is enabled,
+/// - calls [`SteppedMigration::transactional_step`] with a fresh meter of the given weight until
+///   the migration is completed,
+/// - fails if the migration exceeds [`SteppedMigration::max_steps`], returns an error or makes a
+///   step that changes neither its cursor nor the state, as it would never complete,
+/// - returns the storage roots before and after the migration as checksums.
+pub struct MultiBlockMigrationHarness<M>(PhantomData<M>);
+
+impl<M: SteppedMigration> MultiBlockMigrationHarness<M> {
+	/// Run the migration to completion, giving every step `weight_per_step`.
+	pub fn run_to_completion(
+		weight_per_step: Weight,
+	) -> Result<MigrationReport, sp_runtime::TryRuntimeError> {
+		#[cfg(feature = "try-runtime")]
+		let state = M::pre_upgrade()?;
+
+		let pre_checksum = Self::checksum();
+		let mut checksum = pre_checksum.clone();
+		let mut cursor: Option<Vec<u8>> = None;
+		let mut steps = 0u32;
+		let mut weight_consumed = Weight::zero();
+
+		loop {
+			if M::max_steps().map_or(false, |max| steps >= max) {
+				return Err("Migration exceeded its maximum number of steps".into())
+			}
+
+			let decoded = cursor
+				.as_ref()
+				.map(|c| M::Cursor::decode(&mut &c[..]))
+				.transpose()
+				.map_err(|_| "Migration returned an undecodable cursor")?;
+			let mut meter = WeightMeter::with_limit(weight_per_step);
+			let next = M::transactional_step(decoded, &mut meter).map_err(|err| {
+				log::error!(target: "runtime::migrations", "Step {steps} failed: {err:?}");
+				"Migration step failed"
+			})?;
+			steps.saturating_inc();
+			weight_consumed.saturating_accrue(meter.consumed());
+
+			let Some(next) = next else { break };
+			let next = next.encode();
+			let next_checksum = Self::checksum();
+			if cursor.as_ref() == Some(&next) && checksum == next_checksum {
+				return Err("Migration step made no progress".into())
+			}
+			cursor = Some(next);
+			checksum = next_checksum;
+		}
+
+		#[cfg(feature = "try-runtime")]
+		M::post_upgrade(state)?;
+
+		Ok(MigrationReport {
+			steps,
+			weight_consumed,
+			pre_checksum,
+			post_checksum: Self::checksum(),
+		})
+	}
+
+	fn checksum() -> Vec<u8> {
+		sp_io::storage::root(StateVersion::V1)
+	}
+}
@@ -31,6 +31,7 @@ pub fn get<T: Decode + Sized>(child_info: &ChildInfo, key: &[u8]) -> Option<T> {
 	match child_info.child_type() {
 		ChildType::ParentKeyId => {
 			let storage_key = child_info.storage_key();
+			crate::weight_refund::note_read();
 			sp_io::default_child_storage::get(storage_key, key).and_then(|v| {
 				Decode::decode(&mut &v[..]).map(Some).unwrap_or_else(|_| {
 					// TODO #3700: error should be handleable.
@@ -73,6 +74,7 @@ pub fn get_or_else<T: Decode + Sized, F: FnOnce() -> T>(
 pub fn put<T: Encode>(child_info: &ChildInfo, key: &[u8], value: &T) {
 	match child_info.child_type() {
 		ChildType::ParentKeyId => value.using_encoded(|slice| {
+			crate::weight_refund::note_write();
 			sp_io::default_child_storage::set(child_info.storage_key(), key, slice)
 		}),
 	}
@@ -112,8 +114,10 @@ pub fn take_or_else<T: Codec + Sized, F: FnOnce() -> T>(
 /// Check to see if `key` has an explicit entry in storage.
 pub fn exists(child_info: &ChildInfo, key: &[u8]) -> bool {
 	match child_info.child_type() {
-		ChildType::ParentKeyId =>
-			sp_io::default_child_storage::exists(child_info.storage_key(), key),
+		ChildType::ParentKeyId => {
+			crate::weight_refund::note_read();
+			sp_io::default_child_storage::exists(child_info.storage_key(), key)
+		},
 	}
 }
 
@@ -138,10 +142,15 @@ pub fn exists(child_info: &ChildInfo, key: &[u8]) -> bool {
 /// blocks.
 #[deprecated = "Use `clear_storage` instead"]
 pub fn kill_storage(child_info: &ChildInfo, limit: Option<u32>) -> KillStorageResult {
-	match child_info.child_type() {
+	let result = match child_info.child_type() {
 		ChildType::ParentKeyId =>
 			sp_io::default_child_storage::storage_kill(child_info.storage_key(), limit),
-	}
+	};
+	let removed = match result {
+		KillStorageResult::AllRemoved(i) | KillStorageResult::SomeRemaining(i) => i,
+	};
+	crate::weight_refund::note_writes(removed.into());
+	result
 }
 
 /// Partially clear the child storage of each key-value pair.
@@ -193,6 +202,7 @@ pub fn clear_storage(
 		AllRemoved(db) => (None, db),
 		SomeRemaining(db) => (Some(child_info.storage_key().to_vec()), db),
 	};
+	crate::weight_refund::note_writes(backend.into());
 	MultiRemovalResults { maybe_cursor, backend, unique: backend, loops: backend }
 }
 
@@ -200,6 +210,7 @@ pub fn clear_storage(
 pub fn kill(child_info: &ChildInfo, key: &[u8]) {
 	match child_info.child_type() {
 		ChildType::ParentKeyId => {
+			crate::weight_refund::note_write();
 			sp_io::default_child_storage::clear(child_info.storage_key(), key);
 		},
 	}
@@ -208,15 +219,20 @@ pub fn kill(child_info: &ChildInfo, key: &[u8]) {
 /// Get a Vec of bytes from storage.
 pub fn get_raw(child_info: &ChildInfo, key: &[u8]) -> Option<Vec<u8>> {
 	match child_info.child_type() {
-		ChildType::ParentKeyId => sp_io::default_child_storage::get(child_info.storage_key(), key),
+		ChildType::ParentKeyId => {
+			crate::weight_refund::note_read();
+			sp_io::default_child_storage::get(child_info.storage_key(), key)
+		},
 	}
 }
 
 /// Put a raw byte slice into storage.
 pub fn put_raw(child_info: &ChildInfo, key: &[u8], value: &[u8]) {
 	match child_info.child_type() {
-		ChildType::ParentKeyId =>
-			sp_io::default_child_storage::set(child_info.storage_key(), key, value),
+		ChildType::ParentKeyId => {
+			crate::weight_refund::note_write();
+			sp_io::default_child_storage::set(child_info.storage_key(), key, value)
+		},
 	}
 }
 
@@ -233,6 +249,7 @@ pub fn len(child_info: &ChildInfo, key: &[u8]) -> Option<u32> {
 	match child_info.child_type() {
 		ChildType::ParentKeyId => {
 			let mut buffer = [0; 0];
+			crate::weight_refund::note_read();
 			sp_io::default_child_storage::read(child_info.storage_key(), key, &mut buffer, 0)
 		},
 	}
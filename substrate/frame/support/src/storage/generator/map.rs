@@ -295,6 +295,7 @@ impl<K: FullEncode, V: FullCodec, G: StorageMap<K, V>> storage::StorageMap<K, V>
 		V: StorageAppend<Item>,
 	{
 		let key = Self::storage_map_final_key(key);
+		crate::weight_refund::note_write();
 		sp_io::storage::append(&key, item.encode());
 	}
 
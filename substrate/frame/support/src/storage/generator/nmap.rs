@@ -287,6 +287,7 @@ where
 		V: StorageAppend<Item>,
 	{
 		let final_key = Self::storage_n_map_final_key::<K, _>(key);
+		crate::weight_refund::note_write();
 		sp_io::storage::append(&final_key, item.encode());
 	}
 
@@ -1484,6 +1484,7 @@ pub trait StorageDecodeLength: private::Sealed + codec::DecodeLength {
 	fn decode_len(key: &[u8]) -> Option<usize> {
 		// `Compact<u32>` is 5 bytes in maximum.
 		let mut data = [0u8; 5];
+		crate::weight_refund::note_read();
 		let len = sp_io::storage::read(key, &mut data, 0)?;
 		let len = data.len().min(len as usize);
 		<Self as codec::DecodeLength>::len(&data[..len]).ok()
@@ -1507,6 +1508,7 @@ pub trait StorageDecodeNonDedupLength: private::Sealed + codec::DecodeLength {
 	/// Returns `None` if the storage value does not exist or the decoding failed.
 	fn decode_non_dedup_len(key: &[u8]) -> Option<usize> {
 		let mut data = [0u8; 5];
+		crate::weight_refund::note_read();
 		let len = sp_io::storage::read(key, &mut data, 0)?;
 		let len = data.len().min(len as usize);
 		<Self as codec::DecodeLength>::len(&data[..len]).ok()
@@ -1606,6 +1608,7 @@ where
 			// NOTE: we cannot reuse the implementation for `Vec<T>` here because we never want to
 			// mark `BoundedVec<T, S>` as `StorageAppend`.
 			let key = Self::storage_value_final_key();
+			crate::weight_refund::note_write();
 			sp_io::storage::append(&key, item.encode());
 			Ok(())
 		} else {
@@ -1640,6 +1643,7 @@ where
 		let current = Self::decode_len(key.clone()).unwrap_or_default();
 		if current < bound {
 			let key = Self::storage_map_final_key(key);
+			crate::weight_refund::note_write();
 			sp_io::storage::append(&key, item.encode());
 			Ok(())
 		} else {
@@ -1685,6 +1689,7 @@ where
 		let current = Self::decode_len(key1.clone(), key2.clone()).unwrap_or_default();
 		if current < bound {
 			let double_map_key = Self::storage_double_map_final_key(key1, key2);
+			crate::weight_refund::note_write();
 			sp_io::storage::append(&double_map_key, item.encode());
 			Ok(())
 		} else {
@@ -1725,6 +1730,7 @@ where
 		let current = Self::decode_len(key.clone()).unwrap_or_default();
 		if current < bound {
 			let key = Self::storage_n_map_final_key::<K, _>(key);
+			crate::weight_refund::note_write();
 			sp_io::storage::append(&key, item.encode());
 			Ok(())
 		} else {
@@ -28,6 +28,8 @@ mod double_map;
 mod key;
 mod map;
 mod nmap;
+mod paged_map;
+mod paged_value;
 mod value;
 
 pub use counted_map::{CountedStorageMap, CountedStorageMapInstance, Counter};
@@ -39,6 +41,10 @@ pub use key::{
 };
 pub use map::StorageMap;
 pub use nmap::StorageNMap;
+pub use paged_map::StoragePagedMap;
+pub use paged_value::{
+	PageIndex, PagedCursor, PagedIterator, PagedMeta, PagedStorageInstance, StoragePagedValue,
+};
 pub use value::StorageValue;
 
 /// Trait implementing how the storage optional value is converted into the queried type.
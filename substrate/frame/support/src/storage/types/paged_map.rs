@@ -0,0 +1,398 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage paged map type. Stores a logical list of values split over bounded pages per key.
+
+use crate::{
+	storage::{
+		bounded_vec::BoundedVec,
+		types::{
+			paged_value::{append_paged, clear_paged, meter_limit},
+			OptionQuery, PageIndex, PagedCursor, PagedIterator, PagedMeta, PagedStorageInstance,
+			StorageDoubleMap, StorageEntryMetadataBuilder, StorageMap,
+		},
+	},
+	traits::{Get, GetDefault, StorageInfo},
+	ReversibleStorageHasher, Twox64Concat,
+};
+use alloc::{vec, vec::Vec};
+use codec::{EncodeLike, FullCodec, MaxEncodedLen};
+use sp_io::MultiRemovalResults;
+use sp_metadata_ir::StorageEntryMetadataIR;
+use sp_runtime::traits::Saturating;
+use sp_weights::{Weight, WeightMeter};
+
+/// A map from `Key` to a list of values stored over multiple pages of at most `ValuesPerPage`
+/// values each.
+///
+/// Every key holds an independent paged list which behaves like a
+/// [`StoragePagedValue`](super::StoragePagedValue): values can only be appended, iteration can be
+/// resumed with a [`PagedCursor`] and pages are removed from the front with weight-bounded calls.
+///
+/// The pages are stored as a [`StorageDoubleMap`] from `Key` and [`PageIndex`] to
+/// `BoundedVec<Value, ValuesPerPage>` under the storage prefix of the item. The [`PagedMeta`] of
+/// every key is stored as a [`StorageMap`] under [`PagedStorageInstance::MetaPrefix`]. Both are
+/// exposed in the metadata, so clients can reconstruct the logical value of a key by reading its
+/// meta and then its pages from `first_page` to `last_page`.
+///
+/// `MaxPages` is only used to report [`StorageInfo`], it is not enforced.
+///
+/// For general information regarding the `#[pallet::storage]` attribute, refer to
+/// [`crate::pallet_macros::storage`].
+///
+/// # Example
+///
+/// ```
+/// #[frame_support::pallet]
+/// mod pallet {
+/// # 	use frame_support::pallet_prelude::*;
+/// # 	#[pallet::config]
+/// # 	pub trait Config: frame_system::Config {}
+/// # 	#[pallet::pallet]
+/// # 	pub struct Pallet<T>(_);
+/// 	/// Lists of `u32` per era, stored in pages of 64 values.
+/// 	#[pallet::storage]
+/// 	pub type Foo<T> = StoragePagedMap<_, Twox64Concat, u32, u32, ConstU32<64>>;
+///
+/// 	/// Alternative named syntax.
+/// 	#[pallet::storage]
+/// 	pub type Bar<T> = StoragePagedMap<
+/// 		Hasher = Twox64Concat,
+/// 		Key = u32,
+/// 		Value = u32,
+/// 		ValuesPerPage = ConstU32<64>,
+/// 	>;
+/// }
+/// ```
+pub struct StoragePagedMap<Prefix, Hasher, Key, Value, ValuesPerPage, MaxPages = GetDefault>(
+	core::marker::PhantomData<(Prefix, Hasher, Key, Value, ValuesPerPage, MaxPages)>,
+);
+
+// Private helper trait to access the pages and meta of a paged map.
+trait PagedMapWrapper {
+	type Pages;
+	type Meta;
+}
+
+impl<P: PagedStorageInstance, H, K, V, S, M> PagedMapWrapper for StoragePagedMap<P, H, K, V, S, M>
+where
+	S: Get<u32>,
+{
+	type Pages = StorageDoubleMap<
+		P,
+		H,
+		K,
+		Twox64Concat,
+		PageIndex,
+		BoundedVec<V, S>,
+		OptionQuery,
+		GetDefault,
+		M,
+	>;
+	type Meta = StorageMap<<P as PagedStorageInstance>::MetaPrefix, H, K, PagedMeta, OptionQuery>;
+}
+
+impl<Prefix, Hasher, Key, Value, ValuesPerPage, MaxPages>
+	StoragePagedMap<Prefix, Hasher, Key, Value, ValuesPerPage, MaxPages>
+where
+	Prefix: PagedStorageInstance,
+	Hasher: crate::hash::StorageHasher,
+	Key: FullCodec,
+	Value: FullCodec + 'static,
+	ValuesPerPage: Get<u32> + 'static,
+	MaxPages: Get<Option<u32>>,
+{
+	/// The key used to store the paging metadata of `key`.
+	pub fn meta_storage_final_key<KArg: EncodeLike<Key>>(key: KArg) -> Vec<u8> {
+		<Self as PagedMapWrapper>::Meta::hashed_key_for(key)
+	}
+
+	/// The prefix used to generate the keys of the pages.
+	pub fn pages_storage_final_prefix() -> Vec<u8> {
+		use crate::storage::generator::StorageDoubleMap;
+		<Self as PagedMapWrapper>::Pages::prefix_hash().to_vec()
+	}
+
+	/// The paging metadata of `key`, `None` if its list is empty.
+	pub fn meta<KArg: EncodeLike<Key>>(key: KArg) -> Option<PagedMeta> {
+		<Self as PagedMapWrapper>::Meta::get(key)
+	}
+
+	/// Whether `key` holds a non-empty list.
+	pub fn contains_key<KArg: EncodeLike<Key>>(key: KArg) -> bool {
+		<Self as PagedMapWrapper>::Meta::contains_key(key)
+	}
+
+	/// The number of values in the list of `key`.
+	pub fn len<KArg: EncodeLike<Key>>(key: KArg) -> u64 {
+		Self::meta(key).map_or(0, |meta| meta.len())
+	}
+
+	/// The values stored in page `index` of `key`.
+	pub fn page<KArg: EncodeLike<Key>>(
+		key: KArg,
+		index: PageIndex,
+	) -> Option<BoundedVec<Value, ValuesPerPage>> {
+		<Self as PagedMapWrapper>::Pages::get(key, index)
+	}
+
+	/// Append a single value to the list of `key`.
+	///
+	/// See [`Self::append_many`] for when this fails.
+	pub fn append_one<KArg: EncodeLike<Key> + Clone>(key: KArg, item: Value) -> Result<(), ()> {
+		Self::append_many(key, core::iter::once(item))
+	}
+
+	/// Append all `items` to the list of `key`.
+	///
+	/// Only the last page and the meta of `key` are written to, new pages are started as needed.
+	///
+	/// Fails without appending anything if `ValuesPerPage` is lower than the page size the list of
+	/// `key` was created with. The list has to be cleared before values can be appended again.
+	pub fn append_many<KArg: EncodeLike<Key> + Clone>(
+		key: KArg,
+		items: impl IntoIterator<Item = Value>,
+	) -> Result<(), ()> {
+		let meta = Self::meta(key.clone());
+		let new_meta = append_paged::<_, ValuesPerPage>(meta, items, |page, item| {
+			<Self as PagedMapWrapper>::Pages::try_append::<_, _, Value, _>(key.clone(), page, item)
+		})?;
+		if new_meta != meta {
+			<Self as PagedMapWrapper>::Meta::set(key, new_meta);
+		}
+		Ok(())
+	}
+
+	/// Iterate over all values of the list of `key`, in insertion order.
+	pub fn iter<KArg: EncodeLike<Key> + Clone>(
+		key: KArg,
+	) -> PagedIterator<Value, impl FnMut(PageIndex) -> Option<Vec<Value>>> {
+		let first_page = Self::meta(key.clone()).map_or(0, |meta| meta.first_page);
+		Self::iter_from(key, PagedCursor { page: first_page, index: 0 })
+	}
+
+	/// Iterate over the values of the list of `key`, starting at `cursor`.
+	pub fn iter_from<KArg: EncodeLike<Key> + Clone>(
+		key: KArg,
+		cursor: PagedCursor,
+	) -> PagedIterator<Value, impl FnMut(PageIndex) -> Option<Vec<Value>>> {
+		PagedIterator::new(Self::meta(key.clone()), cursor, move |page| {
+			Self::page(key.clone(), page).map(Into::into)
+		})
+	}
+
+	/// Remove up to `limit` pages from the front of the list of `key`.
+	///
+	/// Returns [`MultiRemovalResults`](sp_io::MultiRemovalResults) to inform about the result. Once
+	/// the resultant `maybe_cursor` field is `None`, the list is empty and `key` is removed.
+	/// Otherwise it holds the encoded [`PagedCursor`] of the first remaining value.
+	///
+	/// Progress is tracked in the [`PagedMeta`], so no cursor needs to be passed back in and
+	/// values can still be appended between calls.
+	pub fn clear<KArg: EncodeLike<Key> + Clone>(key: KArg, limit: u32) -> MultiRemovalResults {
+		let (meta, mut result) = clear_paged(Self::meta(key.clone()), limit, |page| {
+			<Self as PagedMapWrapper>::Pages::remove(key.clone(), page)
+		});
+		if result.unique > 0 {
+			<Self as PagedMapWrapper>::Meta::set(key, meta);
+			result.backend.saturating_inc();
+		}
+		result
+	}
+
+	/// Remove as many pages from the front of the list of `key` as `meter` allows, consuming
+	/// `weight_per_page` for each removed page.
+	///
+	/// See [`Self::clear`] for the returned result.
+	pub fn clear_with_meter<KArg: EncodeLike<Key> + Clone>(
+		key: KArg,
+		meter: &mut WeightMeter,
+		weight_per_page: Weight,
+	) -> MultiRemovalResults {
+		let result = Self::clear(key, meter_limit(meter, weight_per_page));
+		meter.consume(weight_per_page.saturating_mul(result.loops.into()));
+		result
+	}
+}
+
+impl<Prefix, Hasher, Key, Value, ValuesPerPage, MaxPages>
+	StoragePagedMap<Prefix, Hasher, Key, Value, ValuesPerPage, MaxPages>
+where
+	Prefix: PagedStorageInstance,
+	Hasher: crate::hash::StorageHasher + ReversibleStorageHasher,
+	Key: FullCodec,
+	Value: FullCodec + 'static,
+	ValuesPerPage: Get<u32> + 'static,
+	MaxPages: Get<Option<u32>>,
+{
+	/// Enumerate all keys holding a non-empty list in no particular order.
+	///
+	/// If you alter the map while doing this, you'll get undefined results.
+	pub fn iter_keys() -> crate::storage::KeyPrefixIterator<Key> {
+		<Self as PagedMapWrapper>::Meta::iter_keys()
+	}
+}
+
+impl<Prefix, Hasher, Key, Value, ValuesPerPage, MaxPages> StorageEntryMetadataBuilder
+	for StoragePagedMap<Prefix, Hasher, Key, Value, ValuesPerPage, MaxPages>
+where
+	Prefix: PagedStorageInstance,
+	Hasher: crate::hash::StorageHasher,
+	Key: FullCodec + scale_info::StaticTypeInfo,
+	Value: FullCodec + scale_info::StaticTypeInfo,
+	ValuesPerPage: Get<u32> + 'static,
+	MaxPages: Get<Option<u32>>,
+{
+	fn build_metadata(
+		deprecation_status: sp_metadata_ir::DeprecationStatusIR,
+		docs: Vec<&'static str>,
+		entries: &mut Vec<StorageEntryMetadataIR>,
+	) {
+		<Self as PagedMapWrapper>::Pages::build_metadata(deprecation_status.clone(), docs, entries);
+		<Self as PagedMapWrapper>::Meta::build_metadata(
+			deprecation_status,
+			if cfg!(feature = "no-metadata-docs") {
+				vec![]
+			} else {
+				vec!["Paging metadata for the related paged storage map"]
+			},
+			entries,
+		);
+	}
+}
+
+impl<Prefix, Hasher, Key, Value, ValuesPerPage, MaxPages> crate::traits::StorageInfoTrait
+	for StoragePagedMap<Prefix, Hasher, Key, Value, ValuesPerPage, MaxPages>
+where
+	Prefix: PagedStorageInstance,
+	Hasher: crate::hash::StorageHasher,
+	Key: FullCodec + MaxEncodedLen,
+	Value: FullCodec + MaxEncodedLen + 'static,
+	ValuesPerPage: Get<u32> + 'static,
+	MaxPages: Get<Option<u32>>,
+{
+	fn storage_info() -> Vec<StorageInfo> {
+		[
+			<Self as PagedMapWrapper>::Pages::storage_info(),
+			<Self as PagedMapWrapper>::Meta::storage_info(),
+		]
+		.concat()
+	}
+}
+
+/// It doesn't require to implement `MaxEncodedLen` and give no information for `max_size`.
+impl<Prefix, Hasher, Key, Value, ValuesPerPage, MaxPages> crate::traits::PartialStorageInfoTrait
+	for StoragePagedMap<Prefix, Hasher, Key, Value, ValuesPerPage, MaxPages>
+where
+	Prefix: PagedStorageInstance,
+	Hasher: crate::hash::StorageHasher,
+	Key: FullCodec,
+	Value: FullCodec + 'static,
+	ValuesPerPage: Get<u32> + 'static,
+	MaxPages: Get<Option<u32>>,
+{
+	fn partial_storage_info() -> Vec<StorageInfo> {
+		[
+			<Self as PagedMapWrapper>::Pages::partial_storage_info(),
+			<Self as PagedMapWrapper>::Meta::partial_storage_info(),
+		]
+		.concat()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		hash::*,
+		traits::{ConstU32, StorageInstance},
+	};
+	use codec::Encode;
+	use sp_io::{hashing::twox_128, TestExternalities};
+
+	struct Prefix;
+	impl StorageInstance for Prefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "foo";
+	}
+
+	struct MetaPrefix;
+	impl StorageInstance for MetaPrefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "paged_meta_for_foo";
+	}
+	impl PagedStorageInstance for Prefix {
+		type MetaPrefix = MetaPrefix;
+	}
+
+	type A = StoragePagedMap<Prefix, Twox64Concat, u16, u32, ConstU32<3>>;
+
+	#[test]
+	fn lists_are_independent_per_key() {
+		TestExternalities::default().execute_with(|| {
+			A::append_many(1, 0..5).unwrap();
+			A::append_many(2, 10..12).unwrap();
+
+			assert_eq!(A::len(1), 5);
+			assert_eq!(A::len(2), 2);
+			assert!(!A::contains_key(3));
+			assert_eq!(A::iter(1).collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+			assert_eq!(A::iter(2).collect::<Vec<_>>(), vec![10, 11]);
+			assert_eq!(A::page(1, 1).unwrap().into_inner(), vec![3, 4]);
+
+			let mut keys = A::iter_keys().collect::<Vec<_>>();
+			keys.sort();
+			assert_eq!(keys, vec![1, 2]);
+
+			let mut k: Vec<u8> = vec![];
+			k.extend(&twox_128(b"test"));
+			k.extend(&twox_128(b"paged_meta_for_foo"));
+			k.extend(&1u16.twox_64_concat());
+			assert_eq!(A::meta_storage_final_key(1), k);
+		});
+	}
+
+	#[test]
+	fn cursor_and_clear_work() {
+		TestExternalities::default().execute_with(|| {
+			A::append_many(1, 0..7).unwrap();
+			A::append_one(2, 10).unwrap();
+
+			let mut iter = A::iter(1);
+			assert_eq!(iter.by_ref().take(3).collect::<Vec<_>>(), vec![0, 1, 2]);
+			let cursor = iter.cursor().unwrap();
+			assert_eq!(A::iter_from(1, cursor).collect::<Vec<_>>(), vec![3, 4, 5, 6]);
+
+			let result = A::clear(1, 2);
+			assert_eq!(result.unique, 2);
+			assert_eq!(result.maybe_cursor, Some(PagedCursor { page: 2, index: 0 }.encode()));
+			assert_eq!(A::iter(1).collect::<Vec<_>>(), vec![6]);
+
+			let mut meter = WeightMeter::new();
+			let result = A::clear_with_meter(1, &mut meter, Weight::from_parts(1, 1));
+			assert_eq!(result.unique, 1);
+			assert_eq!(result.maybe_cursor, None);
+			assert!(!A::contains_key(1));
+			assert_eq!(A::iter_keys().collect::<Vec<_>>(), vec![2]);
+			assert_eq!(A::iter(2).collect::<Vec<_>>(), vec![10]);
+		});
+	}
+}
@@ -0,0 +1,667 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage paged value type. Stores a logical list of values split over bounded pages.
+
+use crate::{
+	storage::{
+		bounded_vec::BoundedVec,
+		types::{OptionQuery, StorageEntryMetadataBuilder, StorageMap, StorageValue},
+	},
+	traits::{Get, GetDefault, StorageInfo, StorageInstance},
+	Twox64Concat,
+};
+use alloc::{vec, vec::Vec};
+use codec::{Decode, Encode, FullCodec, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_arithmetic::traits::SaturatedConversion;
+use sp_io::MultiRemovalResults;
+use sp_metadata_ir::StorageEntryMetadataIR;
+use sp_runtime::{traits::Saturating, RuntimeDebug};
+use sp_weights::{Weight, WeightMeter};
+
+/// A list of values stored over multiple pages of at most `ValuesPerPage` values each.
+///
+/// Unlike a [`StorageValue`] holding a `Vec`, appending to a paged value only touches the last
+/// page and the paging metadata, and reading can be spread over multiple blocks with a
+/// [`PagedCursor`]. This keeps the proof size of every operation bounded by the page size
+/// rather than by the length of the whole list.
+///
+/// The pages are stored as a [`StorageMap`] from [`PageIndex`] to
+/// `BoundedVec<Value, ValuesPerPage>` under the storage prefix of the item. The [`PagedMeta`]
+/// is stored as a [`StorageValue`] under [`PagedStorageInstance::MetaPrefix`]. Both are exposed
+/// in the metadata, so clients can reconstruct the logical value by reading the meta and then
+/// the pages from `first_page` to `last_page`.
+///
+/// Values can only be appended. Pages are removed from the front with [`Self::clear`] or
+/// [`Self::clear_with_meter`], which bound the amount of work done per call.
+///
+/// `MaxPages` is only used to report [`StorageInfo`], it is not enforced.
+///
+/// For general information regarding the `#[pallet::storage]` attribute, refer to
+/// [`crate::pallet_macros::storage`].
+///
+/// # Example
+///
+/// ```
+/// #[frame_support::pallet]
+/// mod pallet {
+/// # 	use frame_support::pallet_prelude::*;
+/// # 	#[pallet::config]
+/// # 	pub trait Config: frame_system::Config {}
+/// # 	#[pallet::pallet]
+/// # 	pub struct Pallet<T>(_);
+/// 	/// A list of `u32` stored in pages of 64 values.
+/// 	#[pallet::storage]
+/// 	pub type Foo<T> = StoragePagedValue<_, u32, ConstU32<64>>;
+///
+/// 	/// Alternative named syntax.
+/// 	#[pallet::storage]
+/// 	pub type Bar<T> = StoragePagedValue<Value = u32, ValuesPerPage = ConstU32<64>>;
+/// }
+/// ```
+pub struct StoragePagedValue<Prefix, Value, ValuesPerPage, MaxPages = GetDefault>(
+	core::marker::PhantomData<(Prefix, Value, ValuesPerPage, MaxPages)>,
+);
+
+/// The requirement for an instance of [`StoragePagedValue`] and
+/// [`StoragePagedMap`](super::StoragePagedMap).
+pub trait PagedStorageInstance: StorageInstance {
+	/// The prefix to use for the paging metadata.
+	type MetaPrefix: StorageInstance;
+}
+
+/// The index of a page.
+pub type PageIndex = u32;
+
+/// Book-keeping of a paged list.
+///
+/// All pages from `first_page` up to but excluding `last_page` hold exactly `page_size` values.
+#[derive(
+	Encode, Decode, MaxEncodedLen, Clone, Copy, PartialEq, Eq, Default, RuntimeDebug, TypeInfo,
+)]
+pub struct PagedMeta {
+	/// The first page that still holds values.
+	pub first_page: PageIndex,
+	/// The page that values are appended to.
+	pub last_page: PageIndex,
+	/// The number of values in `last_page`.
+	pub last_page_len: u32,
+	/// The number of values per page, fixed when the list is created.
+	pub page_size: u32,
+}
+
+impl PagedMeta {
+	/// The number of values in the list.
+	pub fn len(&self) -> u64 {
+		let full_pages = self.last_page.saturating_sub(self.first_page) as u64;
+		full_pages
+			.saturating_mul(self.page_size.into())
+			.saturating_add(self.last_page_len.into())
+	}
+
+	/// Whether the list holds no values.
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// The number of pages in the list.
+	pub fn page_count(&self) -> u32 {
+		self.last_page.saturating_sub(self.first_page).saturating_add(1)
+	}
+}
+
+/// Position of a value in a paged list, used to resume iteration.
+#[derive(
+	Encode, Decode, MaxEncodedLen, Clone, Copy, PartialEq, Eq, Default, RuntimeDebug, TypeInfo,
+)]
+pub struct PagedCursor {
+	/// The page of the value.
+	pub page: PageIndex,
+	/// The index of the value within its page.
+	pub index: u32,
+}
+
+/// Iterator over the values of a paged list.
+///
+/// Pages are loaded lazily, one at a time. Use [`Self::cursor`] to get the position of the next
+/// value and resume from it later with `iter_from`.
+pub struct PagedIterator<Value, Fetch> {
+	page: PageIndex,
+	index: u32,
+	last_page: PageIndex,
+	last_page_len: u32,
+	values: Option<vec::IntoIter<Value>>,
+	fetch: Fetch,
+}
+
+impl<Value, Fetch> PagedIterator<Value, Fetch>
+where
+	Fetch: FnMut(PageIndex) -> Option<Vec<Value>>,
+{
+	/// Start iterating `meta` from `cursor`.
+	///
+	/// A cursor pointing to a page that was cleared in the meantime resumes from the first
+	/// remaining value.
+	pub(crate) fn new(meta: Option<PagedMeta>, cursor: PagedCursor, fetch: Fetch) -> Self {
+		let (mut page, mut index, last_page, last_page_len) = match meta {
+			Some(m) => (cursor.page, cursor.index, m.last_page, m.last_page_len),
+			None => (1, 0, 0, 0),
+		};
+		if let Some(m) = meta {
+			if page < m.first_page {
+				page = m.first_page;
+				index = 0;
+			}
+		}
+		Self { page, index, last_page, last_page_len, values: None, fetch }
+	}
+
+	/// The position of the next value, or `None` if the iterator is exhausted.
+	pub fn cursor(&self) -> Option<PagedCursor> {
+		let exhausted = self.page > self.last_page ||
+			(self.page == self.last_page && self.index >= self.last_page_len);
+		(!exhausted).then_some(PagedCursor { page: self.page, index: self.index })
+	}
+}
+
+impl<Value, Fetch> Iterator for PagedIterator<Value, Fetch>
+where
+	Fetch: FnMut(PageIndex) -> Option<Vec<Value>>,
+{
+	type Item = Value;
+
+	fn next(&mut self) -> Option<Value> {
+		loop {
+			if self.page > self.last_page {
+				return None
+			}
+			if self.values.is_none() {
+				let mut values = (self.fetch)(self.page).unwrap_or_default().into_iter();
+				if self.index > 0 {
+					values.nth(self.index as usize - 1);
+				}
+				self.values = Some(values);
+			}
+			if let Some(value) = self.values.as_mut().and_then(Iterator::next) {
+				self.index.saturating_inc();
+				return Some(value)
+			}
+			self.values = None;
+			self.index = 0;
+			match self.page.checked_add(1) {
+				Some(next) => self.page = next,
+				None => {
+					self.last_page = 0;
+					self.page = 1;
+				},
+			}
+		}
+	}
+}
+
+/// Append `items` to the list described by `meta`, pushing each value with `push`.
+///
+/// Returns the updated meta, or `None` if the list is still empty.
+///
+/// Fails without pushing anything if `ValuesPerPage` was lowered below the page size of the list,
+/// since the pages of the list could then no longer hold `page_size` values each.
+pub(crate) fn append_paged<Value, ValuesPerPage: Get<u32>>(
+	meta: Option<PagedMeta>,
+	items: impl IntoIterator<Item = Value>,
+	mut push: impl FnMut(PageIndex, Value) -> Result<(), ()>,
+) -> Result<Option<PagedMeta>, ()> {
+	if meta.map_or(false, |m| m.page_size > ValuesPerPage::get()) {
+		return Err(())
+	}
+	let mut meta = meta;
+	for item in items {
+		let m = meta.get_or_insert_with(|| PagedMeta {
+			page_size: ValuesPerPage::get().max(1),
+			..Default::default()
+		});
+		if m.last_page_len >= m.page_size {
+			m.last_page.saturating_inc();
+			m.last_page_len = 0;
+		}
+		push(m.last_page, item)?;
+		m.last_page_len.saturating_inc();
+	}
+	Ok(meta)
+}
+
+/// Remove up to `limit` pages from the front of the list described by `meta`, removing each page
+/// with `remove`.
+///
+/// Returns the updated meta, `None` once the list is empty, along with the removal results.
+pub(crate) fn clear_paged(
+	meta: Option<PagedMeta>,
+	limit: u32,
+	mut remove: impl FnMut(PageIndex),
+) -> (Option<PagedMeta>, MultiRemovalResults) {
+	let mut result = MultiRemovalResults { maybe_cursor: None, backend: 0, unique: 0, loops: 0 };
+	let Some(mut meta) = meta else { return (None, result) };
+
+	while result.unique < limit {
+		remove(meta.first_page);
+		result.unique.saturating_inc();
+		if meta.first_page >= meta.last_page {
+			result.backend = result.unique;
+			result.loops = result.unique;
+			return (None, result)
+		}
+		meta.first_page.saturating_inc();
+	}
+
+	result.backend = result.unique;
+	result.loops = result.unique;
+	result.maybe_cursor = Some(PagedCursor { page: meta.first_page, index: 0 }.encode());
+	(Some(meta), result)
+}
+
+/// The number of pages that can be removed with the weight left in `meter`.
+pub(crate) fn meter_limit(meter: &WeightMeter, weight_per_page: Weight) -> u32 {
+	meter
+		.remaining()
+		.checked_div_per_component(&weight_per_page)
+		.unwrap_or(u64::MAX)
+		.saturated_into()
+}
+
+// Private helper trait to access the pages and meta of a paged value.
+trait PagedValueWrapper {
+	type Pages;
+	type Meta;
+}
+
+impl<P: PagedStorageInstance, V, S, M> PagedValueWrapper for StoragePagedValue<P, V, S, M>
+where
+	S: Get<u32>,
+{
+	type Pages =
+		StorageMap<P, Twox64Concat, PageIndex, BoundedVec<V, S>, OptionQuery, GetDefault, M>;
+	type Meta = StorageValue<<P as PagedStorageInstance>::MetaPrefix, PagedMeta, OptionQuery>;
+}
+
+impl<Prefix, Value, ValuesPerPage, MaxPages>
+	StoragePagedValue<Prefix, Value, ValuesPerPage, MaxPages>
+where
+	Prefix: PagedStorageInstance,
+	Value: FullCodec + 'static,
+	ValuesPerPage: Get<u32> + 'static,
+	MaxPages: Get<Option<u32>>,
+{
+	/// The key used to store the paging metadata.
+	pub fn meta_storage_final_key() -> [u8; 32] {
+		<Self as PagedValueWrapper>::Meta::hashed_key()
+	}
+
+	/// The prefix used to generate the keys of the pages.
+	pub fn pages_storage_final_prefix() -> Vec<u8> {
+		use crate::storage::generator::StorageMap;
+		<Self as PagedValueWrapper>::Pages::prefix_hash().to_vec()
+	}
+
+	/// The paging metadata, `None` if the list is empty.
+	pub fn meta() -> Option<PagedMeta> {
+		<Self as PagedValueWrapper>::Meta::get()
+	}
+
+	/// The number of values in the list.
+	pub fn len() -> u64 {
+		Self::meta().map_or(0, |meta| meta.len())
+	}
+
+	/// Whether the list holds no values.
+	pub fn is_empty() -> bool {
+		Self::meta().is_none()
+	}
+
+	/// The values stored in page `index`.
+	pub fn page(index: PageIndex) -> Option<BoundedVec<Value, ValuesPerPage>> {
+		<Self as PagedValueWrapper>::Pages::get(index)
+	}
+
+	/// Append a single value to the list.
+	///
+	/// See [`Self::append_many`] for when this fails.
+	pub fn append_one(item: Value) -> Result<(), ()> {
+		Self::append_many(core::iter::once(item))
+	}
+
+	/// Append all `items` to the list.
+	///
+	/// Only the last page and the meta are written to, new pages are started as needed.
+	///
+	/// Fails without appending anything if `ValuesPerPage` is lower than the page size the list was
+	/// created with. The list has to be cleared before values can be appended again.
+	pub fn append_many(items: impl IntoIterator<Item = Value>) -> Result<(), ()> {
+		let meta = Self::meta();
+		let new_meta = append_paged::<_, ValuesPerPage>(meta, items, |page, item| {
+			<Self as PagedValueWrapper>::Pages::try_append::<_, Value, _>(page, item)
+		})?;
+		if new_meta != meta {
+			<Self as PagedValueWrapper>::Meta::set(new_meta);
+		}
+		Ok(())
+	}
+
+	/// Iterate over all values of the list, in insertion order.
+	pub fn iter() -> PagedIterator<Value, impl FnMut(PageIndex) -> Option<Vec<Value>>> {
+		let first_page = Self::meta().map_or(0, |meta| meta.first_page);
+		Self::iter_from(PagedCursor { page: first_page, index: 0 })
+	}
+
+	/// Iterate over the values of the list, starting at `cursor`.
+	pub fn iter_from(
+		cursor: PagedCursor,
+	) -> PagedIterator<Value, impl FnMut(PageIndex) -> Option<Vec<Value>>> {
+		PagedIterator::new(Self::meta(), cursor, |page| Self::page(page).map(Into::into))
+	}
+
+	/// Remove up to `limit` pages from the front of the list.
+	///
+	/// Returns [`MultiRemovalResults`](sp_io::MultiRemovalResults) to inform about the result. Once
+	/// the resultant `maybe_cursor` field is `None`, the list is empty. Otherwise it holds the
+	/// encoded [`PagedCursor`] of the first remaining value.
+	///
+	/// Unlike [`StorageMap::clear`], progress is tracked in the [`PagedMeta`], so no cursor needs
+	/// to be passed back in and values can still be appended between calls.
+	pub fn clear(limit: u32) -> MultiRemovalResults {
+		let (meta, mut result) = clear_paged(Self::meta(), limit, |page| {
+			<Self as PagedValueWrapper>::Pages::remove(page)
+		});
+		if result.unique > 0 {
+			<Self as PagedValueWrapper>::Meta::set(meta);
+			result.backend.saturating_inc();
+		}
+		result
+	}
+
+	/// Remove as many pages from the front of the list as `meter` allows, consuming
+	/// `weight_per_page` for each removed page.
+	///
+	/// See [`Self::clear`] for the returned result.
+	pub fn clear_with_meter(
+		meter: &mut WeightMeter,
+		weight_per_page: Weight,
+	) -> MultiRemovalResults {
+		let result = Self::clear(meter_limit(meter, weight_per_page));
+		meter.consume(weight_per_page.saturating_mul(result.loops.into()));
+		result
+	}
+}
+
+impl<Prefix, Value, ValuesPerPage, MaxPages> StorageEntryMetadataBuilder
+	for StoragePagedValue<Prefix, Value, ValuesPerPage, MaxPages>
+where
+	Prefix: PagedStorageInstance,
+	Value: FullCodec + scale_info::StaticTypeInfo,
+	ValuesPerPage: Get<u32> + 'static,
+	MaxPages: Get<Option<u32>>,
+{
+	fn build_metadata(
+		deprecation_status: sp_metadata_ir::DeprecationStatusIR,
+		docs: Vec<&'static str>,
+		entries: &mut Vec<StorageEntryMetadataIR>,
+	) {
+		<Self as PagedValueWrapper>::Pages::build_metadata(
+			deprecation_status.clone(),
+			docs,
+			entries,
+		);
+		<Self as PagedValueWrapper>::Meta::build_metadata(
+			deprecation_status,
+			if cfg!(feature = "no-metadata-docs") {
+				vec![]
+			} else {
+				vec!["Paging metadata for the related paged storage value"]
+			},
+			entries,
+		);
+	}
+}
+
+impl<Prefix, Value, ValuesPerPage, MaxPages> crate::traits::StorageInfoTrait
+	for StoragePagedValue<Prefix, Value, ValuesPerPage, MaxPages>
+where
+	Prefix: PagedStorageInstance,
+	Value: FullCodec + MaxEncodedLen + 'static,
+	ValuesPerPage: Get<u32> + 'static,
+	MaxPages: Get<Option<u32>>,
+{
+	fn storage_info() -> Vec<StorageInfo> {
+		[
+			<Self as PagedValueWrapper>::Pages::storage_info(),
+			<Self as PagedValueWrapper>::Meta::storage_info(),
+		]
+		.concat()
+	}
+}
+
+/// It doesn't require to implement `MaxEncodedLen` and give no information for `max_size`.
+impl<Prefix, Value, ValuesPerPage, MaxPages> crate::traits::PartialStorageInfoTrait
+	for StoragePagedValue<Prefix, Value, ValuesPerPage, MaxPages>
+where
+	Prefix: PagedStorageInstance,
+	Value: FullCodec + 'static,
+	ValuesPerPage: Get<u32> + 'static,
+	MaxPages: Get<Option<u32>>,
+{
+	fn partial_storage_info() -> Vec<StorageInfo> {
+		[
+			<Self as PagedValueWrapper>::Pages::partial_storage_info(),
+			<Self as PagedValueWrapper>::Meta::storage_info(),
+		]
+		.concat()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::traits::ConstU32;
+	use sp_io::{hashing::twox_128, TestExternalities};
+	use sp_metadata_ir::{StorageEntryModifierIR, StorageEntryTypeIR, StorageHasherIR};
+
+	struct Prefix;
+	impl StorageInstance for Prefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "foo";
+	}
+
+	struct MetaPrefix;
+	impl StorageInstance for MetaPrefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "paged_meta_for_foo";
+	}
+	impl PagedStorageInstance for Prefix {
+		type MetaPrefix = MetaPrefix;
+	}
+
+	type A = StoragePagedValue<Prefix, u32, ConstU32<3>>;
+
+	#[test]
+	fn append_and_iter_works() {
+		TestExternalities::default().execute_with(|| {
+			assert!(A::is_empty());
+			assert_eq!(A::iter().collect::<Vec<_>>(), Vec::<u32>::new());
+
+			A::append_one(0).unwrap();
+			A::append_many(1..8).unwrap();
+
+			assert_eq!(A::len(), 8);
+			assert_eq!(
+				A::meta(),
+				Some(PagedMeta { first_page: 0, last_page: 2, last_page_len: 2, page_size: 3 })
+			);
+			assert_eq!(A::page(0).unwrap().into_inner(), vec![0, 1, 2]);
+			assert_eq!(A::page(2).unwrap().into_inner(), vec![6, 7]);
+			assert_eq!(A::iter().collect::<Vec<_>>(), (0..8).collect::<Vec<_>>());
+
+			let mut k: Vec<u8> = vec![];
+			k.extend(&twox_128(b"test"));
+			k.extend(&twox_128(b"paged_meta_for_foo"));
+			assert_eq!(A::meta_storage_final_key().to_vec(), k);
+		});
+	}
+
+	#[test]
+	fn cursor_resumes_iteration() {
+		TestExternalities::default().execute_with(|| {
+			A::append_many(0..8).unwrap();
+
+			let mut iter = A::iter();
+			assert_eq!(iter.by_ref().take(4).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+			let cursor = iter.cursor().unwrap();
+			assert_eq!(cursor, PagedCursor { page: 1, index: 1 });
+
+			assert_eq!(A::iter_from(cursor).collect::<Vec<_>>(), vec![4, 5, 6, 7]);
+
+			let mut iter = A::iter_from(cursor);
+			assert_eq!(iter.by_ref().count(), 4);
+			assert_eq!(iter.cursor(), None);
+		});
+	}
+
+	#[test]
+	fn clear_removes_pages_from_the_front() {
+		TestExternalities::default().execute_with(|| {
+			A::append_many(0..8).unwrap();
+
+			let result = A::clear(2);
+			assert_eq!(result.unique, 2);
+			assert_eq!(result.backend, 3);
+			assert_eq!(result.maybe_cursor, Some(PagedCursor { page: 2, index: 0 }.encode()));
+			assert_eq!(A::iter().collect::<Vec<_>>(), vec![6, 7]);
+			assert_eq!(A::len(), 2);
+
+			// A cursor into a cleared page resumes from the first remaining value.
+			assert_eq!(
+				A::iter_from(PagedCursor { page: 0, index: 2 }).collect::<Vec<_>>(),
+				vec![6, 7]
+			);
+
+			// Appending between calls is fine.
+			A::append_one(8).unwrap();
+			assert_eq!(A::iter().collect::<Vec<_>>(), vec![6, 7, 8]);
+
+			let result = A::clear(2);
+			assert_eq!(result.unique, 1);
+			assert_eq!(result.maybe_cursor, None);
+			assert!(A::is_empty());
+			assert_eq!(A::page(2), None);
+			assert!(!sp_io::storage::exists(&A::meta_storage_final_key()));
+
+			// A new list starts from the first page again.
+			A::append_one(9).unwrap();
+			assert_eq!(A::meta().unwrap().first_page, 0);
+			assert_eq!(A::iter().collect::<Vec<_>>(), vec![9]);
+		});
+	}
+
+	#[test]
+	fn clear_with_meter_works() {
+		TestExternalities::default().execute_with(|| {
+			A::append_many(0..9).unwrap();
+
+			let weight_per_page = Weight::from_parts(10, 0);
+			let mut meter = WeightMeter::with_limit(Weight::from_parts(25, 0));
+			let result = A::clear_with_meter(&mut meter, weight_per_page);
+			assert_eq!(result.unique, 2);
+			assert!(result.maybe_cursor.is_some());
+			assert_eq!(meter.consumed(), Weight::from_parts(20, 0));
+
+			let mut meter = WeightMeter::new();
+			let result = A::clear_with_meter(&mut meter, weight_per_page);
+			assert_eq!(result.unique, 1);
+			assert_eq!(result.maybe_cursor, None);
+			assert!(A::is_empty());
+		});
+	}
+
+	#[test]
+	fn page_size_is_fixed_per_list() {
+		TestExternalities::default().execute_with(|| {
+			A::append_many(0..4).unwrap();
+			type B = StoragePagedValue<Prefix, u32, ConstU32<5>>;
+
+			B::append_many(4..6).unwrap();
+			assert_eq!(B::meta().unwrap().page_size, 3);
+			assert_eq!(B::iter().collect::<Vec<_>>(), (0..6).collect::<Vec<_>>());
+		});
+	}
+
+	#[test]
+	fn append_fails_if_page_size_was_lowered() {
+		TestExternalities::default().execute_with(|| {
+			A::append_many(0..4).unwrap();
+			let meta = A::meta();
+			type B = StoragePagedValue<Prefix, u32, ConstU32<2>>;
+
+			assert_eq!(B::append_one(4), Err(()));
+			assert_eq!(B::append_many(4..6), Err(()));
+			assert_eq!(B::meta(), meta);
+			assert_eq!(B::iter().collect::<Vec<_>>(), (0..4).collect::<Vec<_>>());
+
+			// Once cleared, the list starts over with the lowered page size.
+			let _ = B::clear(u32::MAX);
+			B::append_many(0..3).unwrap();
+			assert_eq!(B::meta().unwrap().page_size, 2);
+			assert_eq!(B::page(1).unwrap().into_inner(), vec![2]);
+		});
+	}
+
+	#[test]
+	fn metadata_works() {
+		let mut entries = vec![];
+		A::build_metadata(sp_metadata_ir::DeprecationStatusIR::NotDeprecated, vec![], &mut entries);
+		assert_eq!(
+			entries,
+			vec![
+				StorageEntryMetadataIR {
+					name: "foo",
+					modifier: StorageEntryModifierIR::Optional,
+					ty: StorageEntryTypeIR::Map {
+						hashers: vec![StorageHasherIR::Twox64Concat],
+						key: scale_info::meta_type::<PageIndex>(),
+						value: scale_info::meta_type::<BoundedVec<u32, ConstU32<3>>>(),
+					},
+					default: Option::<BoundedVec<u32, ConstU32<3>>>::None.encode(),
+					docs: vec![],
+					deprecation_info: sp_metadata_ir::DeprecationStatusIR::NotDeprecated,
+				},
+				StorageEntryMetadataIR {
+					name: "paged_meta_for_foo",
+					modifier: StorageEntryModifierIR::Optional,
+					ty: StorageEntryTypeIR::Plain(scale_info::meta_type::<PagedMeta>()),
+					default: Option::<PagedMeta>::None.encode(),
+					docs: if cfg!(feature = "no-metadata-docs") {
+						vec![]
+					} else {
+						vec!["Paging metadata for the related paged storage value"]
+					},
+					deprecation_info: sp_metadata_ir::DeprecationStatusIR::NotDeprecated,
+				},
+			]
+		);
+	}
+}
@@ -22,6 +22,7 @@ use codec::{Decode, Encode};
 
 /// Return the value of the item in storage under `key`, or `None` if there is no explicit entry.
 pub fn get<T: Decode + Sized>(key: &[u8]) -> Option<T> {
+	crate::weight_refund::note_read();
 	sp_io::storage::get(key).and_then(|val| {
 		Decode::decode(&mut &val[..]).map(Some).unwrap_or_else(|e| {
 			// TODO #3700: error should be handleable.
@@ -56,6 +57,7 @@ pub fn get_or_else<T: Decode + Sized, F: FnOnce() -> T>(key: &[u8], default_valu
 
 /// Put `value` in storage under `key`.
 pub fn put<T: Encode + ?Sized>(key: &[u8], value: &T) {
+	crate::weight_refund::note_write();
 	value.using_encoded(|slice| sp_io::storage::set(key, slice));
 }
 
@@ -88,11 +90,13 @@ pub fn take_or_else<T: Decode + Sized, F: FnOnce() -> T>(key: &[u8], default_val
 
 /// Check to see if `key` has an explicit entry in storage.
 pub fn exists(key: &[u8]) -> bool {
+	crate::weight_refund::note_read();
 	sp_io::storage::exists(key)
 }
 
 /// Ensure `key` has no explicit entry in storage.
 pub fn kill(key: &[u8]) {
+	crate::weight_refund::note_write();
 	sp_io::storage::clear(key);
 }
 
@@ -102,7 +106,12 @@ pub fn kill_prefix(prefix: &[u8], limit: Option<u32>) -> sp_io::KillStorageResul
 	// TODO: Once the network has upgraded to include the new host functions, this code can be
 	// enabled.
 	// clear_prefix(prefix, limit).into()
-	sp_io::storage::clear_prefix(prefix, limit)
+	let result = sp_io::storage::clear_prefix(prefix, limit);
+	let removed = match result {
+		sp_io::KillStorageResult::AllRemoved(i) | sp_io::KillStorageResult::SomeRemaining(i) => i,
+	};
+	crate::weight_refund::note_writes(removed.into());
+	result
 }
 
 /// Partially clear the storage of all keys under a common `prefix`.
@@ -166,6 +175,7 @@ pub fn contains_prefixed_key(prefix: &[u8]) -> bool {
 
 /// Get a Vec of bytes from storage.
 pub fn get_raw(key: &[u8]) -> Option<Vec<u8>> {
+	crate::weight_refund::note_read();
 	sp_io::storage::get(key).map(|value| value.to_vec())
 }
 
@@ -175,5 +185,6 @@ pub fn get_raw(key: &[u8]) -> Option<Vec<u8>> {
 /// you should also call `frame_system::RuntimeUpgraded::put(true)` to trigger the
 /// `on_runtime_upgrade` logic.
 pub fn put_raw(key: &[u8], value: &[u8]) {
+	crate::weight_refund::note_write();
 	sp_io::storage::set(key, value)
 }
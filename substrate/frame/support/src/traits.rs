@@ -100,13 +100,20 @@ pub use storage::{
 	StorageInfo, StorageInfoTrait, StorageInstance, TrackedStorageKey, WhitelistedStorageKeys,
 };
 
+mod storage_layout;
+pub use storage_layout::{
+	check_storage_layouts, storage_layout_fingerprint, CheckStorageLayouts, PalletStorageLayout,
+	StorageLayoutFingerprint, StorageLayouts,
+};
+
 mod dispatch;
 #[allow(deprecated)]
 pub use dispatch::EnsureOneOf;
 pub use dispatch::{
-	AsEnsureOriginWithArg, CallerTrait, EitherOf, EitherOfDiverse, EnsureOrigin,
+	AsEnsureOriginWithArg, Authorize, CallerTrait, EitherOf, EitherOfDiverse, EnsureOrigin,
 	EnsureOriginEqualOrHigherPrivilege, EnsureOriginWithArg, MapSuccess, NeverEnsureOrigin,
-	OriginTrait, TryMapSuccess, TryWithMorphedArg, UnfilteredDispatchable,
+	OriginTrait, TransactionValidityWithRefund, TryMapSuccess, TryWithMorphedArg,
+	UnfilteredDispatchable,
 };
 
 mod voting;
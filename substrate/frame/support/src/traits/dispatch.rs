@@ -22,8 +22,10 @@ use codec::MaxEncodedLen;
 use core::{cmp::Ordering, marker::PhantomData};
 use sp_runtime::{
 	traits::{BadOrigin, Get, Member, Morph, TryMorph},
+	transaction_validity::{TransactionSource, TransactionValidityError, ValidTransaction},
 	Either,
 };
+use sp_weights::Weight;
 
 use super::misc;
 
@@ -449,6 +451,33 @@ pub trait UnfilteredDispatchable {
 	fn dispatch_bypass_filter(self, origin: Self::RuntimeOrigin) -> DispatchResultWithPostInfo;
 }
 
+/// The result of the authorization of a call: the validity of the transaction and the weight
+/// refunded from the worst case weight of the authorization, see [`Authorize`].
+pub type TransactionValidityWithRefund =
+	Result<(ValidTransaction, Weight), TransactionValidityError>;
+
+/// Authorize a call, giving it the system `Authorized` origin.
+///
+/// Implemented for pallet dispatchable type by the [`pallet`](crate::pallet) macro, using the
+/// functions given to the [`authorize`](crate::pallet_macros::authorize) attribute, and for
+/// runtime dispatchable by `construct_runtime`.
+///
+/// It is used by the `AuthorizeCall` transaction extension of `frame_system` to give the
+/// [`RawOrigin::Authorized`] origin to unsigned transactions that are authorized by the call
+/// they contain.
+pub trait Authorize {
+	/// The authorize function.
+	///
+	/// Returns
+	/// * `Some(Ok((valid_transaction, unspent weight)))` if the call is successfully authorized,
+	/// * `Some(Err(error))` if the call authorization is invalid,
+	/// * `None` if the call doesn't provide any authorization.
+	fn authorize(&self, source: TransactionSource) -> Option<TransactionValidityWithRefund>;
+
+	/// The worst case weight of the authorization of the call.
+	fn weight_of_authorize(&self) -> Weight;
+}
+
 /// The trait implemented by the overarching enumeration of the different pallets' origins.
 /// Unlike `OriginTrait` impls, this does not include any kind of dispatch/call filter. Also, this
 /// trait is more flexible in terms of how it can be used: it is a `Parameter` and `Member`, so it
@@ -474,6 +503,11 @@ pub trait CallerTrait<AccountId>: Parameter + Member + From<RawOrigin<AccountId>
 	fn is_none(&self) -> bool {
 		self.as_system_ref().map_or(false, RawOrigin::is_none)
 	}
+
+	/// Returns `true` if `self` is a system `Authorized` origin, `false` otherwise.
+	fn is_authorized(&self) -> bool {
+		self.as_system_ref().map_or(false, RawOrigin::is_authorized)
+	}
 }
 
 /// Methods available on `frame_system::Config::RuntimeOrigin`.
@@ -0,0 +1,363 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fingerprints of the storage layout of pallets.
+//!
+//! The storage layout of a pallet is the encoding of the keys and values of all its storage items,
+//! as described by their [`scale_info`] types. Changing it requires a migration and a bump of the
+//! [`StorageVersion`] of the pallet. [`check_storage_layouts`] compares the layouts of a runtime
+//! against the ones of the previously released runtime and reports pallets whose layout changed
+//! without such a version bump. [`CheckStorageLayouts`] runs this check as part of the
+//! `try-runtime` upgrade checks.
+
+use super::{OnRuntimeUpgrade, StorageVersion};
+use alloc::{string::String, vec, vec::Vec};
+use codec::{Decode, Encode};
+use core::marker::PhantomData;
+use impl_trait_for_tuples::impl_for_tuples;
+use scale_info::{IntoPortable, PortableRegistry, Registry, TypeDef};
+use sp_metadata_ir::{PalletStorageMetadataIR, StorageEntryTypeIR};
+use sp_runtime::RuntimeDebug;
+
+#[cfg(feature = "try-runtime")]
+use sp_core::Get;
+#[cfg(feature = "try-runtime")]
+use sp_runtime::TryRuntimeError;
+
+/// Provides the fingerprint of the storage layout of a pallet.
+///
+/// Implemented for every pallet by the [`pallet`](crate::pallet) macro.
+pub trait StorageLayoutFingerprint {
+	/// The fingerprint of the storage layout of all storage items of the pallet.
+	fn storage_layout_fingerprint() -> [u8; 32];
+
+	/// The in-code storage version of the pallet, which must be bumped whenever the fingerprint
+	/// changes.
+	fn storage_layout_version() -> StorageVersion;
+}
+
+/// The storage layout of a pallet together with its storage versions.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub struct PalletStorageLayout {
+	/// Name of the pallet.
+	pub pallet: String,
+	/// The in-code storage version of the pallet.
+	pub in_code_storage_version: StorageVersion,
+	/// The on-chain storage version of the pallet.
+	pub on_chain_storage_version: StorageVersion,
+	/// The fingerprint of the storage layout, see [`StorageLayoutFingerprint`].
+	pub fingerprint: [u8; 32],
+}
+
+/// Provides the storage layouts of one or more pallets.
+///
+/// Implemented for every pallet by the [`pallet`](crate::pallet) macro and for tuples of pallets,
+/// like `AllPalletsWithSystem`.
+pub trait StorageLayouts {
+	/// The storage layouts of all pallets represented by this type.
+	fn storage_layouts() -> Vec<PalletStorageLayout>;
+}
+
+#[cfg_attr(all(not(feature = "tuples-96"), not(feature = "tuples-128")), impl_for_tuples(64))]
+#[cfg_attr(all(feature = "tuples-96", not(feature = "tuples-128")), impl_for_tuples(96))]
+#[cfg_attr(feature = "tuples-128", impl_for_tuples(128))]
+impl StorageLayouts for Tuple {
+	fn storage_layouts() -> Vec<PalletStorageLayout> {
+		let mut res = vec![];
+		for_tuples!( #( res.extend(Tuple::storage_layouts()); )* );
+		res
+	}
+}
+
+/// Compute the fingerprint of the storage layout described by `metadata`.
+///
+/// The fingerprint covers the name, the hashers and the structure of the key and value types of
+/// every storage entry. It does not depend on the order of the entries, on documentation, on
+/// default values or on the names of types, fields and variants, since none of them affect the
+/// encoding of stored data.
+pub fn storage_layout_fingerprint(metadata: PalletStorageMetadataIR) -> [u8; 32] {
+	let mut registry = Registry::new();
+	let mut entries = metadata.into_portable(&mut registry).entries;
+	entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+	let mut hasher =
+		LayoutHasher { types: &PortableRegistry::from(registry), visiting: vec![], out: vec![] };
+	for entry in entries {
+		entry.name.encode_to(&mut hasher.out);
+		match entry.ty {
+			StorageEntryTypeIR::Plain(value) => {
+				0u8.encode_to(&mut hasher.out);
+				hasher.ty(value.id);
+			},
+			StorageEntryTypeIR::Map { hashers, key, value } => {
+				1u8.encode_to(&mut hasher.out);
+				hashers.encode_to(&mut hasher.out);
+				hasher.ty(key.id);
+				hasher.ty(value.id);
+			},
+		}
+	}
+
+	sp_io::hashing::blake2_256(&hasher.out)
+}
+
+/// Serializes the structure of types.
+struct LayoutHasher<'a> {
+	types: &'a PortableRegistry,
+	/// Types that are currently being serialized, to handle recursive types.
+	visiting: Vec<u32>,
+	out: Vec<u8>,
+}
+
+impl LayoutHasher<'_> {
+	fn ty(&mut self, id: u32) {
+		if let Some(depth) = self.visiting.iter().position(|v| *v == id) {
+			// Recursive type, refer to the type by its position on the stack.
+			(b'r', depth as u32).encode_to(&mut self.out);
+			return
+		}
+		let types = self.types;
+		let Some(ty) = types.resolve(id) else {
+			b'?'.encode_to(&mut self.out);
+			return
+		};
+
+		self.visiting.push(id);
+		match &ty.type_def {
+			TypeDef::Composite(composite) => {
+				(b'c', composite.fields.len() as u32).encode_to(&mut self.out);
+				self.fields(composite.fields.iter().map(|f| f.ty.id));
+			},
+			TypeDef::Variant(variant) => {
+				(b'v', variant.variants.len() as u32).encode_to(&mut self.out);
+				for v in &variant.variants {
+					(v.index, v.fields.len() as u32).encode_to(&mut self.out);
+					self.fields(v.fields.iter().map(|f| f.ty.id));
+				}
+			},
+			TypeDef::Sequence(sequence) => {
+				b's'.encode_to(&mut self.out);
+				self.ty(sequence.type_param.id);
+			},
+			TypeDef::Array(array) => {
+				(b'a', array.len).encode_to(&mut self.out);
+				self.ty(array.type_param.id);
+			},
+			TypeDef::Tuple(tuple) => {
+				(b't', tuple.fields.len() as u32).encode_to(&mut self.out);
+				self.fields(tuple.fields.iter().map(|f| f.id));
+			},
+			TypeDef::Primitive(primitive) => {
+				(b'p', primitive).encode_to(&mut self.out);
+			},
+			TypeDef::Compact(compact) => {
+				b'C'.encode_to(&mut self.out);
+				self.ty(compact.type_param.id);
+			},
+			TypeDef::BitSequence(bits) => {
+				b'b'.encode_to(&mut self.out);
+				self.ty(bits.bit_store_type.id);
+				self.ty(bits.bit_order_type.id);
+			},
+		}
+		self.visiting.pop();
+	}
+
+	fn fields(&mut self, ids: impl Iterator<Item = u32>) {
+		for id in ids {
+			self.ty(id);
+		}
+	}
+}
+
+/// Compare the storage layouts of a runtime against the ones of the previously released runtime.
+///
+/// `previous` is usually a snapshot of [`StorageLayouts::storage_layouts`] taken from the released
+/// runtime and `current` the storage layouts of the new runtime after all migrations were run, for
+/// example in a `try-runtime` check. For every pallet that is part of both and whose fingerprint
+/// changed, the in-code storage version must have been bumped and the on-chain storage version
+/// must match the in-code version, which shows that a migration was applied.
+///
+/// Returns the names of all offending pallets as error.
+pub fn check_storage_layouts(
+	previous: &[PalletStorageLayout],
+	current: &[PalletStorageLayout],
+) -> Result<(), Vec<String>> {
+	let mut offending = vec![];
+	for layout in current {
+		let Some(prev) = previous.iter().find(|prev| prev.pallet == layout.pallet) else {
+			continue
+		};
+		if prev.fingerprint == layout.fingerprint {
+			continue
+		}
+
+		if layout.in_code_storage_version <= prev.in_code_storage_version {
+			log::error!(
+				target: "runtime::storage_layout",
+				"Storage layout of `{}` changed without bumping its storage version {:?}",
+				layout.pallet,
+				layout.in_code_storage_version,
+			);
+			offending.push(layout.pallet.clone());
+		} else if layout.on_chain_storage_version != layout.in_code_storage_version {
+			log::error!(
+				target: "runtime::storage_layout",
+				"Storage layout of `{}` changed, but no migration to {:?} was applied, the on-chain \
+				storage version is {:?}",
+				layout.pallet,
+				layout.in_code_storage_version,
+				layout.on_chain_storage_version,
+			);
+			offending.push(layout.pallet.clone());
+		}
+	}
+
+	if offending.is_empty() {
+		Ok(())
+	} else {
+		Err(offending)
+	}
+}
+
+/// Checks in `try-runtime` that every pallet whose storage layout changed since the previously
+/// released runtime was migrated.
+///
+/// `Released` provides the pallet names, in-code storage versions and storage layout fingerprints
+/// of the released runtime, as found under the
+/// [`STORAGE_LAYOUT_FINGERPRINTS`](sp_metadata_ir::STORAGE_LAYOUT_FINGERPRINTS) key of its
+/// unstable metadata. See [`check_storage_layouts`] for the performed checks.
+///
+/// This must be the last of the migrations passed to `frame_executive::Executive`, so that it
+/// observes the storage versions set by the other migrations. It does nothing outside of
+/// `try-runtime`.
+pub struct CheckStorageLayouts<Pallets, Released>(PhantomData<(Pallets, Released)>);
+
+#[cfg(not(feature = "try-runtime"))]
+impl<Pallets, Released> OnRuntimeUpgrade for CheckStorageLayouts<Pallets, Released> {}
+
+#[cfg(feature = "try-runtime")]
+impl<Pallets, Released> OnRuntimeUpgrade for CheckStorageLayouts<Pallets, Released>
+where
+	Pallets: StorageLayouts,
+	Released: Get<Vec<(String, u16, [u8; 32])>>,
+{
+	fn post_upgrade(_: Vec<u8>) -> Result<(), TryRuntimeError> {
+		let previous = Released::get()
+			.into_iter()
+			.map(|(pallet, version, fingerprint)| PalletStorageLayout {
+				pallet,
+				in_code_storage_version: StorageVersion::new(version),
+				on_chain_storage_version: StorageVersion::new(version),
+				fingerprint,
+			})
+			.collect::<Vec<_>>();
+
+		check_storage_layouts(&previous, &Pallets::storage_layouts()).map_err(|_| {
+			"Storage layout changed without a storage version bump and migration".into()
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use scale_info::{meta_type, TypeInfo};
+	use sp_metadata_ir::{StorageEntryMetadataIR, StorageEntryModifierIR, StorageHasherIR};
+
+	fn storage<V: TypeInfo + 'static>(name: &'static str, docs: &'static str) -> [u8; 32] {
+		storage_layout_fingerprint(PalletStorageMetadataIR {
+			prefix: "Pallet",
+			entries: vec![StorageEntryMetadataIR {
+				name,
+				modifier: StorageEntryModifierIR::Optional,
+				ty: StorageEntryTypeIR::Map {
+					hashers: vec![StorageHasherIR::Twox64Concat],
+					key: meta_type::<u32>(),
+					value: meta_type::<V>(),
+				},
+				default: vec![],
+				docs: vec![docs],
+				deprecation_info: sp_metadata_ir::DeprecationStatusIR::NotDeprecated,
+			}],
+		})
+	}
+
+	#[derive(TypeInfo)]
+	#[allow(dead_code)]
+	struct A {
+		a: u32,
+		b: Option<u64>,
+	}
+
+	#[derive(TypeInfo)]
+	#[allow(dead_code)]
+	struct RenamedA {
+		x: u32,
+		y: Option<u64>,
+	}
+
+	#[derive(TypeInfo)]
+	#[allow(dead_code)]
+	struct B {
+		a: u32,
+		b: Option<u32>,
+	}
+
+	#[derive(TypeInfo)]
+	#[allow(dead_code)]
+	struct Recursive {
+		next: Option<alloc::boxed::Box<Recursive>>,
+	}
+
+	#[test]
+	fn fingerprint_only_depends_on_layout() {
+		assert_eq!(storage::<A>("Item", "Docs"), storage::<RenamedA>("Item", "Other docs"));
+		assert_ne!(storage::<A>("Item", ""), storage::<B>("Item", ""));
+		assert_ne!(storage::<A>("Item", ""), storage::<A>("Other", ""));
+		assert_eq!(storage::<Recursive>("Item", ""), storage::<Recursive>("Item", ""));
+	}
+
+	fn layout(version: u16, on_chain: u16, fingerprint: u8) -> PalletStorageLayout {
+		PalletStorageLayout {
+			pallet: "Pallet".into(),
+			in_code_storage_version: StorageVersion::new(version),
+			on_chain_storage_version: StorageVersion::new(on_chain),
+			fingerprint: [fingerprint; 32],
+		}
+	}
+
+	#[test]
+	fn check_storage_layouts_works() {
+		// Unchanged layout.
+		assert_eq!(check_storage_layouts(&[layout(1, 1, 0)], &[layout(1, 1, 0)]), Ok(()));
+		// Changed layout with version bump and migration.
+		assert_eq!(check_storage_layouts(&[layout(1, 1, 0)], &[layout(2, 2, 1)]), Ok(()));
+		// Changed layout without version bump.
+		assert_eq!(
+			check_storage_layouts(&[layout(1, 1, 0)], &[layout(1, 1, 1)]),
+			Err(vec!["Pallet".into()])
+		);
+		// Changed layout with version bump, but without migration.
+		assert_eq!(
+			check_storage_layouts(&[layout(1, 1, 0)], &[layout(2, 1, 1)]),
+			Err(vec!["Pallet".into()])
+		);
+		// New pallets are ignored.
+		assert_eq!(check_storage_layouts(&[], &[layout(1, 1, 0)]), Ok(()));
+	}
+}
@@ -20,11 +20,11 @@
 use super::StorageInstance;
 use crate::{
 	storage::types::{
-		CountedStorageMapInstance, CountedStorageNMapInstance, Counter, KeyGenerator,
-		QueryKindTrait,
+		CountedStorageMapInstance, CountedStorageNMapInstance, Counter, KeyGenerator, PagedMeta,
+		PagedStorageInstance, QueryKindTrait,
 	},
 	traits::{PartialStorageInfoTrait, StorageInfo},
-	StorageHasher,
+	BoundedVec, StorageHasher,
 };
 use alloc::{vec, vec::Vec};
 use codec::{Decode, DecodeAll, FullCodec};
@@ -292,6 +292,46 @@ where
 	}
 }
 
+impl<Prefix, Value, ValuesPerPage, MaxPages> TryDecodeEntireStorage
+	for crate::storage::types::StoragePagedValue<Prefix, Value, ValuesPerPage, MaxPages>
+where
+	Prefix: PagedStorageInstance,
+	Value: FullCodec + 'static,
+	ValuesPerPage: Get<u32> + 'static,
+	MaxPages: Get<Option<u32>>,
+{
+	fn try_decode_entire_state() -> Result<usize, Vec<TryDecodeEntireStorageError>> {
+		let (pages_info, meta_info) = match &Self::partial_storage_info()[..] {
+			[a, b] => (a.clone(), b.clone()),
+			_ => panic!("Paged value has two storage info items; qed"),
+		};
+		let mut decoded = decode_storage_info::<PagedMeta>(meta_info)?;
+		decoded += decode_storage_info::<BoundedVec<Value, ValuesPerPage>>(pages_info)?;
+		Ok(decoded)
+	}
+}
+
+impl<Prefix, Hasher, Key, Value, ValuesPerPage, MaxPages> TryDecodeEntireStorage
+	for crate::storage::types::StoragePagedMap<Prefix, Hasher, Key, Value, ValuesPerPage, MaxPages>
+where
+	Prefix: PagedStorageInstance,
+	Hasher: StorageHasher,
+	Key: FullCodec,
+	Value: FullCodec + 'static,
+	ValuesPerPage: Get<u32> + 'static,
+	MaxPages: Get<Option<u32>>,
+{
+	fn try_decode_entire_state() -> Result<usize, Vec<TryDecodeEntireStorageError>> {
+		let (pages_info, meta_info) = match &Self::partial_storage_info()[..] {
+			[a, b] => (a.clone(), b.clone()),
+			_ => panic!("Paged map has two storage info items; qed"),
+		};
+		let mut decoded = decode_storage_info::<PagedMeta>(meta_info)?;
+		decoded += decode_storage_info::<BoundedVec<Value, ValuesPerPage>>(pages_info)?;
+		Ok(decoded)
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
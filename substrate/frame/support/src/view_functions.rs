@@ -0,0 +1,147 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Traits and types to dispatch pallet view functions.
+//!
+//! View functions are read-only queries declared inside a pallet with
+//! `#[pallet::view_functions]`. `construct_runtime!` aggregates them into a single
+//! [`DispatchViewFunction`] implementation on the runtime, which can then be exposed to clients
+//! through the generic [`runtime_api::RuntimeViewFunction`] runtime API.
+
+use alloc::vec::Vec;
+use codec::{Decode, DecodeAll, Encode, Output};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+
+/// The unique identifier of a view function.
+///
+/// It is made of the twox128 hash of the pallet name (the prefix) and the twox128 hash of the
+/// view function signature (the suffix).
+#[derive(Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct ViewFunctionId {
+	/// The part of the id identifying the pallet.
+	pub prefix: [u8; 16],
+	/// The part of the id identifying the view function inside of the pallet.
+	pub suffix: [u8; 16],
+}
+
+impl From<ViewFunctionId> for [u8; 32] {
+	fn from(value: ViewFunctionId) -> Self {
+		let mut output = [0u8; 32];
+		output[..16].copy_from_slice(&value.prefix);
+		output[16..].copy_from_slice(&value.suffix);
+		output
+	}
+}
+
+/// Error returned when dispatching a view function.
+#[derive(Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum ViewFunctionDispatchError {
+	/// View functions are not implemented for this runtime.
+	NotImplemented,
+	/// A view function with the given id was not found.
+	NotFound(ViewFunctionId),
+	/// Failed to decode the view function input or to encode its output.
+	Codec,
+}
+
+impl From<codec::Error> for ViewFunctionDispatchError {
+	fn from(_: codec::Error) -> Self {
+		ViewFunctionDispatchError::Codec
+	}
+}
+
+/// Implemented by both pallets and the runtime. The runtime is dispatching by prefix using the
+/// pallet implementation of `ViewFunctionIdPrefix` then the pallet is dispatching by suffix using
+/// the methods implementation of `ViewFunctionIdSuffix`.
+///
+/// In more details, `ViewFunctionId` = `ViewFunctionIdPrefix` ++ `ViewFunctionIdSuffix`, where
+/// `ViewFunctionIdPrefix=twox_128(pallet_name)` and
+/// `ViewFunctionIdSuffix=twox_128("fn_name(fnarg_types) -> return_ty")`.
+pub trait DispatchViewFunction {
+	/// Dispatch the view function with the given `id`, decoding its arguments from `input` and
+	/// writing the encoded result into `output`.
+	fn dispatch_view_function<O: Output>(
+		id: &ViewFunctionId,
+		input: &mut &[u8],
+		output: &mut O,
+	) -> Result<(), ViewFunctionDispatchError>;
+}
+
+impl DispatchViewFunction for () {
+	fn dispatch_view_function<O: Output>(
+		id: &ViewFunctionId,
+		_input: &mut &[u8],
+		_output: &mut O,
+	) -> Result<(), ViewFunctionDispatchError> {
+		Err(ViewFunctionDispatchError::NotFound(id.clone()))
+	}
+}
+
+/// Automatically implemented for each pallet by the macro [`pallet`](crate::pallet).
+pub trait ViewFunctionIdPrefix {
+	/// The prefix of all the view function ids of the pallet.
+	fn prefix() -> [u8; 16];
+}
+
+/// Automatically implemented for each pallet view function method by the macro
+/// [`pallet`](crate::pallet).
+pub trait ViewFunctionIdSuffix {
+	/// The suffix identifying the view function inside of its pallet.
+	const SUFFIX: [u8; 16];
+}
+
+/// Automatically implemented for each pallet view function method by the macro
+/// [`pallet`](crate::pallet).
+pub trait ViewFunction: DecodeAll {
+	/// The full id of the view function.
+	fn id() -> ViewFunctionId;
+	/// The type returned by the view function.
+	type ReturnType: Encode;
+
+	/// Run the view function with the decoded arguments.
+	fn invoke(self) -> Self::ReturnType;
+
+	/// Decode the arguments from `input`, run the view function and write the encoded result
+	/// into `output`.
+	fn execute<O: Output>(
+		input: &mut &[u8],
+		output: &mut O,
+	) -> Result<(), ViewFunctionDispatchError> {
+		let view_function = Self::decode_all(input)?;
+		let result = view_function.invoke();
+		Encode::encode_to(&result, output);
+		Ok(())
+	}
+}
+
+/// Runtime API to execute pallet view functions.
+pub mod runtime_api {
+	use super::*;
+
+	sp_api::decl_runtime_apis! {
+		#[api_version(1)]
+		/// Runtime API for executing view functions
+		pub trait RuntimeViewFunction {
+			/// Execute a view function query.
+			fn execute_view_function(
+				query_id: ViewFunctionId,
+				input: Vec<u8>,
+			) -> Result<Vec<u8>, ViewFunctionDispatchError>;
+		}
+	}
+}
@@ -0,0 +1,338 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Automatic weight refunds for dispatchables.
+//!
+//! Calls annotated with
+//! [`#[pallet::weight_refund(auto(..))]`](frame_support::pallet_macros::weight_refund) are
+//! executed through [`dispatch_with_auto_refund`]. While the call runs, every storage operation
+//! that goes through the FRAME storage APIs is counted and the size of the storage proof is sampled
+//! before and after the call. The measured resources are converted into an actual weight which is
+//! put into the [`PostDispatchInfo`](crate::dispatch::PostDispatchInfo) of the call, so that the
+//! existing weight accounting (`CheckWeight` and the transaction payment extension) refunds the
+//! difference against the pre-dispatch weight.
+//!
+//! # Ref time
+//!
+//! Only the storage part of the ref time is refunded. The call declares how many storage reads and
+//! writes its pre-dispatch weight accounts for, and the ref time of the storage operations that
+//! were declared but not done, priced with the [`RuntimeDbWeight`] of the runtime, is subtracted
+//! from the pre-dispatch weight. The rest of the pre-dispatch weight, which covers the computation
+//! of the call, is always charged.
+//!
+//! # Metered storage operations
+//!
+//! The following operations are counted:
+//!
+//! - reads and writes of [`crate::storage::unhashed`] and [`crate::storage::child`], which all the
+//!   storage types are built on,
+//! - appends and length reads (`decode_len`) of the storage types,
+//! - removals of keys by prefix or of whole child tries, one write per removed key.
+//!
+//! Iterating over storage (`next_key`), streaming reads of
+//! [`StorageStreamIter`](crate::storage::StorageStreamIter) and direct calls to `sp_io::storage`
+//! or `sp_io::default_child_storage` are *not* counted. Calls doing such operations must not use
+//! the automatic refund, or must not declare these operations as refundable reads and writes.
+//!
+//! # Proof size
+//!
+//! The proof size is measured using the [`StorageProofSize`] configured in `frame_system`. For
+//! parachains this is usually backed by the `storage_proof_size` host function which reports the
+//! size of the proof recorded so far. If no proof size is available, for example on a solo chain
+//! that is not recording a proof, the proof size of the pre-dispatch weight is kept.
+//!
+//! In all cases the actual weight never exceeds the pre-dispatch weight and an actual weight that
+//! was explicitly returned by the call takes precedence.
+
+use crate::{
+	dispatch::{DispatchResultWithPostInfo, PostDispatchInfo},
+	weights::{RuntimeDbWeight, Weight},
+};
+use sp_runtime::Saturating;
+
+environmental::environmental!(STORAGE_OPS: StorageOps);
+
+/// Number of storage operations done while being metered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StorageOps {
+	/// Number of storage reads.
+	pub reads: u64,
+	/// Number of storage writes.
+	pub writes: u64,
+}
+
+/// Note that a storage read happened.
+///
+/// Does nothing if not called inside of [`meter`].
+pub fn note_read() {
+	STORAGE_OPS::with(|ops| ops.reads.saturating_inc());
+}
+
+/// Note that a storage write happened.
+///
+/// Does nothing if not called inside of [`meter`].
+pub fn note_write() {
+	note_writes(1);
+}
+
+/// Note that `count` storage writes happened.
+///
+/// Does nothing if not called inside of [`meter`].
+pub fn note_writes(count: u64) {
+	STORAGE_OPS::with(|ops| ops.writes.saturating_accrue(count));
+}
+
+/// Provides the size of the storage proof recorded so far.
+pub trait StorageProofSize {
+	/// Returns the current size of the storage proof in bytes.
+	///
+	/// Returns `None` if no storage proof is being recorded.
+	fn storage_proof_size() -> Option<u64>;
+}
+
+impl StorageProofSize for () {
+	fn storage_proof_size() -> Option<u64> {
+		None
+	}
+}
+
+/// The resources measured by [`meter`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MeteredResources {
+	/// The storage operations done.
+	pub storage_ops: StorageOps,
+	/// The growth of the storage proof in bytes, if it could be measured.
+	pub proof_size: Option<u64>,
+}
+
+impl MeteredResources {
+	/// Convert the measured resources into the actual weight of a call with the given pre-dispatch
+	/// weight.
+	///
+	/// `declared` are the storage operations accounted for in `pre_dispatch_weight`. Only the ref
+	/// time of the declared operations which were not done is refunded.
+	///
+	/// The result is never bigger than `pre_dispatch_weight`.
+	pub fn actual_weight(
+		&self,
+		pre_dispatch_weight: Weight,
+		declared: StorageOps,
+		db_weight: RuntimeDbWeight,
+	) -> Weight {
+		let declared_cost = db_weight.reads_writes(declared.reads, declared.writes).ref_time();
+		let metered_cost = db_weight
+			.reads_writes(self.storage_ops.reads, self.storage_ops.writes)
+			.ref_time();
+		let ref_time = pre_dispatch_weight
+			.ref_time()
+			.saturating_sub(declared_cost.saturating_sub(metered_cost));
+		let proof_size = self.proof_size.unwrap_or(pre_dispatch_weight.proof_size());
+
+		pre_dispatch_weight.min(Weight::from_parts(ref_time, proof_size))
+	}
+}
+
+/// Execute `f` while metering the storage operations and the proof size.
+///
+/// Metering can be nested, the resources measured by an inner call are also accounted to the
+/// outer call.
+pub fn meter<P: StorageProofSize, R>(f: impl FnOnce() -> R) -> (R, MeteredResources) {
+	let proof_size_before = P::storage_proof_size();
+	let mut ops = StorageOps::default();
+	let res = STORAGE_OPS::using(&mut ops, f);
+	let proof_size_after = P::storage_proof_size();
+
+	STORAGE_OPS::with(|outer| {
+		outer.reads.saturating_accrue(ops.reads);
+		outer.writes.saturating_accrue(ops.writes);
+	});
+
+	let proof_size = match (proof_size_before, proof_size_after) {
+		(Some(before), Some(after)) => Some(after.saturating_sub(before)),
+		_ => None,
+	};
+
+	(res, MeteredResources { storage_ops: ops, proof_size })
+}
+
+/// Execute the dispatchable `f` and set its actual weight based on the metered resources.
+///
+/// `declared` are the storage operations accounted for in `pre_dispatch_weight`, see
+/// [`MeteredResources::actual_weight`]. If `f` already returns an actual weight, it is kept as is.
+pub fn dispatch_with_auto_refund<P: StorageProofSize>(
+	pre_dispatch_weight: Weight,
+	declared: StorageOps,
+	db_weight: RuntimeDbWeight,
+	f: impl FnOnce() -> DispatchResultWithPostInfo,
+) -> DispatchResultWithPostInfo {
+	let (res, resources) = meter::<P, _>(f);
+	let actual_weight = resources.actual_weight(pre_dispatch_weight, declared, db_weight);
+
+	let fill = |post_info: &mut PostDispatchInfo| {
+		post_info.actual_weight.get_or_insert(actual_weight);
+	};
+
+	match res {
+		Ok(mut post_info) => {
+			fill(&mut post_info);
+			Ok(post_info)
+		},
+		Err(mut err) => {
+			fill(&mut err.post_info);
+			Err(err)
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::storage::unhashed;
+
+	struct FixedProofSize;
+
+	environmental::environmental!(PROOF_SIZE: u64);
+
+	impl StorageProofSize for FixedProofSize {
+		fn storage_proof_size() -> Option<u64> {
+			PROOF_SIZE::with(|s| {
+				*s += 100;
+				*s
+			})
+		}
+	}
+
+	const DB_WEIGHT: RuntimeDbWeight = RuntimeDbWeight { read: 10, write: 100 };
+
+	#[test]
+	fn meter_counts_storage_operations() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			let (_, resources) = meter::<(), _>(|| {
+				unhashed::put(b"key", &1u32);
+				let _ = unhashed::get::<u32>(b"key");
+				let _ = unhashed::exists(b"key");
+				unhashed::kill(b"key");
+			});
+
+			assert_eq!(resources.storage_ops, StorageOps { reads: 2, writes: 2 });
+			assert_eq!(resources.proof_size, None);
+		});
+	}
+
+	#[test]
+	fn nested_meter_is_accounted_to_outer() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			let (inner, outer) = meter::<(), _>(|| {
+				unhashed::put(b"key", &1u32);
+				meter::<(), _>(|| {
+					let _ = unhashed::get::<u32>(b"key");
+				})
+				.1
+			});
+
+			assert_eq!(inner.storage_ops, StorageOps { reads: 1, writes: 0 });
+			assert_eq!(outer.storage_ops, StorageOps { reads: 1, writes: 1 });
+		});
+	}
+
+	#[test]
+	fn meter_counts_child_storage_and_length_reads() {
+		use crate::storage::{child, StorageDecodeLength};
+
+		sp_io::TestExternalities::default().execute_with(|| {
+			let child_info = child::ChildInfo::new_default(b"child");
+			let (_, resources) = meter::<(), _>(|| {
+				child::put(&child_info, b"key", &vec![1u32]);
+				let _ = child::get::<Vec<u32>>(&child_info, b"key");
+				let _ = child::exists(&child_info, b"key");
+				child::kill(&child_info, b"key");
+				let _ = <Vec<u32> as StorageDecodeLength>::decode_len(b"key");
+			});
+
+			assert_eq!(resources.storage_ops, StorageOps { reads: 3, writes: 2 });
+		});
+	}
+
+	#[test]
+	fn actual_weight_only_refunds_unused_storage() {
+		let pre = Weight::from_parts(1_000, 1_000);
+		let declared = StorageOps { reads: 2, writes: 2 };
+		let metered = |reads, writes| MeteredResources {
+			storage_ops: StorageOps { reads, writes },
+			proof_size: None,
+		};
+
+		// 1 read and 1 write unused, the computation part of the weight is kept.
+		assert_eq!(
+			metered(1, 1).actual_weight(pre, declared, DB_WEIGHT),
+			Weight::from_parts(890, 1_000)
+		);
+		// Nothing done, only the declared storage is refunded.
+		assert_eq!(
+			metered(0, 0).actual_weight(pre, declared, DB_WEIGHT),
+			Weight::from_parts(780, 1_000)
+		);
+		// More than declared, nothing is refunded.
+		assert_eq!(metered(5, 5).actual_weight(pre, declared, DB_WEIGHT), pre);
+		// Declaring more storage than the pre-dispatch weight can't refund more than it.
+		assert_eq!(
+			metered(0, 0).actual_weight(pre, StorageOps { reads: 0, writes: 20 }, DB_WEIGHT),
+			Weight::from_parts(0, 1_000)
+		);
+	}
+
+	#[test]
+	fn dispatch_with_auto_refund_sets_actual_weight() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			let mut proof_size = 0;
+			let pre = Weight::from_parts(1_000, 1_000);
+			let declared = StorageOps { reads: 5, writes: 5 };
+
+			let post = PROOF_SIZE::using(&mut proof_size, || {
+				dispatch_with_auto_refund::<FixedProofSize>(pre, declared, DB_WEIGHT, || {
+					unhashed::put(b"key", &1u32);
+					let _ = unhashed::get::<u32>(b"key");
+					Ok(().into())
+				})
+			})
+			.unwrap();
+
+			// 4 reads and 4 writes are refunded.
+			assert_eq!(post.actual_weight, Some(Weight::from_parts(560, 100)));
+		});
+	}
+
+	#[test]
+	fn dispatch_with_auto_refund_keeps_explicit_weight_and_caps() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			let pre = Weight::from_parts(50, 1_000);
+			let declared = StorageOps { reads: 1, writes: 0 };
+
+			let post = dispatch_with_auto_refund::<()>(pre, declared, DB_WEIGHT, || {
+				Ok(Some(Weight::from_parts(5, 5)).into())
+			})
+			.unwrap();
+			assert_eq!(post.actual_weight, Some(Weight::from_parts(5, 5)));
+
+			let err = dispatch_with_auto_refund::<()>(pre, declared, DB_WEIGHT, || {
+				Err(sp_runtime::DispatchError::Other("fail").into())
+			})
+			.unwrap_err();
+			assert_eq!(err.post_info.actual_weight, Some(Weight::from_parts(40, 1_000)));
+		});
+	}
+}
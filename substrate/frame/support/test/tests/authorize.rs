@@ -0,0 +1,221 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for `#[pallet::authorize]` and the `AuthorizeCall` transaction extension.
+
+use frame_support::{
+	assert_ok, derive_impl,
+	dispatch::{GetDispatchInfo, RawOrigin},
+	pallet_prelude::{InvalidTransaction, TransactionSource::External, Weight},
+	traits::OriginTrait,
+};
+use frame_system::AuthorizeCall;
+use sp_io::TestExternalities;
+use sp_runtime::{
+	traits::{DispatchTransaction, Dispatchable, TransactionExtension},
+	DispatchError,
+};
+
+#[frame_support::pallet(dev_mode)]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::storage]
+	pub type Value<T> = StorageValue<_, u32>;
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		#[pallet::call_index(0)]
+		#[pallet::authorize(|_source, a: &u32| -> TransactionValidityWithRefund {
+			if *a == 42 {
+				let validity = ValidTransaction::with_tag_prefix("Example")
+					.priority(10)
+					.and_provides(a)
+					.build()?;
+				Ok((validity, Weight::from_parts(10, 0)))
+			} else {
+				Err(InvalidTransaction::Call.into())
+			}
+		})]
+		#[pallet::weight_of_authorize(Weight::from_parts(30, 0))]
+		pub fn authorized_call(origin: OriginFor<T>, a: u32) -> DispatchResult {
+			frame_system::ensure_authorized(origin)?;
+			Value::<T>::put(a);
+			Ok(())
+		}
+
+		#[pallet::call_index(1)]
+		#[pallet::authorize(Pallet::<T>::authorize_any)]
+		pub fn authorized_call_with_fn(origin: OriginFor<T>) -> DispatchResult {
+			frame_system::ensure_authorized(origin)?;
+			Ok(())
+		}
+
+		#[pallet::call_index(2)]
+		pub fn not_authorized_call(origin: OriginFor<T>) -> DispatchResult {
+			ensure_signed(origin)?;
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		fn authorize_any(_source: TransactionSource) -> TransactionValidityWithRefund {
+			Ok((ValidTransaction::default(), Weight::zero()))
+		}
+	}
+}
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Runtime {
+	type Block = Block;
+}
+
+impl pallet::Config for Runtime {}
+
+pub type Block = frame_system::mocking::MockBlock<Runtime>;
+
+frame_support::construct_runtime!(
+	pub enum Runtime {
+		System: frame_system,
+		Example: pallet,
+	}
+);
+
+#[test]
+fn valid_authorization_gives_authorized_origin() {
+	TestExternalities::default().execute_with(|| {
+		let call = RuntimeCall::Example(pallet::Call::authorized_call { a: 42 });
+		let info = call.get_dispatch_info();
+
+		let (validity, unspent, origin) = AuthorizeCall::<Runtime>::new()
+			.validate_only(RuntimeOrigin::none(), &call, &info, 0, External, 0)
+			.unwrap();
+		assert_eq!(validity.priority, 10);
+		assert_eq!(unspent, Weight::from_parts(10, 0));
+		assert_eq!(origin.as_system_ref(), Some(&RawOrigin::Authorized));
+
+		let call = RuntimeCall::Example(pallet::Call::authorized_call_with_fn {});
+		let (_, _, origin) = AuthorizeCall::<Runtime>::new()
+			.validate_only(RuntimeOrigin::none(), &call, &info, 0, External, 0)
+			.unwrap();
+		assert_eq!(origin.as_system_ref(), Some(&RawOrigin::Authorized));
+	});
+}
+
+#[test]
+fn invalid_authorization_is_rejected() {
+	TestExternalities::default().execute_with(|| {
+		let call = RuntimeCall::Example(pallet::Call::authorized_call { a: 1 });
+		let info = call.get_dispatch_info();
+
+		assert_eq!(
+			AuthorizeCall::<Runtime>::new()
+				.validate_only(RuntimeOrigin::none(), &call, &info, 0, External, 0)
+				.unwrap_err(),
+			InvalidTransaction::Call.into(),
+		);
+	});
+}
+
+#[test]
+fn calls_without_authorization_are_passed_through() {
+	TestExternalities::default().execute_with(|| {
+		let call = RuntimeCall::Example(pallet::Call::not_authorized_call {});
+		let info = call.get_dispatch_info();
+
+		let (_, unspent, origin) = AuthorizeCall::<Runtime>::new()
+			.validate_only(RuntimeOrigin::none(), &call, &info, 0, External, 0)
+			.unwrap();
+		assert_eq!(unspent, Weight::zero());
+		assert_eq!(origin.as_system_ref(), Some(&RawOrigin::None));
+
+		// Signed transactions are not authorized, even if the call provides an authorization. The
+		// weight of the authorization is not spent.
+		let call = RuntimeCall::Example(pallet::Call::authorized_call { a: 42 });
+		let (_, unspent, origin) = AuthorizeCall::<Runtime>::new()
+			.validate_only(RuntimeOrigin::signed(1), &call, &info, 0, External, 0)
+			.unwrap();
+		assert_eq!(unspent, Weight::from_parts(30, 0));
+		assert_eq!(origin.as_system_ref(), Some(&RawOrigin::Signed(1)));
+	});
+}
+
+#[test]
+fn weight_of_authorize_is_refunded_when_passed_through() {
+	TestExternalities::default().execute_with(|| {
+		let call = RuntimeCall::Example(pallet::Call::authorized_call { a: 42 });
+		let mut info = call.get_dispatch_info();
+		info.extension_weight = AuthorizeCall::<Runtime>::new().weight(&call);
+		assert_eq!(info.extension_weight, Weight::from_parts(30, 0));
+
+		let post_info = AuthorizeCall::<Runtime>::new()
+			.dispatch_transaction(RuntimeOrigin::signed(1), call.clone(), &info, 0, 0)
+			.unwrap()
+			.unwrap_err()
+			.post_info;
+		assert_eq!(post_info.actual_weight, Some(info.call_weight));
+
+		// An authorized call only gets the unspent weight returned by the authorization refunded.
+		let post_info = AuthorizeCall::<Runtime>::new()
+			.dispatch_transaction(RuntimeOrigin::none(), call, &info, 0, 0)
+			.unwrap()
+			.unwrap();
+		assert_eq!(
+			post_info.actual_weight,
+			Some(info.call_weight.saturating_add(Weight::from_parts(20, 0)))
+		);
+	});
+}
+
+#[test]
+fn authorized_call_dispatch_works() {
+	TestExternalities::default().execute_with(|| {
+		let call = RuntimeCall::Example(pallet::Call::authorized_call { a: 42 });
+		let info = call.get_dispatch_info();
+
+		assert_ok!(AuthorizeCall::<Runtime>::new()
+			.dispatch_transaction(RuntimeOrigin::none(), call.clone(), &info, 0, 0)
+			.unwrap());
+		assert_eq!(pallet::Value::<Runtime>::get(), Some(42));
+
+		// The call itself checks the origin.
+		assert_eq!(
+			call.dispatch(RuntimeOrigin::signed(1)).map_err(|e| e.error),
+			Err(DispatchError::BadOrigin),
+		);
+	});
+}
+
+#[test]
+fn weight_of_authorize_works() {
+	let call = RuntimeCall::Example(pallet::Call::authorized_call { a: 42 });
+	assert_eq!(AuthorizeCall::<Runtime>::new().weight(&call), Weight::from_parts(30, 0));
+
+	// Dev mode defaults to zero.
+	let call = RuntimeCall::Example(pallet::Call::authorized_call_with_fn {});
+	assert_eq!(AuthorizeCall::<Runtime>::new().weight(&call), Weight::zero());
+
+	let call = RuntimeCall::Example(pallet::Call::not_authorized_call {});
+	assert_eq!(AuthorizeCall::<Runtime>::new().weight(&call), Weight::zero());
+}
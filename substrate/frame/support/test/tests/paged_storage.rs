@@ -0,0 +1,100 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for paged storage types declared with `#[pallet::storage]`.
+
+use frame_support::{derive_impl, storage::types::PagedMeta, traits::StorageInfoTrait};
+use sp_io::TestExternalities;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::storage]
+	pub type Values<T: Config> = StoragePagedValue<_, u32, ConstU32<2>>;
+
+	#[pallet::storage]
+	pub type Lists<T: Config> = StoragePagedMap<
+		Hasher = Twox64Concat,
+		Key = u32,
+		Value = u64,
+		ValuesPerPage = ConstU32<4>,
+		MaxPages = ConstU32<100>,
+	>;
+}
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Runtime {
+	type Block = Block;
+}
+
+impl pallet::Config for Runtime {}
+
+pub type Block = frame_system::mocking::MockBlock<Runtime>;
+
+frame_support::construct_runtime!(
+	pub enum Runtime {
+		System: frame_system,
+		PagedExample: pallet,
+	}
+);
+
+#[test]
+fn paged_storage_works() {
+	TestExternalities::default().execute_with(|| {
+		pallet::Values::<Runtime>::append_many(0..5).unwrap();
+		assert_eq!(pallet::Values::<Runtime>::iter().collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+		assert_eq!(
+			pallet::Values::<Runtime>::meta(),
+			Some(PagedMeta { first_page: 0, last_page: 2, last_page_len: 1, page_size: 2 })
+		);
+
+		pallet::Lists::<Runtime>::append_many(7, 0..6).unwrap();
+		assert_eq!(pallet::Lists::<Runtime>::len(7), 6);
+		assert_eq!(pallet::Lists::<Runtime>::page(7, 1).unwrap().into_inner(), vec![4, 5]);
+	});
+}
+
+#[test]
+fn paged_storage_metadata_and_info() {
+	let metadata_ir = Runtime::metadata_ir();
+	let pallet = metadata_ir
+		.pallets
+		.iter()
+		.find(|pallet| pallet.name == "PagedExample")
+		.expect("pallet is part of the runtime; qed");
+	let entries = pallet
+		.storage
+		.as_ref()
+		.expect("pallet has storage; qed")
+		.entries
+		.iter()
+		.map(|entry| entry.name)
+		.collect::<Vec<_>>();
+	assert_eq!(entries, vec!["Values", "PagedMetaForValues", "Lists", "PagedMetaForLists"]);
+
+	let info = pallet::Pallet::<Runtime>::storage_info();
+	assert_eq!(info.len(), 4);
+	assert_eq!(info[2].storage_name, b"Lists".to_vec());
+	assert_eq!(info[2].max_values, Some(100));
+}
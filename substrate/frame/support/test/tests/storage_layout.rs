@@ -0,0 +1,195 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for the storage layout fingerprints generated by the pallet macro.
+
+use frame_support::{
+	derive_impl,
+	traits::{
+		check_storage_layouts, PalletStorageLayout, StorageLayoutFingerprint, StorageLayouts,
+		StorageVersion,
+	},
+};
+use sp_io::TestExternalities;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {}
+
+	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	pub struct Pallet<T>(_);
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
+	#[pallet::storage]
+	pub type Value<T: Config> = StorageValue<_, u32>;
+
+	#[pallet::storage]
+	pub type Map<T: Config> = StorageMap<_, Twox64Concat, u32, u64>;
+}
+
+/// Same layout as [`pallet`], but with a different value type in `Map`.
+#[frame_support::pallet]
+pub mod pallet_changed {
+	use frame_support::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	/// Documentation does not change the layout.
+	#[pallet::storage]
+	pub type Value<T: Config> = StorageValue<_, u32>;
+
+	#[pallet::storage]
+	pub type Map<T: Config> = StorageMap<_, Twox64Concat, u32, u32>;
+}
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Runtime {
+	type Block = Block;
+}
+
+impl pallet::Config for Runtime {}
+impl pallet_changed::Config for Runtime {}
+
+pub type Block = frame_system::mocking::MockBlock<Runtime>;
+
+frame_support::construct_runtime!(
+	pub enum Runtime {
+		System: frame_system,
+		Example: pallet,
+		Changed: pallet_changed,
+	}
+);
+
+fn new_test_ext() -> TestExternalities {
+	let mut ext = TestExternalities::default();
+	ext.execute_with(|| {
+		<AllPalletsWithSystem as frame_support::traits::OnGenesis>::on_genesis();
+	});
+	ext
+}
+
+#[test]
+fn storage_layouts_are_reported() {
+	new_test_ext().execute_with(|| {
+		let layouts = AllPalletsWithSystem::storage_layouts();
+		assert_eq!(
+			layouts.iter().map(|l| l.pallet.as_str()).collect::<Vec<_>>(),
+			vec!["System", "Example", "Changed"]
+		);
+
+		let example = &layouts[1];
+		assert_eq!(example.in_code_storage_version, StorageVersion::new(3));
+		assert_eq!(example.on_chain_storage_version, StorageVersion::new(3));
+		assert_eq!(example.fingerprint, pallet::Pallet::<Runtime>::storage_layout_fingerprint());
+		assert_ne!(
+			pallet::Pallet::<Runtime>::storage_layout_fingerprint(),
+			pallet_changed::Pallet::<Runtime>::storage_layout_fingerprint()
+		);
+	});
+}
+
+#[test]
+fn storage_layout_fingerprint_is_in_metadata() {
+	let metadata = Runtime::metadata_ir();
+	let example = metadata.pallets.iter().find(|p| p.name == "Example").unwrap();
+	assert_eq!(
+		example.storage_layout_fingerprint,
+		Some(pallet::Pallet::<Runtime>::storage_layout_fingerprint())
+	);
+	assert_eq!(example.storage_version, Some(3));
+}
+
+#[test]
+fn changed_layout_requires_version_bump_and_migration() {
+	new_test_ext().execute_with(|| {
+		let current = pallet::Pallet::<Runtime>::storage_layouts();
+		let released = |version: u16, fingerprint: [u8; 32]| {
+			vec![PalletStorageLayout {
+				pallet: "Example".into(),
+				in_code_storage_version: StorageVersion::new(version),
+				on_chain_storage_version: StorageVersion::new(version),
+				fingerprint,
+			}]
+		};
+		let changed_fingerprint = pallet_changed::Pallet::<Runtime>::storage_layout_fingerprint();
+
+		// Same layout as the released runtime.
+		assert_eq!(check_storage_layouts(&released(3, current[0].fingerprint), &current), Ok(()));
+		// Layout changed and the version was bumped by a migration.
+		assert_eq!(check_storage_layouts(&released(2, changed_fingerprint), &current), Ok(()));
+		// Layout changed without a version bump.
+		assert_eq!(
+			check_storage_layouts(&released(3, changed_fingerprint), &current),
+			Err(vec!["Example".into()])
+		);
+
+		// Layout changed and the version was bumped, but the migration did not run.
+		StorageVersion::new(2).put::<pallet::Pallet<Runtime>>();
+		let current = pallet::Pallet::<Runtime>::storage_layouts();
+		assert_eq!(
+			check_storage_layouts(&released(2, changed_fingerprint), &current),
+			Err(vec!["Example".into()])
+		);
+	});
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn check_storage_layouts_runs_in_try_runtime_upgrade_checks() {
+	use frame_support::traits::{CheckStorageLayouts, Get, OnRuntimeUpgrade};
+
+	/// The `Example` pallet of the released runtime had the layout of [`pallet_changed`].
+	struct Released<const VERSION: u16>;
+	impl<const VERSION: u16> Get<Vec<(String, u16, [u8; 32])>> for Released<VERSION> {
+		fn get() -> Vec<(String, u16, [u8; 32])> {
+			vec![(
+				"Example".into(),
+				VERSION,
+				pallet_changed::Pallet::<Runtime>::storage_layout_fingerprint(),
+			)]
+		}
+	}
+
+	new_test_ext().execute_with(|| {
+		// The storage version was bumped and migrated.
+		assert!(CheckStorageLayouts::<AllPalletsWithSystem, Released<2>>::try_on_runtime_upgrade(
+			true
+		)
+		.is_ok());
+		// The storage version was not bumped.
+		assert!(CheckStorageLayouts::<AllPalletsWithSystem, Released<3>>::try_on_runtime_upgrade(
+			true
+		)
+		.is_err());
+
+		// The storage version was bumped, but the migration did not run.
+		StorageVersion::new(2).put::<pallet::Pallet<Runtime>>();
+		assert!(CheckStorageLayouts::<AllPalletsWithSystem, Released<2>>::try_on_runtime_upgrade(
+			true
+		)
+		.is_err());
+	});
+}
@@ -0,0 +1,169 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for pallet view functions.
+
+use codec::{Decode, Encode};
+use frame_support::{
+	derive_impl,
+	view_functions::{ViewFunction, ViewFunctionDispatchError, ViewFunctionId},
+};
+use sp_io::TestExternalities;
+use sp_metadata_ir::PalletViewFunctionParamMetadataIR;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::storage]
+	pub type SomeValue<T: Config> = StorageValue<_, u32>;
+
+	#[pallet::storage]
+	pub type SomeMap<T: Config> = StorageMap<_, Twox64Concat, u32, u32, OptionQuery>;
+
+	#[pallet::view_functions]
+	impl<T: Config> Pallet<T> {
+		/// Query value no args.
+		pub fn get_value() -> Option<u32> {
+			SomeValue::<T>::get()
+		}
+
+		/// Query value with args.
+		pub fn get_value_with_arg(key: u32) -> Option<u32> {
+			SomeMap::<T>::get(key)
+		}
+	}
+}
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Runtime {
+	type Block = Block;
+}
+
+impl pallet::Config for Runtime {}
+
+pub type Block = frame_system::mocking::MockBlock<Runtime>;
+
+frame_support::construct_runtime!(
+	pub enum Runtime {
+		System: frame_system,
+		ViewFunctionsExample: pallet,
+	}
+);
+
+fn new_test_ext() -> TestExternalities {
+	TestExternalities::default()
+}
+
+fn execute_view_function<V: ViewFunction>(
+	view_function: V,
+) -> Result<V::ReturnType, ViewFunctionDispatchError>
+where
+	V::ReturnType: Decode,
+{
+	let output = Runtime::execute_view_function(V::id(), view_function.encode())?;
+	Ok(V::ReturnType::decode(&mut &output[..])?)
+}
+
+#[test]
+fn pallet_get_value_query() {
+	new_test_ext().execute_with(|| {
+		let some_value = Some(99);
+		pallet::SomeValue::<Runtime>::set(some_value);
+		assert_eq!(some_value, pallet::Pallet::<Runtime>::get_value());
+
+		let query = pallet::GetValueViewFunction::<Runtime>::new();
+		assert_eq!(execute_view_function(query).unwrap(), some_value);
+	});
+}
+
+#[test]
+fn pallet_get_value_with_arg_query() {
+	new_test_ext().execute_with(|| {
+		let some_key = 1u32;
+		let some_value = Some(123);
+		pallet::SomeMap::<Runtime>::set(some_key, some_value);
+		assert_eq!(some_value, pallet::Pallet::<Runtime>::get_value_with_arg(some_key));
+
+		let query = pallet::GetValueWithArgViewFunction::<Runtime>::new(some_key);
+		assert_eq!(execute_view_function(query).unwrap(), some_value);
+	});
+}
+
+#[test]
+fn unknown_view_function_is_not_found() {
+	new_test_ext().execute_with(|| {
+		let id = pallet::GetValueViewFunction::<Runtime>::id();
+
+		let unknown_suffix = ViewFunctionId { prefix: id.prefix, suffix: [0; 16] };
+		assert!(matches!(
+			Runtime::execute_view_function(unknown_suffix, vec![]),
+			Err(ViewFunctionDispatchError::NotFound(_))
+		));
+
+		let unknown_prefix = ViewFunctionId { prefix: [0; 16], suffix: id.suffix };
+		assert!(matches!(
+			Runtime::execute_view_function(unknown_prefix, vec![]),
+			Err(ViewFunctionDispatchError::NotFound(_))
+		));
+	});
+}
+
+#[test]
+fn invalid_input_is_a_codec_error() {
+	new_test_ext().execute_with(|| {
+		let id = pallet::GetValueWithArgViewFunction::<Runtime>::id();
+		assert!(matches!(
+			Runtime::execute_view_function(id, vec![1]),
+			Err(ViewFunctionDispatchError::Codec)
+		));
+	});
+}
+
+#[test]
+fn metadata_ir_definitions() {
+	let metadata_ir = Runtime::metadata_ir();
+	let pallet = metadata_ir
+		.pallets
+		.iter()
+		.find(|pallet| pallet.name == "ViewFunctionsExample")
+		.expect("pallet is part of the runtime; qed");
+
+	let get_value = &pallet.view_functions[0];
+	assert_eq!(get_value.name, "get_value");
+	assert_eq!(get_value.id, <[u8; 32]>::from(pallet::GetValueViewFunction::<Runtime>::id()));
+	assert!(get_value.inputs.is_empty());
+	assert_eq!(get_value.output, scale_info::meta_type::<Option<u32>>());
+
+	let get_value_with_arg = &pallet.view_functions[1];
+	assert_eq!(get_value_with_arg.name, "get_value_with_arg");
+	assert_eq!(
+		get_value_with_arg.id,
+		<[u8; 32]>::from(pallet::GetValueWithArgViewFunction::<Runtime>::id())
+	);
+	assert_eq!(
+		get_value_with_arg.inputs,
+		vec![PalletViewFunctionParamMetadataIR { name: "key", ty: scale_info::meta_type::<u32>() }]
+	);
+	assert_eq!(get_value_with_arg.output, scale_info::meta_type::<Option<u32>>());
+}
@@ -0,0 +1,170 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for `#[pallet::weight_refund(auto(..))]`.
+
+use frame_support::{
+	derive_impl,
+	dispatch::{GetDispatchInfo, PostDispatchInfo},
+	parameter_types,
+	traits::UnfilteredDispatchable,
+	weight_refund::StorageProofSize,
+	weights::{RuntimeDbWeight, Weight},
+};
+use sp_io::TestExternalities;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::storage]
+	pub type Values<T: Config> = StorageMap<_, Twox64Concat, u32, u32>;
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		#[pallet::call_index(0)]
+		#[pallet::weight(
+			Weight::from_parts(1_000, 10_000).saturating_add(T::DbWeight::get().writes(*max as u64))
+		)]
+		#[pallet::weight_refund(auto(reads = 0, writes = *max as u64))]
+		pub fn set_values(origin: OriginFor<T>, count: u32, max: u32) -> DispatchResult {
+			ensure_root(origin)?;
+			for i in 0..count {
+				Values::<T>::insert(i, i);
+			}
+			Ok(())
+		}
+
+		#[pallet::call_index(1)]
+		#[pallet::weight(Weight::from_parts(10_000, 10_000))]
+		#[pallet::weight_refund(auto(reads = 0, writes = 1))]
+		pub fn explicit_refund(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			Values::<T>::insert(0, 0);
+			Ok(Some(Weight::from_parts(1, 1)).into())
+		}
+
+		#[pallet::call_index(2)]
+		#[pallet::weight(Weight::from_parts(10_000, 10_000))]
+		pub fn no_refund(origin: OriginFor<T>) -> DispatchResult {
+			ensure_root(origin)?;
+			Values::<T>::insert(0, 0);
+			Ok(())
+		}
+	}
+}
+
+parameter_types! {
+	pub const DbWeight: RuntimeDbWeight = RuntimeDbWeight { read: 10, write: 100 };
+	pub static ProofSize: Option<u64> = None;
+}
+
+/// Mock proof size which grows by 50 bytes on every query.
+pub struct MockProofSize;
+
+impl StorageProofSize for MockProofSize {
+	fn storage_proof_size() -> Option<u64> {
+		let size = ProofSize::get().map(|size| size + 50);
+		ProofSize::set(size);
+		size
+	}
+}
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Runtime {
+	type Block = Block;
+	type DbWeight = DbWeight;
+	type StorageProofSize = MockProofSize;
+}
+
+impl pallet::Config for Runtime {}
+
+pub type Block = frame_system::mocking::MockBlock<Runtime>;
+
+frame_support::construct_runtime!(
+	pub enum Runtime {
+		System: frame_system,
+		WeightRefund: pallet,
+	}
+);
+
+fn dispatch(call: pallet::Call<Runtime>) -> PostDispatchInfo {
+	call.dispatch_bypass_filter(RuntimeOrigin::root()).unwrap()
+}
+
+#[test]
+fn auto_refund_meters_storage_writes() {
+	TestExternalities::default().execute_with(|| {
+		ProofSize::set(None);
+		let call = pallet::Call::<Runtime>::set_values { count: 3, max: 10 };
+		assert_eq!(call.get_dispatch_info().call_weight, Weight::from_parts(2_000, 10_000));
+
+		// The 7 unused writes are refunded.
+		let post_info = dispatch(call);
+		assert_eq!(post_info.actual_weight, Some(Weight::from_parts(1_300, 10_000)));
+	});
+}
+
+#[test]
+fn auto_refund_keeps_computation_weight() {
+	TestExternalities::default().execute_with(|| {
+		ProofSize::set(None);
+		let post_info = dispatch(pallet::Call::<Runtime>::set_values { count: 0, max: 10 });
+		assert_eq!(post_info.actual_weight, Some(Weight::from_parts(1_000, 10_000)));
+	});
+}
+
+#[test]
+fn auto_refund_meters_proof_size() {
+	TestExternalities::default().execute_with(|| {
+		ProofSize::set(Some(0));
+		let post_info = dispatch(pallet::Call::<Runtime>::set_values { count: 1, max: 1 });
+		assert_eq!(post_info.actual_weight, Some(Weight::from_parts(1_100, 50)));
+	});
+}
+
+#[test]
+fn auto_refund_is_capped_by_pre_dispatch_weight() {
+	TestExternalities::default().execute_with(|| {
+		ProofSize::set(None);
+		let post_info = dispatch(pallet::Call::<Runtime>::set_values { count: 20, max: 10 });
+		assert_eq!(post_info.actual_weight, Some(Weight::from_parts(2_000, 10_000)));
+	});
+}
+
+#[test]
+fn explicit_actual_weight_takes_precedence() {
+	TestExternalities::default().execute_with(|| {
+		let post_info = dispatch(pallet::Call::<Runtime>::explicit_refund {});
+		assert_eq!(post_info.actual_weight, Some(Weight::from_parts(1, 1)));
+	});
+}
+
+#[test]
+fn calls_without_attribute_are_not_refunded() {
+	TestExternalities::default().execute_with(|| {
+		let post_info = dispatch(pallet::Call::<Runtime>::no_refund {});
+		assert_eq!(post_info.actual_weight, None);
+	});
+}
@@ -31,6 +31,7 @@ sp_api::decl_runtime_apis! {
 		Nonce: codec::Codec,
 	{
 		/// Get current account nonce of given `AccountId`.
+		#[pure]
 		fn account_nonce(account: AccountId) -> Nonce;
 	}
 }
@@ -0,0 +1,155 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{Config, RawOrigin};
+use codec::{Decode, Encode};
+use core::marker::PhantomData;
+use frame_support::{
+	dispatch::DispatchInfo,
+	pallet_prelude::{TransactionSource, Weight},
+	traits::{Authorize, OriginTrait},
+	CloneNoBound, DefaultNoBound, EqNoBound, PartialEqNoBound,
+};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{
+		DispatchInfoOf, Dispatchable, PostDispatchInfoOf, TransactionExtension, ValidateResult,
+	},
+	transaction_validity::TransactionValidityError,
+	DispatchResult,
+};
+
+/// A transaction extension that authorizes some calls (i.e. dispatchable functions) to be
+/// included in the block.
+///
+/// This transaction extension use the runtime implementation of the trait
+/// [`Authorize`](frame_support::traits::Authorize) to set the validity of the transaction.
+///
+/// If the origin of the transaction is not `None`, or if the call doesn't provide any
+/// authorization, the transaction is passed through untouched and the whole weight of the
+/// authorization is refunded. Otherwise the transaction validity is the one returned by the call
+/// authorization, and the origin is transformed into [`RawOrigin::Authorized`].
+#[derive(Encode, Decode, CloneNoBound, EqNoBound, PartialEqNoBound, DefaultNoBound, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct AuthorizeCall<T>(PhantomData<T>);
+
+impl<T> core::fmt::Debug for AuthorizeCall<T> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		write!(f, "AuthorizeCall")
+	}
+
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut core::fmt::Formatter) -> core::fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T> AuthorizeCall<T> {
+	/// Create new `TransactionExtension` to authorize calls.
+	pub fn new() -> Self {
+		Self(Default::default())
+	}
+}
+
+impl<T: Config + Send + Sync> TransactionExtension<T::RuntimeCall> for AuthorizeCall<T>
+where
+	T::RuntimeCall: Dispatchable<Info = DispatchInfo> + Authorize,
+{
+	const IDENTIFIER: &'static str = "AuthorizeCall";
+	type Implicit = ();
+	/// The weight unspent by the authorization.
+	type Val = Weight;
+	/// The weight unspent by the authorization.
+	type Pre = Weight;
+
+	fn weight(&self, call: &T::RuntimeCall) -> Weight {
+		call.weight_of_authorize()
+	}
+
+	fn validate(
+		&self,
+		mut origin: T::RuntimeOrigin,
+		call: &T::RuntimeCall,
+		_info: &DispatchInfoOf<T::RuntimeCall>,
+		_len: usize,
+		_self_implicit: Self::Implicit,
+		_inherited_implication: &impl Encode,
+		source: TransactionSource,
+	) -> ValidateResult<Self::Val, T::RuntimeCall> {
+		if let Some(RawOrigin::None) = origin.as_system_ref() {
+			if let Some(authorize) = call.authorize(source) {
+				return authorize.map(|(validity, unspent)| {
+					origin.set_caller_from(RawOrigin::Authorized);
+					(validity, unspent, origin)
+				})
+			}
+		}
+
+		Ok((Default::default(), self.weight(call), origin))
+	}
+
+	fn prepare(
+		self,
+		val: Self::Val,
+		_origin: &T::RuntimeOrigin,
+		_call: &T::RuntimeCall,
+		_info: &DispatchInfoOf<T::RuntimeCall>,
+		_len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		Ok(val)
+	}
+
+	fn post_dispatch_details(
+		pre: Self::Pre,
+		_info: &DispatchInfoOf<T::RuntimeCall>,
+		_post_info: &PostDispatchInfoOf<T::RuntimeCall>,
+		_len: usize,
+		_result: &DispatchResult,
+	) -> Result<Weight, TransactionValidityError> {
+		Ok(pre)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test, CALL};
+	use sp_runtime::{
+		traits::AsTransactionAuthorizedOrigin, transaction_validity::TransactionSource::External,
+	};
+
+	#[test]
+	fn calls_without_authorization_are_passed_through() {
+		new_test_ext().execute_with(|| {
+			let info = DispatchInfo::default();
+			let len = 0_usize;
+
+			let (_, unspent, origin) = AuthorizeCall::<Test>::new()
+				.validate(None.into(), CALL, &info, len, (), CALL, External)
+				.unwrap();
+			assert_eq!(unspent, AuthorizeCall::<Test>::new().weight(CALL));
+			assert!(!origin.is_transaction_authorized());
+
+			let (_, unspent, origin) = AuthorizeCall::<Test>::new()
+				.validate(Some(1).into(), CALL, &info, len, (), CALL, External)
+				.unwrap();
+			assert_eq!(unspent, AuthorizeCall::<Test>::new().weight(CALL));
+			assert_eq!(origin.as_signer(), Some(&1));
+		})
+	}
+}
@@ -167,7 +167,7 @@ pub mod weights;
 pub mod migrations;
 
 pub use extensions::{
-	check_genesis::CheckGenesis, check_mortality::CheckMortality,
+	authorize_call::AuthorizeCall, check_genesis::CheckGenesis, check_mortality::CheckMortality,
 	check_non_zero_sender::CheckNonZeroSender, check_nonce::CheckNonce,
 	check_spec_version::CheckSpecVersion, check_tx_version::CheckTxVersion,
 	check_weight::CheckWeight, WeightInfo as ExtensionsWeightInfo,
@@ -323,6 +323,7 @@ pub mod pallet {
 			type BlockWeights = ();
 			type BlockLength = ();
 			type DbWeight = ();
+			type StorageProofSize = ();
 			#[inject_runtime_type]
 			type RuntimeEvent = ();
 			#[inject_runtime_type]
@@ -408,6 +409,9 @@ pub mod pallet {
 			/// The weight of database operations that the runtime can invoke.
 			type DbWeight = ();
 
+			/// No storage proof size is measured by default.
+			type StorageProofSize = ();
+
 			/// The ubiquitous event type injected by `construct_runtime!`.
 			#[inject_runtime_type]
 			type RuntimeEvent = ();
@@ -576,6 +580,13 @@ pub mod pallet {
 		#[pallet::constant]
 		type DbWeight: Get<RuntimeDbWeight>;
 
+		/// Provides the size of the storage proof recorded so far.
+		///
+		/// Used to reclaim the proof size of calls annotated with
+		/// `#[pallet::weight_refund(auto(..))]`. Parachains should use an implementation backed by the
+		/// `storage_proof_size` host function.
+		type StorageProofSize: frame_support::weight_refund::StorageProofSize;
+
 		/// Get the chain's in-code version.
 		#[pallet::constant]
 		type Version: Get<RuntimeVersion>;
@@ -1418,6 +1429,18 @@ where
 	}
 }
 
+/// Ensure that the origin `o` represents an extrinsic authorized by the call it contains, see
+/// [`AuthorizeCall`]. Returns `Ok` or an `Err` otherwise.
+pub fn ensure_authorized<OuterOrigin, AccountId>(o: OuterOrigin) -> Result<(), BadOrigin>
+where
+	OuterOrigin: Into<Result<RawOrigin<AccountId>, OuterOrigin>>,
+{
+	match o.into() {
+		Ok(RawOrigin::Authorized) => Ok(()),
+		_ => Err(BadOrigin),
+	}
+}
+
 /// Reference status; can be either referenced or unreferenced.
 #[derive(RuntimeDebug)]
 pub enum RefStatus {
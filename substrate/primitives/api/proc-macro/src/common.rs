@@ -33,6 +33,16 @@ pub const CHANGED_IN_ATTRIBUTE: &str = "changed_in";
 ///
 /// Is used when a trait method was renamed.
 pub const RENAMED_ATTRIBUTE: &str = "renamed";
+/// The `pure` attribute.
+///
+/// Is used to mark a trait method whose result only depends on the block state and the
+/// arguments, so that the client is allowed to cache it.
+pub const PURE_ATTRIBUTE: &str = "pure";
 /// All attributes that we support in the declaration of a runtime api trait.
-pub const SUPPORTED_ATTRIBUTE_NAMES: &[&str] =
-	&[CORE_TRAIT_ATTRIBUTE, API_VERSION_ATTRIBUTE, CHANGED_IN_ATTRIBUTE, RENAMED_ATTRIBUTE];
+pub const SUPPORTED_ATTRIBUTE_NAMES: &[&str] = &[
+	CORE_TRAIT_ATTRIBUTE,
+	API_VERSION_ATTRIBUTE,
+	CHANGED_IN_ATTRIBUTE,
+	RENAMED_ATTRIBUTE,
+	PURE_ATTRIBUTE,
+];
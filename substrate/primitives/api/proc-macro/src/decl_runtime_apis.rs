@@ -18,7 +18,7 @@
 use crate::{
 	common::{
 		API_VERSION_ATTRIBUTE, BLOCK_GENERIC_IDENT, CHANGED_IN_ATTRIBUTE, CORE_TRAIT_ATTRIBUTE,
-		RENAMED_ATTRIBUTE, SUPPORTED_ATTRIBUTE_NAMES,
+		PURE_ATTRIBUTE, RENAMED_ATTRIBUTE, SUPPORTED_ATTRIBUTE_NAMES,
 	},
 	utils::{
 		extract_parameter_names_types_and_borrows, fold_fn_decl_for_client_side,
@@ -188,6 +188,12 @@ fn generate_runtime_decls(decls: &[ItemTrait]) -> Result<TokenStream> {
 		extend_generics_with_block(&mut decl.generics);
 		let mod_name = generate_runtime_mod_name_for_trait(&decl.ident);
 		let found_attributes = remove_supported_attributes(&mut decl.attrs);
+		if let Some(pure) = found_attributes.get(PURE_ATTRIBUTE) {
+			return Err(Error::new(
+				pure.span(),
+				"`#[pure]` is only supported on methods of a runtime api trait.",
+			))
+		}
 		let api_version = get_api_version(&found_attributes).map(generate_runtime_api_version)?;
 		let id = generate_runtime_api_id(&decl.ident.to_string());
 
@@ -316,6 +322,7 @@ impl<'a> ToClientSideDecl<'a> {
 				at: #block_hash,
 				params: std::vec::Vec<u8>,
 				fn_name: &dyn Fn(#crate_::RuntimeVersion) -> &'static str,
+				pure: bool,
 			) -> std::result::Result<std::vec::Vec<u8>, #crate_::ApiError>;
 		});
 
@@ -367,6 +374,7 @@ impl<'a> ToClientSideDecl<'a> {
 		let crate_ = self.crate_;
 
 		let found_attributes = remove_supported_attributes(&mut method.attrs);
+		let pure = found_attributes.contains_key(PURE_ATTRIBUTE);
 
 		// Parse the renamed attributes.
 		let mut renames = Vec::new();
@@ -447,7 +455,8 @@ impl<'a> ToClientSideDecl<'a> {
 						)*
 
 						#function_name
-					}
+					},
+					#pure,
 				)
 				.and_then(|r|
 					std::result::Result::map_err(
@@ -558,6 +558,7 @@ impl<'a> ApiRuntimeImplToApiRuntimeApiImpl<'a> {
 				at: <__SrApiBlock__ as #crate_::BlockT>::Hash,
 				params: std::vec::Vec<u8>,
 				fn_name: &dyn Fn(#crate_::RuntimeVersion) -> &'static str,
+				pure: bool,
 			) -> std::result::Result<std::vec::Vec<u8>, #crate_::ApiError> {
 				// If we are not already in a transaction, we should create a new transaction
 				// and then commit/roll it back at the end!
@@ -596,6 +597,7 @@ impl<'a> ApiRuntimeImplToApiRuntimeApiImpl<'a> {
 						at,
 						function: (*fn_name)(version),
 						arguments: params,
+						pure,
 						overlayed_changes: &self.changes,
 						call_context: self.call_context,
 						recorder: &self.recorder,
@@ -135,6 +135,7 @@ fn implement_common_api_traits(block_type: TypePath, self_ty: Type) -> Result<To
 				_: <#block_type as #crate_::BlockT>::Hash,
 				_: std::vec::Vec<u8>,
 				_: &dyn Fn(#crate_::RuntimeVersion) -> &'static str,
+				_: bool,
 			) -> std::result::Result<std::vec::Vec<u8>, #crate_::ApiError> {
 				unimplemented!("`__runtime_api_internal_call_api_at` not implemented for runtime api mocks")
 			}
@@ -242,6 +243,7 @@ impl<'a> FoldRuntimeApiImpl<'a> {
 				_: <#block_type as #crate_::BlockT>::Hash,
 				_: std::vec::Vec<u8>,
 				_: &dyn Fn(#crate_::RuntimeVersion) -> &'static str,
+				_: bool,
 			) -> std::result::Result<std::vec::Vec<u8>, #crate_::ApiError> {
 				unimplemented!(
 					"`__runtime_api_internal_call_api_at` not implemented for runtime api mocks. \
@@ -259,6 +259,26 @@ pub const MAX_EXTRINSIC_DEPTH: u32 = 256;
 /// Note that the latest version (4 in our example above) always contains all methods from all
 /// the versions before.
 ///
+/// # Pure functions
+///
+/// A method can be tagged with `#[pure]` to declare that its result only depends on the state
+/// of the block it is called at and on its arguments. The client is then allowed to cache the
+/// result of calling it (see `ClientConfig::runtime_api_cache_size` in `sc-service`). Methods
+/// that depend on the call context, e.g. on changes done by earlier calls on the same runtime api
+/// instance, must not be tagged. The attribute is only supported on methods, not on the trait.
+///
+/// ```rust
+/// sp_api::decl_runtime_apis! {
+///     pub trait Balance {
+///         /// Get the balance.
+///         #[pure]
+///         fn get_balance() -> u64;
+///     }
+/// }
+///
+/// # fn main() {}
+/// ```
+///
 /// ## Note on deprecation.
 ///
 /// - Usage of `deprecated` attribute will propagate deprecation information to the metadata.
@@ -656,6 +676,11 @@ pub struct CallApiAtParams<'a, Block: BlockT> {
 	pub function: &'static str,
 	/// The encoded arguments of the function.
 	pub arguments: Vec<u8>,
+	/// Whether the function was declared as `#[pure]`.
+	///
+	/// The result of a pure function only depends on the state at `at` and on `arguments`, so
+	/// the caller is allowed to cache it.
+	pub pure: bool,
 	/// The overlayed changes that are on top of the state.
 	pub overlayed_changes: &'a RefCell<OverlayedChanges<HashingFor<Block>>>,
 	/// The call context of this call.
@@ -830,17 +855,20 @@ decl_runtime_apis! {
 	#[api_version(2)]
 	pub trait Metadata {
 		/// Returns the metadata of a runtime.
+		#[pure]
 		fn metadata() -> OpaqueMetadata;
 
 		/// Returns the metadata at a given version.
 		///
 		/// If the given `version` isn't supported, this will return `None`.
 		/// Use [`Self::metadata_versions`] to find out about supported metadata version of the runtime.
+		#[pure]
 		fn metadata_at_version(version: u32) -> Option<OpaqueMetadata>;
 
 		/// Returns the supported metadata versions.
 		///
 		/// This can be used to call `metadata_at_version`.
+		#[pure]
 		fn metadata_versions() -> alloc::vec::Vec<u32>;
 	}
 }
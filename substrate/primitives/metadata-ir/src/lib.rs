@@ -31,6 +31,7 @@ use frame_metadata::RuntimeMetadataPrefixed;
 pub use types::*;
 
 mod unstable;
+pub use unstable::{ViewFunctionMetadata, ViewFunctionParamMetadata};
 mod v14;
 mod v15;
 
@@ -43,6 +44,17 @@ const V15: u32 = 15;
 /// Unstable metadata V16.
 const UNSTABLE_V16: u32 = u32::MAX;
 
+/// Key of the custom metadata holding the storage layout fingerprints of the pallets.
+///
+/// The value is a `Vec<(String, u16, [u8; 32])>` of the pallet names, their in-code storage
+/// versions and the fingerprints of their storage layouts. Only emitted in the unstable metadata.
+pub const STORAGE_LAYOUT_FINGERPRINTS: &str = "storage_layout_fingerprints";
+
+/// Key of the custom metadata holding the view functions of the pallets.
+///
+/// The value is a `Vec<ViewFunctionMetadata>`. Only emitted in the unstable metadata.
+pub const VIEW_FUNCTIONS: &str = "view_functions";
+
 /// Transform the IR to the specified version.
 ///
 /// Use [`supported_versions`] to find supported versions.
@@ -144,4 +156,95 @@ mod test {
 
 		assert!(matches!(metadata.1, RuntimeMetadata::V15(_)));
 	}
+
+	#[test]
+	fn storage_layout_fingerprints_are_in_unstable_metadata() {
+		let mut ir = ir_metadata();
+		ir.pallets = [("WithStorage", Some([1; 32]), Some(2)), ("WithoutStorage", None, None)]
+			.into_iter()
+			.map(|(name, storage_layout_fingerprint, storage_version)| PalletMetadataIR {
+				name,
+				storage: None,
+				storage_layout_fingerprint,
+				storage_version,
+				calls: None,
+				event: None,
+				constants: vec![],
+				error: None,
+				associated_types: vec![],
+				view_functions: vec![],
+				index: 0,
+				docs: vec![],
+				deprecation_info: DeprecationStatusIR::NotDeprecated,
+			})
+			.collect();
+
+		let RuntimeMetadata::V16(metadata) = into_version(ir, UNSTABLE_V16).unwrap().1 else {
+			panic!("Expected unstable metadata")
+		};
+		let fingerprints = &metadata.custom.map[STORAGE_LAYOUT_FINGERPRINTS];
+		assert_eq!(
+			<Vec<(String, u16, [u8; 32])> as codec::Decode>::decode(&mut &fingerprints.value[..])
+				.unwrap(),
+			vec![("WithStorage".into(), 2, [1; 32])]
+		);
+	}
+
+	#[test]
+	fn view_functions_are_in_unstable_metadata() {
+		let mut ir = ir_metadata();
+		ir.pallets = vec![PalletMetadataIR {
+			name: "Pallet",
+			storage: None,
+			storage_layout_fingerprint: None,
+			storage_version: None,
+			calls: None,
+			event: None,
+			constants: vec![],
+			error: None,
+			associated_types: vec![],
+			view_functions: vec![PalletViewFunctionMetadataIR {
+				name: "get_value",
+				id: [1; 32],
+				inputs: vec![PalletViewFunctionParamMetadataIR {
+					name: "key",
+					ty: meta_type::<u32>(),
+				}],
+				output: meta_type::<Option<u64>>(),
+				docs: vec!["Get a value."],
+				deprecation_info: DeprecationStatusIR::NotDeprecated,
+			}],
+			index: 0,
+			docs: vec![],
+			deprecation_info: DeprecationStatusIR::NotDeprecated,
+		}];
+
+		let RuntimeMetadata::V16(metadata) = into_version(ir, UNSTABLE_V16).unwrap().1 else {
+			panic!("Expected unstable metadata")
+		};
+		let view_functions = <Vec<ViewFunctionMetadata> as codec::Decode>::decode(
+			&mut &metadata.custom.map[VIEW_FUNCTIONS].value[..],
+		)
+		.unwrap();
+
+		assert_eq!(view_functions.len(), 1);
+		let view_function = &view_functions[0];
+		assert_eq!(
+			(view_function.pallet.as_str(), view_function.name.as_str(), view_function.id),
+			("Pallet", "get_value", [1; 32])
+		);
+		assert_eq!(view_function.docs, vec!["Get a value.".to_string()]);
+
+		// The types are part of the type registry.
+		let input = &view_function.inputs[0];
+		assert_eq!(input.name, "key");
+		assert_eq!(
+			metadata.types.resolve(input.ty).unwrap().type_def,
+			scale_info::TypeDef::Primitive(scale_info::TypeDefPrimitive::U32)
+		);
+		assert_eq!(
+			metadata.types.resolve(view_function.output).unwrap().path.segments,
+			vec!["Option".to_string()]
+		);
+	}
 }
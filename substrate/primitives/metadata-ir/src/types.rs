@@ -125,6 +125,14 @@ pub struct PalletMetadataIR<T: Form = MetaForm> {
 	pub name: T::String,
 	/// Pallet storage metadata.
 	pub storage: Option<PalletStorageMetadataIR<T>>,
+	/// Fingerprint of the storage layout of the pallet.
+	///
+	/// Changes whenever the encoding of a storage key or value of the pallet changes. Emitted in
+	/// the unstable metadata under the
+	/// [`STORAGE_LAYOUT_FINGERPRINTS`](crate::STORAGE_LAYOUT_FINGERPRINTS) custom metadata key.
+	pub storage_layout_fingerprint: Option<[u8; 32]>,
+	/// The in-code storage version of the pallet, if it has storage.
+	pub storage_version: Option<u16>,
 	/// Pallet calls metadata.
 	pub calls: Option<PalletCallMetadataIR<T>>,
 	/// Pallet event metadata.
@@ -135,6 +143,8 @@ pub struct PalletMetadataIR<T: Form = MetaForm> {
 	pub error: Option<PalletErrorMetadataIR<T>>,
 	/// Config's trait associated types.
 	pub associated_types: Vec<PalletAssociatedTypeMetadataIR<T>>,
+	/// Pallet view functions metadata.
+	pub view_functions: Vec<PalletViewFunctionMetadataIR<T>>,
 	/// Define the index of the pallet, this index will be used for the encoding of pallet event,
 	/// call and origin variants.
 	pub index: u8,
@@ -151,11 +161,14 @@ impl IntoPortable for PalletMetadataIR {
 		PalletMetadataIR {
 			name: self.name.into_portable(registry),
 			storage: self.storage.map(|storage| storage.into_portable(registry)),
+			storage_layout_fingerprint: self.storage_layout_fingerprint,
+			storage_version: self.storage_version,
 			calls: self.calls.map(|calls| calls.into_portable(registry)),
 			event: self.event.map(|event| event.into_portable(registry)),
 			constants: registry.map_into_portable(self.constants),
 			error: self.error.map(|error| error.into_portable(registry)),
 			associated_types: registry.map_into_portable(self.associated_types),
+			view_functions: registry.map_into_portable(self.view_functions),
 			index: self.index,
 			docs: registry.map_into_portable(self.docs),
 			deprecation_info: self.deprecation_info.into_portable(registry),
@@ -163,6 +176,58 @@ impl IntoPortable for PalletMetadataIR {
 	}
 }
 
+/// Metadata of a pallet view function.
+#[derive(Clone, PartialEq, Eq, Encode, Debug)]
+pub struct PalletViewFunctionMetadataIR<T: Form = MetaForm> {
+	/// Method name.
+	pub name: T::String,
+	/// Method id, used to dispatch the view function through the runtime API.
+	pub id: [u8; 32],
+	/// Method parameters.
+	pub inputs: Vec<PalletViewFunctionParamMetadataIR<T>>,
+	/// Method output.
+	pub output: T::Type,
+	/// Method documentation.
+	pub docs: Vec<T::String>,
+	/// Deprecation info
+	pub deprecation_info: DeprecationStatusIR<T>,
+}
+
+impl IntoPortable for PalletViewFunctionMetadataIR {
+	type Output = PalletViewFunctionMetadataIR<PortableForm>;
+
+	fn into_portable(self, registry: &mut Registry) -> Self::Output {
+		PalletViewFunctionMetadataIR {
+			name: self.name.into_portable(registry),
+			id: self.id,
+			inputs: registry.map_into_portable(self.inputs),
+			output: registry.register_type(&self.output),
+			docs: registry.map_into_portable(self.docs),
+			deprecation_info: self.deprecation_info.into_portable(registry),
+		}
+	}
+}
+
+/// Metadata of a pallet view function parameter.
+#[derive(Clone, PartialEq, Eq, Encode, Debug)]
+pub struct PalletViewFunctionParamMetadataIR<T: Form = MetaForm> {
+	/// Parameter name.
+	pub name: T::String,
+	/// Parameter type.
+	pub ty: T::Type,
+}
+
+impl IntoPortable for PalletViewFunctionParamMetadataIR {
+	type Output = PalletViewFunctionParamMetadataIR<PortableForm>;
+
+	fn into_portable(self, registry: &mut Registry) -> Self::Output {
+		PalletViewFunctionParamMetadataIR {
+			name: self.name.into_portable(registry),
+			ty: registry.register_type(&self.ty),
+		}
+	}
+}
+
 /// Metadata of the extrinsic used by the runtime.
 #[derive(Clone, PartialEq, Eq, Encode, Debug)]
 pub struct ExtrinsicMetadataIR<T: Form = MetaForm> {
@@ -20,7 +20,8 @@
 use crate::{
 	DeprecationInfoIR, DeprecationStatusIR, OuterEnumsIR, PalletAssociatedTypeMetadataIR,
 	PalletCallMetadataIR, PalletConstantMetadataIR, PalletErrorMetadataIR, PalletEventMetadataIR,
-	PalletStorageMetadataIR, StorageEntryMetadataIR,
+	PalletStorageMetadataIR, PalletViewFunctionMetadataIR, StorageEntryMetadataIR,
+	STORAGE_LAYOUT_FINGERPRINTS, VIEW_FUNCTIONS,
 };
 
 use super::types::{
@@ -28,25 +29,120 @@ use super::types::{
 	RuntimeApiMethodMetadataIR, RuntimeApiMethodParamMetadataIR, TransactionExtensionMetadataIR,
 };
 
+use alloc::{string::String, vec::Vec};
+use codec::{Decode, Encode};
 use frame_metadata::v16::{
-	CustomMetadata, DeprecationInfo, DeprecationStatus, ExtrinsicMetadata, OuterEnums,
-	PalletAssociatedTypeMetadata, PalletCallMetadata, PalletConstantMetadata, PalletErrorMetadata,
-	PalletEventMetadata, PalletMetadata, PalletStorageMetadata, RuntimeApiMetadata,
-	RuntimeApiMethodMetadata, RuntimeApiMethodParamMetadata, RuntimeMetadataV16,
-	StorageEntryMetadata, TransactionExtensionMetadata,
+	CustomMetadata, CustomValueMetadata, DeprecationInfo, DeprecationStatus, ExtrinsicMetadata,
+	OuterEnums, PalletAssociatedTypeMetadata, PalletCallMetadata, PalletConstantMetadata,
+	PalletErrorMetadata, PalletEventMetadata, PalletMetadata, PalletStorageMetadata,
+	RuntimeApiMetadata, RuntimeApiMethodMetadata, RuntimeApiMethodParamMetadata,
+	RuntimeMetadataV16, StorageEntryMetadata, TransactionExtensionMetadata,
 };
+use scale_info::{form::PortableForm, meta_type, IntoPortable, Registry, TypeInfo};
+
+/// Metadata of a pallet view function, emitted under the [`VIEW_FUNCTIONS`] custom metadata key.
+///
+/// The pinned `frame-metadata` release does not describe view functions yet, so they are emitted
+/// as custom metadata which refers to the types of the metadata type registry by their ids.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, TypeInfo, Debug)]
+pub struct ViewFunctionMetadata {
+	/// Name of the pallet declaring the view function.
+	pub pallet: String,
+	/// Name of the view function.
+	pub name: String,
+	/// Id used to dispatch the view function through the runtime API.
+	pub id: [u8; 32],
+	/// Parameters of the view function.
+	pub inputs: Vec<ViewFunctionParamMetadata>,
+	/// Id of the output type in the type registry.
+	#[codec(compact)]
+	pub output: u32,
+	/// Documentation of the view function.
+	pub docs: Vec<String>,
+}
+
+/// Metadata of a parameter of a pallet view function, see [`ViewFunctionMetadata`].
+#[derive(Clone, PartialEq, Eq, Encode, Decode, TypeInfo, Debug)]
+pub struct ViewFunctionParamMetadata {
+	/// Name of the parameter.
+	pub name: String,
+	/// Id of the parameter type in the type registry.
+	#[codec(compact)]
+	pub ty: u32,
+}
+
+impl ViewFunctionMetadata {
+	fn new(pallet: &str, view_function: PalletViewFunctionMetadataIR<PortableForm>) -> Self {
+		ViewFunctionMetadata {
+			pallet: pallet.into(),
+			name: view_function.name,
+			id: view_function.id,
+			inputs: view_function
+				.inputs
+				.into_iter()
+				.map(|input| ViewFunctionParamMetadata { name: input.name, ty: input.ty.id })
+				.collect(),
+			output: view_function.output.id,
+			docs: view_function.docs,
+		}
+	}
+}
 
 impl From<MetadataIR> for RuntimeMetadataV16 {
 	fn from(ir: MetadataIR) -> Self {
-		RuntimeMetadataV16::new(
-			ir.pallets.into_iter().map(Into::into).collect(),
-			ir.extrinsic.into(),
-			ir.apis.into_iter().map(Into::into).collect(),
-			ir.outer_enums.into(),
-			// Substrate does not collect yet the custom metadata fields.
-			// This allows us to extend the V16 easily.
-			CustomMetadata { map: Default::default() },
-		)
+		let mut custom = CustomMetadata { map: Default::default() };
+
+		let fingerprints = ir
+			.pallets
+			.iter()
+			.filter_map(|pallet| {
+				Some((pallet.name, pallet.storage_version?, pallet.storage_layout_fingerprint?))
+			})
+			.collect::<Vec<_>>();
+		if !fingerprints.is_empty() {
+			custom.map.insert(
+				STORAGE_LAYOUT_FINGERPRINTS,
+				CustomValueMetadata {
+					ty: meta_type::<Vec<(String, u16, [u8; 32])>>(),
+					value: fingerprints.encode(),
+				},
+			);
+		}
+
+		let view_functions = ir
+			.pallets
+			.iter()
+			.flat_map(|pallet| pallet.view_functions.iter().map(move |f| (pallet.name, f.clone())))
+			.collect::<Vec<_>>();
+
+		// Same as `RuntimeMetadataV16::new`, but the view functions need to register their types
+		// in the same registry.
+		let mut registry = Registry::new();
+		let pallets =
+			registry.map_into_portable(ir.pallets.into_iter().map(Into::<PalletMetadata>::into));
+		let extrinsic = ExtrinsicMetadata::from(ir.extrinsic).into_portable(&mut registry);
+		let apis =
+			registry.map_into_portable(ir.apis.into_iter().map(Into::<RuntimeApiMetadata>::into));
+		let outer_enums = OuterEnums::from(ir.outer_enums).into_portable(&mut registry);
+		let mut custom = custom.into_portable(&mut registry);
+
+		if !view_functions.is_empty() {
+			let view_functions = view_functions
+				.into_iter()
+				.map(|(pallet, f)| {
+					ViewFunctionMetadata::new(pallet, f.into_portable(&mut registry))
+				})
+				.collect::<Vec<_>>();
+			custom.map.insert(
+				VIEW_FUNCTIONS.into(),
+				CustomValueMetadata {
+					ty: registry.register_type(&meta_type::<Vec<ViewFunctionMetadata>>()),
+					value: view_functions.encode(),
+				},
+			);
+		}
+
+		RuntimeMetadataV16 { types: registry.into(), pallets, extrinsic, apis, outer_enums, custom }
 	}
 }
 
@@ -92,6 +188,8 @@ impl From<PalletMetadataIR> for PalletMetadata {
 			docs: ir.docs,
 			associated_types: ir.associated_types.into_iter().map(Into::into).collect(),
 			deprecation_info: ir.deprecation_info.into(),
+			// Note: the pinned `frame-metadata` release does not describe view functions yet, they
+			// are emitted under the `VIEW_FUNCTIONS` custom metadata key instead.
 		}
 	}
 }
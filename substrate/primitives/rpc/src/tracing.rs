@@ -99,3 +99,61 @@ pub enum TraceBlockResponse {
 	/// Successful block tracing response
 	BlockTrace(BlockTrace),
 }
+
+/// Timeline of a block's execution in the [Chrome trace event format][1].
+///
+/// The trace can be loaded into `chrome://tracing` or [Perfetto](https://ui.perfetto.dev) to
+/// get a flame graph of the block's execution.
+///
+/// [1]: https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ChromeTrace {
+	/// The trace events, ordered by timestamp.
+	pub trace_events: Vec<ChromeTraceEvent>,
+	/// Unit in which the trace viewer displays timestamps.
+	pub display_time_unit: String,
+	/// Information about the traced block.
+	pub other_data: FxHashMap<String, String>,
+}
+
+/// A single event of a [`ChromeTrace`].
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct ChromeTraceEvent {
+	/// Name of the event.
+	pub name: String,
+	/// Category of the event, the tracing target.
+	pub cat: String,
+	/// Phase of the event, e.g. `X` for a complete span, `i` for an instant event or `C` for
+	/// a counter.
+	pub ph: String,
+	/// Timestamp of the event in microseconds, relative to the start of the trace.
+	pub ts: f64,
+	/// Duration of the event in microseconds, only set for complete spans.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub dur: Option<f64>,
+	/// Scope of an instant event.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub s: Option<String>,
+	/// Process id.
+	pub pid: u32,
+	/// Thread id.
+	pub tid: u32,
+	/// Values recorded for the event.
+	#[serde(default)]
+	pub args: FxHashMap<String, ChromeTraceArg>,
+}
+
+/// A value recorded for a [`ChromeTraceEvent`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum ChromeTraceArg {
+	/// A `bool` value.
+	Bool(bool),
+	/// An unsigned integer value.
+	U64(u64),
+	/// A signed integer value.
+	I64(i64),
+	/// A `String` value.
+	String(String),
+}
@@ -363,6 +363,20 @@ pub use sp_std;
 /// for the case when that would create a circular dependency. You usually _do not_ want to add
 /// this flag, as tracing doesn't cost you anything by default anyways (it is added as a no-op)
 /// but is super useful for debugging later.
+///
+/// # Deprecating host functions
+///
+/// Once a runtime imports a host function version, every node needs to provide it to be able to
+/// execute the blocks built by that runtime, even after all runtimes in use switched to a newer
+/// version. To find out which versions the runtimes of a chain import, use the `host-functions`
+/// command of `sc-cli`, which scans all runtime code versions found in the database or in a list
+/// of wasm blobs.
+///
+/// A version that is still imported by historic runtimes can be removed from the native host
+/// functions by providing it as a wasm shim instead, see `HostFunctionShims` in `sc-executor`.
+/// The shim is a small wasm module exporting `ext_FUNCTION_NAME_version_VERSION` with the FFI
+/// signature of the removed version, implemented by calling the versions the node still
+/// provides.
 pub use sp_runtime_interface_proc_macro::runtime_interface;
 
 #[doc(hidden)]
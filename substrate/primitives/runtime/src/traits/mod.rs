@@ -1808,6 +1808,9 @@ pub trait GetNodeBlockType {
 /// function is called right before dispatching the call wrapped by an unsigned extrinsic. The
 /// [`validate_unsigned`](Self::validate_unsigned) function is mainly being used in the context of
 /// the transaction pool to check the validity of the call wrapped by an unsigned extrinsic.
+///
+/// New FRAME pallets should rather authorize their calls with the `#[pallet::authorize]`
+/// attribute, which is checked by the `frame_system::AuthorizeCall` transaction extension.
 pub trait ValidateUnsigned {
 	/// The call to validate
 	type Call;
@@ -187,6 +187,7 @@ decl_runtime_apis! {
 	#[api_version(2)]
 	pub trait TestAPI {
 		/// Return the balance of the given account id.
+		#[pure]
 		fn balance_of(id: AccountId) -> u64;
 		/// A benchmark function that adds one to the given value and returns the result.
 		fn benchmark_add_one(val: &u64) -> u64;
@@ -16,6 +16,7 @@
 // limitations under the License.
 
 use super::{
+	live_state, trace,
 	types::{ComponentRange, ComponentRangeMap},
 	writer, ListOutput, PalletCmd,
 };
@@ -61,7 +62,7 @@ use std::{
 };
 
 /// Logging target
-const LOG_TARGET: &'static str = "polkadot_sdk_frame::benchmark::pallet";
+pub(crate) const LOG_TARGET: &'static str = "polkadot_sdk_frame::benchmark::pallet";
 
 type SubstrateAndExtraHF<T> =
 	(sp_io::SubstrateHostFunctions, frame_benchmarking::benchmarking::HostFunctions, T);
@@ -268,8 +269,22 @@ impl PalletCmd {
 
 		let state_handler =
 			self.state_handler_from_cli::<SubstrateAndExtraHF<ExtraHostFunctions>>(chain_spec)?;
-		let genesis_storage =
-			state_handler.build_storage::<SubstrateAndExtraHF<ExtraHostFunctions>>(None)?;
+		let genesis_storage = match &self.state_snapshot {
+			Some(snapshot) => {
+				let code = state_handler.get_code_bytes()?;
+				let storage =
+					live_state::storage_from_snapshot(snapshot, self.state_uri.as_deref(), &code)?;
+				let layout = live_state::StateLayout::new(&storage);
+				log::info!(
+					target: LOG_TARGET,
+					"Using {} additional trie layers as derived from the state snapshot",
+					layout.trie_layers,
+				);
+				let _ = self.state_layout.set(layout);
+				storage
+			},
+			None => state_handler.build_storage::<SubstrateAndExtraHF<ExtraHostFunctions>>(None)?,
+		};
 
 		let cache_size = Some(self.database_cache_size as usize);
 		let state_with_tracking = BenchmarkingState::<Hasher>::new(
@@ -713,6 +728,19 @@ impl PalletCmd {
 		}
 	}
 
+	/// The number of additional trie layers for the PoV estimation.
+	///
+	/// This is derived from the state snapshot when benchmarking on live state.
+	pub(crate) fn additional_trie_layers(&self) -> u8 {
+		self.state_layout()
+			.map_or(self.additional_trie_layers, |layout| layout.trie_layers)
+	}
+
+	/// The layout of the live state that the benchmarks ran on, if any.
+	pub(crate) fn state_layout(&self) -> Option<&live_state::StateLayout> {
+		self.state_layout.get()
+	}
+
 	/// Allocation strategy for pallet benchmarking.
 	fn alloc_strategy(&self, runtime_heap_pages: Option<u64>) -> HeapAllocStrategy {
 		self.heap_pages.or(runtime_heap_pages).map_or(DEFAULT_HEAP_ALLOC_STRATEGY, |p| {
@@ -733,6 +761,25 @@ impl PalletCmd {
 			self.print_summary(&batches, &storage_info, pov_modes.clone())
 		}
 
+		if let Some(path) = &self.storage_trace {
+			self.write_storage_trace(batches, storage_info, path)?;
+		}
+
+		// Check the results against the baseline weights. This reads the baseline before the new
+		// weight files are written, since they may be the same files, but only reports a failed
+		// comparison once the new files exist.
+		let comparison = self.compare.as_ref().map(|baseline_path| {
+			writer::compare_results(
+				&batches,
+				&storage_info,
+				&component_ranges,
+				pov_modes.clone(),
+				self.default_pov_mode,
+				baseline_path,
+				self,
+			)
+		});
+
 		// Create the weights.rs file.
 		if let Some(output_path) = &self.output {
 			writer::write_results(
@@ -746,6 +793,7 @@ impl PalletCmd {
 			)?;
 		}
 
+		comparison.transpose()?;
 		Ok(())
 	}
 
@@ -806,6 +854,44 @@ impl PalletCmd {
 		Ok(false)
 	}
 
+	/// Writes the storage accesses of each benchmark into a file and warns about the ones that are
+	/// not modelled by the weight.
+	fn write_storage_trace(
+		&self,
+		batches: &[BenchmarkBatchSplitResults],
+		storage_info: &[StorageInfo],
+		path: &std::path::Path,
+	) -> Result<()> {
+		let traces = trace::storage_traces(
+			batches,
+			storage_info,
+			self.worst_case_map_values,
+			self.additional_trie_layers(),
+		);
+
+		for trace in &traces {
+			for access in trace.accesses.iter().filter(|a| a.unmodelled) {
+				log::warn!(
+					target: LOG_TARGET,
+					"Benchmark `{}::{}` accesses {} (r:{} w:{}) which is not modelled by its weight",
+					trace.pallet,
+					trace.benchmark,
+					access.item.as_deref().map_or_else(
+						|| format!("UNKNOWN KEY `{}`", access.prefix),
+						|item| format!("`{item}`")
+					),
+					access.reads,
+					access.writes,
+				);
+			}
+		}
+
+		let json = serde_json::to_string_pretty(&traces)
+			.map_err(|e| format!("Serializing into JSON: {:?}", e))?;
+		fs::write(path, json)?;
+		Ok(())
+	}
+
 	/// Prints the results as human-readable summary without raw timing data.
 	fn print_summary(
 		&self,
@@ -844,7 +930,8 @@ impl PalletCmd {
 					&pov_mode,
 					self.default_pov_mode,
 					self.worst_case_map_values,
-					self.additional_trie_layers,
+					self.additional_trie_layers(),
+					self.state_layout(),
 				);
 				println!("Raw Storage Info\n========");
 				for comment in comments {
@@ -980,6 +1067,18 @@ impl PalletCmd {
 			}
 		}
 
+		if let Some(snapshot) = &self.state_snapshot {
+			if self.state_uri.is_none() && !snapshot.is_file() {
+				return Err((
+					ErrorKind::InvalidValue,
+					format!(
+						"State snapshot could not be found: {snapshot:?}. Pass `--state-uri` to \
+						download it."
+					),
+				));
+			}
+		}
+
 		if let Some(header_file) = &self.header {
 			if !header_file.is_file() {
 				return Err((
@@ -0,0 +1,294 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Compares benchmark results against previously generated weight files.
+
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	fmt,
+};
+
+/// The kind of a weight term.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Metric {
+	RefTime,
+	ProofSize,
+	Reads,
+	Writes,
+}
+
+impl fmt::Display for Metric {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Metric::RefTime => write!(f, "ref_time"),
+			Metric::ProofSize => write!(f, "proof_size"),
+			Metric::Reads => write!(f, "reads"),
+			Metric::Writes => write!(f, "writes"),
+		}
+	}
+}
+
+/// A term of a weight function: the base value of a metric if the component is `None`, otherwise
+/// the slope of the metric for that component.
+pub(crate) type Term = (Metric, Option<String>);
+
+/// The terms of a single weight function. Missing terms are zero.
+pub(crate) type WeightTerms = BTreeMap<Term, u128>;
+
+/// Parse all weight functions of a weight file generated by the `benchmark pallet` command.
+///
+/// Only the first function with a given name is parsed, which is the runtime implementation for
+/// weight files that also contain an implementation for `()`.
+pub(crate) fn parse_weight_file(content: &str) -> BTreeMap<String, WeightTerms> {
+	let mut functions = BTreeMap::new();
+	let mut rest = content;
+
+	while let Some(start) = rest.find("\tfn ") {
+		rest = &rest[start + "\tfn ".len()..];
+		let Some(name_end) = rest.find('(') else { break };
+		let name = rest[..name_end].trim().to_string();
+		// Skip declarations, like the ones of the `WeightInfo` trait.
+		match rest.find(|c| c == ';' || c == '{') {
+			Some(end) if rest[end..].starts_with('{') => {},
+			_ => continue,
+		}
+		let Some(body_start) = rest.find("-> Weight {") else { break };
+		let body = &rest[body_start + "-> Weight {".len()..];
+		let body_end = body.find("\n\t}").unwrap_or(body.len());
+		rest = &body[body_end..];
+		let body = &body[..body_end];
+
+		if !functions.contains_key(&name) {
+			functions.insert(name, parse_weight_body(body));
+		}
+	}
+
+	functions
+}
+
+/// Parse the body of a single weight function.
+fn parse_weight_body(body: &str) -> WeightTerms {
+	let code = body
+		.lines()
+		.map(str::trim)
+		.filter(|line| !line.starts_with("//"))
+		.collect::<String>()
+		.replace(' ', "");
+
+	let mut terms = WeightTerms::new();
+	let mut add = |term: Term, value: u128| {
+		if value != 0 {
+			*terms.entry(term).or_default() += value;
+		}
+	};
+
+	for part in code.split(".saturating_add(") {
+		let component = part
+			.split_once(".saturating_mul(")
+			.map(|(_, c)| c.split('.').next().unwrap_or_default().to_string());
+
+		if let Some(args) = part.strip_prefix("Weight::from_parts(") {
+			let mut args = args.split(|c| c == ',' || c == ')');
+			let ref_time = args.next().and_then(parse_number).unwrap_or_default();
+			let proof_size = args.next().and_then(parse_number).unwrap_or_default();
+			add((Metric::RefTime, component.clone()), ref_time);
+			add((Metric::ProofSize, component), proof_size);
+		} else if let Some((_, access)) = part.split_once("::get().") {
+			let (metric, args) = if let Some(args) = access.strip_prefix("reads(") {
+				(Metric::Reads, args)
+			} else if let Some(args) = access.strip_prefix("writes(") {
+				(Metric::Writes, args)
+			} else {
+				continue
+			};
+			let value = args
+				.trim_start_matches('(')
+				.split(')')
+				.next()
+				.and_then(parse_number)
+				.unwrap_or_default();
+			add((metric, component), value);
+		}
+	}
+
+	terms
+}
+
+/// Parse a number literal like `1_234` or `12_u64`.
+fn parse_number(s: &str) -> Option<u128> {
+	let s = s.trim().trim_end_matches("_u64");
+	s.replace('_', "").parse().ok()
+}
+
+/// The change of a single weight term.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TermChange {
+	pub extrinsic: String,
+	pub term: Term,
+	pub old: u128,
+	pub new: u128,
+}
+
+impl TermChange {
+	/// The change in percent, `None` if the old value was zero.
+	pub fn percent(&self) -> Option<f64> {
+		(self.old != 0).then(|| (self.new as f64 - self.old as f64) / self.old as f64 * 100.0)
+	}
+
+	/// Whether the term increased by more than `threshold` percent. A term that was zero before
+	/// and is not anymore always counts as regression.
+	pub fn is_regression(&self, threshold: f64) -> bool {
+		self.new > self.old && self.percent().map_or(true, |p| p > threshold)
+	}
+}
+
+impl fmt::Display for TermChange {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let (metric, component) = &self.term;
+		let term = match component {
+			Some(c) => format!("{metric}[{c}]"),
+			None => metric.to_string(),
+		};
+		let percent = match self.percent() {
+			Some(p) => format!("{p:+.2}%"),
+			None => "new".into(),
+		};
+		write!(f, "{}: {term}: {} -> {} ({percent})", self.extrinsic, self.old, self.new)
+	}
+}
+
+/// Compare the weight functions of a pallet against their baseline.
+///
+/// Extrinsics that are not part of the baseline are skipped. Terms that did not change are not
+/// reported.
+pub(crate) fn compare_weights(
+	baseline: &BTreeMap<String, WeightTerms>,
+	new: &BTreeMap<String, WeightTerms>,
+) -> Vec<TermChange> {
+	let mut changes = vec![];
+	for (extrinsic, new_terms) in new {
+		let Some(old_terms) = baseline.get(extrinsic) else { continue };
+		let terms = old_terms.keys().chain(new_terms.keys()).collect::<BTreeSet<_>>();
+		for term in terms {
+			let old = old_terms.get(term).copied().unwrap_or_default();
+			let new = new_terms.get(term).copied().unwrap_or_default();
+			if old != new {
+				changes.push(TermChange {
+					extrinsic: extrinsic.clone(),
+					term: term.clone(),
+					old,
+					new,
+				});
+			}
+		}
+	}
+	changes
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const WEIGHTS: &str = r#"
+/// Weight functions for `pallet_example`.
+pub trait WeightInfo {
+	fn noop() -> Weight;
+	fn set_value(n: u32, m: u32, ) -> Weight;
+}
+
+/// Weight functions for `pallet_example`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_example::WeightInfo for WeightInfo<T> {
+	/// Storage: `Example::Value` (r:1 w:1)
+	/// The range of component `n` is `[0, 100]`.
+	fn set_value(n: u32, _m: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `100 + n * (1 ±0)`
+		//  Estimated: `1_489`
+		// Minimum execution time: 9_000_000 picoseconds.
+		Weight::from_parts(10_215_000, 0)
+			.saturating_add(Weight::from_parts(0, 1489))
+			// Standard Error: 1_000
+			.saturating_add(Weight::from_parts(2_500, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(2))
+			.saturating_add(Weight::from_parts(0, 2500).saturating_mul(n.into()))
+	}
+	fn noop() -> Weight {
+		Weight::from_parts(1_000, 0)
+	}
+}
+impl WeightInfo for () {
+	fn noop() -> Weight {
+		Weight::from_parts(5_000, 0)
+	}
+}
+"#;
+
+	fn term(metric: Metric, component: Option<&str>) -> Term {
+		(metric, component.map(Into::into))
+	}
+
+	#[test]
+	fn parse_weight_file_works() {
+		let functions = parse_weight_file(WEIGHTS);
+		assert_eq!(functions.len(), 2);
+
+		let set_value = &functions["set_value"];
+		assert_eq!(set_value[&term(Metric::RefTime, None)], 10_215_000);
+		assert_eq!(set_value[&term(Metric::ProofSize, None)], 1489);
+		assert_eq!(set_value[&term(Metric::RefTime, Some("n"))], 2_500);
+		assert_eq!(set_value[&term(Metric::ProofSize, Some("n"))], 2500);
+		assert_eq!(set_value[&term(Metric::Reads, None)], 1);
+		assert_eq!(set_value[&term(Metric::Reads, Some("n"))], 1);
+		assert_eq!(set_value[&term(Metric::Writes, None)], 2);
+		assert_eq!(set_value.len(), 7);
+
+		// The first implementation wins.
+		assert_eq!(functions["noop"][&term(Metric::RefTime, None)], 1_000);
+	}
+
+	#[test]
+	fn compare_weights_works() {
+		let baseline = parse_weight_file(WEIGHTS);
+		let mut new = baseline.clone();
+		let set_value = new.get_mut("set_value").unwrap();
+		set_value.insert(term(Metric::RefTime, None), 11_236_500);
+		set_value.remove(&term(Metric::Reads, Some("n")));
+		set_value.insert(term(Metric::Writes, Some("n")), 1);
+		new.insert("new_extrinsic".into(), WeightTerms::new());
+
+		let changes = compare_weights(&baseline, &new);
+		assert_eq!(changes.len(), 3);
+
+		let ref_time = &changes[0];
+		assert_eq!(ref_time.term, term(Metric::RefTime, None));
+		assert_eq!(ref_time.percent().map(|p| p.round()), Some(10.0));
+		assert!(ref_time.is_regression(5.0));
+		assert!(!ref_time.is_regression(15.0));
+
+		// Removed reads are an improvement.
+		assert_eq!(changes[1].term, term(Metric::Reads, Some("n")));
+		assert!(!changes[1].is_regression(0.0));
+
+		// New writes are always a regression.
+		assert_eq!(changes[2].term, term(Metric::Writes, Some("n")));
+		assert!(changes[2].is_regression(1000.0));
+		assert_eq!(changes[2].to_string(), "set_value: writes[n]: 0 -> 1 (new)");
+	}
+}
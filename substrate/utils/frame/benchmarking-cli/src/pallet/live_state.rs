@@ -0,0 +1,241 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Loading of live chain state to run the benchmarks on.
+
+use super::command::LOG_TARGET;
+use remote_externalities::{Builder, Mode, OfflineConfig, OnlineConfig, SnapshotConfig};
+use sc_cli::Result;
+use sp_core::storage::{
+	well_known_keys::{CODE, DEFAULT_CHILD_STORAGE_KEY_PREFIX},
+	ChildInfo, Storage, StorageChild,
+};
+use sp_runtime::{generic, traits::BlakeTwo256, OpaqueExtrinsic};
+use sp_state_machine::{Backend, IterArgs};
+use std::{
+	collections::{BTreeMap, HashMap},
+	path::Path,
+};
+
+/// The block type used to decode state snapshots.
+///
+/// Only the header of the block is decoded, which is the same for all chains with `u32` block
+/// numbers and the `BlakeTwo256` hasher.
+type OpaqueBlock = generic::Block<generic::Header<u32, BlakeTwo256>, OpaqueExtrinsic>;
+
+/// Load the storage of the state snapshot at `snapshot`.
+///
+/// If the snapshot does not exist and a `uri` is given, the state is downloaded from the node at
+/// `uri` and written to `snapshot` first. The `:code` of the snapshot is replaced with `code`,
+/// since the benchmarks need a runtime that was built with the `runtime-benchmarks` feature.
+pub(crate) fn storage_from_snapshot(
+	snapshot: &Path,
+	uri: Option<&str>,
+	code: &[u8],
+) -> Result<Storage> {
+	let offline = OfflineConfig { state_snapshot: SnapshotConfig::new(snapshot) };
+	let mode = match uri {
+		Some(uri) => Mode::OfflineOrElseOnline(
+			offline,
+			OnlineConfig {
+				transport: uri.to_string().into(),
+				state_snapshot: Some(SnapshotConfig::new(snapshot)),
+				..Default::default()
+			},
+		),
+		None => Mode::Offline(offline),
+	};
+
+	let runtime = tokio::runtime::Builder::new_multi_thread()
+		.enable_all()
+		.build()
+		.map_err(|e| format!("Failed to start the tokio runtime: {e}"))?;
+	let mut ext = runtime
+		.block_on(Builder::<OpaqueBlock>::new().mode(mode).build())
+		.map_err(|e| format!("Failed to load the state snapshot {snapshot:?}: {e}"))?;
+	let backend = ext.as_backend();
+
+	let mut storage = Storage::default();
+	for pair in backend.pairs(Default::default()).map_err(|e| format!("{e}"))? {
+		let (key, value) = pair.map_err(|e| format!("{e}"))?;
+		match key.strip_prefix(DEFAULT_CHILD_STORAGE_KEY_PREFIX) {
+			Some(storage_key) => {
+				let child_info = ChildInfo::new_default(storage_key);
+				let mut args = IterArgs::default();
+				args.child_info = Some(child_info.clone());
+
+				let mut data = BTreeMap::new();
+				for pair in backend.pairs(args).map_err(|e| format!("{e}"))? {
+					let (key, value) = pair.map_err(|e| format!("{e}"))?;
+					data.insert(key, value);
+				}
+				storage
+					.children_default
+					.insert(storage_key.to_vec(), StorageChild { data, child_info });
+			},
+			None => {
+				storage.top.insert(key, value);
+			},
+		}
+	}
+	storage.top.insert(CODE.to_vec(), code.to_vec());
+
+	log::info!(
+		target: LOG_TARGET,
+		"Loaded {} top-level keys and {} child tries from the state snapshot {snapshot:?}",
+		storage.top.len(),
+		storage.children_default.len(),
+	);
+	Ok(storage)
+}
+
+/// The layout of the trie of a state snapshot, used for the PoV estimation.
+#[derive(Debug, Default)]
+pub(crate) struct StateLayout {
+	/// The number of trie layers above the storage items.
+	///
+	/// This is `log16(n)`, rounded up, where `n` is the number of distinct storage items. Keys of
+	/// a `StorageMap` share the 32 byte prefix of their map, so every prefix counts as one item.
+	/// The layers below that prefix depend on the size of the map, see [`Self::map_entries`].
+	pub trie_layers: u8,
+	/// The number of keys under each 32 byte storage prefix.
+	entries: HashMap<Vec<u8>, u32>,
+}
+
+impl StateLayout {
+	/// Derive the layout of the top-level trie of `storage`.
+	pub fn new(storage: &Storage) -> Self {
+		let mut entries = HashMap::<Vec<u8>, u32>::new();
+		for key in storage.top.keys() {
+			*entries.entry(key[..key.len().min(32)].to_vec()).or_default() += 1;
+		}
+
+		let mut layers = 0;
+		while 16u64.saturating_pow(layers) < entries.len() as u64 {
+			layers += 1;
+		}
+		Self { trie_layers: layers as u8, entries }
+	}
+
+	/// The number of entries of the map with the 32 byte `prefix`.
+	pub fn map_entries(&self, prefix: &[u8]) -> u32 {
+		self.entries.get(prefix).copied().unwrap_or_default()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::{Compact, Encode};
+	use sp_runtime::{
+		traits::{Block as BlockT, Header as _},
+		StateVersion,
+	};
+	use sp_state_machine::TestExternalities;
+
+	fn storage_with_keys(keys: impl IntoIterator<Item = Vec<u8>>) -> Storage {
+		let mut storage = Storage::default();
+		storage.top.extend(keys.into_iter().map(|k| (k, vec![0])));
+		storage
+	}
+
+	#[test]
+	fn state_layout_works() {
+		assert_eq!(StateLayout::new(&Storage::default()).trie_layers, 0);
+		assert_eq!(StateLayout::new(&storage_with_keys([b":code".to_vec()])).trie_layers, 0);
+		assert_eq!(
+			StateLayout::new(&storage_with_keys((0u8..16).map(|i| vec![i; 32]))).trie_layers,
+			1
+		);
+		assert_eq!(
+			StateLayout::new(&storage_with_keys((0u8..17).map(|i| vec![i; 32]))).trie_layers,
+			2
+		);
+		assert_eq!(
+			StateLayout::new(&storage_with_keys((0u8..=255).map(|i| vec![i; 32]))).trie_layers,
+			2
+		);
+	}
+
+	#[test]
+	fn state_layout_counts_map_prefixes_once() {
+		// 100 entries of the same map are a single storage item.
+		let keys = (0u8..100).map(|i| [[7u8; 32].as_slice(), &[i; 16]].concat());
+		assert_eq!(StateLayout::new(&storage_with_keys(keys)).trie_layers, 0);
+
+		let keys = (0u8..100)
+			.map(|i| [[7u8; 32].as_slice(), &[i; 16]].concat())
+			.chain((0u8..16).map(|i| vec![i; 32]));
+		// 16 distinct items: the 16 values, one of which shares its prefix with the map.
+		assert_eq!(StateLayout::new(&storage_with_keys(keys)).trie_layers, 1);
+	}
+
+	#[test]
+	fn state_layout_counts_map_entries() {
+		let keys = (0u8..100)
+			.map(|i| [[7u8; 32].as_slice(), &[i; 16]].concat())
+			.chain([vec![8; 32]]);
+		let layout = StateLayout::new(&storage_with_keys(keys));
+
+		assert_eq!(layout.map_entries(&[7; 32]), 100);
+		assert_eq!(layout.map_entries(&[8; 32]), 1);
+		assert_eq!(layout.map_entries(&[9; 32]), 0);
+	}
+
+	#[test]
+	fn storage_from_snapshot_works() {
+		let child_info = ChildInfo::new_default(b"child");
+		let mut storage = storage_with_keys([vec![1; 32], vec![2; 40]]);
+		storage.top.insert(CODE.to_vec(), b"live code".to_vec());
+		storage.children_default.insert(
+			child_info.storage_key().to_vec(),
+			StorageChild {
+				data: [(b"child key".to_vec(), b"child value".to_vec())].into(),
+				child_info: child_info.clone(),
+			},
+		);
+
+		let ext =
+			TestExternalities::<BlakeTwo256>::new_with_state_version(storage, StateVersion::V1);
+		let (raw_storage, storage_root) = ext.into_raw_snapshot();
+		let header = <OpaqueBlock as BlockT>::Header::new(
+			1,
+			Default::default(),
+			storage_root,
+			Default::default(),
+			Default::default(),
+		);
+		let snapshot = std::env::temp_dir()
+			.join(format!("storage_from_snapshot_works_{}.snap", std::process::id()));
+		std::fs::write(
+			&snapshot,
+			(Compact(4u16), StateVersion::V1, raw_storage, storage_root, header).encode(),
+		)
+		.unwrap();
+
+		let storage = storage_from_snapshot(&snapshot, None, b"benchmark code");
+		std::fs::remove_file(&snapshot).unwrap();
+		let storage = storage.unwrap();
+
+		assert_eq!(storage.top.get(&vec![1; 32]), Some(&vec![0]));
+		assert_eq!(storage.top.get(&vec![2; 40]), Some(&vec![0]));
+		assert_eq!(storage.top.get(CODE), Some(&b"benchmark code".to_vec()));
+		assert_eq!(storage.top.len(), 3);
+		let child = &storage.children_default[child_info.storage_key()];
+		assert_eq!(child.data.get(b"child key".as_slice()), Some(&b"child value".to_vec()));
+	}
+}
@@ -16,6 +16,9 @@
 // limitations under the License.
 
 mod command;
+mod compare;
+mod live_state;
+mod trace;
 mod types;
 mod writer;
 
@@ -26,7 +29,7 @@ use sc_cli::{
 	WasmExecutionMethod, WasmtimeInstantiationStrategy, DEFAULT_WASMTIME_INSTANTIATION_STRATEGY,
 	DEFAULT_WASM_EXECUTION_METHOD,
 };
-use std::{fmt::Debug, path::PathBuf};
+use std::{fmt::Debug, path::PathBuf, sync::OnceLock};
 
 // Add a more relaxed parsing for pallet names by allowing pallet directory names with `-` to be
 // used like crate names with `_`
@@ -94,6 +97,15 @@ pub struct PalletCmd {
 	#[arg(long, conflicts_with = "json_output")]
 	pub json_file: Option<PathBuf>,
 
+	/// Write the storage items that were accessed by each benchmark in JSON format into the
+	/// given file.
+	///
+	/// Accesses that grow with a component that the reads or writes of the benchmark do not grow
+	/// with are flagged as `unmodelled` and reported as warnings. The `proof_size` of each item is
+	/// its `MaxEncodedLen` worst case.
+	#[arg(long, value_name = "PATH")]
+	pub storage_trace: Option<PathBuf>,
+
 	/// Don't print the median-slopes linear regression analysis.
 	#[arg(long)]
 	pub no_median_slopes: bool,
@@ -114,6 +126,19 @@ pub struct PalletCmd {
 	#[arg(long)]
 	pub template: Option<PathBuf>,
 
+	/// Compare the results against the weight files at this path and fail on regressions.
+	///
+	/// The path is resolved like `--output`: either a single weight file or a directory that
+	/// contains one weight file per pallet. Can be combined with `--output`, in which case the new
+	/// weight files are still written if the comparison fails. Only weight files generated with
+	/// the default template can be compared.
+	#[arg(long, value_name = "PATH")]
+	pub compare: Option<PathBuf>,
+
+	/// Percentage by which a weight term may increase before `--compare` reports a regression.
+	#[arg(long, default_value_t = 10.0, requires = "compare")]
+	pub compare_threshold: f64,
+
 	#[allow(missing_docs)]
 	#[clap(flatten)]
 	pub hostinfo_params: HostInfoParams,
@@ -193,6 +218,21 @@ pub struct PalletCmd {
 	#[arg(long, default_value = sp_genesis_builder::DEV_RUNTIME_PRESET)]
 	pub genesis_builder_preset: String,
 
+	/// Run the benchmarks on top of the live chain state in this snapshot file.
+	///
+	/// Snapshots can be created with `try-runtime create-snapshot`. No genesis state is built and
+	/// the runtime code of the snapshot is replaced with the one from `--runtime` or `--chain`.
+	/// The proof sizes are then recorded against the real trie, so no additional trie layers are
+	/// added to `Measured` results. `MaxEncodedLen` results use the number of entries of each map
+	/// in the snapshot instead of its `max_values`, and `--additional-trie-layers` is derived from
+	/// the number of storage items in the snapshot.
+	#[arg(long, value_name = "PATH")]
+	pub state_snapshot: Option<PathBuf>,
+
+	/// Download the live state from this node if the `--state-snapshot` file does not exist yet.
+	#[arg(long, value_name = "URI", requires = "state_snapshot")]
+	pub state_uri: Option<String>,
+
 	/// DEPRECATED: This argument has no effect.
 	#[arg(long = "execution")]
 	pub execution: Option<String>,
@@ -234,6 +274,8 @@ pub struct PalletCmd {
 	/// Each layer will result in an additional 495 bytes PoV per distinct top-level access.
 	/// Therefore multiple `StorageMap` accesses only suffer from this increase once. The exact
 	/// number of storage items depends on the runtime and the deployed pallets.
+	///
+	/// This is derived from the state when benchmarking with `--state-snapshot`.
 	#[clap(long, default_value = "2")]
 	pub additional_trie_layers: u8,
 
@@ -264,6 +306,10 @@ pub struct PalletCmd {
 	/// solo-chains) can disable proof recording to get more accurate results.
 	#[arg(long)]
 	disable_proof_recording: bool,
+
+	/// The layout of the state loaded from `--state-snapshot`, if any.
+	#[arg(skip)]
+	state_layout: OnceLock<live_state::StateLayout>,
 }
 
 /// How the genesis state for benchmarking should be built.
@@ -0,0 +1,300 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Per-benchmark traces of the storage items that were accessed.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+use crate::pallet::writer::single_read_pov_overhead;
+use frame_benchmarking::{
+	Analysis, BenchmarkBatchSplitResults, BenchmarkResult, BenchmarkSelector,
+};
+use frame_support::traits::StorageInfo;
+use sp_core::hexdisplay::HexDisplay;
+
+/// The storage accesses of a single benchmark.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub(crate) struct BenchmarkTrace {
+	pub pallet: String,
+	pub instance: String,
+	pub benchmark: String,
+	pub accesses: Vec<StorageAccess>,
+}
+
+/// The accesses to a single storage item during a benchmark.
+///
+/// The counts are the maximum over all component values that were benchmarked.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub(crate) struct StorageAccess {
+	/// The `Pallet::Item` name of the storage item, or `None` if the key is unknown.
+	pub item: Option<String>,
+	/// The hex encoded storage prefix of the item, or the whole key if it is shorter.
+	pub prefix: String,
+	pub reads: u32,
+	pub writes: u32,
+	pub whitelisted: bool,
+	/// The `MaxEncodedLen` worst case proof size of the reads, if the item has such a bound.
+	///
+	/// This is independent of the PoV estimation mode that the weight of the benchmark uses.
+	pub proof_size: Option<u32>,
+	/// Whether the weight of the benchmark does not account for these accesses.
+	///
+	/// This is the case if the number of reads or writes of the item grows with a component,
+	/// while the reads or writes of the benchmark do not.
+	pub unmodelled: bool,
+}
+
+#[derive(Default)]
+struct Counts {
+	reads: Vec<u32>,
+	writes: Vec<u32>,
+	whitelisted: bool,
+}
+
+/// Trace which storage items were accessed by each benchmark in `batches`.
+pub(crate) fn storage_traces(
+	batches: &[BenchmarkBatchSplitResults],
+	storage_info: &[StorageInfo],
+	worst_case_map_values: u32,
+	additional_trie_layers: u8,
+) -> Vec<BenchmarkTrace> {
+	let storage_info_map = storage_info
+		.iter()
+		.map(|info| (info.prefix.as_slice(), info))
+		.collect::<HashMap<_, _>>();
+
+	batches
+		.iter()
+		.map(|batch| {
+			let results = &batch.db_results;
+
+			// Sum up the accesses per storage prefix in each result.
+			let mut counts = BTreeMap::<Vec<u8>, Counts>::new();
+			for (i, result) in results.iter().enumerate() {
+				for (key, reads, writes, whitelisted) in &result.keys {
+					let prefix = key[..key.len().min(32)].to_vec();
+					let entry = counts.entry(prefix).or_default();
+					entry.reads.resize(results.len(), 0);
+					entry.writes.resize(results.len(), 0);
+					entry.reads[i] += reads;
+					entry.writes[i] += writes;
+					entry.whitelisted |= whitelisted;
+				}
+			}
+
+			let benchmark_reads =
+				growing_components(results, BenchmarkSelector::Reads).collect::<Vec<_>>();
+			let benchmark_writes =
+				growing_components(results, BenchmarkSelector::Writes).collect::<Vec<_>>();
+
+			let accesses = counts
+				.into_iter()
+				.map(|(prefix, counts)| {
+					let info = storage_info_map.get(prefix.as_slice());
+					let reads = counts.reads.iter().copied().max().unwrap_or_default();
+					let writes = counts.writes.iter().copied().max().unwrap_or_default();
+
+					let proof_size = info.and_then(|info| info.max_size).map(|max_size| {
+						let overhead =
+							single_read_pov_overhead(info.max_values, worst_case_map_values);
+						let layers =
+							if reads > 0 { 15 * 33 * additional_trie_layers as u32 } else { 0 };
+						(overhead + max_size) * reads + layers
+					});
+					// The accesses of the item as if they were the only ones of the benchmark.
+					let item_results = results
+						.iter()
+						.zip(counts.reads.iter().zip(&counts.writes))
+						.map(|(result, (reads, writes))| BenchmarkResult {
+							reads: *reads,
+							writes: *writes,
+							..result.clone()
+						})
+						.collect::<Vec<_>>();
+					let unmodelled = !counts.whitelisted &&
+						(growing_components(&item_results, BenchmarkSelector::Reads)
+							.any(|c| !benchmark_reads.contains(&c)) ||
+							growing_components(&item_results, BenchmarkSelector::Writes)
+								.any(|c| !benchmark_writes.contains(&c)));
+
+					StorageAccess {
+						item: info.map(|info| {
+							format!(
+								"{}::{}",
+								String::from_utf8_lossy(&info.pallet_name),
+								String::from_utf8_lossy(&info.storage_name)
+							)
+						}),
+						prefix: format!("0x{}", HexDisplay::from(&prefix)),
+						reads,
+						writes,
+						whitelisted: counts.whitelisted,
+						proof_size,
+						unmodelled,
+					}
+				})
+				.collect();
+
+			BenchmarkTrace {
+				pallet: String::from_utf8_lossy(&batch.pallet).into(),
+				instance: String::from_utf8_lossy(&batch.instance).into(),
+				benchmark: String::from_utf8_lossy(&batch.benchmark).into(),
+				accesses,
+			}
+		})
+		.collect()
+}
+
+/// The components with a non-zero slope in the regression of `selector` over `results`.
+fn growing_components(
+	results: &Vec<BenchmarkResult>,
+	selector: BenchmarkSelector,
+) -> impl Iterator<Item = String> {
+	let analysis = match results.is_empty() {
+		true => None,
+		false => Analysis::min_squares_iqr(results, selector),
+	};
+	analysis
+		.into_iter()
+		.flat_map(|a| a.names.into_iter().zip(a.slopes))
+		.filter(|(_, slope)| *slope != 0)
+		.map(|(name, _)| name)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_benchmarking::{BenchmarkParameter, BenchmarkResult};
+
+	fn result(i: u32, keys: Vec<(Vec<u8>, u32, u32, bool)>) -> BenchmarkResult {
+		BenchmarkResult {
+			components: vec![(BenchmarkParameter::n, i)],
+			reads: keys.iter().map(|k| k.1).sum(),
+			writes: keys.iter().map(|k| k.2).sum(),
+			keys,
+			..Default::default()
+		}
+	}
+
+	fn batch(results: Vec<BenchmarkResult>) -> BenchmarkBatchSplitResults {
+		BenchmarkBatchSplitResults {
+			pallet: b"pallet".to_vec(),
+			instance: b"instance".to_vec(),
+			benchmark: b"benchmark".to_vec(),
+			time_results: results.clone(),
+			db_results: results,
+		}
+	}
+
+	fn storage_info() -> Vec<StorageInfo> {
+		vec![StorageInfo {
+			pallet_name: b"Pallet".to_vec(),
+			storage_name: b"Map".to_vec(),
+			prefix: vec![1; 32],
+			max_values: Some(16),
+			max_size: Some(32),
+		}]
+	}
+
+	fn map_key(i: u8) -> Vec<u8> {
+		[vec![1; 32], vec![i]].concat()
+	}
+
+	#[test]
+	fn storage_traces_resolve_items() {
+		let results = (0..4)
+			.map(|i| {
+				result(
+					i,
+					vec![
+						(map_key(0), 1, 1, false),
+						(map_key(1), 1, 0, false),
+						(b"unknown".to_vec(), 1, 0, false),
+						(b"whitelisted".to_vec(), 1, 1, true),
+					],
+				)
+			})
+			.collect();
+
+		let traces = storage_traces(&[batch(results)], &storage_info(), 1_000, 2);
+		assert_eq!(traces.len(), 1);
+		assert_eq!(traces[0].benchmark, "benchmark");
+
+		let accesses = &traces[0].accesses;
+		assert_eq!(accesses.len(), 3);
+		assert_eq!(accesses[0].item.as_deref(), Some("Pallet::Map"));
+		assert_eq!((accesses[0].reads, accesses[0].writes), (2, 1));
+		// Two reads of a map with 16 values, plus two additional trie layers.
+		assert_eq!(accesses[0].proof_size, Some((15 * 33 + 32) * 2 + 2 * 15 * 33));
+		assert!(!accesses[0].unmodelled);
+
+		// Unknown keys are reported, but not flagged just for being unknown.
+		assert_eq!(accesses[1].item, None);
+		assert_eq!(accesses[1].prefix, "0x756e6b6e6f776e");
+		assert!(!accesses[1].unmodelled);
+
+		assert!(accesses[2].whitelisted);
+		assert!(!accesses[2].unmodelled);
+	}
+
+	#[test]
+	fn storage_traces_flag_component_dependent_accesses() {
+		// The reads of the map grow with `n`, but the reads of the benchmark do not since the
+		// unknown key is read less at the same time.
+		let results = (0..4)
+			.map(|i| {
+				result(
+					i,
+					vec![(map_key(0), 1 + i, 0, false), (b"unknown".to_vec(), 10 - i, 0, false)],
+				)
+			})
+			.collect::<Vec<_>>();
+		let traces = storage_traces(&[batch(results.clone())], &storage_info(), 1_000, 0);
+		assert!(traces[0].accesses[0].unmodelled);
+		// The reads of the unknown key shrink, which the weight does not have to account for.
+		assert!(!traces[0].accesses[1].unmodelled);
+
+		// Once the reads of the benchmark grow with `n`, they are modelled.
+		let results = (0..4)
+			.map(|i| result(i, vec![(map_key(0), 1 + i, 0, false)]))
+			.collect::<Vec<_>>();
+		let traces = storage_traces(&[batch(results)], &storage_info(), 1_000, 0);
+		assert!(!traces[0].accesses[0].unmodelled);
+	}
+
+	#[test]
+	fn storage_traces_judge_each_item_by_its_own_components() {
+		// The reads of the map grow with `n` and the reads of the unknown key grow with `m`. The
+		// reads of the benchmark only grow with `m`, so only the map is unmodelled.
+		let result = |n: u32, m: u32| BenchmarkResult {
+			components: vec![(BenchmarkParameter::n, n), (BenchmarkParameter::m, m)],
+			reads: 11 + m,
+			keys: vec![(map_key(0), 1 + n, 0, false), (b"unknown".to_vec(), 10 + m - n, 0, false)],
+			..Default::default()
+		};
+		let results = (0..4).map(|n| result(n, 0)).chain((1..4).map(|m| result(0, m))).collect();
+
+		let traces = storage_traces(&[batch(results)], &storage_info(), 1_000, 0);
+		let accesses = &traces[0].accesses;
+		assert_eq!(accesses[0].item.as_deref(), Some("Pallet::Map"));
+		assert!(accesses[0].unmodelled);
+		assert_eq!(accesses[1].item, None);
+		assert!(!accesses[1].unmodelled);
+	}
+}
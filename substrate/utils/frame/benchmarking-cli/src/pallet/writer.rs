@@ -18,9 +18,9 @@
 // Outputs benchmark results to Rust files that can be ingested by the runtime.
 
 use std::{
-	collections::{HashMap, HashSet},
+	collections::{BTreeMap, HashMap, HashSet},
 	fs,
-	path::PathBuf,
+	path::{Path, PathBuf},
 };
 
 use inflector::Inflector;
@@ -30,6 +30,8 @@ use serde::Serialize;
 use crate::{
 	pallet::{
 		command::{PovEstimationMode, PovModesMap},
+		compare::{self, Metric, WeightTerms},
+		live_state::StateLayout,
 		types::{ComponentRange, ComponentRangeMap},
 	},
 	shared::UnderscoreHelper,
@@ -142,6 +144,7 @@ fn map_results(
 	pov_analysis_choice: &AnalysisChoice,
 	worst_case_map_values: u32,
 	additional_trie_layers: u8,
+	state_layout: Option<&StateLayout>,
 ) -> Result<HashMap<(String, String), Vec<BenchmarkData>>, std::io::Error> {
 	// Skip if batches is empty.
 	if batches.is_empty() {
@@ -168,6 +171,7 @@ fn map_results(
 			pov_analysis_choice,
 			worst_case_map_values,
 			additional_trie_layers,
+			state_layout,
 		);
 		let pallet_benchmarks = all_benchmarks.entry((pallet_name, instance_name)).or_default();
 		pallet_benchmarks.push(benchmark_data);
@@ -198,6 +202,7 @@ fn get_benchmark_data(
 	pov_analysis_choice: &AnalysisChoice,
 	worst_case_map_values: u32,
 	additional_trie_layers: u8,
+	state_layout: Option<&StateLayout>,
 ) -> BenchmarkData {
 	// Analyze benchmarks to get the linear regression.
 	let analysis_function = match analysis_choice {
@@ -296,6 +301,7 @@ fn get_benchmark_data(
 		default_pov_mode,
 		worst_case_map_values,
 		additional_trie_layers,
+		state_layout,
 	);
 
 	let proof_size_per_components = storage_per_prefix
@@ -413,10 +419,10 @@ pub(crate) fn write_results(
 	let pov_analysis_choice: AnalysisChoice =
 		cmd.output_pov_analysis.clone().try_into().map_err(io_error)?;
 
-	if cmd.additional_trie_layers > 4 {
+	if cmd.additional_trie_layers() > 4 {
 		println!(
 			"WARNING: `additional_trie_layers` is unexpectedly large. It assumes {} storage items.",
-			16f64.powi(cmd.additional_trie_layers as i32)
+			16f64.powi(cmd.additional_trie_layers() as i32)
 		)
 	}
 
@@ -431,15 +437,10 @@ pub(crate) fn write_results(
 		db_cache: cmd.database_cache_size,
 		analysis_choice: format!("{:?}", analysis_choice),
 		worst_case_map_values: cmd.worst_case_map_values,
-		additional_trie_layers: cmd.additional_trie_layers,
+		additional_trie_layers: cmd.additional_trie_layers(),
 	};
 
-	// New Handlebars instance with helpers.
-	let mut handlebars = handlebars::Handlebars::new();
-	handlebars.register_helper("underscore", Box::new(UnderscoreHelper));
-	handlebars.register_helper("join", Box::new(JoinHelper));
-	// Don't HTML escape any characters.
-	handlebars.register_escape_fn(|s| -> String { s.to_string() });
+	let handlebars = new_handlebars();
 
 	// Organize results by pallet into a JSON map
 	let all_results = map_results(
@@ -451,25 +452,13 @@ pub(crate) fn write_results(
 		&analysis_choice,
 		&pov_analysis_choice,
 		cmd.worst_case_map_values,
-		cmd.additional_trie_layers,
+		cmd.additional_trie_layers(),
+		cmd.state_layout(),
 	)?;
 	let mut created_files = Vec::new();
 
 	for ((pallet, instance), results) in all_results.iter() {
-		let mut file_path = path.clone();
-		// If a user only specified a directory...
-		if file_path.is_dir() {
-			// Start with "path/to/pallet_name".
-			let mut file_name = pallet.clone();
-			// Check if there might be multiple instances benchmarked.
-			if all_results.keys().any(|(p, i)| p == pallet && i != instance) {
-				// Append "_instance_name".
-				file_name = format!("{}_{}", file_name, instance.to_snake_case());
-			}
-			// "mod::pallet_name.rs" becomes "mod_pallet_name.rs".
-			file_path.push(file_name.replace("::", "_"));
-			file_path.set_extension("rs");
-		}
+		let file_path = weight_file_path(path, &all_results, pallet, instance);
 
 		let hbs_data = TemplateData {
 			args: args.clone(),
@@ -513,10 +502,172 @@ pub(crate) fn write_results(
 	Ok(())
 }
 
+// New Handlebars instance with helpers.
+fn new_handlebars() -> handlebars::Handlebars<'static> {
+	let mut handlebars = handlebars::Handlebars::new();
+	handlebars.register_helper("underscore", Box::new(UnderscoreHelper));
+	handlebars.register_helper("join", Box::new(JoinHelper));
+	// Don't HTML escape any characters.
+	handlebars.register_escape_fn(|s| -> String { s.to_string() });
+	handlebars
+}
+
+// Returns the path of the weight file of a pallet instance. If `path` is a directory, the file is
+// named after the pallet, and the instance if there are multiple instances of the pallet.
+fn weight_file_path(
+	path: &Path,
+	all_results: &HashMap<(String, String), Vec<BenchmarkData>>,
+	pallet: &str,
+	instance: &str,
+) -> PathBuf {
+	let mut file_path = path.to_path_buf();
+	// If a user only specified a directory...
+	if file_path.is_dir() {
+		// Start with "path/to/pallet_name".
+		let mut file_name = pallet.to_string();
+		// Check if there might be multiple instances benchmarked.
+		if all_results.keys().any(|(p, i)| p == pallet && i != instance) {
+			// Append "_instance_name".
+			file_name = format!("{}_{}", file_name, instance.to_snake_case());
+		}
+		// "mod::pallet_name.rs" becomes "mod_pallet_name.rs".
+		file_path.push(file_name.replace("::", "_"));
+		file_path.set_extension("rs");
+	}
+	file_path
+}
+
+// Converts the results of a benchmark into the terms of its weight function, as they are written
+// by the default template.
+fn weight_terms(benchmark: &BenchmarkData) -> WeightTerms {
+	let mut terms = WeightTerms::new();
+	let mut add = |metric: Metric, component: Option<&str>, value: u128| {
+		if value != 0 {
+			terms.insert((metric, component.map(Into::into)), value);
+		}
+	};
+
+	add(Metric::RefTime, None, benchmark.base_weight);
+	add(Metric::ProofSize, None, benchmark.base_calculated_proof_size);
+	add(Metric::Reads, None, benchmark.base_reads);
+	add(Metric::Writes, None, benchmark.base_writes);
+	for (metric, slopes) in [
+		(Metric::RefTime, &benchmark.component_weight),
+		(Metric::ProofSize, &benchmark.component_calculated_proof_size),
+		(Metric::Reads, &benchmark.component_reads),
+		(Metric::Writes, &benchmark.component_writes),
+	] {
+		for slope in slopes {
+			add(metric, Some(&slope.name), slope.slope);
+		}
+	}
+	terms
+}
+
+/// Compares the benchmark results against the weight files at `baseline`.
+///
+/// Prints the changes of all weight terms and fails if any term increased by more than
+/// `--compare-threshold` percent. It also fails if the baseline of a pallet is missing, contains
+/// no weight functions that can be parsed, or lacks a function for a benchmarked extrinsic, since
+/// nothing would be compared otherwise. Only weight files in the format of the default template
+/// can be parsed.
+pub(crate) fn compare_results(
+	batches: &[BenchmarkBatchSplitResults],
+	storage_info: &[StorageInfo],
+	component_ranges: &ComponentRangeMap,
+	pov_modes: PovModesMap,
+	default_pov_mode: PovEstimationMode,
+	baseline: &Path,
+	cmd: &PalletCmd,
+) -> Result<(), sc_cli::Error> {
+	let analysis_choice: AnalysisChoice =
+		cmd.output_analysis.clone().try_into().map_err(io_error)?;
+	let pov_analysis_choice: AnalysisChoice =
+		cmd.output_pov_analysis.clone().try_into().map_err(io_error)?;
+
+	let all_results = map_results(
+		batches,
+		storage_info,
+		component_ranges,
+		pov_modes,
+		default_pov_mode,
+		&analysis_choice,
+		&pov_analysis_choice,
+		cmd.worst_case_map_values,
+		cmd.additional_trie_layers(),
+		cmd.state_layout(),
+	)?;
+
+	let mut regressions = 0;
+	let mut errors = Vec::new();
+	for ((pallet, instance), results) in all_results.iter().sorted_by(|a, b| a.0.cmp(b.0)) {
+		let file_path = weight_file_path(baseline, &all_results, pallet, instance);
+		let content = match fs::read_to_string(&file_path) {
+			Ok(content) => content,
+			Err(e) => {
+				errors.push(format!(
+					"Could not read the baseline of `{pallet}` at {file_path:?}: {e}"
+				));
+				continue
+			},
+		};
+
+		let old = compare::parse_weight_file(&content);
+		if old.is_empty() {
+			errors.push(format!(
+				"No weight functions could be parsed from the baseline of `{pallet}` at \
+				{file_path:?}. Only files in the format of the default template are supported."
+			));
+			continue
+		}
+
+		let new = results
+			.iter()
+			.map(|b| (b.name.clone(), weight_terms(b)))
+			.collect::<BTreeMap<_, _>>();
+		for extrinsic in new.keys().filter(|e| !old.contains_key(*e)) {
+			errors.push(format!(
+				"The baseline of `{pallet}` at {file_path:?} has no weight function for \
+				`{extrinsic}`"
+			));
+		}
+		let changes = compare::compare_weights(&old, &new);
+
+		println!("Comparing `{pallet}` against {file_path:?}: {} changed terms", changes.len());
+		for change in changes {
+			if change.is_regression(cmd.compare_threshold) {
+				regressions += 1;
+				println!("  REGRESSION {change}");
+			} else {
+				println!("  {change}");
+			}
+		}
+	}
+
+	for error in &errors {
+		println!("ERROR {error}");
+	}
+	if !errors.is_empty() {
+		return Err(format!("{} weight files could not be compared", errors.len()).into())
+	}
+	if regressions > 0 {
+		return Err(format!(
+			"{regressions} weight terms increased by more than {}%",
+			cmd.compare_threshold
+		)
+		.into())
+	}
+	Ok(())
+}
+
 /// This function looks at the keys touched during the benchmark, and the storage info we collected
 /// from the pallets, and creates comments with information about the storage keys touched during
 /// each benchmark.
 ///
+/// When the benchmarks ran on live state, the `state_layout` of that state is used instead of the
+/// `max_values` of each map to derive the depth of its trie. Measured proof sizes were already
+/// recorded against the real trie in that case, so no additional trie layers are added to them.
+///
 /// It returns informational comments for human consumption.
 pub(crate) fn process_storage_results(
 	storage_per_prefix: &mut HashMap<Vec<u8>, Vec<BenchmarkResult>>,
@@ -526,6 +677,7 @@ pub(crate) fn process_storage_results(
 	default_pov_mode: PovEstimationMode,
 	worst_case_map_values: u32,
 	additional_trie_layers: u8,
+	state_layout: Option<&StateLayout>,
 ) -> Vec<String> {
 	let mut comments = Vec::new();
 	let mut storage_info_map = storage_info
@@ -604,10 +756,12 @@ pub(crate) fn process_storage_results(
 				panic!("The syntax currently does not allow to exclude single keys from a top-level `Ignored` pov-mode.");
 			}
 
-			let pov_overhead = single_read_pov_overhead(
-				key_info.and_then(|i| i.max_values),
-				worst_case_map_values,
-			);
+			// The number of entries of the map, either known from the live state or worst case.
+			let map_values = match state_layout {
+				Some(layout) => Some(layout.map_entries(&prefix)),
+				None => key_info.and_then(|i| i.max_values),
+			};
+			let pov_overhead = single_read_pov_overhead(map_values, worst_case_map_values);
 
 			let used_pov_mode = match (override_pov_mode, max_size, default_pov_mode) {
 				// All is ignored by default and no override:
@@ -643,8 +797,11 @@ pub(crate) fn process_storage_results(
 					panic!("Key does not have MEL bound but MEL PoV estimation mode was specified {:?}", &key);
 				},
 			};
-			// Add the additional trie layer overhead for every new prefix.
-			if *reads > 0 && !is_all_ignored {
+			// Add the additional trie layer overhead for every new prefix, unless the proof was
+			// measured on live state and therefore already contains them.
+			let is_measured_on_live_state =
+				state_layout.is_some() && used_pov_mode == PovEstimationMode::Measured;
+			if *reads > 0 && !is_all_ignored && !is_measured_on_live_state {
 				prefix_result.proof_size += 15 * 33 * additional_trie_layers as u32;
 			}
 			storage_per_prefix.entry(prefix.clone()).or_default().push(prefix_result);
@@ -693,7 +850,7 @@ pub(crate) fn process_storage_results(
 				match key_info {
 					Some(key_info) => {
 						match worst_case_pov(
-							key_info.max_values,
+							map_values,
 							key_info.max_size,
 							!is_prefix_identified,
 							worst_case_map_values,
@@ -736,7 +893,7 @@ pub(crate) fn process_storage_results(
 }
 
 /// The PoV overhead when reading a key the first time out of a map with `max_values` entries.
-fn single_read_pov_overhead(max_values: Option<u32>, worst_case_map_values: u32) -> u32 {
+pub(crate) fn single_read_pov_overhead(max_values: Option<u32>, worst_case_map_values: u32) -> u32 {
 	let max_values = max_values.unwrap_or(worst_case_map_values);
 	let depth: u32 = easy_log_16(max_values);
 	// Normally we have 16 entries of 32 byte hashes per tree layer. In the new trie
@@ -959,6 +1116,7 @@ mod test {
 			&AnalysisChoice::MedianSlopes,
 			1_000_000,
 			0,
+			None,
 		)
 		.unwrap();
 		let result =
@@ -1016,6 +1174,7 @@ mod test {
 			&AnalysisChoice::MedianSlopes,
 			1_000_000,
 			0,
+			None,
 		)
 		.unwrap();
 		let result =
@@ -1073,6 +1232,7 @@ mod test {
 			&AnalysisChoice::MedianSlopes,
 			1_000_000,
 			0,
+			None,
 		)
 		.unwrap();
 		let result =
@@ -1128,6 +1288,7 @@ mod test {
 			&AnalysisChoice::MedianSlopes,
 			1_000_000,
 			0,
+			None,
 		)
 		.unwrap();
 		let result =
@@ -1185,6 +1346,7 @@ mod test {
 			&AnalysisChoice::MedianSlopes,
 			1_000_000,
 			0,
+			None,
 		)
 		.unwrap();
 		let result =
@@ -1213,6 +1375,7 @@ mod test {
 			&AnalysisChoice::MedianSlopes,
 			1_000_000,
 			0,
+			None,
 		)
 		.unwrap();
 
@@ -1260,6 +1423,7 @@ mod test {
 			&AnalysisChoice::MedianSlopes,
 			1_000_000,
 			2,
+			None,
 		)
 		.unwrap();
 		let with_layer = &mapped_results
@@ -1275,6 +1439,7 @@ mod test {
 			&AnalysisChoice::MedianSlopes,
 			1_000_000,
 			0,
+			None,
 		)
 		.unwrap();
 		let without_layer = &mapped_results
@@ -1292,6 +1457,48 @@ mod test {
 		);
 	}
 
+	#[test]
+	fn state_layout_works() {
+		let mut storage = sp_core::storage::Storage::default();
+		storage.top.insert(b"bounded".to_vec(), vec![0]);
+		let layout = StateLayout::new(&storage);
+		let map = |pov_mode, additional_trie_layers, state_layout| {
+			map_results(
+				&[test_data(b"first", b"first", BenchmarkParameter::a, 10, 3)],
+				&test_storage_info(),
+				&Default::default(),
+				Default::default(),
+				pov_mode,
+				&AnalysisChoice::default(),
+				&AnalysisChoice::MedianSlopes,
+				1_000_000,
+				additional_trie_layers,
+				state_layout,
+			)
+			.unwrap()
+			.remove(&("first_pallet".to_string(), "instance".to_string()))
+			.unwrap()
+			.remove(0)
+		};
+
+		// Measured proofs on live state already contain the trie layers.
+		let on_live_state = map(PovEstimationMode::Measured, 2, Some(&layout));
+		let without_layer = map(PovEstimationMode::Measured, 0, None);
+		assert_eq!(
+			on_live_state.base_calculated_proof_size,
+			without_layer.base_calculated_proof_size
+		);
+
+		// MEL proofs use the size of the map in the live state instead of its `max_values`.
+		let on_live_state = map(PovEstimationMode::MaxEncodedLen, 0, Some(&layout));
+		let worst_case = map(PovEstimationMode::MaxEncodedLen, 0, None);
+		assert!(on_live_state.base_calculated_proof_size < worst_case.base_calculated_proof_size);
+		assert!(
+			on_live_state.component_calculated_proof_size[0].slope <
+				worst_case.component_calculated_proof_size[0].slope
+		);
+	}
+
 	#[test]
 	fn template_works() {
 		let all_results = map_results(
@@ -1308,6 +1515,7 @@ mod test {
 			&AnalysisChoice::MedianSlopes,
 			1_000_000,
 			0,
+			None,
 		)
 		.unwrap();
 
@@ -1347,4 +1555,44 @@ mod test {
 		assert_eq!(easy_log_16(16u32.pow(7) + 1), 8);
 		assert_eq!(easy_log_16(u32::MAX), 8);
 	}
+
+	#[test]
+	fn default_template_can_be_compared() {
+		let all_results = map_results(
+			&[
+				test_data(b"first", b"first", BenchmarkParameter::a, 10, 3),
+				test_data(b"first", b"second", BenchmarkParameter::b, 9, 2),
+			],
+			&test_storage_info(),
+			&Default::default(),
+			Default::default(),
+			PovEstimationMode::MaxEncodedLen,
+			&AnalysisChoice::default(),
+			&AnalysisChoice::MedianSlopes,
+			1_000_000,
+			0,
+			None,
+		)
+		.unwrap();
+		let results = &all_results[&("first_pallet".to_string(), "instance".to_string())];
+
+		let data = TemplateData {
+			pallet: "first_pallet".into(),
+			instance: "instance".into(),
+			benchmarks: results.clone(),
+			..Default::default()
+		};
+		let rendered = new_handlebars().render_template(TEMPLATE, &data).unwrap();
+
+		let expected = results
+			.iter()
+			.map(|b| (b.name.clone(), weight_terms(b)))
+			.collect::<BTreeMap<_, _>>();
+		// All kinds of terms are rendered: base and slope of time, reads, writes and proof size.
+		assert_eq!(expected["first_benchmark"].len(), 8);
+
+		let parsed = compare::parse_weight_file(&rendered);
+		assert_eq!(parsed, expected);
+		assert!(compare::compare_weights(&parsed, &expected).is_empty());
+	}
 }
@@ -378,14 +378,17 @@ fn get_bool_environment_variable(name: &str) -> Option<bool> {
 	}
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
-enum RuntimeTarget {
+/// The target a runtime is built for.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RuntimeTarget {
+	/// WASM, executed by wasmtime.
 	Wasm,
+	/// RISC-V, executed by PolkaVM.
 	Riscv,
 }
 
 impl RuntimeTarget {
-	/// Creates a new instance.
+	/// Creates a new instance from the `SUBSTRATE_RUNTIME_TARGET` environment variable.
 	fn new() -> Self {
 		let Some(value) = env::var_os(RUNTIME_TARGET) else {
 			return Self::Wasm;
@@ -201,9 +201,7 @@ fn check_wasm_toolchain_installed(
 
 	let version = dummy_crate.get_rustc_version();
 
-	let target = RuntimeTarget::new();
-	assert!(target == RuntimeTarget::Wasm);
-	if target.rustc_target_build_std().is_some() {
+	if RuntimeTarget::Wasm.rustc_target_build_std().is_some() {
 		if let Some(sysroot) = dummy_crate.get_sysroot() {
 			let src_path =
 				Path::new(sysroot.trim()).join("lib").join("rustlib").join("src").join("rust");
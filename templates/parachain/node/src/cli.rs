@@ -39,6 +39,9 @@ pub enum Subcommand {
 	/// The pallet benchmarking moved to the `pallet` sub-command.
 	#[command(subcommand)]
 	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
+
+	/// Report the host functions imported by the runtimes of the chain.
+	HostFunctions(sc_cli::HostFunctionsCmd),
 }
 
 const AFTER_HELP_EXAMPLE: &str = color_print::cstr!(
@@ -1,6 +1,8 @@
 use polkadot_sdk::*;
 
-use cumulus_client_service::storage_proof_size::HostFunctions as ReclaimHostFunctions;
+use cumulus_client_service::{
+	storage_proof_size::HostFunctions as ReclaimHostFunctions, ParachainHostFunctions,
+};
 use cumulus_primitives_core::ParaId;
 use frame_benchmarking_cli::{BenchmarkCmd, SUBSTRATE_REFERENCE_HARDWARE};
 use log::info;
@@ -214,6 +216,11 @@ pub fn run() -> Result<()> {
 				_ => Err("Benchmarking sub-command unsupported".into()),
 			}
 		},
+		Some(Subcommand::HostFunctions(cmd)) => {
+			construct_async_run!(|components, cli, cmd, config| {
+				Ok(cmd.run::<Block, _, _, ParachainHostFunctions>(components.client))
+			})
+		},
 		None => {
 			let runner = cli.create_runner(&cli.run.normalize())?;
 			let collator_options = cli.run.collator_options();
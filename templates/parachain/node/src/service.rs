@@ -19,9 +19,9 @@ use cumulus_client_consensus_aura::collators::lookahead::{self as aura, Params a
 use cumulus_client_consensus_common::ParachainBlockImport as TParachainBlockImport;
 use cumulus_client_consensus_proposer::Proposer;
 use cumulus_client_service::{
-	build_network, build_relay_chain_interface, prepare_node_config, start_relay_chain_tasks,
-	BuildNetworkParams, CollatorSybilResistance, DARecoveryProfile, ParachainHostFunctions,
-	StartRelayChainTasksParams,
+	build_network, build_relay_chain_interface, pending_validation_code_storage_key,
+	prepare_node_config, start_relay_chain_tasks, BuildNetworkParams, CollatorSybilResistance,
+	DARecoveryProfile, ParachainHostFunctions, StartRelayChainTasksParams,
 };
 #[docify::export(cumulus_primitives)]
 use cumulus_primitives_core::{
@@ -89,6 +89,9 @@ pub fn new_partial(config: &Configuration) -> Result<Service, sc_service::Error>
 		.with_offchain_heap_alloc_strategy(heap_pages)
 		.with_max_runtime_instances(config.executor.max_runtime_instances)
 		.with_runtime_cache_size(config.executor.runtime_cache_size)
+		.with_runtime_cache_path(config.executor.runtime_cache_path.clone())
+		.with_runtime_cache_max_size(config.executor.runtime_cache_max_size)
+		.with_host_function_shims(config.executor.host_function_shims.clone())
 		.build();
 
 	let (client, backend, keystore_container, task_manager) =
@@ -237,7 +240,11 @@ pub async fn start_parachain_node(
 	para_id: ParaId,
 	hwbench: Option<sc_sysinfo::HwBench>,
 ) -> sc_service::error::Result<(TaskManager, Arc<ParachainClient>)> {
-	let parachain_config = prepare_node_config(parachain_config);
+	let mut parachain_config = prepare_node_config(parachain_config);
+	parachain_config
+		.executor
+		.pending_code_storage_keys
+		.push(pending_validation_code_storage_key("ParachainSystem"));
 
 	let params = new_partial(&parachain_config)?;
 	let (block_import, mut telemetry, telemetry_worker_handle) = params.other;
@@ -37,10 +37,16 @@ pub enum Subcommand {
 	/// Revert the chain to a previous state.
 	Revert(sc_cli::RevertCmd),
 
+	/// Replay blocks on top of a fork with a new runtime.
+	ReplayBlocks(sc_cli::ReplayBlocksCmd),
+
 	/// Sub-commands concerned with benchmarking.
 	#[command(subcommand)]
 	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
 
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
+
+	/// Report the host functions imported by the runtimes of the chain.
+	HostFunctions(sc_cli::HostFunctionsCmd),
 }